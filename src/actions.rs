@@ -0,0 +1,439 @@
+use crate::clipboard;
+use crate::commands;
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::{bookmarks, processes, repos};
+use crate::reading_list::{self, ReadingState};
+use crate::upload;
+use crate::url_cleaner;
+use anyhow::Result;
+use std::path::Path;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+/// Secondary actions offered for the selected item in the action menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Open,
+    Reveal,
+    CopyPath,
+    CopyUrl,
+    AddToChrome,
+    ReadLater,
+    MarkRead,
+    Archive,
+    OpenWith,
+    MoveToTrash,
+    OpenInEditor,
+    OpenInTerminal,
+    OpenRemote,
+    CopyBranch,
+    OpenCiRun,
+    CreateWorktree,
+    UploadAndCopyLink,
+    BringToFront,
+    Quit,
+    ForceQuit,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Open => "Open",
+            Action::Reveal if cfg!(target_os = "macos") => "Reveal in Finder",
+            Action::Reveal => "Show in folder",
+            Action::CopyPath => "Copy path",
+            Action::CopyUrl => "Copy URL",
+            Action::AddToChrome => "Add to Chrome bookmarks bar",
+            Action::ReadLater => "Read later",
+            Action::MarkRead => "Mark as read",
+            Action::Archive => "Archive",
+            Action::OpenWith => "Open with…",
+            Action::MoveToTrash => "Move to Trash",
+            Action::OpenInEditor => "Open in editor",
+            Action::OpenInTerminal => "Open in terminal",
+            Action::OpenRemote => "Open remote in browser",
+            Action::CopyBranch => "Copy branch name",
+            Action::OpenCiRun => "Open last CI run",
+            Action::CreateWorktree => "Create worktree in…",
+            Action::UploadAndCopyLink => "Upload & copy link",
+            Action::BringToFront => "Bring to front",
+            Action::Quit => "Quit (SIGTERM)",
+            Action::ForceQuit => "Force quit (SIGKILL)",
+        }
+    }
+}
+
+/// The actions that make sense for an item, default action first.
+pub fn actions_for(item: &CommandItem) -> Vec<Action> {
+    match item.handler {
+        Handler::Folder if repos::is_repo(item) => {
+            let mut actions = vec![Action::Open, Action::OpenInEditor, Action::OpenInTerminal, Action::CopyBranch];
+            if item.metadata.contains_key("remote") {
+                actions.extend([Action::OpenRemote, Action::OpenCiRun]);
+            }
+            actions.extend([Action::CreateWorktree, Action::Reveal, Action::CopyPath]);
+            actions
+        }
+        Handler::File => vec![
+            Action::Open,
+            Action::Reveal,
+            Action::CopyPath,
+            Action::OpenWith,
+            Action::UploadAndCopyLink,
+            Action::MoveToTrash,
+        ],
+        Handler::Folder => vec![
+            Action::Open,
+            Action::OpenInTerminal,
+            Action::Reveal,
+            Action::CopyPath,
+            Action::OpenWith,
+            Action::MoveToTrash,
+        ],
+        Handler::App => vec![Action::Open, Action::Reveal, Action::CopyPath],
+        Handler::Url => match reading_list::state_of(item) {
+            Some(ReadingState::Unread) => vec![Action::Open, Action::CopyUrl, Action::MarkRead, Action::Archive],
+            Some(_) => vec![Action::Open, Action::CopyUrl, Action::Archive],
+            None => vec![Action::Open, Action::CopyUrl, Action::AddToChrome, Action::ReadLater],
+        },
+        Handler::Process => vec![Action::Open, Action::BringToFront, Action::Quit, Action::ForceQuit],
+        Handler::Note
+        | Handler::Reminder
+        | Handler::Automation
+        | Handler::Internal
+        | Handler::Copy
+        | Handler::Transform
+        | Handler::Plugin
+        | Handler::Shell
+        | Handler::System
+        | Handler::Wifi
+        | Handler::Ssh
+        | Handler::Kube
+        | Handler::Database => vec![Action::Open],
+    }
+}
+
+/// The action menu opened on an item with Ctrl+K or Right arrow.
+#[derive(Debug, Clone)]
+pub struct ActionMenu {
+    pub item: CommandItem,
+    pub actions: Vec<Action>,
+    pub selected: usize,
+}
+
+impl ActionMenu {
+    pub fn new(item: CommandItem) -> Self {
+        let actions = actions_for(&item);
+        Self {
+            item,
+            actions,
+            selected: 0,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.actions.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = (self.selected + self.actions.len() - 1) % self.actions.len();
+    }
+
+    pub fn selected_action(&self) -> Action {
+        self.actions[self.selected]
+    }
+}
+
+/// A destructive action waiting for the user's go-ahead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirmation {
+    pub action: Action,
+    pub item: CommandItem,
+}
+
+impl Confirmation {
+    /// The question shown in the confirmation dialog.
+    pub fn prompt(&self) -> String {
+        match self.action {
+            Action::MoveToTrash => format!("Move {} to the Trash?", self.item.value),
+            _ if self.item.handler == Handler::System => format!("{}?", self.item.label),
+            action => format!("{} \"{}\"?", action.label(), self.item.label),
+        }
+    }
+}
+
+/// Run an action on the item; `Action::Open` is the same as pressing Enter.
+pub async fn run(action: Action, item: &CommandItem) -> Result<()> {
+    match action {
+        Action::Open => commands::execute_command(item, false).await,
+        Action::Reveal => reveal(&item.value),
+        Action::CopyPath => clipboard::copy(&item.value),
+        Action::CopyUrl => clipboard::copy(&url_cleaner::url_of(item)),
+        Action::AddToChrome => bookmarks::add_to_chrome(&item.label, &item.value),
+        Action::ReadLater | Action::MarkRead | Action::Archive => update_reading_list(action, item),
+        Action::OpenWith => open_with(&item.value),
+        Action::MoveToTrash => move_to_trash(&item.value),
+        Action::OpenInEditor => repos::open_in_editor(&item.value),
+        Action::OpenInTerminal => repos::open_in_terminal(&item.value),
+        Action::OpenRemote => repos::open_remote(&item.value).await,
+        Action::CopyBranch => repos::copy_branch(&item.value).await,
+        Action::OpenCiRun => repos::open_last_ci_run(&item.value).await,
+        Action::CreateWorktree => repos::create_worktree(&item.value).await,
+        Action::UploadAndCopyLink => upload::upload_and_copy_link(&item.value).await,
+        Action::BringToFront => processes::focus(&item.value),
+        Action::Quit => processes::kill(&item.value, false),
+        Action::ForceQuit => processes::kill(&item.value, true),
+    }
+}
+
+/// Save the item to the reading list or move it along there. Quick file
+/// updates, run in place so the `later` list can be refreshed at once.
+pub fn update_reading_list(action: Action, item: &CommandItem) -> Result<()> {
+    match action {
+        Action::ReadLater => reading_list::add(&url_cleaner::url_of(item), &item.label),
+        Action::MarkRead => reading_list::set_state(&item.value, ReadingState::Read),
+        Action::Archive => reading_list::set_state(&item.value, ReadingState::Archived),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn reveal(path: &str) -> Result<()> {
+    Command::new("open").arg("-R").arg(path).spawn()?;
+    Ok(())
+}
+
+/// Select the item in the file manager through the freedesktop
+/// `FileManager1` interface, or open the containing directory.
+#[cfg(target_os = "linux")]
+pub(crate) fn reveal(path: &str) -> Result<()> {
+    let uri = file_uri(path);
+    let shown = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &format!("array:string:{}", uri),
+            "string:",
+        ])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !shown {
+        let parent = Path::new(path).parent().unwrap_or(Path::new("/"));
+        commands::open_target(&parent.to_string_lossy())?;
+    }
+    Ok(())
+}
+
+/// `file://` URI of `path`, percent-encoded so that spaces and the commas
+/// `dbus-send` splits arrays on survive.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn file_uri(path: &str) -> String {
+    let encoded: Vec<String> = path.split('/').map(|segment| urlencoding::encode(segment).into_owned()).collect();
+    format!("file://{}", encoded.join("/"))
+}
+
+/// Without Finder, open the containing directory instead.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub(crate) fn reveal(path: &str) -> Result<()> {
+    let parent = Path::new(path).parent().unwrap_or(Path::new("/"));
+    open::that(parent)?;
+    Ok(())
+}
+
+/// Ask for an application with the system picker and open the item with it.
+#[cfg(target_os = "macos")]
+fn open_with(path: &str) -> Result<()> {
+    let output = Command::new("osascript")
+        .args(["-e", "POSIX path of (choose application)"])
+        .output()?;
+    let app = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || app.is_empty() {
+        // Picker cancelled
+        return Ok(());
+    }
+    Command::new("open").args(["-a", &app, path]).spawn()?;
+    Ok(())
+}
+
+/// Picking the app to open a file with needs the macOS app chooser.
+#[cfg(not(target_os = "macos"))]
+fn open_with(_path: &str) -> Result<()> {
+    anyhow::bail!("Open with… is only supported on macOS")
+}
+
+/// Peek at a file with Quick Look, which closes with Space or Esc.
+#[cfg(target_os = "macos")]
+pub(crate) fn quick_look(path: &str) -> Result<()> {
+    use std::process::Stdio;
+
+    Command::new("qlmanage")
+        .args(["-p", path])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Bytes of a file read for its preview.
+const PREVIEW_BYTES: u64 = 64 * 1024;
+
+/// The first `max_lines` of a text file, or a folder's entries, for the
+/// preview overlay shown where Quick Look is not available.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub(crate) fn text_preview(path: &Path, max_lines: usize) -> Result<String> {
+    use std::io::Read;
+
+    if path.is_dir() {
+        let mut names: Vec<String> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.path().is_dir() { format!("{}/", name) } else { name }
+            })
+            .collect();
+        names.sort();
+        let total = names.len();
+        names.truncate(max_lines);
+        if total > max_lines {
+            names.push(format!("… {} more", total - max_lines));
+        }
+        return Ok(names.join("\n"));
+    }
+
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?.take(PREVIEW_BYTES).read_to_end(&mut bytes)?;
+    let text = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        // Cut in the middle of a character by the size limit
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&bytes[..e.valid_up_to()])?,
+        Err(_) => anyhow::bail!("not a text file"),
+    };
+    if text.contains('\0') {
+        anyhow::bail!("not a text file");
+    }
+    Ok(text.lines().take(max_lines).collect::<Vec<_>>().join("\n"))
+}
+
+fn move_to_trash(path: &str) -> Result<()> {
+    if !Path::new(path).exists() {
+        anyhow::bail!("{} no longer exists", path);
+    }
+    trash::delete(path).map_err(|e| anyhow::anyhow!("Failed to move {} to the Trash: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(dir.path().join("image.bin"), [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+
+        assert_eq!(text_preview(&dir.path().join("notes.txt"), 2).unwrap(), "one\ntwo");
+        assert!(text_preview(&dir.path().join("image.bin"), 2).is_err());
+        assert_eq!(text_preview(dir.path(), 2).unwrap(), "image.bin\nnotes.txt\n… 1 more");
+        assert_eq!(text_preview(dir.path(), 10).unwrap(), "image.bin\nnotes.txt\nsrc/");
+    }
+
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(file_uri("/home/me/report.pdf"), "file:///home/me/report.pdf");
+        assert_eq!(file_uri("/home/me/Q1, Q2 notes.txt"), "file:///home/me/Q1%2C%20Q2%20notes.txt");
+    }
+
+    #[test]
+    fn test_confirmation_prompt() {
+        let file = CommandItem::new("report.pdf", Handler::File, "/tmp/report.pdf");
+        let trash = Confirmation { action: Action::MoveToTrash, item: file };
+        assert_eq!(trash.prompt(), "Move /tmp/report.pdf to the Trash?");
+
+        let process = CommandItem::new("Slack", Handler::Process, "42");
+        let kill = Confirmation { action: Action::ForceQuit, item: process };
+        assert_eq!(kill.prompt(), "Force quit (SIGKILL) \"Slack\"?");
+
+        let restart = CommandItem::new("Restart", Handler::System, "restart");
+        let restart = Confirmation { action: Action::Open, item: restart };
+        assert_eq!(restart.prompt(), "Restart?");
+    }
+
+    #[test]
+    fn test_actions_for_handlers() {
+        let file = CommandItem::new("a.txt", Handler::File, "/tmp/a.txt");
+        let url = CommandItem::new("Docs", Handler::Url, "https://docs.rs");
+        let note = CommandItem::new("Note", Handler::Note, "id");
+
+        assert_eq!(actions_for(&file)[0], Action::Open);
+        assert!(actions_for(&file).contains(&Action::MoveToTrash));
+        assert!(actions_for(&file).contains(&Action::UploadAndCopyLink));
+        let folder = CommandItem::new("src", Handler::Folder, "/tmp/src");
+        assert!(actions_for(&folder).contains(&Action::OpenInTerminal));
+        assert!(!actions_for(&folder).contains(&Action::UploadAndCopyLink));
+        assert_eq!(
+            actions_for(&url),
+            vec![Action::Open, Action::CopyUrl, Action::AddToChrome, Action::ReadLater]
+        );
+        assert_eq!(actions_for(&note), vec![Action::Open]);
+
+        let mut queued = url.clone();
+        queued.metadata.insert("later".to_string(), "unread".to_string());
+        assert_eq!(
+            actions_for(&queued),
+            vec![Action::Open, Action::CopyUrl, Action::MarkRead, Action::Archive]
+        );
+        queued.metadata.insert("later".to_string(), "read".to_string());
+        assert_eq!(actions_for(&queued), vec![Action::Open, Action::CopyUrl, Action::Archive]);
+
+        let process = CommandItem::new("slack (42)", Handler::Process, "42");
+        assert!(actions_for(&process).contains(&Action::ForceQuit));
+
+        let mut repo = CommandItem::new("grinta", Handler::Folder, "/src/grinta");
+        repo.metadata.insert("repo".to_string(), "true".to_string());
+        assert!(!actions_for(&repo).contains(&Action::OpenRemote));
+        assert!(!actions_for(&repo).contains(&Action::OpenCiRun));
+        repo.metadata.insert("remote".to_string(), "https://github.com/getgrinta/grinta-rs".to_string());
+        assert_eq!(
+            actions_for(&repo),
+            vec![
+                Action::Open,
+                Action::OpenInEditor,
+                Action::OpenInTerminal,
+                Action::CopyBranch,
+                Action::OpenRemote,
+                Action::OpenCiRun,
+                Action::CreateWorktree,
+                Action::Reveal,
+                Action::CopyPath,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_menu_navigation_wraps() {
+        let mut menu = ActionMenu::new(CommandItem::new("Docs", Handler::Url, "https://docs.rs"));
+        assert_eq!(menu.selected_action(), Action::Open);
+
+        menu.next();
+        assert_eq!(menu.selected_action(), Action::CopyUrl);
+        menu.next();
+        menu.next();
+        menu.next();
+        assert_eq!(menu.selected_action(), Action::Open);
+        menu.previous();
+        assert_eq!(menu.selected_action(), Action::ReadLater);
+    }
+
+    #[test]
+    fn test_action_labels() {
+        let reveal = if cfg!(target_os = "macos") { "Reveal in Finder" } else { "Show in folder" };
+        assert_eq!(Action::Reveal.label(), reveal);
+        assert_eq!(Action::OpenWith.label(), "Open with…");
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const OLLAMA_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where Tab sends the query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    #[default]
+    #[serde(rename = "chatgpt")]
+    ChatGpt,
+    Claude,
+    Perplexity,
+    /// The `url` template
+    Custom,
+    /// A local Ollama server, answered inside grinta
+    Ollama,
+}
+
+/// Settings for Tab's AI query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiConfig {
+    pub provider: AiProvider,
+    /// URL template for the `custom` provider, `{query}` standing for the
+    /// query
+    pub url: Option<String>,
+    pub ollama_url: String,
+    pub model: String,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            provider: AiProvider::default(),
+            url: None,
+            ollama_url: "http://localhost:11434".to_string(),
+            model: "llama3.2".to_string(),
+        }
+    }
+}
+
+impl AiConfig {
+    /// The chat page asking `query`, or `None` for Ollama.
+    pub fn chat_url(&self, query: &str) -> Result<Option<String>> {
+        let template = match self.provider {
+            AiProvider::ChatGpt => "https://chatgpt.com/?q={query}",
+            AiProvider::Claude => "https://claude.ai/new?q={query}",
+            AiProvider::Perplexity => "https://www.perplexity.ai/search?q={query}",
+            AiProvider::Custom => self
+                .url
+                .as_deref()
+                .context("Set `url` under [ai] for the custom provider")?,
+            AiProvider::Ollama => return Ok(None),
+        };
+        Ok(Some(template.replace("{query}", &urlencoding::encode(query))))
+    }
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    response: String,
+}
+
+/// Ask the configured Ollama model and return its answer.
+pub async fn ask_ollama(config: &AiConfig, query: &str) -> Result<String> {
+    let url = format!("{}/api/generate", config.ollama_url.trim_end_matches('/'));
+    let response: OllamaResponse = reqwest::Client::new()
+        .post(&url)
+        .json(&json!({ "model": config.model, "prompt": query, "stream": false }))
+        .timeout(OLLAMA_TIMEOUT)
+        .send()
+        .await
+        .with_context(|| format!("Ollama is not reachable at {}", config.ollama_url))?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response.response.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_url() {
+        let mut config = AiConfig::default();
+        assert_eq!(
+            config.chat_url("rust lifetimes").unwrap().as_deref(),
+            Some("https://chatgpt.com/?q=rust%20lifetimes")
+        );
+
+        config.provider = AiProvider::Claude;
+        assert_eq!(config.chat_url("hi").unwrap().as_deref(), Some("https://claude.ai/new?q=hi"));
+
+        config.provider = AiProvider::Custom;
+        assert!(config.chat_url("hi").is_err());
+        config.url = Some("https://chat.example.com/?prompt={query}".to_string());
+        assert_eq!(config.chat_url("a&b").unwrap().as_deref(), Some("https://chat.example.com/?prompt=a%26b"));
+
+        config.provider = AiProvider::Ollama;
+        assert_eq!(config.chat_url("hi").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_provider() {
+        let config: AiConfig = toml::from_str("provider = \"chatgpt\"").unwrap();
+        assert_eq!(config.provider, AiProvider::ChatGpt);
+        let config: AiConfig = toml::from_str("provider = \"ollama\"\nmodel = \"mistral\"").unwrap();
+        assert_eq!(config.provider, AiProvider::Ollama);
+        assert_eq!(config.ollama_url, "http://localhost:11434");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::theme::Theme;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+/// How often the system's appearance is checked while following it.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// `defaults read -g AppleInterfaceStyle` prints `Dark` in dark mode and
+/// fails in light mode, where the key is missing.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_interface_style(succeeded: bool, output: &str) -> Theme {
+    if succeeded && output.trim().eq_ignore_ascii_case("dark") {
+        Theme::Dark
+    } else {
+        Theme::Light
+    }
+}
+
+/// The `color-scheme` read from the desktop portal, such as
+/// `(<<uint32 1>>,)`: 1 prefers dark, 2 prefers light and 0 has no
+/// preference.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_portal_color_scheme(output: &str) -> Option<Theme> {
+    let value = output.split("uint32").nth(1)?;
+    let digits: String = value.trim_start().chars().take_while(char::is_ascii_digit).collect();
+    match digits.parse::<u32>().ok()? {
+        1 => Some(Theme::Dark),
+        2 => Some(Theme::Light),
+        _ => None,
+    }
+}
+
+/// GNOME's `color-scheme` setting, such as `'prefer-dark'`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_gsettings_color_scheme(output: &str) -> Option<Theme> {
+    match output.trim().trim_matches('\'') {
+        "prefer-dark" => Some(Theme::Dark),
+        "prefer-light" => Some(Theme::Light),
+        _ => None,
+    }
+}
+
+/// The system's appearance, if it can be told.
+#[cfg(target_os = "macos")]
+pub async fn detect() -> Option<Theme> {
+    let output = Command::new("defaults")
+        .args(["read", "-g", "AppleInterfaceStyle"])
+        .output()
+        .await
+        .ok()?;
+    Some(parse_interface_style(output.status.success(), &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The system's appearance from the desktop portal's settings, which
+/// GNOME, KDE and the other portal backends share, or GNOME's own setting.
+#[cfg(target_os = "linux")]
+pub async fn detect() -> Option<Theme> {
+    let portal = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+            "--method",
+            "org.freedesktop.portal.Settings.Read",
+            "org.freedesktop.appearance",
+            "color-scheme",
+        ])
+        .output()
+        .await;
+    if let Ok(output) = portal {
+        if let Some(theme) = parse_portal_color_scheme(&String::from_utf8_lossy(&output.stdout)) {
+            return Some(theme);
+        }
+    }
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .await
+        .ok()?;
+    parse_gsettings_color_scheme(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Other systems keep the configured theme.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub async fn detect() -> Option<Theme> {
+    None
+}
+
+/// Send the system's appearance whenever it changes from `current`, for as
+/// long as the receiver is around.
+pub async fn watch(mut current: Theme, tx: mpsc::Sender<Theme>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match detect().await {
+            Some(theme) if theme != current => {
+                current = theme;
+                if tx.send(theme).await.is_err() {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interface_style() {
+        assert_eq!(parse_interface_style(true, "Dark\n"), Theme::Dark);
+        // The key is missing in light mode
+        assert_eq!(parse_interface_style(false, ""), Theme::Light);
+    }
+
+    #[test]
+    fn test_parse_color_scheme() {
+        assert_eq!(parse_portal_color_scheme("(<<uint32 1>>,)\n"), Some(Theme::Dark));
+        assert_eq!(parse_portal_color_scheme("(<<uint32 2>>,)\n"), Some(Theme::Light));
+        assert_eq!(parse_portal_color_scheme("(<<uint32 0>>,)\n"), None);
+        assert_eq!(parse_portal_color_scheme(""), None);
+
+        assert_eq!(parse_gsettings_color_scheme("'prefer-dark'\n"), Some(Theme::Dark));
+        assert_eq!(parse_gsettings_color_scheme("'default'\n"), None);
+    }
+}
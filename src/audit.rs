@@ -0,0 +1,126 @@
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+const AUDIT_FILE: &str = "grinta_audit.jsonl";
+
+/// One line of the audit log. Unlike history, entries are never merged or
+/// rewritten; the file is only ever appended to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Local>,
+    pub label: String,
+    pub handler: Handler,
+    pub value: String,
+    pub outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(item: &CommandItem, result: &Result<()>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            label: item.label.clone(),
+            handler: item.handler,
+            // Generated secrets must not end up in the log
+            value: if item.metadata.contains_key("masked") {
+                "<masked>".to_string()
+            } else {
+                item.value.clone()
+            },
+            outcome: if result.is_ok() { "ok" } else { "error" }.to_string(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }
+    }
+}
+
+fn audit_file_path() -> Result<PathBuf> {
+    let mut path = dirs::data_dir().context("Failed to get data directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(AUDIT_FILE);
+    Ok(path)
+}
+
+/// Append the outcome of executing `item` to the audit log.
+pub fn record(item: &CommandItem, result: &Result<()>) -> Result<()> {
+    let mut line = serde_json::to_string(&AuditEntry::new(item, result))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_file_path()?)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Return the raw JSON lines of the last `count` audit entries, oldest first.
+pub fn tail(count: usize) -> Result<Vec<String>> {
+    let path = audit_file_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path)?;
+    let lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+    let start = lines.len().saturating_sub(count);
+    Ok(lines[start..].iter().map(|l| l.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_audit_entry_outcome() {
+        let item = CommandItem::new("Test App", Handler::App, "/Applications/Test.app");
+
+        let ok = AuditEntry::new(&item, &Ok(()));
+        assert_eq!(ok.outcome, "ok");
+        assert!(ok.error.is_none());
+
+        let failed = AuditEntry::new(&item, &Err(anyhow::anyhow!("boom")));
+        assert_eq!(failed.outcome, "error");
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+        assert_eq!(failed.value, "/Applications/Test.app");
+    }
+
+    #[test]
+    fn test_masked_value_not_logged() {
+        let mut item = CommandItem::new("Password (20 symbols): ••••", Handler::Copy, "hunter2");
+        item.metadata.insert("masked".to_string(), "true".to_string());
+
+        assert_eq!(AuditEntry::new(&item, &Ok(())).value, "<masked>");
+    }
+
+    #[test]
+    #[serial]
+    fn test_record_and_tail() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path());
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+
+        assert!(tail(10).unwrap().is_empty());
+
+        for i in 0..5 {
+            let item = CommandItem::new(&format!("Item {}", i), Handler::Url, "https://example.com");
+            record(&item, &Ok(())).unwrap();
+        }
+
+        let lines = tail(2).unwrap();
+        assert_eq!(lines.len(), 2);
+        let last: AuditEntry = serde_json::from_str(&lines[1]).unwrap();
+        assert_eq!(last.label, "Item 4");
+        assert_eq!(last.handler, Handler::Url);
+
+        assert_eq!(tail(100).unwrap().len(), 5);
+    }
+}
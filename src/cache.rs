@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// A cached value with the time it was stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: DateTime<Utc>,
+    value: serde_json::Value,
+}
+
+/// Each namespace (e.g. "weather") is one JSON file in the cache directory.
+fn cache_file_path(namespace: &str) -> Result<PathBuf> {
+    let mut path = dirs::cache_dir().context("Failed to get cache directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(format!("{}.json", namespace));
+    Ok(path)
+}
+
+fn load_namespace(namespace: &str) -> HashMap<String, CacheEntry> {
+    cache_file_path(namespace)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Return the cached value for `key` if it is younger than `ttl`.
+pub fn get<T: DeserializeOwned>(namespace: &str, key: &str, ttl: Duration) -> Option<T> {
+    let (value, age) = get_stale(namespace, key)?;
+    (age <= ttl).then_some(value)
+}
+
+/// Return the cached value for `key` regardless of its age, along with
+/// that age. Used as a fallback when a refresh fails.
+pub fn get_stale<T: DeserializeOwned>(namespace: &str, key: &str) -> Option<(T, Duration)> {
+    let entry = load_namespace(namespace).remove(key)?;
+    let value = serde_json::from_value(entry.value).ok()?;
+    Some((value, Utc::now() - entry.stored_at))
+}
+
+/// Store `value` under `key`, replacing any previous entry.
+pub fn put<T: Serialize>(namespace: &str, key: &str, value: &T) -> Result<()> {
+    let mut entries = load_namespace(namespace);
+    entries.insert(
+        key.to_string(),
+        CacheEntry {
+            stored_at: Utc::now(),
+            value: serde_json::to_value(value)?,
+        },
+    );
+    fs::write(cache_file_path(namespace)?, serde_json::to_string(&entries)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    #[test]
+    #[serial]
+    fn test_put_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path());
+        env::set_var("XDG_CACHE_HOME", temp_dir.path());
+
+        assert_eq!(get::<String>("test", "key", Duration::minutes(5)), None);
+
+        put("test", "key", &"value".to_string()).unwrap();
+        put("test", "other", &42).unwrap();
+
+        assert_eq!(get::<String>("test", "key", Duration::minutes(5)), Some("value".to_string()));
+        assert_eq!(get::<i32>("test", "other", Duration::minutes(5)), Some(42));
+        // Expired entries are ignored
+        assert_eq!(get::<String>("test", "key", Duration::seconds(-1)), None);
+        // Type mismatches are treated as misses
+        assert_eq!(get::<i32>("test", "key", Duration::minutes(5)), None);
+
+        let (stale, age) = get_stale::<String>("test", "key").unwrap();
+        assert_eq!(stale, "value");
+        assert!(age < Duration::minutes(1));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::cache;
+use crate::config;
+use crate::core::CommandItem;
+use crate::data_sources::custom::CustomItem;
+use crate::internal::InternalCommand;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "catalogs";
+const TRUST_FILE: &str = "trusted_catalogs.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the TUI re-reads catalogs while it stays open.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A remote list of items maintained by a team, such as runbooks and
+/// dashboards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogConfig {
+    pub name: String,
+    /// JSON or TOML document with an `items` list
+    pub url: String,
+    /// How long a fetched catalog is used before fetching it again
+    #[serde(default = "default_refresh_hours")]
+    pub refresh_hours: i64,
+}
+
+fn default_refresh_hours() -> i64 {
+    24
+}
+
+/// The document served at a catalog URL. Items use the same fields as
+/// `[[items]]` in the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Catalog {
+    #[serde(default)]
+    pub items: Vec<CustomItem>,
+}
+
+/// Parse a catalog served as JSON or TOML.
+pub fn parse_catalog(text: &str) -> Result<Catalog> {
+    if text.trim_start().starts_with('{') {
+        serde_json::from_str(text).context("Invalid JSON catalog")
+    } else {
+        toml::from_str(text).context("Invalid TOML catalog")
+    }
+}
+
+fn trust_file_path() -> Result<PathBuf> {
+    let mut path = dirs::data_dir().context("Failed to get data directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(TRUST_FILE);
+    Ok(path)
+}
+
+/// URLs the user agreed to load items (including shell commands) from.
+fn trusted_urls() -> Vec<String> {
+    trust_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Remember that the catalog at `url` may be loaded.
+pub fn trust(url: &str) -> Result<()> {
+    let mut urls = trusted_urls();
+    if !urls.iter().any(|trusted| trusted == url) {
+        urls.push(url.to_string());
+    }
+    fs::write(trust_file_path()?, serde_json::to_string_pretty(&urls)?)?;
+    Ok(())
+}
+
+/// Items asking to trust each subscribed catalog that is not trusted yet.
+pub fn trust_items() -> Vec<CommandItem> {
+    let trusted = trusted_urls();
+    config::get()
+        .catalogs
+        .iter()
+        .filter(|catalog| !trusted.contains(&catalog.url))
+        .map(|catalog| {
+            let label = format!("Trust catalog \"{}\" from {}", catalog.name, catalog.url);
+            let mut item = InternalCommand::TrustCatalog(catalog.url.clone()).to_item(&label);
+            item.icon = "📚".to_string();
+            item
+        })
+        .collect()
+}
+
+fn catalog_items(catalog: &CatalogConfig, contents: &Catalog) -> Vec<CommandItem> {
+    contents
+        .items
+        .iter()
+        .filter_map(CustomItem::to_item)
+        .map(|mut item| {
+            if item.icon.is_empty() || item.icon == item.handler.to_icon() {
+                item.icon = "📚".to_string();
+            }
+            item.metadata.insert("catalog".to_string(), catalog.name.clone());
+            item
+        })
+        .collect()
+}
+
+async fn fetch(url: &str) -> Result<Catalog> {
+    let text = reqwest::Client::new()
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    parse_catalog(&text)
+}
+
+fn trusted_catalogs() -> Vec<CatalogConfig> {
+    let trusted = trusted_urls();
+    config::get().catalogs.iter().filter(|c| trusted.contains(&c.url)).cloned().collect()
+}
+
+/// Items of every trusted catalog, from the last copy fetched by
+/// `refresh_catalogs`, however old.
+pub fn get_catalog_items() -> Vec<CommandItem> {
+    trusted_catalogs()
+        .iter()
+        .filter_map(|catalog| {
+            let (contents, _) = cache::get_stale::<Catalog>(CACHE_NAMESPACE, &catalog.url)?;
+            Some(catalog_items(catalog, &contents))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Fetch, all at once, the trusted catalogs whose cached copy is older
+/// than their refresh interval. Returns whether any copy was updated; a
+/// catalog that can't be fetched keeps its last copy.
+pub async fn refresh_catalogs() -> bool {
+    let mut fetches = tokio::task::JoinSet::new();
+    for catalog in trusted_catalogs() {
+        let ttl = chrono::Duration::hours(catalog.refresh_hours);
+        if cache::get::<Catalog>(CACHE_NAMESPACE, &catalog.url, ttl).is_some() {
+            continue;
+        }
+        fetches.spawn(async move {
+            let contents = fetch(&catalog.url).await.ok()?;
+            Some((catalog.url, contents))
+        });
+    }
+    // Written one at a time, as each write rewrites the whole namespace
+    let mut updated = false;
+    while let Some(fetched) = fetches.join_next().await {
+        if let Ok(Some((url, contents))) = fetched {
+            updated |= cache::put(CACHE_NAMESPACE, &url, &contents).is_ok();
+        }
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+
+    #[test]
+    fn test_parse_catalog() {
+        let json = r#"{"items": [{"label": "Grafana", "url": "https://grafana.example.com", "keywords": ["metrics"]}]}"#;
+        let catalog = parse_catalog(json).unwrap();
+        assert_eq!(catalog.items[0].label, "Grafana");
+        assert_eq!(catalog.items[0].keywords, vec!["metrics"]);
+
+        let toml = "[[items]]\nlabel = \"Restart API\"\ncommand = \"kubectl rollout restart deploy/api\"\n";
+        assert_eq!(parse_catalog(toml).unwrap().items[0].command.as_deref(), Some("kubectl rollout restart deploy/api"));
+
+        assert!(parse_catalog("{ not json").is_err());
+    }
+
+    #[test]
+    fn test_catalog_items() {
+        let catalog = CatalogConfig {
+            name: "ops".to_string(),
+            url: "https://example.com/ops.json".to_string(),
+            refresh_hours: 24,
+        };
+        let contents = parse_catalog(r#"{"items": [{"label": "Runbook", "url": "https://wiki.example.com/runbook"}, {"label": "Empty"}]}"#).unwrap();
+        let items = catalog_items(&catalog, &contents);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].handler, Handler::Url);
+        assert_eq!(items[0].icon, "📚");
+        assert_eq!(items[0].metadata["catalog"], "ops");
+        assert_eq!(items[0].metadata["custom"], "true");
+    }
+}
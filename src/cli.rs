@@ -1,11 +1,26 @@
-use crate::core::CommandItem;
+use crate::audit;
+use crate::commands;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use crate::daemon;
 use crate::data_sources;
+use crate::events;
+use crate::history;
+use crate::launchers::{self, LauncherFormat};
+use crate::pick::{self, Outcome, PickOptions};
+use crate::ranking::Rank;
+use crate::relative_time;
+use crate::rpc;
+use crate::send;
+use crate::time_tracking;
+use crate::window;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use serde::Serialize;
 use serde_json::json;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
@@ -15,6 +30,23 @@ use fuzzy_matcher::skim::SkimMatcherV2;
 pub struct Cli {
     #[command(subcommand)]
     pub search_command: Option<SearchCommand>,
+
+    /// Optimize rendering for high-latency terminals (auto-detected over SSH)
+    #[arg(long)]
+    pub remote: bool,
+
+    /// Save energy: longer debounces, no web suggestions and fewer redraws
+    /// (auto-detected from the system's low-power mode)
+    #[arg(long)]
+    pub low_power: bool,
+
+    /// List the justfile and Makefile tasks of this folder first
+    #[arg(long)]
+    pub cwd: Option<PathBuf>,
+
+    /// Quit once an item is launched, as `grinta window --popup` does
+    #[arg(long, hide = true)]
+    pub close_on_launch: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,16 +55,105 @@ pub enum SearchCommand {
     Search {
         /// Query string to search for
         query: String,
+        /// Print one document in another launcher's result schema instead
+        /// of streaming JSON lines
+        #[arg(long, value_enum)]
+        format: Option<LauncherFormat>,
+    },
+    /// Pick one of the lines read from stdin and print it, as dmenu does
+    Pick {
+        /// Accepted for compatibility with dmenu and rofi invocations
+        #[arg(long)]
+        dmenu: bool,
+        /// Prompt shown left of the input
+        #[arg(short = 'p')]
+        prompt: Option<String>,
+        /// Number of entries shown at once
+        #[arg(short = 'l', default_value_t = 10)]
+        lines: usize,
+        /// Match case-insensitively
+        #[arg(short = 'i')]
+        ignore_case: bool,
+    },
+    /// Execute an item printed by `grinta search --format`
+    Run {
+        /// The item's JSON
+        item: String,
+    },
+    /// Inspect the audit log of executed commands
+    Log {
+        #[command(subcommand)]
+        action: LogCommand,
+    },
+    /// Summarize the local time tracking log
+    Track {
+        #[command(subcommand)]
+        action: TrackCommand,
+    },
+    /// Keep apps, notes, bookmarks and shortcuts indexed and serve them
+    /// over a Unix socket
+    Daemon {
+        /// Seconds between re-indexing
+        #[arg(long, default_value_t = 300)]
+        refresh_secs: u64,
+    },
+    /// Answer newline-delimited JSON search requests on stdin/stdout
+    Serve {
+        /// Listen on this Unix socket instead of stdin/stdout
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+    /// Open Grinta in a new kitty, alacritty or wezterm window
+    Window {
+        /// Center the window on the monitor with the pointer and close it
+        /// once an item is launched
+        #[arg(long)]
+        popup: bool,
+    },
+    /// Open a URL or path, or search the web for a phrase. Reads stdin
+    /// when no text is given, as macOS Services do
+    Send {
+        /// Text or path to act on
+        text: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LogCommand {
+    /// Print the most recent audit log entries as JSON lines
+    Tail {
+        /// Number of entries to print
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrackCommand {
+    /// Print the time spent per task
+    Report {
+        /// Number of days to include
+        #[arg(short, long, default_value_t = 7)]
+        days: i64,
     },
 }
 
 #[derive(Serialize)]
-struct StreamResponse {
+pub(crate) struct StreamResponse {
     #[serde(rename = "type")]
     response_type: String,
     data: CommandOutput,
 }
 
+impl StreamResponse {
+    pub(crate) fn new(item: &CommandItem, result_type: &str) -> Self {
+        Self {
+            response_type: result_type.to_string(),
+            data: CommandOutput::from(item),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct CommandOutput {
     label: String,
@@ -56,19 +177,108 @@ impl From<&CommandItem> for CommandOutput {
 }
 
 fn stream_result(item: &CommandItem, result_type: &str) -> Result<()> {
-    let response = StreamResponse {
-        response_type: result_type.to_string(),
-        data: CommandOutput::from(item),
-    };
-    
+    let response = StreamResponse::new(item, result_type);
+
     let json = serde_json::to_string(&response)?;
     println!("{}", json);
     io::stdout().flush()?;
     Ok(())
 }
 
-pub async fn run_search_command(command: SearchCommand) -> Result<()> {
-    let result = run_search_command_inner(command).await;
+pub async fn run_command(command: SearchCommand) -> Result<()> {
+    match command {
+        SearchCommand::Search { query, format: None } => run_search_command(query).await,
+        SearchCommand::Search { query, format: Some(format) } => {
+            let items: Vec<CommandItem> = search_results(&query).await.into_iter().map(|(item, _)| item).collect();
+            println!("{}", serde_json::to_string(&launchers::render(format, &items))?);
+            Ok(())
+        }
+        SearchCommand::Run { item } => run_item_command(&item).await,
+        SearchCommand::Pick {
+            dmenu: _,
+            prompt,
+            lines,
+            ignore_case,
+        } => run_pick_command(PickOptions {
+            prompt,
+            lines,
+            ignore_case,
+        }),
+        SearchCommand::Log { action } => run_log_command(action),
+        SearchCommand::Track { action } => run_track_command(action),
+        SearchCommand::Daemon { refresh_secs } => daemon::run(refresh_secs).await,
+        SearchCommand::Serve { socket } => rpc::run(socket.as_deref()).await,
+        SearchCommand::Send { text } => run_send_command(text).await,
+        SearchCommand::Window { popup } => window::open(popup),
+    }
+}
+
+async fn run_send_command(text: Vec<String>) -> Result<()> {
+    let input = if text.is_empty() {
+        io::read_to_string(io::stdin())?
+    } else {
+        text.join(" ")
+    };
+    let item = send::resolve(&input).ok_or_else(|| anyhow::anyhow!("Nothing to send"))?;
+    let result = commands::execute_command(&item, false).await;
+    events::record_execution(&item, &result).await;
+    result
+}
+
+fn run_pick_command(options: PickOptions) -> Result<()> {
+    match pick::run(&options)? {
+        Outcome::Selected(selection) => {
+            println!("{}", selection);
+            Ok(())
+        }
+        // dmenu exits with 1 when cancelled, which scripts check
+        Outcome::Cancelled => std::process::exit(1),
+    }
+}
+
+async fn run_item_command(argument: &str) -> Result<()> {
+    let item = launchers::parse_run_argument(argument)?;
+    let result = commands::execute_command(&item, false).await;
+    events::record_execution(&item, &result).await;
+    result
+}
+
+fn run_log_command(command: LogCommand) -> Result<()> {
+    let LogCommand::Tail { lines } = command;
+    let mut stdout = io::stdout();
+    for line in audit::tail(lines)? {
+        writeln!(stdout, "{}", line)?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn run_track_command(command: TrackCommand) -> Result<()> {
+    let TrackCommand::Report { days } = command;
+    let now = chrono::Local::now();
+    let entries = time_tracking::load_entries()?;
+    let summary = time_tracking::summarize(&entries, now - chrono::Duration::days(days), now);
+
+    let mut stdout = io::stdout();
+    if summary.is_empty() {
+        writeln!(stdout, "No time tracked in the last {} days", days)?;
+    } else {
+        let total = summary.iter().fold(chrono::Duration::zero(), |acc, (_, d)| acc + *d);
+        for (description, duration) in &summary {
+            writeln!(stdout, "{:>9}  {}", time_tracking::format_total(*duration), description)?;
+        }
+        writeln!(stdout, "{:>9}  total", time_tracking::format_total(total))?;
+        if let Some(last) = entries.iter().map(|entry| entry.start).max() {
+            let style = relative_time::DateStyle::current();
+            writeln!(stdout, "Last started {}", relative_time::format(last, now, style))?;
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+pub async fn run_search_command(query: String) -> Result<()> {
+    let result = run_search_command_inner(query).await;
     
     // Always send completion marker
     let completion = match &result {
@@ -89,16 +299,13 @@ pub async fn run_search_command(command: SearchCommand) -> Result<()> {
     result
 }
 
-async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
-    let SearchCommand::Search { query } = command;
-    
-    // Create channel for collecting results
-    let (tx, mut rx) = mpsc::channel::<(CommandItem, String)>(100);
-    
-    let lower_query = query.to_lowercase();
-    
-    // Spawn separate tasks for each data source
-    let handles = vec![
+/// Read apps, notes, bookmarks and shortcuts directly, one task each.
+fn spawn_source_searches(
+    tx: &mpsc::Sender<(CommandItem, String)>,
+    lower_query: &str,
+) -> Vec<tokio::task::JoinHandle<()>> {
+    let lower_query = lower_query.to_string();
+    vec![
         // macOS Applications
         {
             let tx = tx.clone();
@@ -126,6 +333,14 @@ async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
                         }
                     }
                 }
+                #[cfg(target_os = "linux")]
+                {
+                    for app in data_sources::desktop_entries::get_linux_applications().await {
+                        if app.label.to_lowercase().contains(&query) {
+                            let _ = tx.send((app, "app".to_string())).await;
+                        }
+                    }
+                }
             })
         },
         
@@ -182,7 +397,47 @@ async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
                 }
             })
         },
-        
+    ]
+}
+
+async fn run_search_command_inner(query: String) -> Result<()> {
+    for (item, result_type) in search_results(&query).await {
+        stream_result(&item, &result_type)?;
+    }
+    Ok(())
+}
+
+/// Every result for `query` with its result type, best first. Computed
+/// answers (calculator, ...) come ahead of everything else.
+pub(crate) async fn search_results(query: &str) -> Vec<(CommandItem, String)> {
+    let query = query.to_string();
+
+    // Create channel for collecting results
+    let (tx, mut rx) = mpsc::channel::<(CommandItem, String)>(100);
+    
+    let lower_query = query.to_lowercase();
+
+    // A running daemon already has apps, notes, bookmarks and shortcuts
+    // indexed; otherwise read each source now
+    let mut handles = match daemon::request(daemon::Request::Search { query: lower_query.clone() }).await {
+        Some(items) => {
+            let tx = tx.clone();
+            vec![tokio::spawn(async move {
+                for item in items {
+                    let result_type = match item.handler {
+                        Handler::App => "app",
+                        Handler::Note => "note",
+                        Handler::Automation => "shortcut",
+                        _ => "bookmark",
+                    };
+                    let _ = tx.send((item, result_type.to_string())).await;
+                }
+            })]
+        }
+        None => spawn_source_searches(&tx, &lower_query),
+    };
+
+    handles.extend([
         // File System Search  
         {
             let tx = tx.clone();
@@ -200,14 +455,15 @@ async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
             let tx = tx.clone();
             let query_web = query.clone();
             tokio::spawn(async move {
-                if let Ok(suggestions) = data_sources::web_search::get_web_search_suggestions(query_web).await {
+                if let Ok(mut suggestions) = data_sources::web_search::get_web_search_suggestions(query_web).await {
+                    data_sources::web_search::add_instant_answer(&mut suggestions).await;
                     for suggestion in suggestions {
                         let _ = tx.send((suggestion, "web_suggestion".to_string())).await;
                     }
                 }
             })
         },
-    ];
+    ]);
     
     // Drop the original sender so the receiver knows when all tasks are done
     drop(tx);
@@ -225,7 +481,7 @@ async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
     
     // Sort results using fuzzy matching
     let matcher = SkimMatcherV2::default();
-    let mut scored_results: Vec<((CommandItem, String), i64)> = all_results
+    let scored_results: Vec<((CommandItem, String), i64)> = all_results
         .into_iter()
         .filter_map(|(item, result_type)| {
             // Try fuzzy matching on both label and value
@@ -241,45 +497,26 @@ async fn run_search_command_inner(command: SearchCommand) -> Result<()> {
         })
         .collect();
     
-    // Sort by combined score: fuzzy match score + type priority bonus
-    scored_results.sort_by(|a, b| {
-        use crate::core::CommandType;
-        
-        // Calculate type priority bonus (higher bonus for preferred types)
-        // Increased bonuses to make type priority more significant
-        let a_type_bonus = match a.0.0.kind {
-            CommandType::App => 200,       // Apps get very high bonus
-            CommandType::Note => 150,      // Notes get high bonus  
-            CommandType::Bookmark => 100,  // Bookmarks get medium bonus
-            CommandType::Unknown => 50,    // Files get small bonus
-            CommandType::WebSearch => 25,  // Web search gets tiny bonus
-            CommandType::WebSuggestion => 0, // Web suggestions get no bonus
-        };
-        
-        let b_type_bonus = match b.0.0.kind {
-            CommandType::App => 200,
-            CommandType::Note => 150,
-            CommandType::Bookmark => 100,
-            CommandType::Unknown => 50,
-            CommandType::WebSearch => 25,
-            CommandType::WebSuggestion => 0,
-        };
-        
-        // Combined score = fuzzy score + type bonus
-        let a_combined_score = a.1 + a_type_bonus;
-        let b_combined_score = b.1 + b_type_bonus;
-        
-        // Sort by combined score (descending), then alphabetically for stable sorting
-        match b_combined_score.cmp(&a_combined_score) {
-            std::cmp::Ordering::Equal => a.0.0.label.to_lowercase().cmp(&b.0.0.label.to_lowercase()),
-            other => other
-        }
+    // Ranked the same way as the TUI: match score plus type bonus, then
+    // frecency and priority
+    let ranking = &config::get().ranking;
+    let frecency = history::frecency_scores(&history::load_history().unwrap_or_default());
+    let mut ranked: Vec<(Rank, (CommandItem, String))> = scored_results
+        .into_iter()
+        .map(|(result, score)| {
+            let rank = ranking.rank(&query, score, history::item_frecency(&frecency, &result.0), &result.0);
+            (rank, result)
+        })
+        .collect();
+    ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+        a_rank
+            .order(b_rank)
+            .then_with(|| a.0.label.to_lowercase().cmp(&b.0.label.to_lowercase()))
     });
-    
-    // Stream sorted results
-    for ((item, result_type), _score) in scored_results {
-        stream_result(&item, &result_type)?;
-    }
-    
-    Ok(())
+
+    data_sources::get_query_items(&query)
+        .into_iter()
+        .map(|item| (item, "answer".to_string()))
+        .chain(ranked.into_iter().map(|(_, result)| result))
+        .collect()
 }
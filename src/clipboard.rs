@@ -0,0 +1,37 @@
+use anyhow::Result;
+use arboard::Clipboard;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+/// The clipboard, opened on first use and kept while Grinta runs. On X11
+/// copied text is served by the process that copied it, so a handle
+/// dropped right away would take the text with it.
+static CLIPBOARD: Lazy<Mutex<Option<Clipboard>>> = Lazy::new(|| Mutex::new(None));
+
+fn with_clipboard<T>(use_clipboard: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>) -> Result<T> {
+    let mut shared = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    let mut clipboard = match shared.take() {
+        Some(clipboard) => clipboard,
+        None => Clipboard::new()?,
+    };
+    let result = use_clipboard(&mut clipboard);
+    *shared = Some(clipboard);
+    Ok(result?)
+}
+
+/// Copy `text` to the system clipboard.
+pub fn copy(text: &str) -> Result<()> {
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
+
+/// Read the text currently on the system clipboard.
+pub fn paste() -> Result<String> {
+    with_clipboard(|clipboard| clipboard.get_text())
+}
+
+/// Close the clipboard before exiting, which hands copied text over to
+/// the clipboard manager on X11.
+pub fn release() {
+    let mut shared = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    drop(shared.take());
+}
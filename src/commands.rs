@@ -1,28 +1,60 @@
+use crate::clipboard;
+use crate::config;
 use crate::data_sources;
-use anyhow::Result;
+use crate::plugins;
+use crate::shell;
+use crate::url_cleaner;
+use anyhow::{Context, Result};
 use open;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::process::Command;
 
 pub use crate::core::{CommandItem, Handler};
 
+tokio::task_local! {
+    /// The active workspace's `env`, for the commands an execution starts.
+    static WORKSPACE_ENV: BTreeMap<String, String>;
+}
+
+/// Run `task` with `env` added to the environment of the commands it
+/// starts, leaving Grinta's own environment alone.
+pub async fn with_workspace_env<F: Future>(env: BTreeMap<String, String>, task: F) -> F::Output {
+    WORKSPACE_ENV.scope(env, task).await
+}
+
+/// The workspace environment of the running execution, if any.
+pub fn workspace_env() -> BTreeMap<String, String> {
+    WORKSPACE_ENV.try_with(BTreeMap::clone).unwrap_or_default()
+}
+
 pub async fn execute_command(item: &CommandItem, alt_modifier_active: bool) -> Result<()> {
     match item.handler {
-        Handler::Url => {
-            open::that(&item.value)?;
-        }
+        Handler::Url => match item.metadata.get("open_with") {
+            Some(app) => open_in_app(app, &url_cleaner::url_of(item))?,
+            None => open_target(&url_cleaner::url_of(item))?,
+        },
         Handler::App => {
             #[cfg(target_os = "macos")]
             {
-                Command::new("open").arg(&item.value).spawn()?;
+                Command::new("open").arg(&item.value).envs(workspace_env()).spawn()?;
+            }
+            #[cfg(target_os = "linux")]
+            {
+                launch_desktop_entry(&item.value)?;
             }
-            #[cfg(not(target_os = "macos"))]
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
             {
-                open::that(&item.value)?;
+                open_target(&item.value)?;
             }
         }
         Handler::Note => {
             data_sources::notes::open_note(&item.value).await?;
         }
+        Handler::Reminder => {
+            data_sources::reminders::open_reminder(&item.value).await?;
+        }
         Handler::File | Handler::Folder => {
             if alt_modifier_active {
                 #[cfg(target_os = "macos")]
@@ -31,25 +63,192 @@ pub async fn execute_command(item: &CommandItem, alt_modifier_active: bool) -> R
                 }
                 #[cfg(not(target_os = "macos"))]
                 {
-                    // For non-macOS, Alt+Enter on a file/folder could potentially
-                    // open its parent directory. This is a placeholder for that logic.
-                    // For now, it will just open the item directly.
-                    open::that(&item.value)?;
+                    crate::actions::reveal(&item.value)?;
                 }
+            } else if data_sources::zoxide::opens_in_terminal(item) {
+                data_sources::repos::open_in_terminal(&item.value)?;
             } else {
-                open::that(&item.value)?;
+                open_target(&item.value)?;
             }
         }
         Handler::Automation => {
             #[cfg(target_os = "macos")]
             {
-                Command::new("shortcuts").args(["run", &item.value]).spawn()?;
+                Command::new("shortcuts").args(["run", &item.value]).envs(workspace_env()).spawn()?;
+            }
+        }
+        Handler::Internal => {
+            // Internal commands change Grinta's own state and are handled by
+            // the UI before anything is spawned
+        }
+        Handler::Copy => {
+            clipboard::copy(&item.value)?;
+        }
+        Handler::Transform => {
+            if item.value.is_empty() {
+                anyhow::bail!("{}", item.label);
+            }
+            clipboard::copy(&item.value)?;
+        }
+        Handler::Plugin => {
+            plugins::execute(item).await?;
+        }
+        Handler::Process => {
+            let kill = item.metadata.get("process_action").map(String::as_str) == Some("kill");
+            if kill {
+                data_sources::processes::kill(&item.value, alt_modifier_active)?;
+            } else {
+                data_sources::processes::focus(&item.value)?;
+            }
+        }
+        Handler::System => {
+            data_sources::system::run(&item.value).await?;
+        }
+        Handler::Wifi => {
+            data_sources::wifi::run(&item.value).await?;
+        }
+        Handler::Ssh => {
+            let port = item.metadata.get("port").and_then(|port| port.parse().ok());
+            open_in_terminal(config::get().terminal, &data_sources::ssh::command(&item.value, port))?;
+        }
+        Handler::Kube => {
+            data_sources::kube::use_context(&item.value).await?;
+        }
+        Handler::Database => {
+            data_sources::databases::open(&item.value).await?;
+        }
+        Handler::Shell => {
+            if alt_modifier_active || shell::runs_in_terminal(item) {
+                open_in_terminal(config::get().terminal, &item.value)?;
+            } else {
+                shell::run(&item.value).await?;
             }
         }
     }
     Ok(())
 }
 
+/// Open a URL or path with its default app. On Linux the opener
+/// (`xdg-open`, then `gio open` and the desktop-specific tools) is detached,
+/// so a terminal browser it might start cannot take over the TUI.
+pub fn open_target(target: &str) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        open::that_detached(target)
+            .map_err(|e| anyhow::anyhow!("Failed to open {} (is xdg-open installed?): {}", target, e))?;
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        open::that(target)?;
+    }
+    Ok(())
+}
+
+/// Open a URL or path with `app`, such as a media player.
+pub fn open_in_app(app: &str, target: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open").args(["-a", app, target]).envs(workspace_env()).spawn()?;
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Command::new(app)
+            .arg(target)
+            .envs(workspace_env())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", app))?;
+    }
+    Ok(())
+}
+
+/// Terminal that interactive commands, such as `ssh` or a shell command
+/// run with Alt+Enter, open in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalApp {
+    /// Terminal.app on macOS, `$TERMINAL` or `x-terminal-emulator` elsewhere
+    #[default]
+    Terminal,
+    /// iTerm2, on macOS
+    Iterm,
+    Wezterm,
+}
+
+/// `command` followed by the user's shell, so the window stays open once
+/// the command is done.
+fn keep_open(command: &str) -> String {
+    format!("{}; exec {}", command, shell::user_shell())
+}
+
+/// `text` as an AppleScript string literal.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Run `command` in a new window of `terminal`, which stays open
+/// afterwards.
+pub fn open_in_terminal(terminal: TerminalApp, command: &str) -> Result<()> {
+    match terminal {
+        TerminalApp::Wezterm => {
+            Command::new("wezterm")
+                .args(["start", "--", &shell::user_shell(), "-c", &keep_open(command)])
+                .envs(workspace_env())
+                .spawn()
+                .context("Failed to start wezterm")?;
+        }
+        #[cfg(target_os = "macos")]
+        TerminalApp::Terminal => {
+            let script = format!(
+                "tell application \"Terminal\"\nactivate\ndo script {}\nend tell",
+                applescript_string(command)
+            );
+            Command::new("osascript").args(["-e", &script]).envs(workspace_env()).spawn()?;
+        }
+        #[cfg(target_os = "macos")]
+        TerminalApp::Iterm => {
+            let script = format!(
+                "tell application \"iTerm\"\nactivate\nset newWindow to (create window with default profile)\ntell current session of newWindow to write text {}\nend tell",
+                applescript_string(command)
+            );
+            Command::new("osascript").args(["-e", &script]).envs(workspace_env()).spawn()?;
+        }
+        #[cfg(not(target_os = "macos"))]
+        TerminalApp::Terminal => {
+            let terminal = std::env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".to_string());
+            Command::new(&terminal)
+                .args(["-e", &shell::user_shell(), "-c", &keep_open(command)])
+                .envs(workspace_env())
+                .spawn()
+                .with_context(|| format!("Failed to start {}", terminal))?;
+        }
+        #[cfg(not(target_os = "macos"))]
+        TerminalApp::Iterm => anyhow::bail!("iTerm is only available on macOS"),
+    }
+    Ok(())
+}
+
+/// Launch a `.desktop` entry, preferring `gio launch` and falling back to
+/// `gtk-launch` (which takes the desktop id rather than a path).
+#[cfg(target_os = "linux")]
+fn launch_desktop_entry(path: &str) -> Result<()> {
+    if !path.ends_with(".desktop") {
+        open_target(path)?;
+        return Ok(());
+    }
+
+    if Command::new("gio").args(["launch", path]).envs(workspace_env()).spawn().is_ok() {
+        return Ok(());
+    }
+
+    let desktop_id = std::path::Path::new(path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(path);
+    Command::new("gtk-launch").arg(desktop_id).envs(workspace_env()).spawn()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,6 +258,12 @@ mod tests {
         CommandItem::new(label, handler, value)
     }
 
+    #[test]
+    fn test_terminal_commands() {
+        assert_eq!(applescript_string(r#"echo "hi" \ bye"#), r#""echo \"hi\" \\ bye""#);
+        assert!(keep_open("ssh box").starts_with("ssh box; exec "));
+    }
+
     #[tokio::test]
     async fn test_execute_command_url() {
         let item = create_test_item("Test URL", Handler::Url, "https://example.com");
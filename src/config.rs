@@ -0,0 +1,434 @@
+use crate::ai::AiConfig;
+use crate::catalogs::CatalogConfig;
+use crate::commands::TerminalApp;
+use crate::data_sources::calendar::CalendarConfig;
+use crate::data_sources::currency::CurrencyConfig;
+use crate::data_sources::custom::CustomItem;
+use crate::data_sources::databases::DatabaseConfig;
+use crate::data_sources::feeds::FeedConfig;
+use crate::data_sources::generators::GeneratorsConfig;
+use crate::data_sources::github::GithubConfig;
+use crate::data_sources::issues::IssuesConfig;
+use crate::data_sources::notes::NotesConfig;
+use crate::data_sources::packages::PackagesConfig;
+use crate::data_sources::quotes::QuotesConfig;
+use crate::data_sources::repos::ReposConfig;
+use crate::data_sources::rest::RestConfig;
+use crate::data_sources::tables::TableConfig;
+use crate::data_sources::tasks::TasksConfig;
+use crate::data_sources::timezones::TimezonesConfig;
+use crate::data_sources::translate::TranslateConfig;
+use crate::data_sources::web_search::SearchConfig;
+use crate::data_sources::zoxide::ZoxideConfig;
+use crate::hotkeys::KeysConfig;
+use crate::plugins::PluginConfig;
+use crate::ranking::RankingConfig;
+use crate::hooks::HooksConfig;
+use crate::theme::{Density, ThemeMode};
+use crate::time_tracking::TimeTrackingConfig;
+use crate::upload::UploadConfig;
+use crate::url_cleaner::UrlCleanerConfig;
+use crate::window::WindowConfig;
+use crate::workspace::{SourcePrefixes, Workspace};
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "config.toml";
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// User configuration read from `~/.config/grinta/config.toml`.
+/// Every field has a default so a missing or partial file is fine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Append every executed item to the audit log
+    pub audit_log: bool,
+    /// Named workspaces, switchable with the `ws` command
+    pub workspaces: BTreeMap<String, Workspace>,
+    /// Optional Toggl sync for `track start` / `track stop`
+    pub time_tracking: TimeTrackingConfig,
+    /// Provider for `$AAPL` / `btc` quotes
+    pub quotes: QuotesConfig,
+    /// Exchange rates for `100 usd to eur`
+    pub currency: CurrencyConfig,
+    /// Optional shipment status API for `track <number>`
+    pub packages: PackagesConfig,
+    /// Defaults for the `password` generator
+    pub generators: GeneratorsConfig,
+    /// Script filters run from the plugins directory
+    pub plugins: Vec<PluginConfig>,
+    /// Backend for `tr en>de ...`
+    pub translate: TranslateConfig,
+    /// Colors for a `dark` or `light` terminal background, or `auto` to
+    /// follow the system's appearance
+    pub theme: ThemeMode,
+    /// Links, files and commands added to the catalog
+    pub items: Vec<CustomItem>,
+    /// CSV and JSON files whose rows are added to the catalog
+    pub tables: Vec<TableConfig>,
+    /// Query prefixes scoping the results to one source
+    pub prefixes: SourcePrefixes,
+    /// Search APIs queried with a prefix
+    pub rest: Vec<RestConfig>,
+    /// Remote item lists shared by a team
+    pub catalogs: Vec<CatalogConfig>,
+    /// Podcasts and YouTube channels whose latest episodes are listed
+    pub feeds: Vec<FeedConfig>,
+    /// Database connections opened in a client, with their password in
+    /// the keychain
+    pub databases: Vec<DatabaseConfig>,
+    /// Web search engines and `!bang` shortcuts
+    pub search: SearchConfig,
+    /// Terminal started by `grinta window`
+    pub window: WindowConfig,
+    /// Where Tab sends the query
+    pub ai: AiConfig,
+    /// Draw file and folder icons with Nerd Font glyphs instead of emoji
+    pub nerd_font: bool,
+    /// Rebound keys of built-in actions
+    pub keys: KeysConfig,
+    /// Apple Notes indexing
+    pub notes: NotesConfig,
+    /// Frecency decay and the weights of each kind of result
+    pub ranking: RankingConfig,
+    /// Results on one line, or with the path or URL underneath
+    pub density: Density,
+    /// Shell commands run after executions and on exit
+    pub hooks: HooksConfig,
+    /// Terminal that `ssh` hosts and Alt+Enter shell commands open in
+    pub terminal: TerminalApp,
+    /// Folders scanned for git repositories
+    pub repos: ReposConfig,
+    /// Token for the `gh` search and the repositories' GitHub status
+    pub github: GithubConfig,
+    /// Linear or Jira, searched with `issue`
+    pub issues: IssuesConfig,
+    /// Directories ranked by zoxide
+    pub zoxide: ZoxideConfig,
+    /// Justfile and Makefile tasks of the repositories
+    pub tasks: TasksConfig,
+    /// The world clocks of the `time` query
+    pub timezones: TimezonesConfig,
+    /// Calendars whose meetings get a join item when about to start
+    pub calendar: CalendarConfig,
+    /// Where `Upload & copy link` sends files
+    pub upload: UploadConfig,
+    /// Tracking parameters stripped from opened and copied URLs
+    pub url_cleaner: UrlCleanerConfig,
+}
+
+/// Directory holding the config file and user extensions.
+pub fn config_dir() -> Result<PathBuf> {
+    let mut path = dirs::home_dir().context("Failed to get home directory")?;
+    path.push(".config");
+    path.push("grinta");
+    Ok(path)
+}
+
+/// Expand a leading `~` to the home directory.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+fn config_file_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(CONFIG_FILE))
+}
+
+/// Path of the config file, created empty when missing so it can be
+/// opened in an editor.
+pub fn ensure_config_file() -> Result<PathBuf> {
+    let path = config_file_path()?;
+    if !path.exists() {
+        fs::create_dir_all(config_dir()?)?;
+        fs::write(&path, "")?;
+    }
+    Ok(path)
+}
+
+pub fn parse_config(contents: &str) -> Result<Config> {
+    let config: Config = toml::from_str(contents).context("Invalid config file")?;
+    config.ranking.validate().context("Invalid config file")?;
+    Ok(config)
+}
+
+pub fn load_config() -> Result<Config> {
+    let path = config_file_path()?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_config(&contents)
+}
+
+/// Install the configuration used by the rest of the app.
+/// Returns false if a configuration was already installed.
+pub fn init(config: Config) -> bool {
+    CONFIG.set(config).is_ok()
+}
+
+/// The active configuration, loaded lazily (falling back to defaults when
+/// the file is missing or invalid) if `init` was never called.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| load_config().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_sources::notes::NoteFolder;
+
+    #[test]
+    fn test_parse_empty_config() {
+        let config = parse_config("").unwrap();
+        assert_eq!(config, Config::default());
+        assert!(!config.audit_log);
+    }
+
+    #[test]
+    fn test_parse_audit_log() {
+        let config = parse_config("audit_log = true").unwrap();
+        assert!(config.audit_log);
+    }
+
+    #[test]
+    fn test_parse_invalid_config() {
+        assert!(parse_config("audit_log = \"maybe\"").is_err());
+        assert!(parse_config("not toml at all [").is_err());
+    }
+
+    #[test]
+    fn test_parse_workspaces() {
+        let config = parse_config(
+            r#"
+            [workspaces.clientx]
+            dirs = ["~/work/clientx"]
+            sources = ["files", "bookmarks"]
+            env = { JIRA_PROJECT = "CX" }
+            pins = [{ label = "Repo", value = "~/work/clientx", handler = "Folder" }]
+            "#,
+        )
+        .unwrap();
+
+        let workspace = &config.workspaces["clientx"];
+        assert_eq!(workspace.dirs, vec!["~/work/clientx"]);
+        assert_eq!(workspace.sources.len(), 2);
+        assert_eq!(workspace.env.get("JIRA_PROJECT").map(String::as_str), Some("CX"));
+        assert_eq!(workspace.pins[0].label, "Repo");
+    }
+
+    #[test]
+    fn test_parse_time_tracking() {
+        let config = parse_config(
+            r#"
+            [time_tracking]
+            toggl_token = "secret"
+            toggl_workspace_id = 42
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.time_tracking.toggl_token.as_deref(), Some("secret"));
+        assert_eq!(config.time_tracking.toggl_workspace_id, Some(42));
+    }
+
+    #[test]
+    fn test_parse_quotes() {
+        let config = parse_config("[quotes]\nprovider = \"finnhub\"\napi_key = \"abc\"").unwrap();
+        assert_eq!(config.quotes.provider, crate::data_sources::quotes::QuoteProvider::Finnhub);
+        assert_eq!(config.quotes.api_key.as_deref(), Some("abc"));
+        // Unset fields keep their defaults
+        assert_eq!(config.quotes.currency, "usd");
+    }
+
+    #[test]
+    fn test_parse_generators() {
+        use crate::data_sources::generators::Charset;
+
+        let config = parse_config("[generators]\npassword_charset = \"alphanumeric\"").unwrap();
+        assert_eq!(config.generators.password_charset, Charset::Alphanumeric);
+        assert_eq!(config.generators.password_length, 20);
+    }
+
+    #[test]
+    fn test_parse_plugins() {
+        let config = parse_config(
+            "[[plugins]]\nname = \"github\"\ncommand = \"gh-search\"\nprefix = \"gh\"",
+        )
+        .unwrap();
+        assert_eq!(config.plugins.len(), 1);
+        assert_eq!(config.plugins[0].prefix, "gh");
+        assert_eq!(config.plugins[0].timeout_ms, 2000);
+    }
+
+    #[test]
+    fn test_parse_items() {
+        let config = parse_config(
+            "[[items]]\nlabel = \"Intranet\"\nurl = \"https://intranet.example.com\"\nkeywords = [\"wiki\"]",
+        )
+        .unwrap();
+        assert_eq!(config.items.len(), 1);
+        assert_eq!(config.items[0].label, "Intranet");
+        assert_eq!(config.items[0].keywords, vec!["wiki"]);
+    }
+
+    #[test]
+    fn test_parse_window() {
+        use crate::window::Terminal;
+
+        let config = parse_config("[window]\nterminal = \"wezterm\"\nrows = 30").unwrap();
+        assert_eq!(config.window.terminal, Some(Terminal::Wezterm));
+        assert_eq!(config.window.rows, 30);
+        assert_eq!(config.window.columns, 100);
+    }
+
+    #[test]
+    fn test_parse_nerd_font() {
+        assert!(!parse_config("").unwrap().nerd_font);
+        assert!(parse_config("nerd_font = true").unwrap().nerd_font);
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(parse_config("").unwrap().keys.copy, "ctrl+y");
+        assert_eq!(parse_config("[keys]\ncopy = \"alt+c\"").unwrap().keys.copy, "alt+c");
+    }
+
+    #[test]
+    fn test_parse_ranking() {
+        let config = parse_config("[ranking]\nhalf_life_days = 7\n[ranking.type_bonus]\nnote = 300").unwrap();
+        assert_eq!(config.ranking.half_life_days, 7.0);
+        assert_eq!(config.ranking.type_bonus.note, 300);
+        // Kinds left out keep their defaults
+        assert_eq!(config.ranking.type_bonus.app, RankingConfig::default().type_bonus.app);
+        assert_eq!(config.ranking.priority, RankingConfig::default().priority);
+        let config = parse_config("[ranking.priority]\nweb_suggestion = 0").unwrap();
+        assert_eq!(config.ranking.priority.web_suggestion, 0);
+        assert_eq!(config.ranking.priority.web_search, 2);
+        assert!(parse_config("[ranking]\nhalf_life_days = 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_notes() {
+        assert!(!parse_config("").unwrap().notes.bodies);
+        assert!(parse_config("[notes]\nbodies = true").unwrap().notes.bodies);
+        let notes = parse_config("[notes]\naccount = \"Exchange\"\nfolder = \"Work\"").unwrap().notes;
+        assert_eq!(notes.note_folder().to_string(), "Work · Exchange");
+        assert_eq!(parse_config("").unwrap().notes.note_folder(), NoteFolder::default());
+    }
+
+    #[test]
+    fn test_parse_search() {
+        let config = parse_config("[search]\ndefault = \"g\"\n[search.engines]\nmdn = \"https://developer.mozilla.org/search?q={query}\"").unwrap();
+        assert_eq!(config.search.default, "g");
+        assert_eq!(config.search.engines.len(), 1);
+        assert_eq!(parse_config("").unwrap().search.default, "ddg");
+        assert!(!parse_config("").unwrap().search.instant_answers);
+        assert!(parse_config("[search]\ninstant_answers = true").unwrap().search.instant_answers);
+    }
+
+    #[test]
+    fn test_parse_catalogs() {
+        let config = parse_config("[[catalogs]]\nname = \"ops\"\nurl = \"https://example.com/ops.toml\"").unwrap();
+        assert_eq!(config.catalogs[0].name, "ops");
+        assert_eq!(config.catalogs[0].refresh_hours, 24);
+    }
+
+    #[test]
+    fn test_parse_prefixes() {
+        use crate::workspace::Source;
+
+        assert_eq!(parse_config("").unwrap().prefixes.parse("b docs"), Some((Source::Bookmarks, "docs")));
+        let config = parse_config("[prefixes]\nfile = \"files\"").unwrap();
+        assert_eq!(config.prefixes.parse("file report"), Some((Source::Files, "report")));
+        assert_eq!(config.prefixes.parse("f report"), None);
+    }
+
+    #[test]
+    fn test_parse_translate() {
+        use crate::data_sources::translate::TranslateProvider;
+
+        let config = parse_config("[translate]\nprovider = \"deepl\"\napi_key = \"abc:fx\"").unwrap();
+        assert_eq!(config.translate.provider, TranslateProvider::Deepl);
+        assert_eq!(config.translate.url, "http://localhost:5000");
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        // Other tests point HOME at temp dirs, so only check the shape
+        let expanded = expand_tilde("~/work");
+        assert!(expanded.is_absolute());
+        assert!(expanded.ends_with("work"));
+        assert!(expand_tilde("~").is_absolute());
+        assert_eq!(expand_tilde("/tmp/x"), PathBuf::from("/tmp/x"));
+        assert_eq!(expand_tilde("~other/x"), PathBuf::from("~other/x"));
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        let config = parse_config("theme = \"light\"").unwrap();
+        assert_eq!(config.theme, ThemeMode::Light);
+        assert_eq!(parse_config("").unwrap().theme, ThemeMode::Dark);
+        assert_eq!(parse_config("theme = \"auto\"").unwrap().theme, ThemeMode::Auto);
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let config = parse_config("[hooks]\nafter_execute = \"jq -c . >> ~/launches.jsonl\"").unwrap();
+        assert_eq!(config.hooks.after_execute.as_deref(), Some("jq -c . >> ~/launches.jsonl"));
+        assert_eq!(config.hooks.on_exit, None);
+    }
+
+    #[test]
+    fn test_parse_terminal() {
+        assert_eq!(parse_config("terminal = \"iterm\"").unwrap().terminal, TerminalApp::Iterm);
+        assert_eq!(parse_config("").unwrap().terminal, TerminalApp::Terminal);
+    }
+
+    #[test]
+    fn test_parse_repos() {
+        let config = parse_config("[repos]\nroots = [\"~/Projects\"]\neditor = \"code -n\"").unwrap();
+        assert_eq!(config.repos.roots, vec!["~/Projects"]);
+        assert_eq!(config.repos.depth, 3);
+        assert_eq!(config.repos.editor.as_deref(), Some("code -n"));
+    }
+
+    #[test]
+    fn test_parse_rest() {
+        let config = parse_config(
+            "[[rest]]\nname = \"wiki\"\nprefix = \"wiki\"\nurl = \"https://wiki/search?q={query}\"\nlabel = \"title\"\nvalue = \"url\"\nauth = { keychain = \"wiki-token\" }",
+        )
+        .unwrap();
+        let rest = &config.rest[0];
+        assert_eq!(rest.items, "$");
+        assert_eq!(rest.min_interval_ms, 1000);
+        let auth = rest.auth.as_ref().unwrap();
+        assert_eq!(auth.header, "Authorization");
+        assert_eq!(auth.value, "Bearer {secret}");
+        assert!(parse_config("[[rest]]\nname = \"wiki\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_density() {
+        let config = parse_config("density = \"comfortable\"").unwrap();
+        assert_eq!(config.density, Density::Comfortable);
+        assert_eq!(parse_config("").unwrap().density, Density::Compact);
+        assert!(parse_config("density = \"roomy\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_ignores_unknown_keys() {
+        let config = parse_config("something_else = 1\naudit_log = true").unwrap();
+        assert!(config.audit_log);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::core::CommandItem;
+use crate::internal::InternalCommand;
+use crate::workspace::Source;
+
+/// Separates nested folders in the `folder` metadata of an item, as in
+/// `Bookmarks bar/Rust`.
+pub const SEPARATOR: char = '/';
+
+/// Sources whose items are kept in folders that can be drilled into.
+pub const SOURCES: [Source; 3] = [Source::Bookmarks, Source::Notes, Source::Shortcuts];
+
+/// A folder of one source, such as a bookmark folder or a Notes folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub source: Source,
+    /// The folder and its parents, separated by [`SEPARATOR`]
+    pub path: String,
+}
+
+/// The folder an item is kept in, if its source records one.
+pub fn folder_of(item: &CommandItem) -> Option<&str> {
+    item.metadata.get("folder").map(String::as_str)
+}
+
+/// `path` without its last folder, `None` for a top-level folder.
+fn parent_path(path: &str) -> Option<&str> {
+    path.rsplit_once(SEPARATOR).map(|(parent, _)| parent)
+}
+
+fn source_title(source: Source) -> &'static str {
+    match source {
+        Source::Bookmarks => "Bookmarks",
+        Source::Notes => "Notes",
+        Source::Shortcuts => "Shortcuts",
+        _ => "Items",
+    }
+}
+
+/// What the folders of `source` are called, such as `Bookmark folder`.
+fn folder_kind(source: Source) -> &'static str {
+    match source {
+        Source::Bookmarks => "Bookmark folder",
+        Source::Notes => "Note folder",
+        Source::Shortcuts => "Shortcut folder",
+        _ => "Folder",
+    }
+}
+
+impl Container {
+    pub fn new(source: Source, path: &str) -> Self {
+        Self {
+            source,
+            path: path.to_string(),
+        }
+    }
+
+    /// The innermost folder's name.
+    pub fn name(&self) -> &str {
+        self.path.rsplit(SEPARATOR).next().unwrap_or(&self.path)
+    }
+
+    /// Whether `folder` is this folder or one of its subfolders.
+    fn holds(&self, folder: &str) -> bool {
+        folder
+            .strip_prefix(self.path.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(SEPARATOR))
+    }
+
+    /// Whether `item` is kept in this folder or one of its subfolders.
+    pub fn contains(&self, item: &CommandItem) -> bool {
+        Source::of(item) == self.source && folder_of(item).is_some_and(|folder| self.holds(folder))
+    }
+
+    /// The item drilling into this folder.
+    pub fn to_item(&self) -> CommandItem {
+        let label = format!("{} ({})", self.name(), folder_kind(self.source));
+        let mut item = InternalCommand::EnterContainer(self.source, self.path.clone()).to_item(&label);
+        item.icon = "📂".to_string();
+        item
+    }
+}
+
+/// Every folder holding items of [`SOURCES`], parents included, sorted by
+/// source and path.
+pub fn all_containers(items: &[CommandItem]) -> Vec<Container> {
+    let mut containers = Vec::new();
+    for item in items {
+        let source = Source::of(item);
+        if !SOURCES.contains(&source) {
+            continue;
+        }
+        let mut folder = folder_of(item).filter(|folder| !folder.is_empty());
+        while let Some(path) = folder {
+            containers.push(Container::new(source, path));
+            folder = parent_path(path);
+        }
+    }
+    containers.sort_by(|a, b| (a.source.name(), &a.path).cmp(&(b.source.name(), &b.path)));
+    containers.dedup();
+    containers
+}
+
+/// What is listed inside `container`: its subfolders first, then its items.
+/// With `nested`, the contents of the subfolders are included too, for
+/// searching the whole folder.
+pub fn children(container: &Container, containers: &[Container], items: &[CommandItem], nested: bool) -> Vec<CommandItem> {
+    let subfolders = containers.iter().filter(|other| {
+        other.source == container.source
+            && other.path != container.path
+            && if nested {
+                container.holds(&other.path)
+            } else {
+                parent_path(&other.path) == Some(container.path.as_str())
+            }
+    });
+    let mut children: Vec<CommandItem> = subfolders.map(Container::to_item).collect();
+    let mut contained: Vec<CommandItem> = items
+        .iter()
+        .filter(|item| container.contains(item))
+        .filter(|item| nested || folder_of(item) == Some(container.path.as_str()))
+        .cloned()
+        .collect();
+    contained.sort_by_cached_key(|item| item.label.to_lowercase());
+    children.extend(contained);
+    children
+}
+
+/// `Bookmarks › Bookmarks bar › Rust` for the innermost of the folders
+/// drilled into.
+pub fn breadcrumb(containers: &[Container]) -> Option<String> {
+    let container = containers.last()?;
+    let mut crumbs = vec![source_title(container.source)];
+    crumbs.extend(container.path.split(SEPARATOR));
+    Some(crumbs.join(" › "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+
+    fn in_folder(label: &str, handler: Handler, folder: &str) -> CommandItem {
+        let mut item = CommandItem::new(label, handler, &label.to_lowercase());
+        item.metadata.insert("folder".to_string(), folder.to_string());
+        item
+    }
+
+    fn items() -> Vec<CommandItem> {
+        vec![
+            in_folder("Docs (Bookmark)", Handler::Url, "Bookmarks bar/Rust"),
+            in_folder("Async (Bookmark)", Handler::Url, "Bookmarks bar/Rust/Async"),
+            in_folder("News (Bookmark)", Handler::Url, "Bookmarks bar"),
+            in_folder("Standup", Handler::Note, "Work"),
+            // Folders of other sources aren't containers
+            in_folder("report.pdf", Handler::File, "Bookmarks bar"),
+        ]
+    }
+
+    #[test]
+    fn test_all_containers() {
+        let containers = all_containers(&items());
+        let paths: Vec<(&str, &str)> = containers.iter().map(|c| (c.source.name(), c.path.as_str())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("bookmarks", "Bookmarks bar"),
+                ("bookmarks", "Bookmarks bar/Rust"),
+                ("bookmarks", "Bookmarks bar/Rust/Async"),
+                ("notes", "Work"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_children() {
+        let items = items();
+        let containers = all_containers(&items);
+        let bar = Container::new(Source::Bookmarks, "Bookmarks bar");
+
+        let labels: Vec<String> = children(&bar, &containers, &items, false).into_iter().map(|i| i.label).collect();
+        assert_eq!(labels, vec!["Rust (Bookmark folder)", "News (Bookmark)"]);
+
+        let labels: Vec<String> = children(&bar, &containers, &items, true).into_iter().map(|i| i.label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Rust (Bookmark folder)",
+                "Async (Bookmark folder)",
+                "Async (Bookmark)",
+                "Docs (Bookmark)",
+                "News (Bookmark)",
+            ]
+        );
+
+        // `Bookmarks bar/Rusty` is not inside `Bookmarks bar/Rust`
+        let rust = Container::new(Source::Bookmarks, "Bookmarks bar/Rust");
+        assert!(!rust.contains(&in_folder("Other", Handler::Url, "Bookmarks bar/Rusty")));
+    }
+
+    #[test]
+    fn test_to_item_round_trip() {
+        let container = Container::new(Source::Notes, "Work");
+        let item = container.to_item();
+        assert_eq!(item.label, "Work (Note folder)");
+        assert_eq!(
+            InternalCommand::parse(&item.value),
+            Some(InternalCommand::EnterContainer(Source::Notes, "Work".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_breadcrumb() {
+        assert_eq!(breadcrumb(&[]), None);
+        let containers = [
+            Container::new(Source::Bookmarks, "Bookmarks bar"),
+            Container::new(Source::Bookmarks, "Bookmarks bar/Rust"),
+        ];
+        assert_eq!(breadcrumb(&containers).as_deref(), Some("Bookmarks › Bookmarks bar › Rust"));
+    }
+}
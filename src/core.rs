@@ -1,7 +1,7 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Handler {
     App,
     Note,
@@ -9,6 +9,18 @@ pub enum Handler {
     Automation,
     Folder,
     File,
+    Internal,
+    Copy,
+    Transform,
+    Plugin,
+    Shell,
+    Process,
+    Reminder,
+    System,
+    Wifi,
+    Ssh,
+    Kube,
+    Database,
 }
 
 impl Handler {
@@ -20,6 +32,18 @@ impl Handler {
             Handler::File => "File",
             Handler::Folder => "Folder",
             Handler::Automation => "Shortcut",
+            Handler::Internal => "Grinta",
+            Handler::Copy => "Copy",
+            Handler::Transform => "Transform",
+            Handler::Plugin => "Plugin",
+            Handler::Shell => "Shell",
+            Handler::Process => "Process",
+            Handler::Reminder => "Reminder",
+            Handler::System => "System",
+            Handler::Wifi => "Wi-Fi",
+            Handler::Ssh => "SSH",
+            Handler::Kube => "Kubernetes",
+            Handler::Database => "Database",
         }
     }
 
@@ -31,10 +55,104 @@ impl Handler {
             Handler::File => "📄",
             Handler::Folder => "📁",
             Handler::Automation => "⚡",
+            Handler::Internal => "🔧",
+            Handler::Copy => "📋",
+            Handler::Transform => "🔀",
+            Handler::Plugin => "🧩",
+            Handler::Shell => "🐚",
+            Handler::Process => "⚙️",
+            Handler::Reminder => "☑️",
+            Handler::System => "🖥️",
+            Handler::Wifi => "📶",
+            Handler::Ssh => "🔐",
+            Handler::Kube => "☸️",
+            Handler::Database => "🛢️",
+        }
+    }
+
+    /// Short tag shown in the context column, such as `APP`.
+    pub fn badge(&self) -> &'static str {
+        match self {
+            Handler::Url => "URL",
+            Handler::App => "APP",
+            Handler::Note => "NOTE",
+            Handler::File => "FILE",
+            Handler::Folder => "DIR",
+            Handler::Automation => "AUTO",
+            Handler::Internal => "CMD",
+            Handler::Copy => "COPY",
+            Handler::Transform => "TEXT",
+            Handler::Plugin => "PLUG",
+            Handler::Shell => "SH",
+            Handler::Process => "PROC",
+            Handler::Reminder => "TODO",
+            Handler::System => "SYS",
+            Handler::Wifi => "WIFI",
+            Handler::Ssh => "SSH",
+            Handler::Kube => "KUBE",
+            Handler::Database => "DB",
+        }
+    }
+}
+
+/// Broad kinds of files, picked from the extension for their icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Image,
+    Code,
+    Pdf,
+    Archive,
+    Audio,
+    Video,
+    Other,
+}
+
+impl FileKind {
+    pub fn of(path: &str) -> FileKind {
+        let Some(extension) = std::path::Path::new(path).extension().and_then(|e| e.to_str()) else {
+            return FileKind::Other;
+        };
+        match extension.to_ascii_lowercase().as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "heic" | "svg" | "bmp" | "tiff" | "ico" => FileKind::Image,
+            "rs" | "py" | "js" | "ts" | "tsx" | "jsx" | "go" | "c" | "h" | "cpp" | "java" | "kt" | "swift" | "rb"
+            | "sh" | "lua" | "json" | "toml" | "yaml" | "yml" | "html" | "css" => FileKind::Code,
+            "pdf" => FileKind::Pdf,
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "7z" | "rar" | "zst" | "dmg" => FileKind::Archive,
+            "mp3" | "wav" | "flac" | "aac" | "m4a" | "ogg" | "opus" => FileKind::Audio,
+            "mp4" | "mov" | "mkv" | "avi" | "webm" | "m4v" => FileKind::Video,
+            _ => FileKind::Other,
+        }
+    }
+
+    pub fn to_icon(self) -> &'static str {
+        match self {
+            FileKind::Image => "🖼️",
+            FileKind::Code => "💻",
+            FileKind::Pdf => "📕",
+            FileKind::Archive => "📦",
+            FileKind::Audio => "🎵",
+            FileKind::Video => "🎬",
+            FileKind::Other => Handler::File.to_icon(),
+        }
+    }
+
+    /// Font Awesome glyph from a Nerd Font patched font.
+    pub fn to_glyph(self) -> &'static str {
+        match self {
+            FileKind::Image => "\u{f1c5}",
+            FileKind::Code => "\u{f1c9}",
+            FileKind::Pdf => "\u{f1c1}",
+            FileKind::Archive => "\u{f1c6}",
+            FileKind::Audio => "\u{f1c7}",
+            FileKind::Video => "\u{f1c8}",
+            FileKind::Other => "\u{f15b}",
         }
     }
 }
 
+/// Nerd Font glyph of the folder icon.
+pub const FOLDER_GLYPH: &str = "\u{f07b}";
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CommandType {
     App,
@@ -65,6 +183,9 @@ pub struct CommandItem {
     pub metadata: std::collections::HashMap<String, String>,
     #[serde(default)]
     pub kind: CommandType,
+    /// How many times the item was launched (tracked in history)
+    #[serde(default)]
+    pub launch_count: u32,
 }
 
 impl CommandItem {
@@ -73,11 +194,16 @@ impl CommandItem {
             label: label.to_string(),
             handler,
             value: value.to_string(),
-            icon: handler.to_icon().to_string(),
+            icon: match handler {
+                Handler::File => FileKind::of(value).to_icon(),
+                _ => handler.to_icon(),
+            }
+            .to_string(),
             ran_at: None,
             base64_icon: None,
             metadata: std::collections::HashMap::new(),
             kind: CommandType::Unknown,
+            launch_count: 0,
         }
     }
 
@@ -100,6 +226,12 @@ mod tests {
         assert_eq!(Handler::File.to_string(), "File");
         assert_eq!(Handler::Folder.to_string(), "Folder");
         assert_eq!(Handler::Automation.to_string(), "Shortcut");
+        assert_eq!(Handler::Internal.to_string(), "Grinta");
+        assert_eq!(Handler::Copy.to_string(), "Copy");
+        assert_eq!(Handler::Transform.to_string(), "Transform");
+        assert_eq!(Handler::Plugin.to_string(), "Plugin");
+        assert_eq!(Handler::Shell.to_string(), "Shell");
+        assert_eq!(Handler::Process.to_string(), "Process");
     }
 
     #[test]
@@ -110,6 +242,33 @@ mod tests {
         assert_eq!(Handler::File.to_icon(), "📄");
         assert_eq!(Handler::Folder.to_icon(), "📁");
         assert_eq!(Handler::Automation.to_icon(), "⚡");
+        assert_eq!(Handler::Internal.to_icon(), "🔧");
+        assert_eq!(Handler::Copy.to_icon(), "📋");
+        assert_eq!(Handler::Transform.to_icon(), "🔀");
+        assert_eq!(Handler::Plugin.to_icon(), "🧩");
+        assert_eq!(Handler::Shell.to_icon(), "🐚");
+        assert_eq!(Handler::Process.to_icon(), "⚙️");
+    }
+
+    #[test]
+    fn test_handler_badge() {
+        assert_eq!(Handler::App.badge(), "APP");
+        assert_eq!(Handler::File.badge(), "FILE");
+        assert_eq!(Handler::Note.badge(), "NOTE");
+        assert_eq!(Handler::Url.badge(), "URL");
+        assert_eq!(Handler::Automation.badge(), "AUTO");
+    }
+
+    #[test]
+    fn test_file_kind_icons() {
+        assert_eq!(FileKind::of("/tmp/Photo.JPG"), FileKind::Image);
+        assert_eq!(FileKind::of("src/main.rs"), FileKind::Code);
+        assert_eq!(FileKind::of("backup.tar.gz"), FileKind::Archive);
+        assert_eq!(FileKind::of("Makefile"), FileKind::Other);
+        assert_eq!(CommandItem::new("doc.pdf", Handler::File, "/path/doc.pdf").icon, "📕");
+        assert_eq!(CommandItem::new("notes", Handler::File, "/path/notes").icon, "📄");
+        // Only files get a kind icon
+        assert_eq!(CommandItem::new("clip.mp4", Handler::Url, "https://example.com/clip.mp4").icon, "🔗");
     }
 
     #[test]
@@ -136,6 +295,7 @@ mod tests {
         assert!(item.base64_icon.is_none());
         assert!(item.metadata.is_empty());
         assert_eq!(item.kind, CommandType::Unknown);
+        assert_eq!(item.launch_count, 0);
     }
 
     #[test]
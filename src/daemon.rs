@@ -0,0 +1,309 @@
+use crate::catalogs;
+use crate::core::CommandItem;
+use crate::data_sources;
+use crate::profile;
+use crate::rpc;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// How long clients wait for the daemon before indexing themselves.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+/// How long a wedged daemon can keep a client waiting for its answer.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(2);
+/// A refresh re-indexes every source first, which takes longer.
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A request to the daemon, sent as one JSON line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    /// Every indexed item
+    Items,
+    /// Indexed items whose label or value contains the query
+    Search { query: String },
+    /// Re-index now and return every item
+    Refresh,
+}
+
+/// The daemon's answer, sent as one JSON line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    #[serde(default)]
+    pub items: Vec<CommandItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Request {
+    /// How long the daemon gets to answer.
+    fn timeout(&self) -> Duration {
+        match self {
+            Request::Refresh => REFRESH_TIMEOUT,
+            Request::Items | Request::Search { .. } => ANSWER_TIMEOUT,
+        }
+    }
+}
+
+type Index = Arc<RwLock<Vec<CommandItem>>>;
+
+/// The socket lives in the runtime directory when there is one.
+pub fn socket_path() -> Result<PathBuf> {
+    let mut path = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .context("Failed to get runtime directory")?;
+    path.push("grinta-rs");
+    std::fs::create_dir_all(&path)?;
+    path.push("daemon.sock");
+    Ok(path)
+}
+
+/// Items whose label or value contains `query`, case-insensitively.
+pub fn search_items(items: &[CommandItem], query: &str) -> Vec<CommandItem> {
+    let query = query.to_lowercase();
+    items
+        .iter()
+        .filter(|item| {
+            item.label.to_lowercase().contains(&query) || item.value.to_lowercase().contains(&query)
+        })
+        .cloned()
+        .collect()
+}
+
+async fn handle_request(index: &Index, request: Request) -> Response {
+    let items = match request {
+        Request::Items => index.read().await.clone(),
+        Request::Search { query } => search_items(&index.read().await, &query),
+        Request::Refresh => {
+            let items = data_sources::get_all_items(extract_icons()).await;
+            *index.write().await = items.clone();
+            items
+        }
+    };
+    Response { items, error: None }
+}
+
+/// Answer one JSON request line.
+async fn handle_line(index: &Index, line: &str) -> Response {
+    match serde_json::from_str::<Request>(line) {
+        Ok(request) => handle_request(index, request).await,
+        Err(e) => Response {
+            items: Vec::new(),
+            error: Some(format!("Invalid request: {}", e)),
+        },
+    }
+}
+
+/// App icons are extracted unless the system is in low-power mode, which is
+/// checked again at every re-index.
+fn extract_icons() -> bool {
+    !profile::is_low_power_mode()
+}
+
+/// Make way for the daemon's socket at `path`: fail when a daemon already
+/// listens there, and remove a socket left behind by one that did not exit
+/// cleanly. Anything else at `path` is left alone.
+#[cfg(unix)]
+async fn claim_socket(path: &Path) -> Result<()> {
+    if tokio::net::UnixStream::connect(path).await.is_ok() {
+        anyhow::bail!("A grinta daemon is already listening on {}", path.display());
+    }
+    rpc::remove_stale_socket(path)
+}
+
+/// Run the daemon: index every `refresh_secs` and serve requests on the
+/// socket until killed.
+#[cfg(unix)]
+pub async fn run(refresh_secs: u64) -> Result<()> {
+    let path = socket_path()?;
+    claim_socket(&path).await?;
+
+    tokio::join!(catalogs::refresh_catalogs(), data_sources::feeds::refresh_feeds());
+    let index: Index = Arc::new(RwLock::new(data_sources::get_all_items(extract_icons()).await));
+    let refresher = index.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(refresh_secs.max(1)));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            tokio::join!(catalogs::refresh_catalogs(), data_sources::feeds::refresh_feeds());
+            let items = data_sources::get_all_items(extract_icons()).await;
+            *refresher.write().await = items;
+        }
+    });
+
+    eprintln!("grinta daemon listening on {}", path.display());
+    serve(&path, index).await
+}
+
+#[cfg(not(unix))]
+pub async fn run(_refresh_secs: u64) -> Result<()> {
+    anyhow::bail!("The daemon needs Unix domain sockets")
+}
+
+#[cfg(unix)]
+async fn serve(path: &Path, index: Index) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind {}", path.display()))?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let index = index.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let response = handle_line(&index, &line).await;
+                let Ok(mut json) = serde_json::to_string(&response) else {
+                    break;
+                };
+                json.push('\n');
+                if writer.write_all(json.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+async fn request_at(path: &Path, request: &Request) -> Result<Response> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::UnixStream::connect(path))
+        .await
+        .context("Timed out connecting to the daemon")??;
+    let exchange = async {
+        let (reader, mut writer) = stream.into_split();
+        let mut json = serde_json::to_string(request)?;
+        json.push('\n');
+        writer.write_all(json.as_bytes()).await?;
+
+        BufReader::new(reader)
+            .lines()
+            .next_line()
+            .await?
+            .context("The daemon closed the connection")
+    };
+    let line = tokio::time::timeout(request.timeout(), exchange)
+        .await
+        .context("Timed out waiting for the daemon")??;
+    let response: Response = serde_json::from_str(&line)?;
+    if let Some(error) = response.error {
+        anyhow::bail!("{}", error);
+    }
+    Ok(response)
+}
+
+#[cfg(not(unix))]
+async fn request_at(_path: &Path, _request: &Request) -> Result<Response> {
+    anyhow::bail!("The daemon needs Unix domain sockets")
+}
+
+/// Ask a running daemon; `None` when there is none, so callers fall back
+/// to indexing themselves.
+pub async fn request(request: Request) -> Option<Vec<CommandItem>> {
+    let path = socket_path().ok()?;
+    if !path.exists() {
+        return None;
+    }
+    request_at(&path, &request).await.ok().map(|response| response.items)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+    use tempfile::TempDir;
+
+    fn index() -> Index {
+        Arc::new(RwLock::new(vec![
+            CommandItem::new("Firefox", Handler::App, "/usr/share/applications/firefox.desktop"),
+            CommandItem::new("Rust (Bookmark)", Handler::Url, "https://www.rust-lang.org/"),
+        ]))
+    }
+
+    #[test]
+    fn test_request_format() {
+        assert_eq!(serde_json::to_string(&Request::Items).unwrap(), r#"{"method":"items"}"#);
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"method":"search","query":"fire"}"#).unwrap(),
+            Request::Search { query: "fire".to_string() }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_line() {
+        let index = index();
+        let response = handle_line(&index, r#"{"method":"search","query":"RUST"}"#).await;
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.items[0].label, "Rust (Bookmark)");
+
+        let response = handle_line(&index, "not json").await;
+        assert!(response.items.is_empty());
+        assert!(response.error.unwrap().starts_with("Invalid request"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_over_socket() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("daemon.sock");
+        let server = tokio::spawn({
+            let path = path.clone();
+            async move { serve(&path, index()).await }
+        });
+        while !path.exists() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let response = request_at(&path, &Request::Items).await.unwrap();
+        assert_eq!(response.items.len(), 2);
+        let response = request_at(&path, &Request::Search { query: "fire".to_string() })
+            .await
+            .unwrap();
+        assert_eq!(response.items[0].label, "Firefox");
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_wedged_daemon_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("daemon.sock");
+        // Accepts connections and never answers
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let started = std::time::Instant::now();
+        assert!(request_at(&path, &Request::Items).await.is_err());
+        assert!(started.elapsed() < ANSWER_TIMEOUT + Duration::from_secs(1));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_only_sockets_are_replaced() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(claim_socket(&file).await.is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+
+        // Left behind by a daemon that is gone
+        let socket = temp_dir.path().join("daemon.sock");
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        claim_socket(&socket).await.unwrap();
+        assert!(!socket.exists());
+
+        let _listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        assert!(claim_socket(&socket).await.is_err());
+        assert!(socket.exists());
+    }
+}
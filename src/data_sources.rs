@@ -1,32 +1,271 @@
 pub mod automation;
 pub mod bookmarks;
+pub mod calculator;
+pub mod calendar;
+pub mod currency;
+pub mod custom;
+pub mod databases;
+pub mod dates;
+pub mod define;
+pub mod desktop_entries;
+pub mod emoji;
+pub mod feeds;
 pub mod fs;
+pub mod generators;
+pub mod github;
+pub mod issues;
+pub mod kube;
+pub mod linux_desktop;
 pub mod notes;
+pub mod packages;
+pub mod processes;
+pub mod quotes;
+pub mod reminders;
+pub mod repos;
+pub mod rest;
+pub mod snippets;
+pub mod spell;
+pub mod ssh;
+pub mod system;
+pub mod tables;
+pub mod tasks;
+pub mod timezones;
+pub mod translate;
+pub mod weather;
 pub mod web_search;
+pub mod wifi;
+pub mod windows;
+pub mod zoxide;
 
+use crate::catalogs;
 use crate::core::{CommandItem, Handler};
 use crate::icons;
+use crate::index_cache;
+use crate::plugins;
+use crate::shell;
+use crate::transforms;
+use crate::workspace::Source;
+use std::path::PathBuf;
 
 pub async fn get_all_items(extract_icons: bool) -> Vec<CommandItem> {
     let mut items = Vec::new();
+    for source in INDEXED_SOURCES {
+        items.extend(get_source_items(source, extract_icons).await);
+    }
+    items
+}
+
+/// Sources whose items are read up front and kept in memory. Files and
+/// web suggestions are searched per query instead.
+pub const INDEXED_SOURCES: [Source; 5] =
+    [Source::Apps, Source::Notes, Source::Bookmarks, Source::Shortcuts, Source::Reminders];
+
+/// Read the items of a single source, for targeted refreshes.
+/// Indexed sources are also saved for the next startup.
+pub async fn get_source_items(source: Source, extract_icons: bool) -> Vec<CommandItem> {
+    let mut items = match source {
+        Source::Apps => {
+            let mut items = get_applications(extract_icons).await;
+            items.extend(custom::get_custom_items());
+            items.extend(tables::get_table_items());
+            items.extend(snippets::get_snippet_items());
+            items.extend(system::get_system_items());
+            items.extend(ssh::get_ssh_items());
+            items.extend(kube::get_kube_items());
+            items.extend(databases::get_database_items());
+            items.extend(repos::get_repo_items());
+            items.extend(zoxide::get_zoxide_items().await);
+            items.extend(tasks::get_task_items());
+            items.extend(catalogs::get_catalog_items());
+            items.extend(feeds::get_feed_items());
+            items
+        }
+        #[cfg(target_os = "macos")]
+        Source::Notes => notes::get_notes().await,
+        Source::Bookmarks => bookmarks::get_browser_bookmarks().await,
+        #[cfg(target_os = "macos")]
+        Source::Shortcuts => automation::get_shortcuts().await,
+        #[cfg(target_os = "macos")]
+        Source::Reminders => reminders::get_reminders().await,
+        _ => return Vec::new(),
+    };
+    for item in &mut items {
+        source.tag(item);
+    }
+    let _ = index_cache::store_source(source, &items);
+    items
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        items.extend(get_macos_applications(extract_icons).await);
-        items.extend(notes::get_notes().await);
-        items.extend(bookmarks::get_browser_bookmarks().await);
-        items.extend(automation::get_shortcuts().await);
+/// Scan the application directories, unless none changed since the last
+/// scan.
+async fn scan_applications(extract_icons: bool) -> Vec<CommandItem> {
+    let stamps = index_cache::stamps(&application_dirs());
+    if let Some(items) = index_cache::cached_apps(&stamps, extract_icons) {
+        return items;
     }
-    
+    let items = read_applications(extract_icons).await;
+    let _ = index_cache::store_apps(stamps, extract_icons, &items);
     items
 }
 
+#[cfg(target_os = "macos")]
+fn application_dirs() -> Vec<PathBuf> {
+    ["/Applications", "/System/Applications", "/System/Applications/Utilities"]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn application_dirs() -> Vec<PathBuf> {
+    desktop_entries::application_dirs()
+}
+
+#[cfg(target_os = "windows")]
+fn application_dirs() -> Vec<PathBuf> {
+    windows::application_dirs()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn application_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+async fn read_applications(extract_icons: bool) -> Vec<CommandItem> {
+    get_macos_applications(extract_icons).await
+}
+
+#[cfg(target_os = "linux")]
+async fn read_applications(_extract_icons: bool) -> Vec<CommandItem> {
+    desktop_entries::get_linux_applications().await
+}
+
+#[cfg(target_os = "windows")]
+async fn read_applications(_extract_icons: bool) -> Vec<CommandItem> {
+    windows::get_start_apps().await
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+async fn read_applications(_extract_icons: bool) -> Vec<CommandItem> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+async fn get_applications(extract_icons: bool) -> Vec<CommandItem> {
+    let mut items = scan_applications(extract_icons).await;
+    let mut ocr = crate::internal::InternalCommand::ScreenOcr.to_item("Copy text from screen area");
+    ocr.icon = "🔍".to_string();
+    items.push(ocr);
+    items
+}
+
+#[cfg(target_os = "linux")]
+async fn get_applications(extract_icons: bool) -> Vec<CommandItem> {
+    let mut items = scan_applications(extract_icons).await;
+    items.extend(linux_desktop::get_desktop_items().await);
+    items
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn get_applications(extract_icons: bool) -> Vec<CommandItem> {
+    scan_applications(extract_icons).await
+}
+
+/// Items computed from the query itself, such as calculator results or
+/// carrier tracking links.
+/// They are cheap to produce and ranked above every other result.
+pub fn get_query_items(query: &str) -> Vec<CommandItem> {
+    let mut items = Vec::new();
+    items.extend(calculator::calculate(query));
+    items.extend(dates::get_date_items(query));
+    items.extend(timezones::get_time_items(query));
+    items.extend(packages::tracking_items(query));
+    items.extend(generators::generator_items(query));
+    items.extend(emoji::emoji_items(query));
+    items.extend(transforms::registry().items_for(query));
+    items.extend(shell::shell_items(query));
+    items.extend(web_search::bang_items(query));
+    items
+}
+
+/// Whether the query is a command answered over the network or by an
+/// external tool, such as `weather berlin`, `$AAPL`, `spell recieve`,
+/// `define word` or `kill slack`.
+pub fn wants_async_answer(query: &str) -> bool {
+    weather::parse_query(query).is_some()
+        || quotes::parse_query(query).is_some()
+        || packages::parse_query(query).is_some()
+        || spell::parse_query(query).is_some()
+        || define::parse_query(query).is_some()
+        || translate::parse_query(query).is_some()
+        || processes::parse_query(query).is_some()
+        || wifi::parse_query(query).is_some()
+        || currency::parse_query(query).is_some()
+        || rest::wants_query(query)
+        || github::wants_search(query)
+        || issues::wants_query(query)
+        || plugins::wants_query(query)
+}
+
+/// Answers that need the network or an external tool. Like `get_query_items` they are ranked
+/// above everything else, but arrive asynchronously.
+pub async fn get_async_query_items(query: String) -> Vec<CommandItem> {
+    if let Some(city) = weather::parse_query(&query) {
+        return weather::get_weather_items(city).await;
+    }
+    if let Some(symbol) = quotes::parse_query(&query) {
+        return quotes::get_quote_items(&symbol).await;
+    }
+    if let Some(number) = packages::parse_query(&query) {
+        return packages::get_status_items(&number).await;
+    }
+    if let Some(word) = spell::parse_query(&query) {
+        return spell::get_spell_items(word).await;
+    }
+    if let Some(word) = define::parse_query(&query) {
+        return define::get_define_items(word).await;
+    }
+    if let Some(translation) = translate::parse_query(&query) {
+        return translate::get_translation_items(&translation).await;
+    }
+    if let Some((action, filter)) = processes::parse_query(&query) {
+        return processes::get_process_items(action, filter.to_string()).await;
+    }
+    if let Some(filter) = wifi::parse_query(&query) {
+        return wifi::get_wifi_items(filter.to_string()).await;
+    }
+    if let Some(conversion) = currency::parse_query(&query) {
+        return currency::get_conversion_items(&conversion).await;
+    }
+    if rest::wants_query(&query) {
+        return rest::get_rest_items(&query).await;
+    }
+    if github::wants_search(&query) {
+        return github::get_search_items(&query).await;
+    }
+    if issues::wants_query(&query) {
+        return issues::get_issue_items(&query).await;
+    }
+    plugins::get_plugin_items(&query).await
+}
+
+/// `CFBundleIdentifier` of an XML `Info.plist`. Binary plists give None,
+/// and their apps are told apart by bundle name instead.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_bundle_identifier(plist: &str) -> Option<String> {
+    let (_, after_key) = plist.split_once("<key>CFBundleIdentifier</key>")?;
+    let value = after_key.trim_start().strip_prefix("<string>")?;
+    let (identifier, _) = value.split_once("</string>")?;
+    let identifier = identifier.trim();
+    (!identifier.is_empty()).then(|| identifier.to_string())
+}
+
 #[cfg(target_os = "macos")]
 async fn get_macos_applications(extract_icons: bool) -> Vec<CommandItem> {
-    let applications_dirs = vec!["/Applications", "/System/Applications", "/System/Applications/Utilities"];
     let mut apps = Vec::new();
 
-    for dir in applications_dirs {
+    for dir in application_dirs() {
         if let Ok(mut entries) = tokio::fs::read_dir(dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
                 let path = entry.path();
@@ -34,6 +273,14 @@ async fn get_macos_applications(extract_icons: bool) -> Vec<CommandItem> {
                     if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
                         let path_str = path.to_str().unwrap_or("");
                         let mut item = CommandItem::new(name, Handler::App, path_str);
+                        let info_plist = path.join("Contents/Info.plist");
+                        if let Some(bundle_id) = tokio::fs::read_to_string(info_plist)
+                            .await
+                            .ok()
+                            .and_then(|plist| parse_bundle_identifier(&plist))
+                        {
+                            item.metadata.insert("bundle_id".to_string(), bundle_id);
+                        }
                         if extract_icons {
                             item.base64_icon = icons::extract_app_icon(path_str).await;
                         }
@@ -45,3 +292,15 @@ async fn get_macos_applications(extract_icons: bool) -> Vec<CommandItem> {
     }
     apps
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bundle_identifier() {
+        let plist = "<dict>\n\t<key>CFBundleName</key>\n\t<string>Slack</string>\n\t<key>CFBundleIdentifier</key>\n\t<string>com.tinyspeck.slackmacgap</string>\n</dict>";
+        assert_eq!(parse_bundle_identifier(plist).as_deref(), Some("com.tinyspeck.slackmacgap"));
+        assert_eq!(parse_bundle_identifier("bplist00"), None);
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(target_os = "macos")]
+use std::collections::HashMap;
 use tokio::process::Command;
 
 use crate::core::{CommandItem, CommandType, Handler};
@@ -23,17 +25,39 @@ pub async fn get_shortcuts() -> Vec<CommandItem> {
         return vec![];
     }
 
+    let folders = shortcut_folders().await;
     String::from_utf8_lossy(&output.stdout)
         .lines()
         .map(|line| {
             let mut cmd = CommandItem::new(line, Handler::Automation, line);
             cmd.metadata.insert("type".to_string(), "shortcut".to_string());
+            if let Some(folder) = folders.get(line) {
+                cmd.metadata.insert("folder".to_string(), folder.clone());
+            }
             cmd.kind = CommandType::App; // treat as app-like
             cmd
         })
         .collect()
 }
 
+/// The folder of each shortcut kept in one, by shortcut name.
+#[cfg(target_os = "macos")]
+async fn shortcut_folders() -> HashMap<String, String> {
+    let list = |args: Vec<&str>| async move {
+        match Command::new("shortcuts").arg("list").args(args).output().await {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).to_string(),
+            _ => String::new(),
+        }
+    };
+    let mut folders = HashMap::new();
+    for folder in list(vec!["--folders"]).await.lines() {
+        for shortcut in list(vec!["--folder-name", folder]).await.lines() {
+            folders.insert(shortcut.to_string(), folder.to_string());
+        }
+    }
+    folders
+}
+
 /// Stub implementation for non-macOS targets.
 #[cfg(not(target_os = "macos"))]
 pub async fn get_shortcuts() -> Vec<CommandItem> {
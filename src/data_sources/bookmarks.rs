@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tokio::io::AsyncReadExt;
 
+use crate::containers;
 use crate::core::{CommandItem, Handler};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -31,7 +34,7 @@ struct BookmarkFile {
     version: u32,
 }
 
-/// Get all bookmarks from Chrome and Chromium browsers
+/// Get all bookmarks from Chrome, Chromium and Firefox
 pub async fn get_browser_bookmarks() -> Vec<CommandItem> {
     let mut bookmarks = Vec::new();
     
@@ -40,14 +43,26 @@ pub async fn get_browser_bookmarks() -> Vec<CommandItem> {
     
     // Get Chromium bookmarks
     bookmarks.extend(get_chromium_bookmarks().await);
+
+    // Get Firefox bookmarks
+    bookmarks.extend(get_firefox_bookmarks().await);
     
     bookmarks
 }
 
+/// Directory holding Chrome's profiles
+fn chrome_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    if cfg!(target_os = "macos") {
+        home_dir.join("Library/Application Support/Google/Chrome")
+    } else {
+        home_dir.join(".config/google-chrome")
+    }
+}
+
 /// Get bookmarks from Chrome browser
 async fn get_chrome_bookmarks() -> Vec<CommandItem> {
-    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    let base_path = home_dir.join("Library/Application Support/Google/Chrome");
+    let base_path = chrome_dir();
     
     // Check Default profile
     let mut bookmarks = get_bookmarks_from_profile(&base_path.join("Default")).await;
@@ -110,30 +125,331 @@ fn extract_bookmarks_from_file(bookmark_file: BookmarkFile) -> Vec<CommandItem>
     let mut bookmarks = Vec::new();
     
     // Process bookmark bar
-    process_bookmark_node(&bookmark_file.roots.bookmark_bar, &mut bookmarks);
+    process_bookmark_node(&bookmark_file.roots.bookmark_bar, None, &mut bookmarks);
     
     // Process other bookmarks
-    process_bookmark_node(&bookmark_file.roots.other, &mut bookmarks);
+    process_bookmark_node(&bookmark_file.roots.other, None, &mut bookmarks);
     
     // Process synced bookmarks
-    process_bookmark_node(&bookmark_file.roots.synced, &mut bookmarks);
+    process_bookmark_node(&bookmark_file.roots.synced, None, &mut bookmarks);
     
     bookmarks
 }
 
-/// Recursively process a bookmark node and extract all bookmarks
-fn process_bookmark_node(node: &BookmarkNode, bookmarks: &mut Vec<CommandItem>) {
+/// A bookmark item, kept in `folder` if it is in one.
+fn bookmark_item(name: &str, url: &str, folder: Option<&str>) -> CommandItem {
+    let mut item = CommandItem::new(format!("{} (Bookmark)", name).as_str(), Handler::Url, url);
+    if let Some(folder) = folder.filter(|folder| !folder.is_empty()) {
+        item.metadata.insert("folder".to_string(), folder.to_string());
+    }
+    item
+}
+
+/// `folder/name`, or `name` at the top level.
+fn child_folder(folder: Option<&str>, name: &str) -> String {
+    match folder {
+        Some(folder) => format!("{}{}{}", folder, containers::SEPARATOR, name),
+        None => name.to_string(),
+    }
+}
+
+/// Recursively process a bookmark node and extract all bookmarks, in
+/// `folder` (the path of the node's parent folders)
+fn process_bookmark_node(node: &BookmarkNode, folder: Option<&str>, bookmarks: &mut Vec<CommandItem>) {
     // If this is a URL bookmark, add it to the list
     if let (Some(name), Some(url), Some(node_type)) = (&node.name, &node.url, &node.node_type) {
         if node_type == "url" {
-            bookmarks.push(CommandItem::new(format!("{} (Bookmark)", name).as_str(), Handler::Url, url));
+            bookmarks.push(bookmark_item(name, url, folder));
         }
     }
     
     // Recursively process children
     if let Some(children) = &node.children {
+        let folder = node.name.as_deref().map(|name| child_folder(folder, name));
         for child in children {
-            process_bookmark_node(child, bookmarks);
+            process_bookmark_node(child, folder.as_deref(), bookmarks);
+        }
+    }
+}
+
+/// Distinguishes temporary copies of `places.sqlite` read concurrently
+static PLACES_COPY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Directories holding Firefox profiles
+fn firefox_profiles_dirs() -> Vec<PathBuf> {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    vec![
+        home_dir.join("Library/Application Support/Firefox/Profiles"),
+        home_dir.join(".mozilla/firefox"),
+    ]
+}
+
+/// Get bookmarks from every Firefox profile
+async fn get_firefox_bookmarks() -> Vec<CommandItem> {
+    let mut places_files = Vec::new();
+    for dir in firefox_profiles_dirs() {
+        if let Ok(mut entries) = fs::read_dir(&dir).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let places = entry.path().join("places.sqlite");
+                if places.exists() {
+                    places_files.push(places);
+                }
+            }
+        }
+    }
+
+    let mut bookmarks = Vec::new();
+    for places in places_files {
+        let result = tokio::task::spawn_blocking({
+            let places = places.clone();
+            move || read_firefox_places(&places)
+        })
+        .await;
+        match result {
+            Ok(Ok(items)) => bookmarks.extend(items),
+            Ok(Err(e)) => eprintln!("Error reading bookmarks from {:?}: {}", places, e),
+            Err(_) => {}
+        }
+    }
+    bookmarks
+}
+
+/// Read bookmarks from a Firefox `places.sqlite`. Firefox keeps the
+/// database locked while running, so a copy (with its WAL) is queried.
+fn read_firefox_places(places: &Path) -> Result<Vec<CommandItem>, Box<dyn std::error::Error + Send + Sync>> {
+    let copy_dir = std::env::temp_dir().join(format!(
+        "grinta-places-{}-{}",
+        std::process::id(),
+        PLACES_COPY_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&copy_dir)?;
+    let copy = copy_dir.join("places.sqlite");
+    std::fs::copy(places, &copy)?;
+    let wal = places.with_file_name("places.sqlite-wal");
+    if wal.exists() {
+        std::fs::copy(&wal, copy_dir.join("places.sqlite-wal"))?;
+    }
+
+    let result = query_firefox_bookmarks(&copy);
+    let _ = std::fs::remove_dir_all(&copy_dir);
+    result
+}
+
+fn query_firefox_bookmarks(path: &Path) -> Result<Vec<CommandItem>, Box<dyn std::error::Error + Send + Sync>> {
+    let connection = rusqlite::Connection::open(path)?;
+    // Type 1 rows are bookmarks (2 are folders, 3 separators); `place:`
+    // URLs are smart folders rather than pages
+    let mut statement = connection.prepare(
+        "SELECT b.title, p.url, b.parent FROM moz_bookmarks b \
+         JOIN moz_places p ON b.fk = p.id \
+         WHERE b.type = 1 AND p.url NOT LIKE 'place:%'",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<i64>>(2)?))
+    })?;
+
+    let folders = firefox_folders(&connection)?;
+    let mut bookmarks = Vec::new();
+    for row in rows {
+        let (title, url, parent) = row?;
+        let name = title.filter(|t| !t.is_empty()).unwrap_or_else(|| url.clone());
+        let folder = parent.map(|parent| firefox_folder_path(&folders, parent));
+        bookmarks.push(bookmark_item(&name, &url, folder.as_deref()));
+    }
+    Ok(bookmarks)
+}
+
+/// Firefox's folders by id, with their parent and title.
+type FirefoxFolders = HashMap<i64, (Option<i64>, String)>;
+
+fn firefox_folders(connection: &rusqlite::Connection) -> rusqlite::Result<FirefoxFolders> {
+    let mut statement = connection.prepare("SELECT id, parent, title FROM moz_bookmarks WHERE type = 2")?;
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, Option<String>>(2)?))
+    })?;
+    let mut folders = HashMap::new();
+    for row in rows {
+        let (id, parent, title) = row?;
+        // The built-in folders are stored under their internal names
+        let title = match title.as_deref().unwrap_or_default() {
+            "menu" => "Bookmarks Menu".to_string(),
+            "toolbar" => "Bookmarks Toolbar".to_string(),
+            "unfiled" => "Other Bookmarks".to_string(),
+            "mobile" => "Mobile Bookmarks".to_string(),
+            title => title.to_string(),
+        };
+        folders.insert(id, (parent, title));
+    }
+    Ok(folders)
+}
+
+/// The path of folder `id`, leaving out the untitled root.
+fn firefox_folder_path(folders: &FirefoxFolders, id: i64) -> String {
+    let mut names = Vec::new();
+    let mut current = Some(id);
+    // Bounded in case of a corrupt, cyclic tree
+    while let Some((parent, title)) = current.and_then(|id| folders.get(&id)).filter(|_| names.len() < 64) {
+        if !title.is_empty() {
+            names.push(title.as_str());
         }
+        current = *parent;
+    }
+    names.reverse();
+    names.join(&containers::SEPARATOR.to_string())
+}
+
+/// Chrome keeps its bookmarks in memory and overwrites the file on exit,
+/// so it is only edited while no Chrome process runs.
+fn chrome_running() -> bool {
+    let mut system = sysinfo::System::new();
+    system.refresh_processes();
+    system.processes().values().any(|process| {
+        let name = process.name().to_lowercase();
+        name == "google chrome" || name == "chrome" || name == "chrome.exe"
+    })
+}
+
+/// Microseconds since 1601-01-01, the epoch of Chrome's timestamps.
+fn chrome_timestamp(now: chrono::DateTime<chrono::Utc>) -> String {
+    const EPOCH_OFFSET_SECONDS: i64 = 11_644_473_600;
+    ((now.timestamp() + EPOCH_OFFSET_SECONDS) * 1_000_000 + i64::from(now.timestamp_subsec_micros())).to_string()
+}
+
+fn max_node_id(node: &serde_json::Value) -> u64 {
+    let own = node["id"].as_str().and_then(|id| id.parse().ok()).unwrap_or(0);
+    let children = node["children"].as_array().map_or(0, |children| {
+        children.iter().map(max_node_id).max().unwrap_or(0)
+    });
+    own.max(children)
+}
+
+/// Append a bookmark to the bookmark bar of a parsed `Bookmarks` file. The
+/// file is edited as plain JSON so fields grinta does not know survive.
+fn insert_into_bookmark_bar(
+    file: &mut serde_json::Value,
+    name: &str,
+    url: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<()> {
+    let next_id = max_node_id(&file["roots"]["bookmark_bar"])
+        .max(max_node_id(&file["roots"]["other"]))
+        .max(max_node_id(&file["roots"]["synced"]))
+        + 1;
+    let children = file["roots"]["bookmark_bar"]["children"]
+        .as_array_mut()
+        .ok_or_else(|| anyhow::anyhow!("Chrome's Bookmarks file has no bookmark bar"))?;
+    if children.iter().any(|child| child["url"] == url) {
+        anyhow::bail!("{} is already in the bookmarks bar", url);
+    }
+    children.push(serde_json::json!({
+        "date_added": chrome_timestamp(now),
+        "date_last_used": "0",
+        "guid": uuid::Uuid::new_v4().to_string(),
+        "id": next_id.to_string(),
+        "name": name,
+        "type": "url",
+        "url": url,
+    }));
+    // Chrome recomputes a missing checksum, while a stale one marks the
+    // file as corrupted
+    if let Some(object) = file.as_object_mut() {
+        object.remove("checksum");
+    }
+    Ok(())
+}
+
+/// Save a URL to the bookmark bar of Chrome's default profile. Chrome
+/// must be closed; the file is replaced atomically.
+pub fn add_to_chrome(name: &str, url: &str) -> anyhow::Result<()> {
+    if chrome_running() {
+        anyhow::bail!("Quit Chrome first, it overwrites bookmarks saved while it runs");
+    }
+    let path = chrome_dir().join("Default").join("Bookmarks");
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let mut file: serde_json::Value = serde_json::from_str(&contents)?;
+    let name = name.strip_suffix(" (Bookmark)").unwrap_or(name);
+    insert_into_bookmark_bar(&mut file, name, url, chrono::Utc::now())?;
+
+    let temp_path = path.with_file_name("Bookmarks.grinta.tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(&file)?)?;
+    std::fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_places(path: &Path) {
+        let connection = rusqlite::Connection::open(path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, title TEXT, parent INTEGER);
+                 INSERT INTO moz_places VALUES (1, 'https://www.rust-lang.org/');
+                 INSERT INTO moz_places VALUES (2, 'place:sort=8&maxResults=10');
+                 INSERT INTO moz_places VALUES (3, 'https://docs.rs/');
+                 INSERT INTO moz_bookmarks VALUES (1, 2, NULL, '', 0);
+                 INSERT INTO moz_bookmarks VALUES (2, 2, NULL, 'toolbar', 1);
+                 INSERT INTO moz_bookmarks VALUES (5, 2, NULL, 'Rust', 2);
+                 INSERT INTO moz_bookmarks VALUES (6, 1, 1, 'Rust', 5);
+                 INSERT INTO moz_bookmarks VALUES (7, 1, 2, 'Most Visited', 2);
+                 INSERT INTO moz_bookmarks VALUES (8, 1, 3, NULL, 2);",
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_read_firefox_places() {
+        let temp_dir = TempDir::new().unwrap();
+        let places = temp_dir.path().join("places.sqlite");
+        create_places(&places);
+
+        let bookmarks = read_firefox_places(&places).unwrap();
+        let labels: Vec<&str> = bookmarks.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, vec!["Rust (Bookmark)", "https://docs.rs/ (Bookmark)"]);
+        assert_eq!(bookmarks[0].handler, Handler::Url);
+        assert_eq!(bookmarks[0].value, "https://www.rust-lang.org/");
+        assert_eq!(bookmarks[0].metadata["folder"], "Bookmarks Toolbar/Rust");
+        assert_eq!(bookmarks[1].metadata["folder"], "Bookmarks Toolbar");
+    }
+
+    #[test]
+    fn test_process_chrome_bookmark_node() {
+        let json = r#"{"name": "Folder", "type": "folder", "children": [
+            {"name": "Rust", "type": "url", "url": "https://www.rust-lang.org/"}
+        ]}"#;
+        let node: BookmarkNode = serde_json::from_str(json).unwrap();
+        let mut bookmarks = Vec::new();
+        process_bookmark_node(&node, None, &mut bookmarks);
+
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].label, "Rust (Bookmark)");
+        assert_eq!(bookmarks[0].metadata["folder"], "Folder");
+    }
+
+    #[test]
+    fn test_insert_into_bookmark_bar() {
+        let json = r#"{"checksum": "abc", "version": 1, "roots": {
+            "bookmark_bar": {"id": "1", "type": "folder", "children": [
+                {"id": "5", "name": "Rust", "type": "url", "url": "https://www.rust-lang.org/"}
+            ]},
+            "other": {"id": "2", "type": "folder", "children": [{"id": "7", "type": "url", "url": "https://docs.rs/"}]},
+            "synced": {"id": "3", "type": "folder", "children": []}
+        }}"#;
+        let mut file: serde_json::Value = serde_json::from_str(json).unwrap();
+        let now = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        insert_into_bookmark_bar(&mut file, "Crates", "https://crates.io/", now).unwrap();
+
+        let added = &file["roots"]["bookmark_bar"]["children"][1];
+        assert_eq!(added["id"], "8");
+        assert_eq!(added["name"], "Crates");
+        assert_eq!(added["date_added"], "11644473600000000");
+        assert!(file.get("checksum").is_none());
+
+        let duplicate = insert_into_bookmark_bar(&mut file, "Rust", "https://www.rust-lang.org/", now);
+        assert!(duplicate.is_err());
     }
 }
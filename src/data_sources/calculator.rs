@@ -0,0 +1,350 @@
+use crate::core::{CommandItem, Handler};
+
+/// Evaluate the query as arithmetic (`12*7+3`) or a unit conversion
+/// (`5km in mi`), returning a copyable result item.
+pub fn calculate(query: &str) -> Option<CommandItem> {
+    let query = query.trim();
+    let (label, result) = if let Some((value, from, to)) = convert_units(query) {
+        let result = format_number(value);
+        (format!("{} {} = {} {}", format_number(from.0), from.1, result, to), result)
+    } else {
+        let value = evaluate(query)?;
+        let result = format_number(value);
+        (format!("{} = {}", query, result), result)
+    };
+
+    let mut item = CommandItem::new(&label, Handler::Copy, &result);
+    item.icon = "🧮".to_string();
+    item.metadata.insert("expression".to_string(), query.to_string());
+    Some(item)
+}
+
+/// Evaluate an arithmetic expression. Plain numbers are not treated as
+/// expressions so that typing `2024` doesn't produce a calculator result.
+pub fn evaluate(expression: &str) -> Option<f64> {
+    let tokens = tokenize(expression)?;
+    if !tokens.iter().any(|t| matches!(t, Token::Op(_))) {
+        return None;
+    }
+
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let value = parser.expression()?;
+    (parser.pos == tokens.len() && value.is_finite()).then_some(value)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | ',' | '_' => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' {
+                        number.push(d);
+                        chars.next();
+                    } else if d == ',' || d == '_' {
+                        // Thousands separators
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Number(number.parse().ok()?));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                chars.next();
+            }
+            'x' | '×' => {
+                tokens.push(Token::Op('*'));
+                chars.next();
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// Recursive descent over `+ -`, `* / %`, unary minus and right
+/// associative `^`.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Some(value)
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Some(value)
+    }
+
+    fn unary(&mut self) -> Option<f64> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Some(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Option<f64> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Some(base.powf(exponent));
+        }
+        Some(base)
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Number(n) => Some(n),
+            Token::Open => {
+                let value = self.expression()?;
+                match self.next()? {
+                    Token::Close => Some(value),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Time,
+    Data,
+    Temperature,
+}
+
+/// Unit names with their dimension and size in the dimension's base unit
+/// (metres, grams, litres, seconds, bytes). Temperatures are converted
+/// separately.
+const UNITS: &[(&[&str], Dimension, f64)] = &[
+    (&["mm", "millimeter", "millimeters"], Dimension::Length, 0.001),
+    (&["cm", "centimeter", "centimeters"], Dimension::Length, 0.01),
+    (&["m", "meter", "meters", "metre", "metres"], Dimension::Length, 1.0),
+    (&["km", "kilometer", "kilometers"], Dimension::Length, 1000.0),
+    (&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    (&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    (&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    (&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    (&["mg", "milligram", "milligrams"], Dimension::Mass, 0.001),
+    (&["g", "gram", "grams"], Dimension::Mass, 1.0),
+    (&["kg", "kilogram", "kilograms"], Dimension::Mass, 1000.0),
+    (&["oz", "ounce", "ounces"], Dimension::Mass, 28.349523125),
+    (&["lb", "lbs", "pound", "pounds"], Dimension::Mass, 453.59237),
+    (&["ml", "milliliter", "milliliters"], Dimension::Volume, 0.001),
+    (&["l", "liter", "liters", "litre", "litres"], Dimension::Volume, 1.0),
+    (&["cup", "cups"], Dimension::Volume, 0.2365882365),
+    (&["gal", "gallon", "gallons"], Dimension::Volume, 3.785411784),
+    (&["s", "sec", "second", "seconds"], Dimension::Time, 1.0),
+    (&["min", "minute", "minutes"], Dimension::Time, 60.0),
+    (&["h", "hr", "hour", "hours"], Dimension::Time, 3600.0),
+    (&["d", "day", "days"], Dimension::Time, 86400.0),
+    (&["wk", "week", "weeks"], Dimension::Time, 604800.0),
+    (&["b", "byte", "bytes"], Dimension::Data, 1.0),
+    (&["kb"], Dimension::Data, 1e3),
+    (&["mb"], Dimension::Data, 1e6),
+    (&["gb"], Dimension::Data, 1e9),
+    (&["tb"], Dimension::Data, 1e12),
+    (&["kib"], Dimension::Data, 1024.0),
+    (&["mib"], Dimension::Data, 1048576.0),
+    (&["gib"], Dimension::Data, 1073741824.0),
+    (&["c", "°c", "celsius"], Dimension::Temperature, 0.0),
+    (&["f", "°f", "fahrenheit"], Dimension::Temperature, 0.0),
+    (&["k", "kelvin"], Dimension::Temperature, 0.0),
+];
+
+fn find_unit(name: &str) -> Option<(&'static str, Dimension, f64)> {
+    let name = name.to_lowercase();
+    UNITS
+        .iter()
+        .find(|(names, _, _)| names.contains(&name.as_str()))
+        .map(|(names, dimension, factor)| (names[0], *dimension, *factor))
+}
+
+fn to_celsius(value: f64, unit: &str) -> f64 {
+    match unit {
+        "f" => (value - 32.0) * 5.0 / 9.0,
+        "k" => value - 273.15,
+        _ => value,
+    }
+}
+
+fn from_celsius(value: f64, unit: &str) -> f64 {
+    match unit {
+        "f" => value * 9.0 / 5.0 + 32.0,
+        "k" => value + 273.15,
+        _ => value,
+    }
+}
+
+/// Parse `<amount><unit> in|to <unit>` and convert, returning the result,
+/// the source amount with its canonical unit, and the target unit.
+fn convert_units(query: &str) -> Option<(f64, (f64, &'static str), &'static str)> {
+    let lower = query.to_lowercase();
+    // The last separator, as `in` is also a unit: `12 in to cm`
+    let (source, target) = [" in ", " to "]
+        .into_iter()
+        .filter_map(|separator| lower.rsplit_once(separator))
+        .min_by_key(|(_, target)| target.len())?;
+
+    let source = source.trim();
+    let split = source
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == ','))
+        .filter(|&i| i > 0)?;
+    let amount: f64 = source[..split].replace(',', "").parse().ok()?;
+
+    let (from, from_dimension, from_factor) = find_unit(source[split..].trim())?;
+    let (to, to_dimension, to_factor) = find_unit(target.trim())?;
+    if from_dimension != to_dimension {
+        return None;
+    }
+
+    let value = if from_dimension == Dimension::Temperature {
+        from_celsius(to_celsius(amount, from), to)
+    } else {
+        amount * from_factor / to_factor
+    };
+    Some((value, (amount, from), to))
+}
+
+/// Format a result without float noise: integers print without a decimal
+/// point and everything else is rounded to 10 decimal places.
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let formatted = format!("{:.10}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_arithmetic() {
+        assert_eq!(evaluate("12*7+3"), Some(87.0));
+        assert_eq!(evaluate("(1 + 2) * 3"), Some(9.0));
+        assert_eq!(evaluate("2^3^2"), Some(512.0));
+        assert_eq!(evaluate("-4 + 10 / 4"), Some(-1.5));
+        assert_eq!(evaluate("10 % 4"), Some(2.0));
+        assert_eq!(evaluate("1,000 * 3"), Some(3000.0));
+    }
+
+    #[test]
+    fn test_evaluate_rejects_non_expressions() {
+        assert_eq!(evaluate("2024"), None);
+        assert_eq!(evaluate("firefox"), None);
+        assert_eq!(evaluate("1 +"), None);
+        assert_eq!(evaluate("(1 + 2"), None);
+        assert_eq!(evaluate("1 / 0"), None);
+        assert_eq!(evaluate(""), None);
+    }
+
+    #[test]
+    fn test_convert_units() {
+        let (value, from, to) = convert_units("5km in mi").unwrap();
+        assert!((value - 3.10685596).abs() < 1e-6);
+        assert_eq!(from, (5.0, "km"));
+        assert_eq!(to, "mi");
+
+        let (value, _, _) = convert_units("100 f to c").unwrap();
+        assert!((value - 37.7777777).abs() < 1e-6);
+
+        let (value, from, to) = convert_units("12 in to cm").unwrap();
+        assert!((value - 30.48).abs() < 1e-6);
+        assert_eq!((from, to), ((12.0, "in"), "cm"));
+        let (value, _, _) = convert_units("2 ft in in").unwrap();
+        assert!((value - 24.0).abs() < 1e-6);
+
+        assert!(convert_units("5 km in kg").is_none());
+        assert!(convert_units("books in french").is_none());
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(87.0), "87");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(-1.5), "-1.5");
+    }
+
+    #[test]
+    fn test_calculate_item() {
+        let item = calculate("12*7+3").unwrap();
+        assert_eq!(item.handler, Handler::Copy);
+        assert_eq!(item.value, "87");
+        assert_eq!(item.label, "12*7+3 = 87");
+
+        let conversion = calculate("5km in mi").unwrap();
+        assert_eq!(conversion.value, "3.1068559612");
+        assert_eq!(conversion.label, "5 km = 3.1068559612 mi");
+
+        assert!(calculate("safari").is_none());
+    }
+}
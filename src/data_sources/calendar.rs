@@ -0,0 +1,680 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+
+const CACHE_NAMESPACE: &str = "calendar";
+const CACHE_TTL_MINUTES: i64 = 15;
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// How often the join items are brought up to date.
+pub const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+/// Calendars are read again every this many ticks.
+const FETCH_EVERY_TICKS: u32 = 5;
+/// How far ahead events are read.
+const LOOKAHEAD_HOURS: i64 = 24;
+/// How long a meeting without an end time stays joinable.
+const DEFAULT_LENGTH_MINUTES: i64 = 30;
+
+/// Upcoming meetings read from calendars.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// Read the events of Calendar.app, on macOS
+    pub macos_calendar: bool,
+    /// `.ics` files or URLs, such as a Google Calendar's secret address
+    pub ics: Vec<String>,
+    /// How long before a meeting its join item shows up
+    pub lead_minutes: i64,
+    /// Open Zoom and Teams links in their apps rather than the browser
+    pub native_apps: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            macos_calendar: false,
+            ics: Vec::new(),
+            lead_minutes: 10,
+            native_apps: true,
+        }
+    }
+}
+
+impl CalendarConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.macos_calendar || !self.ics.is_empty()
+    }
+}
+
+/// A timed event, in local time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meeting {
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+    /// Zoom, Meet, Teams or Webex link found in the event
+    pub join_url: Option<String>,
+}
+
+impl Meeting {
+    /// When the meeting is over, or assumed to be.
+    pub fn end(&self) -> NaiveDateTime {
+        self.end.unwrap_or(self.start + Duration::minutes(DEFAULT_LENGTH_MINUTES))
+    }
+}
+
+/// Whether `url` joins a video call.
+fn is_meeting_url(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) else {
+        return false;
+    };
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_lowercase();
+    let is_host = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+    (is_host("zoom.us") && ["j/", "my/", "w/"].iter().any(|prefix| path.starts_with(prefix)))
+        || (host == "meet.google.com" && !path.is_empty())
+        || (host == "teams.microsoft.com" && path.starts_with("l/meetup-join"))
+        || (host == "teams.live.com" && path.starts_with("meet"))
+        || (is_host("webex.com") && !path.is_empty())
+}
+
+/// The first video call link in `text`, such as an event's location or
+/// notes.
+pub fn find_join_url(text: &str) -> Option<String> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '(' | ')' | '[' | ']'))
+        .map(|word| word.trim_end_matches(['.', ',', ';']))
+        .find(|word| is_meeting_url(word))
+        .map(str::to_string)
+}
+
+/// What opens `url`: Zoom and Teams links go straight to their apps with
+/// `native_apps`, other links to the browser.
+pub fn join_target(url: &str, native_apps: bool) -> String {
+    if !native_apps {
+        return url.to_string();
+    }
+    if let Some(path) = url.strip_prefix("https://teams.microsoft.com/") {
+        return format!("msteams:/{}", path);
+    }
+    let rest = url.strip_prefix("https://").unwrap_or(url);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if host.ends_with("zoom.us") {
+        if let Some(meeting) = path.strip_prefix("j/") {
+            let (id, query) = meeting.split_once('?').unwrap_or((meeting, ""));
+            let password = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("pwd="))
+                .map(|pwd| format!("&pwd={}", pwd))
+                .unwrap_or_default();
+            return format!("zoommtg://zoom.us/join?action=join&confno={}{}", id, password);
+        }
+    }
+    url.to_string()
+}
+
+/// Unfolded content lines of an iCalendar document.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=x:value` split into the name, the parameters and the value.
+fn split_property(line: &str) -> Option<(&str, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == ':' && !quoted).then_some(index)
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name, params, value))
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// A `DTSTART` or `DTEND` in local time. UTC times are converted and
+/// times with a `TZID` are taken as local; dates alone (all-day events)
+/// give `None`.
+fn parse_ics_time(params: &str, value: &str) -> Option<NaiveDateTime> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some(match utc {
+        true => Utc.from_utc_datetime(&time).with_timezone(&Local).naive_local(),
+        false => time,
+    })
+}
+
+/// The timed events of an iCalendar document. Recurring events are only
+/// read at their first occurrence.
+pub fn parse_ics(ics: &str) -> Vec<Meeting> {
+    let mut meetings = Vec::new();
+    let mut event: Option<(Meeting, bool)> = None;
+    // Places a call link can be in, in order of preference
+    let mut texts: Vec<(u8, String)> = Vec::new();
+    for line in unfold(ics) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match (name.to_uppercase().as_str(), &mut event) {
+            ("BEGIN", _) if value == "VEVENT" => {
+                let meeting = Meeting {
+                    title: String::new(),
+                    start: NaiveDate::MIN.and_hms_opt(0, 0, 0).unwrap_or_default(),
+                    end: None,
+                    join_url: None,
+                };
+                event = Some((meeting, false));
+                texts.clear();
+            }
+            ("END", Some((meeting, timed))) if value == "VEVENT" => {
+                texts.sort_by_key(|(rank, _)| *rank);
+                meeting.join_url = texts.iter().find_map(|(_, text)| find_join_url(text));
+                if *timed && !meeting.title.is_empty() {
+                    meetings.push(meeting.clone());
+                }
+                event = None;
+            }
+            ("SUMMARY", Some((meeting, _))) => meeting.title = unescape(value).trim().to_string(),
+            ("DTSTART", Some((meeting, timed))) => {
+                if let Some(start) = parse_ics_time(params, value) {
+                    meeting.start = start;
+                    *timed = true;
+                }
+            }
+            ("DTEND", Some((meeting, _))) => meeting.end = parse_ics_time(params, value),
+            // Cancelled events are dropped
+            ("STATUS", Some((_, timed))) if value.eq_ignore_ascii_case("CANCELLED") => *timed = false,
+            ("X-GOOGLE-CONFERENCE", Some(_)) => texts.push((0, unescape(value))),
+            ("URL", Some(_)) => texts.push((1, unescape(value))),
+            ("LOCATION", Some(_)) => texts.push((2, unescape(value))),
+            ("DESCRIPTION", Some(_)) => texts.push((3, unescape(value))),
+            _ => {}
+        }
+    }
+    meetings
+}
+
+/// The meetings worth showing at `now`: not over yet and starting within
+/// the next day, soonest first.
+pub fn upcoming(mut meetings: Vec<Meeting>, now: NaiveDateTime) -> Vec<Meeting> {
+    meetings.retain(|meeting| meeting.end() > now && meeting.start < now + Duration::hours(LOOKAHEAD_HOURS));
+    meetings.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.title.cmp(&b.title)));
+    // The same meeting in several calendars
+    meetings.dedup_by(|a, b| a.start == b.start && a.title == b.title);
+    meetings
+}
+
+/// `in 5 min`, `now` or `started 3 min ago`.
+pub fn starts_in(meeting: &Meeting, now: NaiveDateTime) -> String {
+    let minutes = (meeting.start - now).num_seconds().div_euclid(60);
+    let seconds_left = (meeting.start - now).num_seconds().rem_euclid(60);
+    // Round up, so a meeting a few seconds away isn't `now` yet
+    let minutes = if seconds_left > 0 { minutes + 1 } else { minutes };
+    match minutes {
+        0 => "now".to_string(),
+        m if m > 0 && m < 60 => format!("in {} min", m),
+        m if m > 0 => format!("in {}h{:02}", m / 60, m % 60),
+        m => format!("started {} min ago", -m),
+    }
+}
+
+/// The item joining `meeting`'s call, if it has a link.
+pub fn join_item(meeting: &Meeting, now: NaiveDateTime, settings: &CalendarConfig) -> Option<CommandItem> {
+    let url = meeting.join_url.as_ref()?;
+    let label = format!("Join {} ({})", meeting.title, starts_in(meeting, now));
+    let mut item = CommandItem::new(&label, Handler::Url, &join_target(url, settings.native_apps));
+    item.icon = "📹".to_string();
+    item.metadata.insert("meeting".to_string(), "true".to_string());
+    item.metadata.insert("keywords".to_string(), "join meeting call".to_string());
+    Some(item)
+}
+
+/// Whether `meeting` starts within `lead_minutes` or is under way.
+fn is_imminent(meeting: &Meeting, now: NaiveDateTime, settings: &CalendarConfig) -> bool {
+    meeting.start - Duration::minutes(settings.lead_minutes) <= now && now < meeting.end()
+}
+
+/// `Join <meeting> (in 5 min)` for the meetings with a call link starting
+/// within `lead_minutes` or under way.
+pub fn join_items(meetings: &[Meeting], now: NaiveDateTime, settings: &CalendarConfig) -> Vec<CommandItem> {
+    meetings
+        .iter()
+        .filter(|meeting| is_imminent(meeting, now, settings))
+        .filter_map(|meeting| join_item(meeting, now, settings))
+        .collect()
+}
+
+/// The meeting under way, or else the next one, of meetings sorted by
+/// start.
+pub fn next_meeting(meetings: &[Meeting], now: NaiveDateTime) -> Option<&Meeting> {
+    meetings.iter().find(|meeting| meeting.end() > now)
+}
+
+/// The meetings to remind of at `now`: those entering their lead time that
+/// were not reminded of yet, remembered in `reminded`.
+pub fn due_reminders(
+    meetings: &[Meeting],
+    now: NaiveDateTime,
+    settings: &CalendarConfig,
+    reminded: &mut HashSet<(String, NaiveDateTime)>,
+) -> Vec<Meeting> {
+    meetings
+        .iter()
+        .filter(|meeting| is_imminent(meeting, now, settings))
+        .filter(|meeting| reminded.insert((meeting.title.clone(), meeting.start)))
+        .cloned()
+        .collect()
+}
+
+/// The upcoming meetings, sent to the UI every minute.
+#[derive(Debug, Clone, Default)]
+pub struct Agenda {
+    pub meetings: Vec<Meeting>,
+    /// Meetings about to start, to notify of
+    pub reminders: Vec<Meeting>,
+    /// Whether Do Not Disturb or a Focus is on, holding notifications back
+    pub do_not_disturb: bool,
+}
+
+/// Whether a Focus is on, read from the assertions macOS keeps for its
+/// Focus modes.
+#[cfg(target_os = "macos")]
+async fn do_not_disturb() -> bool {
+    let Some(path) = dirs::home_dir().map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json")) else {
+        return false;
+    };
+    let Ok(text) = tokio::fs::read_to_string(path).await else {
+        return false;
+    };
+    serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|assertions| {
+            let records = assertions.get("data")?.get(0)?.get("storeAssertionRecords")?;
+            Some(records.as_array().is_some_and(|records| !records.is_empty()))
+        })
+        .unwrap_or(false)
+}
+
+/// Whether GNOME's Do Not Disturb is on, or dunst's notifications paused.
+#[cfg(not(target_os = "macos"))]
+async fn do_not_disturb() -> bool {
+    let output = |program: &'static str, args: &'static [&'static str]| async move {
+        let output = tokio::process::Command::new(program).args(args).output().await.ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let banners = output("gsettings", &["get", "org.gnome.desktop.notifications", "show-banners"]).await;
+    if banners.as_deref() == Some("false") {
+        return true;
+    }
+    output("dunstctl", &["is-paused"]).await.as_deref() == Some("true")
+}
+
+/// An event as read from Calendar.app.
+#[derive(Debug, Deserialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct CalendarEvent {
+    title: String,
+    /// Local time, `YYYY-MM-DD HH:MM`
+    start: String,
+    end: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn calendar_event_meeting(event: CalendarEvent) -> Option<Meeting> {
+    let parse = |time: &str| NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").ok();
+    let join_url = [&event.url, &event.location, &event.notes]
+        .into_iter()
+        .flatten()
+        .find_map(|text| find_join_url(text));
+    Some(Meeting {
+        start: parse(&event.start)?,
+        end: event.end.as_deref().and_then(parse),
+        title: event.title,
+        join_url,
+    })
+}
+
+/// Timed events of Calendar.app from an hour ago to a day ahead.
+#[cfg(target_os = "macos")]
+async fn read_macos_calendar() -> Vec<Meeting> {
+    let script = r#"
+        const Calendar = Application("Calendar");
+        const pad = (n) => String(n).padStart(2, "0");
+        const format = (d) => d ? `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())} ${pad(d.getHours())}:${pad(d.getMinutes())}` : null;
+        const from = new Date(Date.now() - 3600 * 1000);
+        const until = new Date(Date.now() + 24 * 3600 * 1000);
+        const events = [];
+        Calendar.calendars().forEach(function(calendar) {
+            const found = calendar.events.whose({ _and: [{ startDate: { _greaterThan: from } }, { startDate: { _lessThan: until } }] });
+            const titles = found.summary();
+            const starts = found.startDate();
+            const ends = found.endDate();
+            const allDay = found.alldayEvent();
+            const urls = found.url();
+            const locations = found.location();
+            const notes = found.description();
+            for (let i = 0; i < titles.length; i++) {
+                if (allDay[i]) continue;
+                events.push({ title: titles[i], start: format(starts[i]), end: format(ends[i]), url: urls[i], location: locations[i], notes: notes[i] });
+            }
+        });
+        console.log(JSON.stringify(events));
+    "#;
+    let Ok(output) = tokio::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    // console.log writes to stderr
+    let json = String::from_utf8_lossy(&output.stderr);
+    match serde_json::from_str::<Vec<CalendarEvent>>(json.trim()) {
+        Ok(events) => events.into_iter().filter_map(calendar_event_meeting).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Off macOS only the `.ics` calendars are read.
+#[cfg(not(target_os = "macos"))]
+async fn read_macos_calendar() -> Vec<Meeting> {
+    Vec::new()
+}
+
+/// An `.ics` file, or a URL fetched at most every 15 minutes. The last
+/// copy is used offline.
+async fn read_ics(source: &str) -> Option<String> {
+    if !source.starts_with("http://") && !source.starts_with("https://") && !source.starts_with("webcal://") {
+        return std::fs::read_to_string(config::expand_tilde(source)).ok();
+    }
+    let ttl = chrono::Duration::minutes(CACHE_TTL_MINUTES);
+    if let Some(ics) = cache::get::<String>(CACHE_NAMESPACE, source, ttl) {
+        return Some(ics);
+    }
+    let url = match source.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => source.to_string(),
+    };
+    let fetched = async {
+        reqwest::Client::new()
+            .get(url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    };
+    match fetched.await {
+        Ok(ics) => {
+            let _ = cache::put(CACHE_NAMESPACE, source, &ics);
+            Some(ics)
+        }
+        Err(_) => cache::get_stale::<String>(CACHE_NAMESPACE, source).map(|(ics, _)| ics),
+    }
+}
+
+/// The upcoming meetings of every configured calendar.
+pub async fn get_meetings() -> Vec<Meeting> {
+    let settings = &config::get().calendar;
+    let mut meetings = Vec::new();
+    if settings.macos_calendar {
+        meetings.extend(read_macos_calendar().await);
+    }
+    for source in &settings.ics {
+        if let Some(ics) = read_ics(source).await {
+            meetings.extend(parse_ics(&ics));
+        }
+    }
+    upcoming(meetings, Local::now().naive_local())
+}
+
+/// Send the upcoming meetings every minute, so countdowns stay current,
+/// reading the calendars again every few minutes.
+pub async fn watch(tx: mpsc::Sender<Agenda>) {
+    let settings = &config::get().calendar;
+    let mut interval = tokio::time::interval(TICK);
+    let mut meetings = Vec::new();
+    let mut reminded = HashSet::new();
+    let mut ticks = 0;
+    loop {
+        interval.tick().await;
+        let now = Local::now().naive_local();
+        if ticks % FETCH_EVERY_TICKS == 0 {
+            meetings = get_meetings().await;
+        } else {
+            meetings = upcoming(meetings, now);
+        }
+        ticks += 1;
+        let mut reminders = due_reminders(&meetings, now, settings, &mut reminded);
+        // Meetings already close when grinta starts aren't news
+        if ticks == 1 {
+            reminders.clear();
+        }
+        let do_not_disturb = do_not_disturb().await;
+        if do_not_disturb {
+            reminders.clear();
+        }
+        let agenda = Agenda {
+            meetings: meetings.clone(),
+            reminders,
+            do_not_disturb,
+        };
+        if tx.send(agenda).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn meeting(title: &str, start: NaiveDateTime, join_url: Option<&str>) -> Meeting {
+        Meeting {
+            title: title.to_string(),
+            start,
+            end: Some(start + Duration::minutes(30)),
+            join_url: join_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_join_url() {
+        assert_eq!(
+            find_join_url("Join: <https://us02web.zoom.us/j/123456?pwd=abc>.").as_deref(),
+            Some("https://us02web.zoom.us/j/123456?pwd=abc")
+        );
+        assert_eq!(
+            find_join_url("Room 4 / https://meet.google.com/abc-defg-hij").as_deref(),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+        assert!(find_join_url("https://teams.microsoft.com/l/meetup-join/19%3ameeting_x%40thread.v2/0").is_some());
+        assert!(find_join_url("https://acme.webex.com/meet/jdoe").is_some());
+        // Not calls
+        assert_eq!(find_join_url("https://zoom.us/pricing https://meet.google.com/"), None);
+        assert_eq!(find_join_url("https://notzoom.us/j/1"), None);
+    }
+
+    #[test]
+    fn test_join_target() {
+        assert_eq!(
+            join_target("https://us02web.zoom.us/j/123456?pwd=abc&from=addon", true),
+            "zoommtg://zoom.us/join?action=join&confno=123456&pwd=abc"
+        );
+        assert_eq!(join_target("https://zoom.us/j/42", true), "zoommtg://zoom.us/join?action=join&confno=42");
+        assert_eq!(
+            join_target("https://teams.microsoft.com/l/meetup-join/abc", true),
+            "msteams:/l/meetup-join/abc"
+        );
+        assert_eq!(join_target("https://meet.google.com/abc", true), "https://meet.google.com/abc");
+        assert_eq!(join_target("https://zoom.us/j/42", false), "https://zoom.us/j/42");
+    }
+
+    #[test]
+    fn test_parse_ics() {
+        let ics = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+SUMMARY:Standup\\, daily\r
+DTSTART;TZID=Europe/Berlin:20261016T093000\r
+DTEND;TZID=Europe/Berlin:20261016T094500\r
+DESCRIPTION:Agenda\\nJoin: https://zoom.us/j/\r
+ 987?pwd=x\r
+LOCATION:https://meet.google.com/aaa-bbbb-ccc\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20261016\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Cancelled sync\r
+DTSTART:20261016T120000\r
+STATUS:CANCELLED\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:1:1\r
+DTSTART:20261016T140000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let meetings = parse_ics(ics);
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[0].title, "Standup, daily");
+        assert_eq!(meetings[0].start, at(9, 30));
+        assert_eq!(meetings[0].end, Some(at(9, 45)));
+        // The location comes before the description
+        assert_eq!(meetings[0].join_url.as_deref(), Some("https://meet.google.com/aaa-bbbb-ccc"));
+        assert_eq!(meetings[1].title, "1:1");
+        assert_eq!(meetings[1].join_url, None);
+        assert_eq!(meetings[1].end(), at(14, 30));
+    }
+
+    #[test]
+    fn test_parse_ics_time() {
+        let utc = parse_ics_time("", "20261016T120000Z").unwrap();
+        let expected = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap().with_timezone(&Local).naive_local();
+        assert_eq!(utc, expected);
+        assert_eq!(parse_ics_time("VALUE=DATE", "20261016"), None);
+        assert_eq!(parse_ics_time("", "garbage"), None);
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let meetings = vec![
+            meeting("Later", at(15, 0), None),
+            meeting("Over", at(8, 0), None),
+            meeting("Soon", at(10, 5), None),
+            meeting("Soon", at(10, 5), None),
+        ];
+        let titles: Vec<String> = upcoming(meetings, at(10, 0)).into_iter().map(|m| m.title).collect();
+        assert_eq!(titles, vec!["Soon", "Later"]);
+    }
+
+    #[test]
+    fn test_starts_in() {
+        let standup = meeting("Standup", at(10, 0), None);
+        assert_eq!(starts_in(&standup, at(9, 55)), "in 5 min");
+        assert_eq!(starts_in(&standup, at(9, 59) + Duration::seconds(30)), "in 1 min");
+        assert_eq!(starts_in(&standup, at(10, 0)), "now");
+        assert_eq!(starts_in(&standup, at(10, 3)), "started 3 min ago");
+        assert_eq!(starts_in(&standup, at(8, 30)), "in 1h30");
+    }
+
+    #[test]
+    fn test_join_items() {
+        let settings = CalendarConfig::default();
+        let meetings = vec![
+            meeting("Standup", at(10, 0), Some("https://meet.google.com/abc")),
+            meeting("Review", at(10, 5), None),
+            meeting("Planning", at(11, 0), Some("https://zoom.us/j/1")),
+        ];
+        let items = join_items(&meetings, at(9, 55), &settings);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Join Standup (in 5 min)");
+        assert_eq!(items[0].handler, Handler::Url);
+        assert_eq!(items[0].value, "https://meet.google.com/abc");
+        assert!(items[0].metadata.contains_key("meeting"));
+
+        // Still joinable while it runs, gone once it ended
+        assert_eq!(join_items(&meetings, at(10, 20), &settings).len(), 1);
+        assert!(join_items(&meetings, at(10, 45), &settings).is_empty());
+    }
+
+    #[test]
+    fn test_next_meeting() {
+        let meetings = vec![meeting("Standup", at(10, 0), None), meeting("Review", at(11, 0), None)];
+        assert_eq!(next_meeting(&meetings, at(9, 0)).map(|m| m.title.as_str()), Some("Standup"));
+        // Under way
+        assert_eq!(next_meeting(&meetings, at(10, 15)).map(|m| m.title.as_str()), Some("Standup"));
+        assert_eq!(next_meeting(&meetings, at(10, 30)).map(|m| m.title.as_str()), Some("Review"));
+        assert_eq!(next_meeting(&meetings, at(12, 0)), None);
+    }
+
+    #[test]
+    fn test_due_reminders() {
+        let settings = CalendarConfig::default();
+        let meetings = vec![meeting("Standup", at(10, 0), None), meeting("Review", at(11, 0), None)];
+        let mut reminded = HashSet::new();
+        assert!(due_reminders(&meetings, at(9, 45), &settings, &mut reminded).is_empty());
+        let due = due_reminders(&meetings, at(9, 50), &settings, &mut reminded);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].title, "Standup");
+        // Once per meeting
+        assert!(due_reminders(&meetings, at(9, 51), &settings, &mut reminded).is_empty());
+    }
+
+    #[test]
+    fn test_calendar_event_meeting() {
+        let json = r#"{"title": "Sync", "start": "2026-10-16 10:00", "end": null, "url": null,
+                       "location": "Zoom", "notes": "https://zoom.us/j/55"}"#;
+        let meeting = calendar_event_meeting(serde_json::from_str(json).unwrap()).unwrap();
+        assert_eq!(meeting.start, at(10, 0));
+        assert_eq!(meeting.join_url.as_deref(), Some("https://zoom.us/j/55"));
+    }
+}
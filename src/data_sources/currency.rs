@@ -0,0 +1,216 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "currency";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+/// Rates are fetched for this currency and crossed for the others.
+const BASE: &str = "USD";
+
+/// Currency codes recognised in queries, so `5 min to sec` stays a unit
+/// conversion.
+const CODES: &[&str] = &[
+    "AED", "ARS", "AUD", "BGN", "BRL", "CAD", "CHF", "CLP", "CNY", "COP", "CZK", "DKK", "EGP", "EUR", "GBP", "HKD",
+    "HUF", "IDR", "ILS", "INR", "ISK", "JPY", "KRW", "MXN", "MYR", "NGN", "NOK", "NZD", "PHP", "PLN", "RON", "RUB",
+    "SAR", "SEK", "SGD", "THB", "TRY", "TWD", "UAH", "USD", "VND", "ZAR",
+];
+
+/// Currency symbols written before an amount, as in `€20 to usd`.
+const SYMBOLS: &[(char, &str)] = &[('$', "USD"), ('€', "EUR"), ('£', "GBP"), ('¥', "JPY"), ('₹', "INR")];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencyConfig {
+    /// Exchange rate API, with `{base}` for the base currency. It must
+    /// answer with a `rates` object, as open.er-api.com and Frankfurter do
+    pub url: String,
+    /// How long fetched rates are used before fetching them again
+    pub refresh_hours: i64,
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            url: "https://open.er-api.com/v6/latest/{base}".to_string(),
+            refresh_hours: 24,
+        }
+    }
+}
+
+/// `100 usd to eur`, `€20 in gbp` or `1,500 jpy to usd`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+}
+
+fn currency_code(text: &str) -> Option<String> {
+    let code = text.trim().to_uppercase();
+    CODES.contains(&code.as_str()).then_some(code)
+}
+
+pub fn parse_query(query: &str) -> Option<Conversion> {
+    let lower = query.trim().to_lowercase();
+    let (source, target) = lower.split_once(" to ").or_else(|| lower.split_once(" in "))?;
+    let to = currency_code(target)?;
+    let source = source.trim();
+
+    let (symbol, source) = match SYMBOLS.iter().find(|(symbol, _)| source.starts_with(*symbol)) {
+        Some((symbol, code)) => (Some(*code), &source[symbol.len_utf8()..]),
+        None => (None, source),
+    };
+    let split = source
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(source.len());
+    let amount: f64 = source[..split].replace(',', "").parse().ok()?;
+    let from = match (symbol, source[split..].trim()) {
+        (Some(code), "") => code.to_string(),
+        (None, code) => currency_code(code)?,
+        _ => return None,
+    };
+    Some(Conversion { amount, from, to })
+}
+
+/// The rates of a response, per unit of `base`.
+fn parse_rates(base: &str, response: &Value) -> Option<HashMap<String, f64>> {
+    let mut rates: HashMap<String, f64> = response
+        .get("rates")?
+        .as_object()?
+        .iter()
+        .filter_map(|(code, rate)| Some((code.to_uppercase(), rate.as_f64()?)))
+        .collect();
+    // Frankfurter leaves the base out
+    rates.entry(base.to_string()).or_insert(1.0);
+    Some(rates)
+}
+
+/// `amount` of `from` in `to`, crossed through the base currency.
+fn convert(rates: &HashMap<String, f64>, conversion: &Conversion) -> Option<f64> {
+    let from = rates.get(&conversion.from)?;
+    let to = rates.get(&conversion.to)?;
+    (*from > 0.0).then(|| conversion.amount * to / from)
+}
+
+/// Cents for most amounts, more digits for the small ones.
+fn format_amount(value: f64) -> String {
+    if value.abs() >= 1.0 || value == 0.0 {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.6}", value).trim_end_matches('0').to_string()
+    }
+}
+
+fn conversion_item(conversion: &Conversion, value: f64, stale_hours: Option<i64>) -> CommandItem {
+    let result = format_amount(value);
+    let mut label = format!(
+        "{} {} = {} {}",
+        crate::data_sources::calculator::format_number(conversion.amount),
+        conversion.from,
+        result,
+        conversion.to
+    );
+    if let Some(hours) = stale_hours {
+        label.push_str(&format!(" (offline, rates {} h old)", hours));
+    }
+    let mut item = CommandItem::new(&label, Handler::Copy, &result);
+    item.icon = "💱".to_string();
+    item
+}
+
+async fn fetch_rates(url: &str) -> Result<HashMap<String, f64>> {
+    let response = reqwest::Client::new()
+        .get(url.replace("{base}", BASE))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    parse_rates(BASE, &response).context("No rates in the answer")
+}
+
+/// The converted amount. Rates are fetched once a day; when the API can't
+/// be reached the last rates are used, marked as offline.
+pub async fn get_conversion_items(conversion: &Conversion) -> Vec<CommandItem> {
+    let settings = &config::get().currency;
+    let ttl = chrono::Duration::hours(settings.refresh_hours);
+    let (rates, stale_hours) = match cache::get::<HashMap<String, f64>>(CACHE_NAMESPACE, &settings.url, ttl) {
+        Some(rates) => (rates, None),
+        None => match fetch_rates(&settings.url).await {
+            Ok(rates) => {
+                let _ = cache::put(CACHE_NAMESPACE, &settings.url, &rates);
+                (rates, None)
+            }
+            Err(_) => match cache::get_stale::<HashMap<String, f64>>(CACHE_NAMESPACE, &settings.url) {
+                Some((rates, age)) => (rates, Some(age.num_hours())),
+                None => return Vec::new(),
+            },
+        },
+    };
+    convert(&rates, conversion)
+        .map(|value| conversion_item(conversion, value, stale_hours))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conversion(amount: f64, from: &str, to: &str) -> Conversion {
+        Conversion {
+            amount,
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("100 usd to eur"), Some(conversion(100.0, "USD", "EUR")));
+        assert_eq!(parse_query("1,500JPY in usd"), Some(conversion(1500.0, "JPY", "USD")));
+        assert_eq!(parse_query("€20.5 to gbp"), Some(conversion(20.5, "EUR", "GBP")));
+        assert_eq!(parse_query("$5 in chf"), Some(conversion(5.0, "USD", "CHF")));
+        // Units and plain text are left alone
+        assert_eq!(parse_query("5 min to sec"), None);
+        assert_eq!(parse_query("72f in c"), None);
+        assert_eq!(parse_query("usd to eur"), None);
+        assert_eq!(parse_query("$5 eur in usd"), None);
+    }
+
+    #[test]
+    fn test_parse_rates() {
+        let rates = parse_rates("USD", &json!({"base": "USD", "rates": {"EUR": 0.9, "jpy": 150}})).unwrap();
+        assert_eq!(rates["USD"], 1.0);
+        assert_eq!(rates["JPY"], 150.0);
+        assert!(parse_rates("USD", &json!({"error": "quota"})).is_none());
+    }
+
+    #[test]
+    fn test_convert() {
+        let rates: HashMap<String, f64> =
+            [("USD", 1.0), ("EUR", 0.8), ("GBP", 0.5)].into_iter().map(|(c, r)| (c.to_string(), r)).collect();
+        assert_eq!(convert(&rates, &conversion(100.0, "USD", "EUR")), Some(80.0));
+        // Crossed through the base currency
+        assert_eq!(convert(&rates, &conversion(40.0, "EUR", "GBP")), Some(25.0));
+        assert_eq!(convert(&rates, &conversion(1.0, "USD", "SEK")), None);
+    }
+
+    #[test]
+    fn test_conversion_item() {
+        let item = conversion_item(&conversion(100.0, "USD", "EUR"), 92.126, None);
+        assert_eq!(item.label, "100 USD = 92.13 EUR");
+        assert_eq!(item.value, "92.13");
+        assert_eq!(item.handler, Handler::Copy);
+
+        let stale = conversion_item(&conversion(1.0, "JPY", "USD"), 0.00667, Some(30));
+        assert_eq!(stale.label, "1 JPY = 0.00667 USD (offline, rates 30 h old)");
+    }
+}
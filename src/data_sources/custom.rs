@@ -0,0 +1,94 @@
+use crate::config::{self, expand_tilde};
+use crate::core::{CommandItem, Handler};
+use serde::{Deserialize, Serialize};
+
+/// An item declared in the config file, such as an intranet link or a
+/// frequent command. Exactly one of `url`, `path` and `command` is used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomItem {
+    pub label: String,
+    pub url: Option<String>,
+    /// File or folder, `~` allowed
+    pub path: Option<String>,
+    /// Run in the user's shell
+    pub command: Option<String>,
+    pub icon: Option<String>,
+    /// Extra words the item is found by
+    pub keywords: Vec<String>,
+}
+
+impl CustomItem {
+    /// The catalog item, or `None` when no target is set.
+    pub fn to_item(&self) -> Option<CommandItem> {
+        let mut item = if let Some(url) = &self.url {
+            CommandItem::new(&self.label, Handler::Url, url)
+        } else if let Some(path) = &self.path {
+            let path = expand_tilde(path);
+            let handler = if path.is_dir() { Handler::Folder } else { Handler::File };
+            CommandItem::new(&self.label, handler, &path.to_string_lossy())
+        } else if let Some(command) = &self.command {
+            CommandItem::new(&self.label, Handler::Shell, command)
+        } else {
+            return None;
+        };
+        if let Some(icon) = &self.icon {
+            item.icon = icon.clone();
+        }
+        if !self.keywords.is_empty() {
+            item.metadata.insert("keywords".to_string(), self.keywords.join(" "));
+        }
+        item.metadata.insert("custom".to_string(), "true".to_string());
+        Some(item)
+    }
+}
+
+/// Items declared under `[[items]]` in the config file.
+pub fn get_custom_items() -> Vec<CommandItem> {
+    config::get().items.iter().filter_map(CustomItem::to_item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_item() {
+        let intranet = CustomItem {
+            label: "Intranet".to_string(),
+            url: Some("https://intranet.example.com".to_string()),
+            icon: Some("🏢".to_string()),
+            keywords: vec!["wiki".to_string(), "hr".to_string()],
+            ..CustomItem::default()
+        };
+        let item = intranet.to_item().unwrap();
+        assert_eq!(item.handler, Handler::Url);
+        assert_eq!(item.icon, "🏢");
+        assert_eq!(item.metadata["keywords"], "wiki hr");
+        assert_eq!(item.metadata["custom"], "true");
+
+        let deploy = CustomItem {
+            label: "Deploy staging".to_string(),
+            command: Some("make deploy-staging".to_string()),
+            ..CustomItem::default()
+        };
+        assert_eq!(deploy.to_item().unwrap().handler, Handler::Shell);
+
+        let empty = CustomItem {
+            label: "Nothing".to_string(),
+            ..CustomItem::default()
+        };
+        assert!(empty.to_item().is_none());
+    }
+
+    #[test]
+    fn test_path_item() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let folder = CustomItem {
+            label: "Scratch".to_string(),
+            path: Some(temp_dir.path().to_string_lossy().into_owned()),
+            ..CustomItem::default()
+        };
+        assert_eq!(folder.to_item().unwrap().handler, Handler::Folder);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::core::{CommandItem, Handler};
+use crate::natural_date;
+use chrono::{Local, NaiveDate};
+
+const DATE_COMMAND: &str = "date";
+
+fn copy_item(label: &str, value: &str) -> CommandItem {
+    let mut item = CommandItem::new(label, Handler::Copy, value);
+    item.icon = "📅".to_string();
+    item
+}
+
+fn relative_label(days: i64) -> String {
+    match days {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        d if d > 0 => format!("in {} days", d),
+        d => format!("{} days ago", -d),
+    }
+}
+
+/// Copyable results for a `date <expression>` query: the resolved date in
+/// a few formats, or a day count for `days until` / `days since`.
+pub fn date_items(query: &str, today: NaiveDate) -> Vec<CommandItem> {
+    let Some(expression) = query
+        .strip_prefix(DATE_COMMAND)
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        .map(str::trim)
+    else {
+        return Vec::new();
+    };
+    let expression = if expression.is_empty() { "today" } else { expression };
+
+    if let Some((days, date)) = natural_date::parse_interval(expression, today) {
+        let label = format!("{} days ({})", days, date.format("%a, %b %-d %Y"));
+        return vec![copy_item(&label, &days.to_string())];
+    }
+
+    let Some(date) = natural_date::parse_date(expression, today) else {
+        return Vec::new();
+    };
+    let relative = relative_label((date - today).num_days());
+    let long = date.format("%A, %B %-d, %Y").to_string();
+    let iso = date.format("%Y-%m-%d").to_string();
+    vec![
+        copy_item(&format!("{} ({})", long, relative), &long),
+        copy_item(&iso, &iso),
+    ]
+}
+
+/// `date_items` relative to the local date.
+pub fn get_date_items(query: &str) -> Vec<CommandItem> {
+    date_items(query, Local::now().date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 6).unwrap()
+    }
+
+    #[test]
+    fn test_date_items() {
+        let items = date_items("date next friday", today());
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].label, "Friday, May 8, 2026 (in 2 days)");
+        assert_eq!(items[0].value, "Friday, May 8, 2026");
+        assert_eq!(items[1].value, "2026-05-08");
+        assert_eq!(items[1].handler, Handler::Copy);
+    }
+
+    #[test]
+    fn test_interval_items() {
+        let items = date_items("date days until dec 25", today());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "233 days (Fri, Dec 25 2026)");
+        assert_eq!(items[0].value, "233");
+    }
+
+    #[test]
+    fn test_non_date_queries() {
+        assert_eq!(date_items("date", today())[1].value, "2026-05-06");
+        assert!(date_items("date someday", today()).is_empty());
+        assert!(date_items("dates", today()).is_empty());
+        assert!(date_items("next friday", today()).is_empty());
+    }
+}
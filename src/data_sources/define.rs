@@ -0,0 +1,269 @@
+use crate::cache;
+use crate::core::{CommandItem, Handler};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+const DEFINE_COMMAND: &str = "define";
+const API_URL: &str = "https://api.dictionaryapi.dev/api/v2/entries/en/";
+const CACHE_NAMESPACE: &str = "definitions";
+const CACHE_TTL_DAYS: i64 = 30;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_SENSES: usize = 5;
+const LABEL_CHARS: usize = 100;
+
+const PARTS_OF_SPEECH: &[&str] = &[
+    "noun",
+    "verb",
+    "adjective",
+    "adverb",
+    "pronoun",
+    "preposition",
+    "conjunction",
+    "exclamation",
+    "interjection",
+];
+
+/// The meanings of a word: the first one for the result, all of them for
+/// the preview pane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Definition {
+    pub word: String,
+    pub part_of_speech: Option<String>,
+    pub first: String,
+    pub text: String,
+}
+
+/// The word or phrase of a `define <word>` query.
+pub fn parse_query(query: &str) -> Option<&str> {
+    let word = query.strip_prefix(DEFINE_COMMAND)?.strip_prefix(' ')?.trim();
+    let is_word = !word.is_empty() && word.chars().all(|c| c.is_alphabetic() || matches!(c, '\'' | '-' | ' '));
+    is_word.then_some(word)
+}
+
+/// A Free Dictionary API answer: a list of entries, each with meanings by
+/// part of speech.
+fn parse_dictionary_api(response: &Value) -> Option<Definition> {
+    let entries = response.as_array()?;
+    let word = entries.first()?.get("word")?.as_str()?.to_string();
+    let phonetic = entries
+        .iter()
+        .filter_map(|entry| entry.get("phonetic")?.as_str())
+        .find(|phonetic| !phonetic.is_empty());
+    let mut text = match phonetic {
+        Some(phonetic) => format!("{}  {}", word, phonetic),
+        None => word.clone(),
+    };
+    let mut first = None;
+    let meanings = entries.iter().filter_map(|entry| entry.get("meanings")?.as_array()).flatten();
+    for meaning in meanings {
+        let part_of_speech = meaning.get("partOfSpeech").and_then(Value::as_str).unwrap_or_default();
+        let Some(senses) = meaning.get("definitions").and_then(Value::as_array) else {
+            continue;
+        };
+        text.push_str(&format!("\n\n{}", part_of_speech));
+        for (index, sense) in senses.iter().take(MAX_SENSES).enumerate() {
+            let Some(definition) = sense.get("definition").and_then(Value::as_str) else {
+                continue;
+            };
+            first.get_or_insert_with(|| (part_of_speech.to_string(), definition.to_string()));
+            text.push_str(&format!("\n{}. {}", index + 1, definition));
+            if let Some(example) = sense.get("example").and_then(Value::as_str) {
+                text.push_str(&format!("\n   \"{}\"", example));
+            }
+        }
+    }
+    let (part_of_speech, first) = first?;
+    Some(Definition {
+        word,
+        part_of_speech: Some(part_of_speech).filter(|part| !part.is_empty()),
+        first,
+        text,
+    })
+}
+
+/// The plain text of the macOS dictionary, such as `word | pron | noun
+/// the meaning: an example. ORIGIN …`, on a few lines.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_dictionary_text(word: &str, text: &str) -> Option<Definition> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let body = text.split(" | ").last().unwrap_or(text).trim();
+    let (part_of_speech, sense) = match body.split_once(' ') {
+        Some((part, rest)) if PARTS_OF_SPEECH.contains(&part) => (Some(part.to_string()), rest),
+        _ => (None, body),
+    };
+    // Numbered senses start with `1 `
+    let sense = sense.strip_prefix("1 ").unwrap_or(sense);
+    let end = sense.find([':', '.', '▶']).unwrap_or(sense.len());
+    let mut preview = text.replace(" ▶", "\n▶");
+    for section in ["PHRASES", "DERIVATIVES", "ORIGIN"] {
+        preview = preview.replace(&format!(" {} ", section), &format!("\n\n{} ", section));
+    }
+    Some(Definition {
+        word: word.to_string(),
+        part_of_speech,
+        first: sense[..end].trim().to_string(),
+        text: preview,
+    })
+}
+
+/// The macOS dictionary's definition, through Dictionary Services.
+#[cfg(target_os = "macos")]
+async fn read_dictionary(word: &str) -> Option<Definition> {
+    let script = r#"
+        ObjC.import("CoreServices");
+        function run(argv) {
+            const word = argv[0];
+            const definition = $.DCSCopyTextDefinition(null, word, $.CFRangeMake(0, word.length));
+            return definition ? ObjC.castRefToObject(definition).js : "";
+        }
+    "#;
+    let output = tokio::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script, word])
+        .output()
+        .await
+        .ok()?;
+    parse_dictionary_text(word, &String::from_utf8_lossy(&output.stdout))
+}
+
+async fn fetch_definition(word: &str) -> Option<Definition> {
+    let key = word.to_lowercase();
+    let ttl = chrono::Duration::days(CACHE_TTL_DAYS);
+    if let Some(definition) = cache::get::<Definition>(CACHE_NAMESPACE, &key, ttl) {
+        return Some(definition);
+    }
+    let response = reqwest::Client::new()
+        .get(format!("{}{}", API_URL, urlencoding::encode(&key)))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .json::<Value>()
+        .await
+        .ok()?;
+    let definition = parse_dictionary_api(&response)?;
+    let _ = cache::put(CACHE_NAMESPACE, &key, &definition);
+    Some(definition)
+}
+
+/// `serendipity (noun): the occurrence…`. Enter opens the word in
+/// Dictionary.app when `open_dictionary`, and copies the first meaning
+/// otherwise.
+fn definition_item(definition: &Definition, open_dictionary: bool) -> CommandItem {
+    let mut first: String = definition.first.chars().take(LABEL_CHARS).collect();
+    if first.len() < definition.first.len() {
+        first.push('…');
+    }
+    let label = match &definition.part_of_speech {
+        Some(part) => format!("{} ({}): {}", definition.word, part, first),
+        None => format!("{}: {}", definition.word, first),
+    };
+    let mut item = match open_dictionary {
+        true => CommandItem::new(&label, Handler::Url, &format!("dict://{}", urlencoding::encode(&definition.word))),
+        false => CommandItem::new(&label, Handler::Copy, &definition.first),
+    };
+    item.icon = "📖".to_string();
+    item.metadata.insert("preview".to_string(), definition.text.clone());
+    item
+}
+
+/// The definition of `word`, from the macOS dictionary or else the Free
+/// Dictionary API.
+pub async fn get_define_items(word: &str) -> Vec<CommandItem> {
+    #[cfg(target_os = "macos")]
+    let definition = match read_dictionary(word).await {
+        Some(definition) => Some(definition),
+        None => fetch_definition(word).await,
+    };
+    #[cfg(not(target_os = "macos"))]
+    let definition = fetch_definition(word).await;
+
+    match definition {
+        Some(definition) => vec![definition_item(&definition, cfg!(target_os = "macos"))],
+        None => {
+            let mut item = CommandItem::new(&format!("No definition found for {}", word), Handler::Copy, word);
+            item.icon = "❓".to_string();
+            vec![item]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("define serendipity"), Some("serendipity"));
+        assert_eq!(parse_query("define ad hoc"), Some("ad hoc"));
+        assert_eq!(parse_query("define 42"), None);
+        assert_eq!(parse_query("defined"), None);
+        assert_eq!(parse_query("define "), None);
+    }
+
+    #[test]
+    fn test_parse_dictionary_api() {
+        let response = json!([
+            {
+                "word": "run",
+                "phonetic": "/ɹʌn/",
+                "meanings": [
+                    {"partOfSpeech": "verb", "definitions": [
+                        {"definition": "To move swiftly.", "example": "She ran home."},
+                        {"definition": "To flee."}
+                    ]}
+                ]
+            },
+            {
+                "word": "run",
+                "meanings": [{"partOfSpeech": "noun", "definitions": [{"definition": "An act of running."}]}]
+            }
+        ]);
+        let definition = parse_dictionary_api(&response).unwrap();
+        assert_eq!(definition.word, "run");
+        assert_eq!(definition.part_of_speech.as_deref(), Some("verb"));
+        assert_eq!(definition.first, "To move swiftly.");
+        assert_eq!(
+            definition.text,
+            "run  /ɹʌn/\n\nverb\n1. To move swiftly.\n   \"She ran home.\"\n2. To flee.\n\nnoun\n1. An act of running."
+        );
+        // Not found
+        assert!(parse_dictionary_api(&json!({"title": "No Definitions Found"})).is_none());
+    }
+
+    #[test]
+    fn test_parse_dictionary_text() {
+        let text = "serendipity | ˌserənˈdipədē | noun the occurrence of events by chance in a happy way: \
+                    a fortunate stroke of serendipity. ORIGIN 1754: coined by Horace Walpole.";
+        let definition = parse_dictionary_text("serendipity", text).unwrap();
+        assert_eq!(definition.part_of_speech.as_deref(), Some("noun"));
+        assert_eq!(definition.first, "the occurrence of events by chance in a happy way");
+        assert!(definition.text.ends_with("serendipity.\n\nORIGIN 1754: coined by Horace Walpole."));
+        assert!(parse_dictionary_text("xqzt", "\n").is_none());
+    }
+
+    #[test]
+    fn test_definition_item() {
+        let definition = Definition {
+            word: "run".to_string(),
+            part_of_speech: Some("verb".to_string()),
+            first: "To move swiftly.".to_string(),
+            text: "run\n\nverb\n1. To move swiftly.".to_string(),
+        };
+        let item = definition_item(&definition, false);
+        assert_eq!(item.label, "run (verb): To move swiftly.");
+        assert_eq!(item.handler, Handler::Copy);
+        assert_eq!(item.value, "To move swiftly.");
+        assert_eq!(item.metadata["preview"], definition.text);
+
+        let item = definition_item(&definition, true);
+        assert_eq!(item.handler, Handler::Url);
+        assert_eq!(item.value, "dict://run");
+    }
+}
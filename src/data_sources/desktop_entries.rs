@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+
+use crate::core::{CommandItem, CommandType, Handler};
+
+/// The subset of a freedesktop `.desktop` file Grinta cares about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    pub name: String,
+    pub exec: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// Directories holding `.desktop` files, in XDG precedence order
+/// (user entries shadow system ones with the same desktop id).
+pub fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    let data_home = env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+    if let Some(data_home) = data_home {
+        dirs.push(data_home.join("applications"));
+    }
+
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        dirs.push(PathBuf::from(dir).join("applications"));
+    }
+
+    // Flatpak exports its apps here, but only sessions started after the
+    // install have the directories in XDG_DATA_DIRS
+    let mut flatpak_dirs = vec![PathBuf::from("/var/lib/flatpak/exports/share/applications")];
+    if let Some(home) = dirs::home_dir() {
+        flatpak_dirs.insert(0, home.join(".local/share/flatpak/exports/share/applications"));
+    }
+    for dir in flatpak_dirs {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    dirs
+}
+
+/// Parse the `[Desktop Entry]` group of a `.desktop` file.
+/// Returns None for entries that should not be listed (hidden, NoDisplay,
+/// non-application types or missing names).
+pub fn parse_desktop_entry(contents: &str) -> Option<DesktopEntry> {
+    parse_entry(contents, false)
+}
+
+/// Like `parse_desktop_entry`, keeping `NoDisplay` entries such as the
+/// settings panels launched by the desktop's settings app.
+pub fn parse_hidden_desktop_entry(contents: &str) -> Option<DesktopEntry> {
+    parse_entry(contents, true)
+}
+
+fn parse_entry(contents: &str, include_no_display: bool) -> Option<DesktopEntry> {
+    let mut in_main_group = false;
+    let mut name = None;
+    let mut exec = None;
+    let mut icon = None;
+    let mut entry_type = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        // Localized keys such as `Name[de]` are ignored in favour of the default
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Exec" => exec = Some(value.trim().to_string()),
+            "Icon" => icon = Some(value.trim().to_string()),
+            "Type" => entry_type = Some(value.trim().to_string()),
+            "NoDisplay" if value.trim() == "true" && !include_no_display => return None,
+            "Hidden" if value.trim() == "true" => return None,
+            _ => {}
+        }
+    }
+
+    if entry_type.as_deref() != Some("Application") {
+        return None;
+    }
+
+    Some(DesktopEntry {
+        name: name.filter(|n| !n.is_empty())?,
+        exec: exec.map(|e| strip_field_codes(&e)),
+        icon,
+    })
+}
+
+/// Remove `%f`, `%U`, ... placeholders from an Exec line for display.
+fn strip_field_codes(exec: &str) -> String {
+    exec.split_whitespace()
+        .filter(|arg| !(arg.len() == 2 && arg.starts_with('%')))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// List applications declared in `.desktop` files across the XDG data dirs.
+/// The item value is the path of the `.desktop` file, which is what
+/// `gio launch` expects.
+pub async fn get_linux_applications() -> Vec<CommandItem> {
+    let mut apps = Vec::new();
+    let mut seen_ids = HashSet::new();
+
+    for dir in application_dirs() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("desktop") {
+                continue;
+            }
+            let Some(id) = path.file_name().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            // Earlier directories take precedence, even when the entry is hidden
+            if !seen_ids.insert(id) {
+                continue;
+            }
+            let Ok(contents) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            if let Some(desktop_entry) = parse_desktop_entry(&contents) {
+                let path_str = path.to_str().unwrap_or("");
+                let mut item = CommandItem::new(&desktop_entry.name, Handler::App, path_str);
+                item.kind = CommandType::App;
+                if let Some(exec) = desktop_entry.exec {
+                    item.metadata.insert("exec".to_string(), exec);
+                }
+                if let Some(icon) = desktop_entry.icon {
+                    item.metadata.insert("icon".to_string(), icon);
+                }
+                apps.push(item);
+            }
+        }
+    }
+
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_basic_entry() {
+        let contents = "[Desktop Entry]\nType=Application\nName=Firefox\nName[de]=Feuerfuchs\nExec=firefox %u\nIcon=firefox\n";
+        let entry = parse_desktop_entry(contents).unwrap();
+
+        assert_eq!(entry.name, "Firefox");
+        assert_eq!(entry.exec.as_deref(), Some("firefox"));
+        assert_eq!(entry.icon.as_deref(), Some("firefox"));
+    }
+
+    #[test]
+    fn test_parse_skips_no_display_and_hidden() {
+        let no_display = "[Desktop Entry]\nType=Application\nName=Helper\nNoDisplay=true\n";
+        let hidden = "[Desktop Entry]\nType=Application\nName=Gone\nHidden=true\n";
+
+        assert!(parse_desktop_entry(no_display).is_none());
+        assert!(parse_desktop_entry(hidden).is_none());
+    }
+
+    #[test]
+    fn test_parse_ignores_other_groups_and_types() {
+        let action_group = "[Desktop Entry]\nType=Application\nName=Editor\n\n[Desktop Action new]\nName=New Window\nNoDisplay=true\n";
+        let link = "[Desktop Entry]\nType=Link\nName=Homepage\nURL=https://example.com\n";
+
+        assert_eq!(parse_desktop_entry(action_group).unwrap().name, "Editor");
+        assert!(parse_desktop_entry(link).is_none());
+    }
+
+    #[test]
+    fn test_parse_hidden_entry() {
+        let panel = "[Desktop Entry]\nType=Application\nName=Wi-Fi\nExec=gnome-control-center wifi\nNoDisplay=true\n";
+        assert!(parse_desktop_entry(panel).is_none());
+        assert_eq!(parse_hidden_desktop_entry(panel).unwrap().name, "Wi-Fi");
+        assert!(parse_hidden_desktop_entry("[Desktop Entry]\nType=Application\nName=Gone\nHidden=true\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_requires_name() {
+        assert!(parse_desktop_entry("[Desktop Entry]\nType=Application\nExec=foo\n").is_none());
+    }
+
+    #[test]
+    fn test_strip_field_codes() {
+        assert_eq!(strip_field_codes("code --new-window %F"), "code --new-window");
+        assert_eq!(strip_field_codes("env FOO=1 app %u %i"), "env FOO=1 app");
+    }
+}
@@ -0,0 +1,364 @@
+use crate::core::{CommandItem, Handler};
+
+const MAX_RESULTS: usize = 30;
+
+/// Bundled emoji and symbols: character, name and extra search words.
+const EMOJI: &[(&str, &str, &str)] = &[
+    ("😀", "grinning face", "smile happy"),
+    ("😃", "smiling face with open mouth", "smile happy joy"),
+    ("😄", "smiling face with smiling eyes", "smile happy laugh"),
+    ("😁", "beaming face", "grin smile teeth"),
+    ("😆", "laughing face", "lol haha"),
+    ("😅", "grinning face with sweat", "relief phew"),
+    ("😂", "face with tears of joy", "lol laugh crying"),
+    ("🤣", "rolling on the floor laughing", "rofl lol"),
+    ("🙂", "slightly smiling face", "smile"),
+    ("🙃", "upside-down face", "sarcasm silly"),
+    ("😉", "winking face", "wink"),
+    ("😊", "smiling face with blush", "blush happy"),
+    ("😇", "smiling face with halo", "angel innocent"),
+    ("🥰", "smiling face with hearts", "love crush"),
+    ("😍", "heart eyes", "love crush"),
+    ("😘", "face blowing a kiss", "kiss love"),
+    ("😋", "face savoring food", "yum tasty"),
+    ("😛", "face with tongue", "tongue silly"),
+    ("😜", "winking face with tongue", "silly joke"),
+    ("🤪", "zany face", "crazy goofy"),
+    ("🤔", "thinking face", "hmm think"),
+    ("🤨", "face with raised eyebrow", "skeptical suspicious"),
+    ("😐", "neutral face", "meh"),
+    ("😑", "expressionless face", "blank"),
+    ("😶", "face without mouth", "silent speechless"),
+    ("🙄", "face with rolling eyes", "eyeroll whatever"),
+    ("😏", "smirking face", "smirk"),
+    ("😬", "grimacing face", "awkward yikes"),
+    ("😌", "relieved face", "calm"),
+    ("😴", "sleeping face", "sleep tired zzz"),
+    ("🤤", "drooling face", "drool"),
+    ("😷", "face with medical mask", "sick mask"),
+    ("🤒", "face with thermometer", "sick ill"),
+    ("🤯", "exploding head", "mind blown shocked"),
+    ("🥳", "partying face", "party celebrate"),
+    ("😎", "smiling face with sunglasses", "cool"),
+    ("🤓", "nerd face", "geek glasses"),
+    ("😕", "confused face", "confused"),
+    ("😟", "worried face", "worry"),
+    ("😮", "face with open mouth", "surprised wow"),
+    ("😲", "astonished face", "shocked wow"),
+    ("😳", "flushed face", "embarrassed"),
+    ("🥺", "pleading face", "puppy eyes please"),
+    ("😢", "crying face", "sad tear"),
+    ("😭", "loudly crying face", "sob sad"),
+    ("😱", "face screaming in fear", "scream scared"),
+    ("😤", "face with steam from nose", "triumph frustrated"),
+    ("😡", "pouting face", "angry mad rage"),
+    ("🤬", "face with symbols on mouth", "swearing cursing"),
+    ("💀", "skull", "dead dying"),
+    ("💩", "pile of poo", "poop"),
+    ("🤡", "clown face", "clown"),
+    ("👻", "ghost", "halloween boo"),
+    ("👽", "alien", "ufo"),
+    ("🤖", "robot", "bot ai"),
+    ("🙈", "see-no-evil monkey", "monkey shy"),
+    ("👋", "waving hand", "wave hello bye"),
+    ("👌", "ok hand", "okay perfect"),
+    ("✌️", "victory hand", "peace"),
+    ("🤞", "crossed fingers", "luck hope"),
+    ("👍", "thumbs up", "like yes approve +1"),
+    ("👎", "thumbs down", "dislike no -1"),
+    ("👏", "clapping hands", "clap applause bravo"),
+    ("🙌", "raising hands", "hooray celebrate"),
+    ("🙏", "folded hands", "please thanks pray"),
+    ("🤝", "handshake", "deal agreement"),
+    ("💪", "flexed biceps", "strong muscle"),
+    ("👀", "eyes", "look see watching"),
+    ("🧠", "brain", "smart think"),
+    ("❤️", "red heart", "love"),
+    ("🧡", "orange heart", "love"),
+    ("💛", "yellow heart", "love"),
+    ("💚", "green heart", "love"),
+    ("💙", "blue heart", "love"),
+    ("💜", "purple heart", "love"),
+    ("🖤", "black heart", "love"),
+    ("💔", "broken heart", "sad heartbreak"),
+    ("💯", "hundred points", "100 perfect score"),
+    ("💥", "collision", "boom explosion"),
+    ("💫", "dizzy", "star"),
+    ("💬", "speech balloon", "comment chat message"),
+    ("💤", "zzz", "sleep"),
+    ("🔥", "fire", "lit hot flame"),
+    ("✨", "sparkles", "shiny magic new"),
+    ("⭐", "star", "favorite"),
+    ("🌟", "glowing star", "shine"),
+    ("⚡", "high voltage", "lightning zap fast"),
+    ("☀️", "sun", "sunny weather"),
+    ("🌙", "crescent moon", "night"),
+    ("☁️", "cloud", "weather"),
+    ("🌧️", "cloud with rain", "rain weather"),
+    ("❄️", "snowflake", "snow cold winter"),
+    ("🌈", "rainbow", "pride"),
+    ("🌊", "water wave", "ocean sea"),
+    ("🌍", "globe europe africa", "earth world"),
+    ("🌸", "cherry blossom", "flower spring"),
+    ("🌹", "rose", "flower"),
+    ("🌻", "sunflower", "flower"),
+    ("🌱", "seedling", "plant grow"),
+    ("🌲", "evergreen tree", "tree forest"),
+    ("🍀", "four leaf clover", "luck"),
+    ("🍁", "maple leaf", "autumn fall"),
+    ("🐶", "dog face", "puppy pet"),
+    ("🐱", "cat face", "kitten pet"),
+    ("🐭", "mouse face", "animal"),
+    ("🦊", "fox", "animal"),
+    ("🐻", "bear", "animal"),
+    ("🐼", "panda", "animal"),
+    ("🐨", "koala", "animal"),
+    ("🦁", "lion", "animal"),
+    ("🐸", "frog", "animal"),
+    ("🐵", "monkey face", "animal"),
+    ("🐔", "chicken", "animal"),
+    ("🐧", "penguin", "animal linux"),
+    ("🐦", "bird", "animal"),
+    ("🦄", "unicorn", "magic"),
+    ("🐝", "honeybee", "bee insect"),
+    ("🐛", "bug", "insect debug"),
+    ("🦀", "crab", "rust ferris"),
+    ("🐍", "snake", "python"),
+    ("🐳", "spouting whale", "docker"),
+    ("🐙", "octopus", "github"),
+    ("🍎", "red apple", "fruit"),
+    ("🍌", "banana", "fruit"),
+    ("🍓", "strawberry", "fruit"),
+    ("🍋", "lemon", "fruit"),
+    ("🥑", "avocado", "fruit"),
+    ("🍕", "pizza", "food"),
+    ("🍔", "hamburger", "burger food"),
+    ("🍟", "french fries", "food"),
+    ("🌮", "taco", "food"),
+    ("🍣", "sushi", "food"),
+    ("🍜", "steaming bowl", "ramen noodles"),
+    ("🍰", "shortcake", "cake dessert"),
+    ("🎂", "birthday cake", "birthday party"),
+    ("🍩", "doughnut", "donut dessert"),
+    ("🍪", "cookie", "dessert"),
+    ("☕", "hot beverage", "coffee tea"),
+    ("🍵", "teacup", "tea"),
+    ("🍺", "beer mug", "beer drink"),
+    ("🍷", "wine glass", "wine drink"),
+    ("🥂", "clinking glasses", "cheers toast"),
+    ("🎉", "party popper", "tada celebrate congrats"),
+    ("🎊", "confetti ball", "celebrate"),
+    ("🎁", "wrapped gift", "present birthday"),
+    ("🎈", "balloon", "party"),
+    ("🏆", "trophy", "win award prize"),
+    ("🥇", "first place medal", "gold winner"),
+    ("⚽", "soccer ball", "football sport"),
+    ("🏀", "basketball", "sport"),
+    ("🎮", "video game", "controller gaming"),
+    ("🎯", "direct hit", "target bullseye goal"),
+    ("🎲", "game die", "dice random"),
+    ("🎵", "musical note", "music"),
+    ("🎧", "headphone", "music listen"),
+    ("🎬", "clapper board", "movie film"),
+    ("📷", "camera", "photo"),
+    ("📱", "mobile phone", "iphone smartphone"),
+    ("💻", "laptop", "computer"),
+    ("🖥️", "desktop computer", "monitor"),
+    ("⌨️", "keyboard", "typing"),
+    ("🖱️", "computer mouse", "click"),
+    ("💾", "floppy disk", "save"),
+    ("📁", "file folder", "directory"),
+    ("📄", "page facing up", "document file"),
+    ("📝", "memo", "note write"),
+    ("📌", "pushpin", "pin"),
+    ("📎", "paperclip", "attachment"),
+    ("📅", "calendar", "date"),
+    ("📈", "chart increasing", "graph up growth"),
+    ("📉", "chart decreasing", "graph down"),
+    ("📊", "bar chart", "graph stats"),
+    ("📦", "package", "box shipping"),
+    ("📧", "e-mail", "email mail"),
+    ("📣", "megaphone", "announcement"),
+    ("🔔", "bell", "notification"),
+    ("🔒", "locked", "lock secure"),
+    ("🔓", "unlocked", "unlock open"),
+    ("🔑", "key", "password"),
+    ("🔍", "magnifying glass", "search find"),
+    ("🔗", "link", "url chain"),
+    ("🔧", "wrench", "tool fix settings"),
+    ("🔨", "hammer", "tool build"),
+    ("⚙️", "gear", "settings cog"),
+    ("🧪", "test tube", "experiment test"),
+    ("💡", "light bulb", "idea"),
+    ("🔋", "battery", "power"),
+    ("🔌", "electric plug", "power"),
+    ("💰", "money bag", "cash rich"),
+    ("💸", "money with wings", "spend"),
+    ("💳", "credit card", "payment"),
+    ("⏰", "alarm clock", "time wake"),
+    ("⏳", "hourglass", "time wait"),
+    ("🚀", "rocket", "launch ship deploy"),
+    ("✈️", "airplane", "travel flight"),
+    ("🚗", "car", "drive"),
+    ("🚲", "bicycle", "bike"),
+    ("🏠", "house", "home"),
+    ("🏢", "office building", "work"),
+    ("🚧", "construction", "wip"),
+    ("🚨", "police light", "alert siren"),
+    ("🛑", "stop sign", "stop"),
+    ("⚠️", "warning", "caution alert"),
+    ("⛔", "no entry", "forbidden"),
+    ("🚫", "prohibited", "forbidden no"),
+    ("✅", "check mark button", "done yes ok"),
+    ("☑️", "check box with check", "done"),
+    ("❌", "cross mark", "no wrong x"),
+    ("❓", "question mark", "question"),
+    ("❗", "exclamation mark", "important"),
+    ("➕", "plus", "add"),
+    ("➖", "minus", "subtract"),
+    ("🆗", "ok button", "okay"),
+    ("🆕", "new button", "new"),
+    ("🔴", "red circle", "dot"),
+    ("🟢", "green circle", "dot"),
+    ("🔵", "blue circle", "dot"),
+    ("🏁", "chequered flag", "finish race"),
+    ("🚩", "triangular flag", "flag"),
+    ("🏳️‍🌈", "rainbow flag", "pride"),
+    ("✓", "check mark", "tick done symbol"),
+    ("✗", "ballot x", "cross symbol"),
+    ("•", "bullet", "dot symbol"),
+    ("·", "middle dot", "interpunct symbol"),
+    ("…", "ellipsis", "dots symbol"),
+    ("—", "em dash", "dash symbol"),
+    ("–", "en dash", "dash range symbol"),
+    ("→", "rightwards arrow", "arrow right symbol"),
+    ("←", "leftwards arrow", "arrow left symbol"),
+    ("↑", "upwards arrow", "arrow up symbol"),
+    ("↓", "downwards arrow", "arrow down symbol"),
+    ("↔", "left right arrow", "arrow symbol"),
+    ("⇒", "rightwards double arrow", "implies arrow symbol"),
+    ("↩", "return arrow", "enter symbol"),
+    ("⌘", "command key", "cmd mac symbol"),
+    ("⌥", "option key", "alt mac symbol"),
+    ("⇧", "shift key", "mac symbol"),
+    ("⌃", "control key", "ctrl mac symbol"),
+    ("⎋", "escape key", "esc symbol"),
+    ("⌫", "delete key", "backspace symbol"),
+    ("©", "copyright sign", "symbol"),
+    ("®", "registered sign", "symbol"),
+    ("™", "trade mark sign", "tm symbol"),
+    ("§", "section sign", "paragraph symbol"),
+    ("¶", "pilcrow sign", "paragraph symbol"),
+    ("°", "degree sign", "temperature symbol"),
+    ("±", "plus-minus sign", "math symbol"),
+    ("×", "multiplication sign", "times math symbol"),
+    ("÷", "division sign", "divide math symbol"),
+    ("≈", "almost equal to", "approximately math symbol"),
+    ("≠", "not equal to", "math symbol"),
+    ("≤", "less-than or equal to", "math symbol"),
+    ("≥", "greater-than or equal to", "math symbol"),
+    ("∞", "infinity", "math symbol"),
+    ("√", "square root", "math symbol"),
+    ("∑", "n-ary summation", "sum sigma math symbol"),
+    ("π", "greek small letter pi", "pi math symbol"),
+    ("µ", "micro sign", "mu symbol"),
+    ("Δ", "greek capital letter delta", "delta change symbol"),
+    ("€", "euro sign", "currency money symbol"),
+    ("£", "pound sign", "currency money symbol"),
+    ("¥", "yen sign", "currency money symbol"),
+    ("₿", "bitcoin sign", "currency crypto symbol"),
+    ("¢", "cent sign", "currency symbol"),
+    ("½", "vulgar fraction one half", "half symbol"),
+    ("¼", "vulgar fraction one quarter", "quarter symbol"),
+    ("¾", "vulgar fraction three quarters", "symbol"),
+    ("‰", "per mille sign", "permille symbol"),
+    ("«", "left guillemet", "quote symbol"),
+    ("»", "right guillemet", "quote symbol"),
+    ("„", "double low quotation mark", "quote german symbol"),
+    ("“", "left double quotation mark", "quote symbol"),
+    ("”", "right double quotation mark", "quote symbol"),
+    ("♥", "heart suit", "love symbol"),
+    ("★", "black star", "star symbol"),
+    ("☆", "white star", "star symbol"),
+    ("♪", "eighth note", "music symbol"),
+    ("☐", "ballot box", "checkbox symbol"),
+    ("☑", "ballot box with check", "checkbox symbol"),
+    ("¯\\_(ツ)_/¯", "shrug", "kaomoji whatever"),
+];
+
+/// The filter of an `emoji [words]` or `:words` query.
+pub fn parse_query(query: &str) -> Option<&str> {
+    if query == "emoji" {
+        return Some("");
+    }
+    if let Some(filter) = query.strip_prefix("emoji ") {
+        return Some(filter.trim());
+    }
+    let filter = query.strip_prefix(':')?;
+    // `:` alone, or `:)`-style text, is not a search
+    (filter.chars().next().is_some_and(char::is_alphanumeric)).then_some(filter.trim())
+}
+
+/// Whether every word of the filter starts a word of the name or keywords.
+fn matches(name: &str, keywords: &str, filter: &str) -> bool {
+    filter.split_whitespace().all(|wanted| {
+        name.split([' ', '-'])
+            .chain(keywords.split(' '))
+            .any(|word| word.starts_with(wanted))
+    })
+}
+
+/// Emoji and symbols matching the filter, exact names first. Enter copies
+/// the character.
+pub fn emoji_items(query: &str) -> Vec<CommandItem> {
+    let Some(filter) = parse_query(query) else {
+        return Vec::new();
+    };
+    let filter = filter.to_lowercase();
+    let mut matched: Vec<&(&str, &str, &str)> = EMOJI
+        .iter()
+        .filter(|(_, name, keywords)| matches(name, keywords, &filter))
+        .collect();
+    // Stable, so the table order breaks ties
+    matched.sort_by_key(|(_, name, _)| *name != filter);
+    matched
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(character, name, _)| {
+            let mut item = CommandItem::new(&format!("{}  {}", character, name), Handler::Copy, character);
+            item.icon = "😀".to_string();
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("emoji"), Some(""));
+        assert_eq!(parse_query("emoji heart"), Some("heart"));
+        assert_eq!(parse_query(":fire"), Some("fire"));
+        assert_eq!(parse_query(":)"), None);
+        assert_eq!(parse_query(":"), None);
+        assert_eq!(parse_query("emojis"), None);
+    }
+
+    #[test]
+    fn test_emoji_items() {
+        let items = emoji_items(":thumbs up");
+        assert_eq!(items[0].value, "👍");
+        assert_eq!(items[0].handler, Handler::Copy);
+
+        // Keywords match too, exact names rank first
+        let items = emoji_items("emoji rocket");
+        assert_eq!(items[0].value, "🚀");
+        assert!(emoji_items("emoji deploy").iter().any(|item| item.value == "🚀"));
+
+        assert!(emoji_items(":cmd").iter().any(|item| item.value == "⌘"));
+        assert_eq!(emoji_items("emoji").len(), MAX_RESULTS);
+        assert!(emoji_items("emoji zzzzqx").is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "feeds";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A podcast, YouTube channel or playlist whose latest episodes are
+/// listed. Declared under `[[feeds]]` with one of `url`,
+/// `youtube_channel` or `youtube_playlist`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedConfig {
+    /// Shown after each episode's title
+    pub name: String,
+    /// RSS or Atom feed, such as a podcast's
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Channel id (`UC…`) of a YouTube channel
+    #[serde(default)]
+    pub youtube_channel: Option<String>,
+    #[serde(default)]
+    pub youtube_playlist: Option<String>,
+    /// Media app episodes open in, such as `IINA` or `mpv`, instead of the
+    /// browser
+    #[serde(default)]
+    pub app: Option<String>,
+    /// How many of the latest episodes are listed
+    #[serde(default = "default_episodes")]
+    pub episodes: usize,
+    /// How long a fetched feed is used before fetching it again
+    #[serde(default = "default_refresh_minutes")]
+    pub refresh_minutes: i64,
+}
+
+fn default_episodes() -> usize {
+    1
+}
+
+fn default_refresh_minutes() -> i64 {
+    60
+}
+
+impl FeedConfig {
+    /// The feed's address, YouTube's own feeds for channels and playlists.
+    pub fn feed_url(&self) -> Option<String> {
+        if let Some(url) = &self.url {
+            return Some(url.clone());
+        }
+        if let Some(channel) = &self.youtube_channel {
+            return Some(format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", channel));
+        }
+        self.youtube_playlist
+            .as_ref()
+            .map(|playlist| format!("https://www.youtube.com/feeds/videos.xml?playlist_id={}", playlist))
+    }
+
+    fn is_youtube(&self) -> bool {
+        self.youtube_channel.is_some()
+            || self.youtube_playlist.is_some()
+            || self.url.as_deref().is_some_and(|url| url.contains("youtube.com/"))
+    }
+}
+
+/// An entry of a feed, newest first as feeds list them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Episode {
+    pub title: String,
+    /// The episode's web page
+    pub link: Option<String>,
+    /// The audio or video file of a podcast episode
+    pub media: Option<String>,
+}
+
+/// `text` with XML entities and CDATA sections resolved.
+fn decode(text: &str) -> String {
+    let text = text.trim();
+    if let Some(data) = text.strip_prefix("<![CDATA[").and_then(|rest| rest.strip_suffix("]]>")) {
+        return data.trim().to_string();
+    }
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = after;
+            continue;
+        };
+        let entity = &after[..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &after[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The start of each `<tag …>` in `xml`, as the index of `<` and of the
+/// character after `>`. `<tag` followed by more letters is another tag.
+fn open_tags(xml: &str, tag: &str) -> Vec<(usize, usize)> {
+    let open = format!("<{}", tag);
+    xml.match_indices(&open)
+        .map(|(start, _)| start)
+        .filter(|start| {
+            xml[start + open.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c == '>' || c == '/' || c.is_whitespace())
+        })
+        .filter_map(|start| Some((start, start + xml[start..].find('>')? + 1)))
+        .collect()
+}
+
+/// The contents of each `<tag>…</tag>` in `xml`.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let close = format!("</{}>", tag);
+    open_tags(xml, tag)
+        .into_iter()
+        .filter(|(start, end)| !xml[*start..*end].ends_with("/>"))
+        .filter_map(|(_, end)| Some(&xml[end..end + xml[end..].find(&close)?]))
+        .collect()
+}
+
+/// The value of `name="…"` in an opening tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {}=", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let quote = tag[start..].chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &tag[start + 1..];
+    Some(decode(&value[..value.find(quote)?]))
+}
+
+/// The text of the first `<tag>` in `xml`.
+fn text(xml: &str, tag: &str) -> Option<String> {
+    elements(xml, tag).first().map(|text| decode(text)).filter(|text| !text.is_empty())
+}
+
+/// The entries of an RSS or Atom feed.
+pub fn parse_feed(xml: &str) -> Vec<Episode> {
+    let rss = elements(xml, "item");
+    let entries = if rss.is_empty() { elements(xml, "entry") } else { rss };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let title = text(entry, "title")?;
+            let links: Vec<&str> = open_tags(entry, "link").into_iter().map(|(start, end)| &entry[start..end]).collect();
+            // Atom links are attributes, RSS links are text
+            let link = links
+                .iter()
+                .filter(|tag| attribute(tag, "rel").is_none_or(|rel| rel == "alternate"))
+                .find_map(|tag| attribute(tag, "href"))
+                .or_else(|| text(entry, "link"));
+            let media = open_tags(entry, "enclosure").into_iter().find_map(|(start, end)| attribute(&entry[start..end], "url"));
+            Some(Episode { title, link, media })
+        })
+        .collect()
+}
+
+fn episode_item(feed: &FeedConfig, episode: &Episode) -> Option<CommandItem> {
+    // A media app plays the file, the browser shows the page
+    let target = match &feed.app {
+        Some(_) => episode.media.as_ref().or(episode.link.as_ref()),
+        None => episode.link.as_ref().or(episode.media.as_ref()),
+    }?;
+    let mut item = CommandItem::new(&format!("{} ({})", episode.title, feed.name), Handler::Url, target);
+    item.icon = if feed.is_youtube() { "▶️" } else { "🎙️" }.to_string();
+    item.metadata.insert("keywords".to_string(), "latest episode".to_string());
+    if let Some(app) = &feed.app {
+        item.metadata.insert("open_with".to_string(), app.clone());
+    }
+    // Configured like the config's own items
+    item.metadata.insert("custom".to_string(), "true".to_string());
+    Some(item)
+}
+
+async fn fetch(url: &str) -> Result<Vec<Episode>> {
+    let xml = reqwest::Client::new()
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_feed(&xml))
+}
+
+/// The latest episodes of every feed, from the last copy fetched by
+/// `refresh_feeds`, however old.
+pub fn get_feed_items() -> Vec<CommandItem> {
+    let mut items = Vec::new();
+    for feed in &config::get().feeds {
+        let Some(url) = feed.feed_url() else {
+            continue;
+        };
+        let Some((episodes, _)) = cache::get_stale::<Vec<Episode>>(CACHE_NAMESPACE, &url) else {
+            continue;
+        };
+        items.extend(
+            episodes
+                .iter()
+                .take(feed.episodes)
+                .filter_map(|episode| episode_item(feed, episode)),
+        );
+    }
+    items
+}
+
+/// Fetch, all at once, the feeds whose cached copy is older than their
+/// refresh interval. Returns whether any copy was updated; a feed that
+/// can't be fetched keeps its last copy.
+pub async fn refresh_feeds() -> bool {
+    let mut fetches = tokio::task::JoinSet::new();
+    for feed in &config::get().feeds {
+        let Some(url) = feed.feed_url() else {
+            continue;
+        };
+        let ttl = chrono::Duration::minutes(feed.refresh_minutes);
+        if cache::get::<Vec<Episode>>(CACHE_NAMESPACE, &url, ttl).is_some() {
+            continue;
+        }
+        fetches.spawn(async move {
+            let episodes = fetch(&url).await.ok()?;
+            Some((url, episodes))
+        });
+    }
+    // Written one at a time, as each write rewrites the whole namespace
+    let mut updated = false;
+    while let Some(fetched) = fetches.join_next().await {
+        if let Ok(Some((url, episodes))) = fetched {
+            updated |= cache::put(CACHE_NAMESPACE, &url, &episodes).is_ok();
+        }
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PODCAST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+<channel>
+  <title>Rustacean Station</title>
+  <link>https://rustacean-station.org</link>
+  <item>
+    <title><![CDATA[Episode 12: Async & you]]></title>
+    <itunes:title>Async</itunes:title>
+    <link>https://rustacean-station.org/episode/12</link>
+    <enclosure url="https://cdn.example.com/12.mp3?a=1&amp;b=2" length="1" type="audio/mpeg"/>
+  </item>
+  <item>
+    <title>Episode 11: Tom &amp; Jerry&#39;s &#x1F980;</title>
+    <enclosure url='https://cdn.example.com/11.mp3' type="audio/mpeg" />
+  </item>
+</channel>
+</rss>"#;
+
+    const YOUTUBE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom">
+ <link rel="self" href="http://www.youtube.com/feeds/videos.xml?channel_id=UC1"/>
+ <title>Rust</title>
+ <entry>
+  <id>yt:video:abc</id>
+  <title>RustConf keynote</title>
+  <link rel="alternate" href="https://www.youtube.com/watch?v=abc"/>
+  <media:group>
+   <media:title>RustConf keynote</media:title>
+  </media:group>
+ </entry>
+</feed>"#;
+
+    fn feed(name: &str) -> FeedConfig {
+        FeedConfig {
+            name: name.to_string(),
+            url: None,
+            youtube_channel: None,
+            youtube_playlist: None,
+            app: None,
+            episodes: default_episodes(),
+            refresh_minutes: default_refresh_minutes(),
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(" a &amp; b &lt;c&gt; &#233;&#xE9; "), "a & b <c> éé");
+        assert_eq!(decode("<![CDATA[ x &amp; y ]]>"), "x &amp; y");
+        assert_eq!(decode("AT&T; R&D"), "AT&T; R&D");
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let episodes = parse_feed(PODCAST);
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].title, "Episode 12: Async & you");
+        assert_eq!(episodes[0].link.as_deref(), Some("https://rustacean-station.org/episode/12"));
+        assert_eq!(episodes[0].media.as_deref(), Some("https://cdn.example.com/12.mp3?a=1&b=2"));
+        assert_eq!(episodes[1].title, "Episode 11: Tom & Jerry's 🦀");
+        assert_eq!(episodes[1].link, None);
+        assert_eq!(episodes[1].media.as_deref(), Some("https://cdn.example.com/11.mp3"));
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let episodes = parse_feed(YOUTUBE);
+        assert_eq!(
+            episodes,
+            vec![Episode {
+                title: "RustConf keynote".to_string(),
+                link: Some("https://www.youtube.com/watch?v=abc".to_string()),
+                media: None,
+            }]
+        );
+        assert!(parse_feed("<html>Not found</html>").is_empty());
+    }
+
+    #[test]
+    fn test_feed_url() {
+        let channel = FeedConfig { youtube_channel: Some("UC1".to_string()), ..feed("Rust") };
+        assert_eq!(
+            channel.feed_url().as_deref(),
+            Some("https://www.youtube.com/feeds/videos.xml?channel_id=UC1")
+        );
+        assert!(channel.is_youtube());
+        let podcast = FeedConfig { url: Some("https://example.com/rss".to_string()), ..feed("Pod") };
+        assert_eq!(podcast.feed_url().as_deref(), Some("https://example.com/rss"));
+        assert!(!podcast.is_youtube());
+        assert_eq!(feed("None").feed_url(), None);
+    }
+
+    #[test]
+    fn test_episode_item() {
+        let episodes = parse_feed(PODCAST);
+        let podcast = FeedConfig { url: Some("https://example.com/rss".to_string()), ..feed("Rustacean Station") };
+
+        let item = episode_item(&podcast, &episodes[0]).unwrap();
+        assert_eq!(item.label, "Episode 12: Async & you (Rustacean Station)");
+        assert_eq!(item.value, "https://rustacean-station.org/episode/12");
+        assert!(!item.metadata.contains_key("open_with"));
+        // Without a page, the browser gets the file
+        assert_eq!(episode_item(&podcast, &episodes[1]).unwrap().value, "https://cdn.example.com/11.mp3");
+
+        let with_app = FeedConfig { app: Some("IINA".to_string()), ..podcast };
+        let item = episode_item(&with_app, &episodes[0]).unwrap();
+        assert_eq!(item.value, "https://cdn.example.com/12.mp3?a=1&b=2");
+        assert_eq!(item.metadata["open_with"], "IINA");
+    }
+}
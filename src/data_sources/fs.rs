@@ -1,17 +1,106 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use std::time::Duration;
 use tokio::process::Command;
+use tokio::sync::mpsc;
 use tokio::time::timeout;
 
 use crate::core::{CommandItem, Handler};
+#[cfg(target_os = "windows")]
+use crate::data_sources::windows;
 
 /// Reduced debounce for better responsiveness
 #[allow(dead_code)]
 const DEBOUNCE_MS: u64 = 150;
 
-/// Timeout for mdfind operations to ensure reliability
+/// Timeout for mdfind (and the non-macOS search backends) to ensure reliability
 const MDFIND_TIMEOUT_MS: u64 = 2000;
 
+/// Interval between the partial results sent while a search streams
+#[cfg(any(target_os = "macos", test))]
+const STREAM_UPDATE_MS: u64 = 50;
+
+/// Upper bound on entries visited by the fallback directory walker
+const WALK_MAX_ENTRIES: usize = 50_000;
+
+/// Maximum depth below the home directory explored by the fallback walker
+const WALK_MAX_DEPTH: usize = 8;
+
+/// Folders of the home directory walked when Spotlight can't search
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const FALLBACK_DIRS: [&str; 8] = [
+    "Desktop",
+    "Documents",
+    "Downloads",
+    "Developer",
+    "Projects",
+    "Pictures",
+    "Movies",
+    "Music",
+];
+
+/// Whether Spotlight can answer file searches, as reported by `mdutil -s /`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum SpotlightStatus {
+    Enabled,
+    Disabled,
+    /// mdutil reports an unknown state while the index is being built
+    Indexing,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl SpotlightStatus {
+    /// Status from `mdutil -s` output, such as `/:\n\tIndexing enabled.`
+    pub fn parse(output: &str) -> SpotlightStatus {
+        let output = output.to_lowercase();
+        if output.contains("disabled") {
+            SpotlightStatus::Disabled
+        } else if output.contains("enabled") {
+            SpotlightStatus::Enabled
+        } else {
+            SpotlightStatus::Indexing
+        }
+    }
+}
+
+/// Last status found by `check_spotlight`; searches use mdfind until then.
+static SPOTLIGHT_STATUS: RwLock<SpotlightStatus> = RwLock::new(SpotlightStatus::Enabled);
+
+fn spotlight_status() -> SpotlightStatus {
+    SPOTLIGHT_STATUS.read().map_or(SpotlightStatus::Enabled, |status| *status)
+}
+
+/// Ask mdutil whether Spotlight indexes the startup disk, so file search
+/// falls back to walking common folders when it doesn't.
+#[cfg(target_os = "macos")]
+pub async fn check_spotlight() -> SpotlightStatus {
+    let mdutil = Command::new("mdutil").args(["-s", "/"]).output();
+    let status = match timeout(Duration::from_millis(MDFIND_TIMEOUT_MS), mdutil).await {
+        Ok(Ok(output)) => SpotlightStatus::parse(&format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )),
+        // Keep using mdfind when mdutil can't tell
+        _ => SpotlightStatus::Enabled,
+    };
+    if let Ok(mut current) = SPOTLIGHT_STATUS.write() {
+        *current = status;
+    }
+    status
+}
+
+/// Status bar notice while file search walks folders instead of using
+/// Spotlight.
+pub fn file_search_notice() -> Option<&'static str> {
+    match spotlight_status() {
+        SpotlightStatus::Enabled => None,
+        SpotlightStatus::Disabled => Some("Spotlight is off · searching common folders"),
+        SpotlightStatus::Indexing => Some("Spotlight is indexing · searching common folders"),
+    }
+}
+
 /// Create a `CommandItem` representing a file or folder found by Spotlight.
 async fn create_fs_command(path: &str) -> Option<CommandItem> {
     // Use async metadata check for better performance
@@ -39,13 +128,80 @@ async fn create_fs_command(path: &str) -> Option<CommandItem> {
     Some(cmd)
 }
 
-/// Optimized mdfind search with better predicates and error handling
-async fn run_mdfind_optimized(query: &str, max_results: usize) -> Result<Vec<String>, String> {
+/// Run the platform file search backend and return the best matching paths.
+/// Searches `roots`, or the home directory when no roots are given. Backends
+/// that stream their output send the best items so far to `updates`.
+async fn run_file_search(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    #[cfg_attr(not(target_os = "macos"), allow(unused_variables))] updates: Option<&mpsc::Sender<Vec<CommandItem>>>,
+) -> Result<Vec<String>, String> {
     let home_path = match dirs::home_dir() {
         Some(p) => p,
         None => return Ok(Vec::new()),
     };
+    let roots = if roots.is_empty() {
+        vec![home_path.clone()]
+    } else {
+        roots.to_vec()
+    };
 
+    #[cfg(target_os = "macos")]
+    {
+        if spotlight_status() != SpotlightStatus::Enabled {
+            return run_fallback_search(query, max_results, &roots, &home_path).await;
+        }
+        run_mdfind_optimized(query, max_results, &roots, &home_path, updates).await
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        run_system_search(query, max_results, &roots, &home_path).await
+    }
+}
+
+/// Bounded walk used while Spotlight is off or indexing. A search of the
+/// whole home directory walks its common folders instead, so it doesn't
+/// get lost in `~/Library`.
+#[cfg(target_os = "macos")]
+async fn run_fallback_search(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    home_path: &Path,
+) -> Result<Vec<String>, String> {
+    let roots = if roots == [home_path.to_path_buf()] {
+        fallback_roots(home_path)
+    } else {
+        roots.to_vec()
+    };
+    let mut results = walk_search(query, &roots, max_results * 2).await?;
+    sort_by_relevance(&mut results, home_path);
+    results.truncate(max_results);
+    Ok(results)
+}
+
+/// The `FALLBACK_DIRS` present in `home_path`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn fallback_roots(home_path: &Path) -> Vec<PathBuf> {
+    FALLBACK_DIRS
+        .iter()
+        .map(|dir| home_path.join(dir))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Optimized mdfind search with better predicates and error handling.
+/// Hits are read as mdfind prints them, so the first ones show up long
+/// before the scan is done.
+#[cfg(target_os = "macos")]
+async fn run_mdfind_optimized(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    home_path: &Path,
+    updates: Option<&mpsc::Sender<Vec<CommandItem>>>,
+) -> Result<Vec<String>, String> {
     // Build a more efficient combined search predicate
     // This reduces mdfind to a single call instead of multiple
     let predicate = format!(
@@ -53,45 +209,273 @@ async fn run_mdfind_optimized(query: &str, max_results: usize) -> Result<Vec<Str
         query.replace("'", "\\'") // Escape single quotes for safety
     );
 
-    // Use async command with timeout for reliability
-    let mdfind_future = Command::new("mdfind")
-        .arg("-onlyin")
-        .arg(&home_path)
-        .arg(&predicate)
-        .output();
-
-    let output = match timeout(Duration::from_millis(MDFIND_TIMEOUT_MS), mdfind_future).await {
-        Ok(Ok(output)) => output,
-        Ok(Err(e)) => {
-            return Err(format!("mdfind command failed: {}", e));
+    let mut mdfind = Command::new("mdfind");
+    for root in roots {
+        mdfind.arg("-onlyin").arg(root);
+    }
+    mdfind.arg(&predicate);
+    stream_paths(mdfind, max_results, home_path, updates).await
+}
+
+/// Read paths from `command` as they are printed and return the best
+/// `max_results`. While it runs, the best items so far go to `updates` at
+/// most every `STREAM_UPDATE_MS`; a closed channel stops the search. On
+/// timeout the paths found so far are kept.
+#[cfg(any(target_os = "macos", test))]
+async fn stream_paths(
+    mut command: Command,
+    max_results: usize,
+    home_path: &Path,
+    updates: Option<&mpsc::Sender<Vec<CommandItem>>>,
+) -> Result<Vec<String>, String> {
+    use tokio::io::AsyncBufReadExt;
+
+    let program = command.as_std().get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("{} command failed: {}", program, e))?;
+    let stdout = child.stdout.take().ok_or_else(|| format!("{} has no output", program))?;
+    let mut lines = tokio::io::BufReader::new(stdout).lines();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(MDFIND_TIMEOUT_MS);
+    let mut paths = Vec::new();
+    let mut last_update: Option<tokio::time::Instant> = None;
+    let mut finished = false;
+    // Take extra for sorting
+    while paths.len() < max_results * 2 {
+        let line = match tokio::time::timeout_at(deadline, lines.next_line()).await {
+            Ok(Ok(Some(line))) => line,
+            Ok(Ok(None)) => {
+                finished = true;
+                break;
+            }
+            Ok(Err(e)) => return Err(format!("{} command failed: {}", program, e)),
+            Err(_) if paths.is_empty() => {
+                return Err(format!("{} timed out after {}ms", program, MDFIND_TIMEOUT_MS));
+            }
+            Err(_) => break,
+        };
+        if line.is_empty() {
+            continue;
+        }
+        paths.push(line);
+
+        let Some(updates) = updates else {
+            continue;
+        };
+        if last_update.is_none_or(|at| at.elapsed() >= Duration::from_millis(STREAM_UPDATE_MS)) {
+            let items = items_for_paths(best_paths(&paths, max_results, home_path)).await;
+            if updates.send(items).await.is_err() {
+                // Superseded by a newer search
+                return Ok(Vec::new());
+            }
+            last_update = Some(tokio::time::Instant::now());
+        }
+    }
+
+    if finished {
+        let status = child.wait().await.map_err(|e| format!("{} command failed: {}", program, e))?;
+        if !status.success() && paths.is_empty() {
+            return Err(format!("{} exited with status: {}", program, status));
         }
-        Err(_) => {
-            return Err(format!("mdfind timed out after {}ms", MDFIND_TIMEOUT_MS));
+    }
+    Ok(best_paths(&paths, max_results, home_path))
+}
+
+/// The `max_results` most relevant of `paths`.
+#[cfg(any(target_os = "macos", test))]
+fn best_paths(paths: &[String], max_results: usize, home_path: &Path) -> Vec<String> {
+    let mut best = paths.to_vec();
+    sort_by_relevance(&mut best, home_path);
+    best.truncate(max_results);
+    best
+}
+
+/// File search without Spotlight: the platform's file index first, then
+/// a bounded directory walk that honours `.ignore`/`.gitignore`.
+/// Matches names starting with the query, like the mdfind predicate.
+#[cfg(not(target_os = "macos"))]
+async fn run_system_search(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    home_path: &Path,
+) -> Result<Vec<String>, String> {
+    let limit = max_results * 2; // Take extra for sorting
+    let mut results = match run_indexed_search(query, roots, limit).await {
+        Some(paths) => paths,
+        None => walk_search(query, roots, limit).await?,
+    };
+
+    sort_by_relevance(&mut results, home_path);
+    results.truncate(max_results);
+    Ok(results)
+}
+
+/// Search with Everything, then the Windows Search index, then `fd`.
+/// Returns None when none of them is usable.
+#[cfg(target_os = "windows")]
+async fn run_indexed_search(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    if let Some(paths) = windows::run_everything(query, roots, limit).await {
+        return Some(paths);
+    }
+    if let Some(paths) = windows::run_windows_search(query, roots, limit).await {
+        return Some(paths);
+    }
+    run_fd(query, roots, limit).await
+}
+
+/// Search with `fd`, then the locate database.
+/// Returns None when neither is usable.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+async fn run_indexed_search(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    match run_fd(query, roots, limit).await {
+        Some(paths) => Some(paths),
+        None => run_locate(query, roots, limit).await,
+    }
+}
+
+/// Search with `fd` (packaged as `fdfind` on Debian/Ubuntu).
+/// Returns None when neither binary is usable so the next backend is tried.
+#[cfg(not(target_os = "macos"))]
+async fn run_fd(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    let pattern = format!("^{}", escape_regex(query));
+    for binary in ["fd", "fdfind"] {
+        let fd_future = Command::new(binary)
+            .args(["--ignore-case", "--absolute-path", "--max-results"])
+            .arg(limit.to_string())
+            .arg(&pattern)
+            .args(roots)
+            .output();
+        if let Ok(Ok(output)) = timeout(Duration::from_millis(MDFIND_TIMEOUT_MS), fd_future).await {
+            if output.status.success() {
+                return Some(output_lines(&output.stdout, limit));
+            }
         }
+    }
+    None
+}
+
+/// Search the locate database, keeping entries under `roots` whose name
+/// starts with the query. Returns None when no locate binary is usable.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+async fn run_locate(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    let query_lower = query.to_lowercase();
+    for binary in ["plocate", "locate"] {
+        let locate_future = Command::new(binary)
+            .args(["--ignore-case", "--basename", "--limit"])
+            .arg((limit * 10).to_string()) // Substring matches are filtered below
+            .arg(query)
+            .output();
+        if let Ok(Ok(output)) = timeout(Duration::from_millis(MDFIND_TIMEOUT_MS), locate_future).await {
+            // locate exits with 1 when nothing matched
+            if output.status.success() || output.status.code() == Some(1) {
+                let paths = output_lines(&output.stdout, usize::MAX)
+                    .into_iter()
+                    .filter(|path| roots.iter().any(|root| Path::new(path).starts_with(root)))
+                    .filter(|path| {
+                        Path::new(path)
+                            .file_name()
+                            .and_then(|s| s.to_str())
+                            .is_some_and(|name| name_matches(name, &query_lower))
+                    })
+                    .take(limit)
+                    .collect();
+                return Some(paths);
+            }
+        }
+    }
+    None
+}
+
+/// Bounded walk of `roots` on a blocking thread, with the usual timeout.
+async fn walk_search(query: &str, roots: &[PathBuf], limit: usize) -> Result<Vec<String>, String> {
+    let query = query.to_string();
+    let roots = roots.to_vec();
+    let walk = tokio::task::spawn_blocking(move || walk_search_blocking(&query, &roots, limit));
+    match timeout(Duration::from_millis(MDFIND_TIMEOUT_MS), walk).await {
+        Ok(Ok(paths)) => Ok(paths),
+        Ok(Err(e)) => Err(format!("file search failed: {}", e)),
+        Err(_) => Err(format!("file search timed out after {}ms", MDFIND_TIMEOUT_MS)),
+    }
+}
+
+/// Walk `roots`, skipping hidden and ignored entries, and collect up to
+/// `limit` paths whose name starts with the query.
+fn walk_search_blocking(query: &str, roots: &[PathBuf], limit: usize) -> Vec<String> {
+    let query_lower = query.to_lowercase();
+    let Some((first, rest)) = roots.split_first() else {
+        return Vec::new();
     };
+    let mut builder = ignore::WalkBuilder::new(first);
+    for root in rest {
+        builder.add(root);
+    }
+    let walker = builder.max_depth(Some(WALK_MAX_DEPTH)).build();
 
-    if !output.status.success() {
-        return Err(format!("mdfind exited with status: {}", output.status));
+    let mut results = Vec::new();
+    for entry in walker.flatten().take(WALK_MAX_ENTRIES) {
+        if entry.depth() == 0 {
+            continue;
+        }
+        let matches = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name_matches(name, &query_lower));
+        if matches {
+            if let Some(path) = entry.path().to_str() {
+                results.push(path.to_string());
+                if results.len() >= limit {
+                    break;
+                }
+            }
+        }
     }
+    results
+}
+
+/// Case-insensitive prefix match on a file name (`query_lower` is lowercased).
+fn name_matches(name: &str, query_lower: &str) -> bool {
+    name.to_lowercase().starts_with(query_lower)
+}
+
+/// Escape regex metacharacters so the query is matched literally by `fd`.
+#[cfg(not(target_os = "macos"))]
+fn escape_regex(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
 
-    // Process results efficiently
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut results: Vec<String> = stdout
+#[cfg(not(target_os = "macos"))]
+fn output_lines(stdout: &[u8], limit: usize) -> Vec<String> {
+    String::from_utf8_lossy(stdout)
         .lines()
         .filter(|line| !line.is_empty())
-        .take(max_results * 2) // Take extra for sorting
+        .take(limit)
         .map(|s| s.to_string())
-        .collect();
+        .collect()
+}
 
+/// Sort paths so shallow, commonly used locations come first.
+fn sort_by_relevance(results: &mut [String], home_path: &Path) {
     // Enhanced depth-based sort with multiple criteria
     results.sort_by(|a, b| {
         let a_path = PathBuf::from(a);
         let b_path = PathBuf::from(b);
         
         // Primary: depth (shallower first)
-        let a_depth = a_path.strip_prefix(&home_path)
+        let a_depth = a_path.strip_prefix(home_path)
             .map_or(usize::MAX, |rel_path| rel_path.components().count());
-        let b_depth = b_path.strip_prefix(&home_path)
+        let b_depth = b_path.strip_prefix(home_path)
             .map_or(usize::MAX, |rel_path| rel_path.components().count());
         
         match a_depth.cmp(&b_depth) {
@@ -110,10 +494,6 @@ async fn run_mdfind_optimized(query: &str, max_results: usize) -> Result<Vec<Str
             other => other
         }
     });
-
-    // Return the top results
-    results.truncate(max_results);
-    Ok(results)
 }
 
 /// Assign priority scores to paths (lower = higher priority)
@@ -162,17 +542,20 @@ pub async fn spotlight_search(query: &str, max_results: usize) -> Vec<CommandIte
         return vec![];
     }
 
-    // Get paths from optimized mdfind
-    let paths = match run_mdfind_optimized(query, max_results).await {
+    // Get paths from the platform search backend
+    let paths = match run_file_search(query, max_results, &[], None).await {
         Ok(paths) => paths,
         Err(_) => return vec![], // Silently fail for now, will add error handling later
     };
     
-    // Convert paths to CommandItems concurrently using tokio
+    items_for_paths(paths).await
+}
+
+/// Convert paths to CommandItems, checking their metadata concurrently.
+async fn items_for_paths(paths: Vec<String>) -> Vec<CommandItem> {
     let mut tasks = Vec::with_capacity(paths.len());
     for path in paths {
-        let path_clone = path.clone();
-        tasks.push(tokio::spawn(async move { create_fs_command(&path_clone).await }));
+        tasks.push(tokio::spawn(async move { create_fs_command(&path).await }));
     }
 
     // Wait for all file metadata checks concurrently
@@ -193,7 +576,7 @@ pub async fn fast_file_search(query: &str, max_results: usize) -> Vec<CommandIte
     }
 
     // No debounce for streaming - immediate response
-    let paths = match run_mdfind_optimized(query, max_results + 5).await {
+    let paths = match run_file_search(query, max_results + 5, &[], None).await {
         Ok(paths) => paths,
         Err(_) => return vec![], // Silently fail for now
     }; // Get extra for better prioritization
@@ -219,7 +602,39 @@ pub async fn fast_file_search(query: &str, max_results: usize) -> Vec<CommandIte
 }
 
 /// Spotlight search that returns errors for UI display
+#[allow(dead_code)]
 pub async fn spotlight_search_with_errors(query: &str, max_results: usize) -> Result<Vec<CommandItem>, String> {
+    scoped_search_with_errors(query, max_results, &[]).await
+}
+
+/// Like `spotlight_search_with_errors`, restricted to `roots`
+/// (the home directory when empty).
+pub async fn scoped_search_with_errors(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+) -> Result<Vec<CommandItem>, String> {
+    search_items(query, max_results, roots, None).await
+}
+
+/// Like `scoped_search_with_errors`, also sending the best items found so
+/// far to `updates` while Spotlight is still searching. Dropping the
+/// receiver cancels the search.
+pub async fn streaming_search_with_errors(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    updates: &mpsc::Sender<Vec<CommandItem>>,
+) -> Result<Vec<CommandItem>, String> {
+    search_items(query, max_results, roots, Some(updates)).await
+}
+
+async fn search_items(
+    query: &str,
+    max_results: usize,
+    roots: &[PathBuf],
+    updates: Option<&mpsc::Sender<Vec<CommandItem>>>,
+) -> Result<Vec<CommandItem>, String> {
     if query.is_empty() || max_results == 0 {
         return Ok(vec![]);
     }
@@ -229,25 +644,9 @@ pub async fn spotlight_search_with_errors(query: &str, max_results: usize) -> Re
         return Ok(vec![]);
     }
 
-    // Get paths from optimized mdfind
-    let paths = run_mdfind_optimized(query, max_results).await?;
-    
-    // Convert paths to CommandItems concurrently using tokio
-    let mut tasks = Vec::with_capacity(paths.len());
-    for path in paths {
-        let path_clone = path.clone();
-        tasks.push(tokio::spawn(async move { create_fs_command(&path_clone).await }));
-    }
-
-    // Wait for all file metadata checks concurrently
-    let mut results = Vec::with_capacity(tasks.len());
-    for task in tasks {
-        if let Ok(Some(item)) = task.await {
-            results.push(item);
-        }
-    }
-    
-    Ok(results)
+    // Get paths from the platform search backend
+    let paths = run_file_search(query, max_results, roots, updates).await?;
+    Ok(items_for_paths(paths).await)
 }
 
 #[cfg(test)]
@@ -471,6 +870,75 @@ mod tests {
         assert_eq!(get_path_priority("/Users/test/developer/file.txt"), 3);
     }
 
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("report"), "report");
+        assert_eq!(escape_regex("a.b+c"), "a\\.b\\+c");
+        assert_eq!(escape_regex("(draft)"), "\\(draft\\)");
+    }
+
+    #[test]
+    fn test_name_matches_prefix_case_insensitive() {
+        assert!(name_matches("Report.pdf", "rep"));
+        assert!(name_matches("report", "report"));
+        assert!(!name_matches("old-report.pdf", "rep"));
+    }
+
+    #[test]
+    fn test_walk_search_honours_ignore_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("Documents/Reports")).unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        std::fs::write(root.join("Documents/report.txt"), "").unwrap();
+        std::fs::write(root.join("build/report.o"), "").unwrap();
+        std::fs::write(root.join(".cache/report.tmp"), "").unwrap();
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        std::fs::write(root.join(".ignore"), "build/\n").unwrap();
+
+        let roots = vec![root.to_path_buf()];
+        let mut results = walk_search_blocking("REP", &roots, 10);
+        results.sort();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].ends_with("Documents/Reports"));
+        assert!(results[1].ends_with("Documents/report.txt"));
+
+        // The limit is respected
+        assert_eq!(walk_search_blocking("rep", &roots, 1).len(), 1);
+
+        // Scoped to sub directories
+        let scoped = walk_search_blocking("rep", &[root.join("Documents")], 10);
+        assert_eq!(scoped.len(), 2);
+        assert!(walk_search_blocking("rep", &[root.join("Documents/Reports")], 10).is_empty());
+    }
+
+    #[test]
+    fn test_parse_spotlight_status() {
+        assert_eq!(SpotlightStatus::parse("/:\n\tIndexing enabled. \n"), SpotlightStatus::Enabled);
+        assert_eq!(SpotlightStatus::parse("/:\n\tIndexing disabled.\n"), SpotlightStatus::Disabled);
+        assert_eq!(
+            SpotlightStatus::parse("/:\n\tIndexing and searching disabled.\n"),
+            SpotlightStatus::Disabled
+        );
+        assert_eq!(
+            SpotlightStatus::parse("/:\n\tError: unknown indexing state.\n"),
+            SpotlightStatus::Indexing
+        );
+    }
+
+    #[test]
+    fn test_fallback_roots() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let home = temp_dir.path();
+        std::fs::create_dir_all(home.join("Documents")).unwrap();
+        std::fs::create_dir_all(home.join("Library")).unwrap();
+        std::fs::write(home.join("Desktop"), "").unwrap();
+        assert_eq!(fallback_roots(home), vec![home.join("Documents")]);
+    }
+
     #[test]
     fn test_metadata_insertion() {
         tokio_test::block_on(async {
@@ -481,4 +949,37 @@ mod tests {
             }
         });
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stream_paths_sends_early_hits() {
+        let home = Path::new("/home/user");
+        let mut slow_search = Command::new("sh");
+        slow_search.args(["-c", "echo /home/user/a/b/late; echo /home/user/first; sleep 0.3; echo /home/user/second"]);
+        let (updates_tx, mut updates_rx) = mpsc::channel(8);
+
+        let paths = stream_paths(slow_search, 2, home, Some(&updates_tx)).await.unwrap();
+        assert_eq!(paths, vec!["/home/user/first", "/home/user/second"]);
+
+        // The first hit arrived before the command was done
+        let first_update = updates_rx.recv().await.unwrap();
+        assert_eq!(first_update.len(), 1);
+        assert_eq!(first_update[0].value, "/home/user/a/b/late");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stream_paths_reports_failures() {
+        let home = Path::new("/home/user");
+        let mut failing = Command::new("sh");
+        failing.args(["-c", "exit 3"]);
+        assert!(stream_paths(failing, 5, home, None).await.is_err());
+
+        // A closed channel stops the search without an error
+        let (updates_tx, updates_rx) = mpsc::channel(1);
+        drop(updates_rx);
+        let mut search = Command::new("sh");
+        search.args(["-c", "echo /home/user/a; sleep 5"]);
+        assert_eq!(stream_paths(search, 5, home, Some(&updates_tx)).await, Ok(Vec::new()));
+    }
 }
@@ -0,0 +1,302 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+const DEFAULT_PASSWORD_LENGTH: usize = 20;
+const DEFAULT_PIN_LENGTH: usize = 4;
+const MAX_LENGTH: usize = 256;
+const MAX_DICE: u32 = 100;
+
+/// A query and the items generated for it.
+type Generated = (String, Vec<CommandItem>);
+
+/// The items of the last query, so filtering again while it stays the
+/// same doesn't change the password under the cursor.
+static LAST: Lazy<Mutex<Option<Generated>>> = Lazy::new(|| Mutex::new(None));
+
+/// Characters a generated password is drawn from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Charset {
+    /// Letters, digits and punctuation
+    #[default]
+    Symbols,
+    Alphanumeric,
+    Letters,
+    Digits,
+    Hex,
+}
+
+impl Charset {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "symbols" | "all" => Some(Charset::Symbols),
+            "alnum" | "alphanumeric" => Some(Charset::Alphanumeric),
+            "letters" | "alpha" => Some(Charset::Letters),
+            "digits" | "numeric" => Some(Charset::Digits),
+            "hex" => Some(Charset::Hex),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Charset::Symbols => "symbols",
+            Charset::Alphanumeric => "alphanumeric",
+            Charset::Letters => "letters",
+            Charset::Digits => "digits",
+            Charset::Hex => "hex",
+        }
+    }
+
+    fn chars(&self) -> &'static [u8] {
+        match self {
+            Charset::Symbols => {
+                b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_=+[]{};:,.<>?/~"
+            }
+            Charset::Alphanumeric => b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+            Charset::Letters => b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Charset::Digits => b"0123456789",
+            Charset::Hex => b"0123456789abcdef",
+        }
+    }
+}
+
+/// Defaults for the `password` generator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorsConfig {
+    pub password_length: usize,
+    pub password_charset: Charset,
+}
+
+impl Default for GeneratorsConfig {
+    fn default() -> Self {
+        Self {
+            password_length: DEFAULT_PASSWORD_LENGTH,
+            password_charset: Charset::default(),
+        }
+    }
+}
+
+/// A uniformly distributed number below `bound`, from the OS RNG.
+/// Values in the incomplete last range are rejected to avoid modulo bias.
+fn random_below(bound: u32) -> Result<u32> {
+    let zone = u32::MAX - u32::MAX % bound;
+    loop {
+        let mut bytes = [0u8; 4];
+        getrandom::getrandom(&mut bytes).map_err(|e| anyhow!("The OS random number generator failed: {}", e))?;
+        let value = u32::from_le_bytes(bytes);
+        if value < zone {
+            return Ok(value % bound);
+        }
+    }
+}
+
+fn random_string(length: usize, charset: Charset) -> Result<String> {
+    let chars = charset.chars();
+    (0..length)
+        .map(|_| Ok(chars[random_below(chars.len() as u32)? as usize] as char))
+        .collect()
+}
+
+/// `2d6` → (2, 6); `d20` → (1, 20).
+fn parse_dice(spec: &str) -> Option<(u32, u32)> {
+    let (count, sides) = spec.split_once('d')?;
+    let count = if count.is_empty() { 1 } else { count.parse().ok()? };
+    let sides = sides.parse().ok()?;
+    ((1..=MAX_DICE).contains(&count) && sides >= 2).then_some((count, sides))
+}
+
+/// The result is copied on Enter but never shown or logged. `generator`
+/// is the query it came from, to make a new one where the value can't go.
+fn masked_item(label: &str, secret: String, generator: &str) -> CommandItem {
+    let mut item = CommandItem::new(
+        &format!("{}: {}", label, "•".repeat(secret.chars().count().min(12))),
+        Handler::Copy,
+        &secret,
+    );
+    item.icon = "🔑".to_string();
+    item.metadata.insert("masked".to_string(), "true".to_string());
+    item.metadata.insert("generator".to_string(), generator.to_string());
+    item
+}
+
+fn password_item(arguments: &[&str]) -> Result<Option<CommandItem>> {
+    let defaults = &config::get().generators;
+    let mut length = defaults.password_length;
+    let mut charset = defaults.password_charset;
+    for argument in arguments {
+        if let Ok(n) = argument.parse::<usize>() {
+            length = n;
+        } else if let Some(parsed) = Charset::parse(argument) {
+            charset = parsed;
+        } else {
+            return Ok(None);
+        }
+    }
+    if !(1..=MAX_LENGTH).contains(&length) {
+        return Ok(None);
+    }
+    let label = format!("Password ({} {})", length, charset.name());
+    let generator = format!("password {} {}", length, charset.name());
+    Ok(Some(masked_item(&label, random_string(length, charset)?, &generator)))
+}
+
+fn pin_item(arguments: &[&str]) -> Result<Option<CommandItem>> {
+    let length = match arguments {
+        [] => DEFAULT_PIN_LENGTH,
+        [n] => match n.parse().ok().filter(|n| (1..=MAX_LENGTH).contains(n)) {
+            Some(length) => length,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let label = format!("PIN ({} digits)", length);
+    Ok(Some(masked_item(&label, random_string(length, Charset::Digits)?, &format!("pin {}", length))))
+}
+
+fn dice_item(arguments: &[&str]) -> Result<Option<CommandItem>> {
+    let (count, sides) = match arguments {
+        [] => (1, 6),
+        [spec] => match parse_dice(spec) {
+            Some(dice) => dice,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let rolls = (0..count).map(|_| Ok(random_below(sides)? + 1)).collect::<Result<Vec<u32>>>()?;
+    // Up to 100 dice of up to u32::MAX sides
+    let total: u64 = rolls.iter().map(|&roll| u64::from(roll)).sum();
+    let label = if count == 1 {
+        format!("{}d{}: {}", count, sides, total)
+    } else {
+        let rolls: Vec<String> = rolls.iter().map(|r| r.to_string()).collect();
+        format!("{}d{}: {} = {}", count, sides, rolls.join(" + "), total)
+    };
+    let mut item = CommandItem::new(&label, Handler::Copy, &total.to_string());
+    item.icon = "🎲".to_string();
+    Ok(Some(item))
+}
+
+fn coin_item() -> Result<CommandItem> {
+    let side = if random_below(2)? == 0 { "Heads" } else { "Tails" };
+    let mut item = CommandItem::new(side, Handler::Copy, side);
+    item.icon = "🪙".to_string();
+    Ok(item)
+}
+
+/// Freshly generated items for `password [length] [charset]`,
+/// `pin [digits]`, `roll [NdM]` and `flip`.
+pub fn generate(query: &str) -> Result<Vec<CommandItem>> {
+    let query = query.trim().to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let Some((command, arguments)) = words.split_first() else {
+        return Ok(Vec::new());
+    };
+    let item = match *command {
+        "password" => password_item(arguments)?,
+        "pin" => pin_item(arguments)?,
+        "roll" | "dice" => dice_item(arguments)?,
+        "flip" | "coin" if arguments.is_empty() => Some(coin_item()?),
+        _ => None,
+    };
+    Ok(item.into_iter().collect())
+}
+
+/// The generated items for `query`, made once while it stays the same.
+pub fn generator_items(query: &str) -> Vec<CommandItem> {
+    generated_once(&LAST, query)
+}
+
+fn generated_once(last: &Mutex<Option<Generated>>, query: &str) -> Vec<CommandItem> {
+    let key = query.trim().to_lowercase();
+    let mut last = last.lock().unwrap();
+    if let Some((_, items)) = last.as_ref().filter(|(last_query, _)| *last_query == key) {
+        return items.clone();
+    }
+    let items = match generate(&key) {
+        Ok(items) => items,
+        Err(e) => {
+            let mut item = CommandItem::new(&format!("Generating failed: {}", e), Handler::Copy, &e.to_string());
+            item.icon = "⚠️".to_string();
+            return vec![item];
+        }
+    };
+    *last = Some((key, items.clone()));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_below_stays_in_range() {
+        for bound in [1, 2, 6, 62, 1000] {
+            for _ in 0..200 {
+                assert!(random_below(bound).unwrap() < bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_password_is_masked() {
+        let items = generator_items("password 32 hex");
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(item.value.len(), 32);
+        assert!(item.value.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(!item.label.contains(&item.value));
+        assert_eq!(item.label, format!("Password (32 hex): {}", "•".repeat(12)));
+        assert_eq!(item.metadata.get("masked").map(String::as_str), Some("true"));
+
+        assert_eq!(generator_items("password")[0].value.len(), DEFAULT_PASSWORD_LENGTH);
+        assert!(generator_items("password 12 emoji").is_empty());
+        assert!(generator_items("password 0").is_empty());
+    }
+
+    #[test]
+    fn test_pin() {
+        let pin = &generator_items("pin 6")[0];
+        assert_eq!(pin.value.len(), 6);
+        assert!(pin.value.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(pin.label, "PIN (6 digits): ••••••");
+    }
+
+    #[test]
+    fn test_dice_and_coin() {
+        assert_eq!(parse_dice("2d6"), Some((2, 6)));
+        assert_eq!(parse_dice("d20"), Some((1, 20)));
+        assert_eq!(parse_dice("2d1"), None);
+        assert_eq!(parse_dice("six"), None);
+
+        let roll = &generator_items("roll 3d6")[0];
+        let total: u32 = roll.value.parse().unwrap();
+        assert!((3..=18).contains(&total));
+        assert!(roll.label.starts_with("3d6: "));
+
+        let big = &generate("roll 100d4000000000").unwrap()[0];
+        let total: u64 = big.value.parse().unwrap();
+        let (rolls, shown) = big.label.trim_start_matches("100d4000000000: ").rsplit_once(" = ").unwrap();
+        let sum: u64 = rolls.split(" + ").map(|roll| roll.parse::<u64>().unwrap()).sum();
+        assert_eq!((sum, shown), (total, big.value.as_str()));
+
+        let flip = &generator_items("flip")[0];
+        assert!(flip.value == "Heads" || flip.value == "Tails");
+        assert!(generator_items("flipboard").is_empty());
+    }
+
+    #[test]
+    fn test_same_query_keeps_its_password() {
+        let last = Mutex::new(None);
+        let first = generated_once(&last, "password 24 alnum");
+        assert_eq!(generated_once(&last, " Password 24 alnum")[0].value, first[0].value);
+        generated_once(&last, "pin");
+        assert_ne!(generated_once(&last, "password 24 alnum")[0].value, first[0].value);
+        assert_ne!(generate("password 24 alnum").unwrap()[0].value, first[0].value);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::repos;
+use crate::keychain;
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::{mpsc, OnceCell};
+
+const API: &str = "https://api.github.com";
+const CACHE_NAMESPACE: &str = "github";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Queries starting with this word search GitHub.
+const SEARCH_PREFIX: &str = "gh";
+const SEARCH_CACHE_NAMESPACE: &str = "github-search";
+/// Searches are answered from the cache for this long, sparing the
+/// search API's rate limit while a query is refined.
+const SEARCH_TTL_MINUTES: i64 = 5;
+const MAX_REPOS: usize = 5;
+const MAX_ISSUES: usize = 15;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubConfig {
+    /// Keychain service holding a personal access token
+    pub keychain: Option<String>,
+    /// A personal access token, when not kept in the keychain
+    pub token: Option<String>,
+}
+
+/// The token of the config, the keychain, `$GITHUB_TOKEN`, `$GH_TOKEN` or
+/// the GitHub CLI, read once.
+static TOKEN: OnceCell<Option<String>> = OnceCell::const_new();
+
+async fn token() -> Option<&'static str> {
+    TOKEN
+        .get_or_init(|| async {
+            let settings = &config::get().github;
+            if let Some(token) = settings.token.clone().filter(|token| !token.is_empty()) {
+                return Some(token);
+            }
+            if let Some(service) = &settings.keychain {
+                if let Ok(token) = keychain::secret(service).await {
+                    return Some(token);
+                }
+            }
+            if let Some(token) = ["GITHUB_TOKEN", "GH_TOKEN"]
+                .into_iter()
+                .find_map(|name| std::env::var(name).ok().filter(|token| !token.is_empty()))
+            {
+                return Some(token);
+            }
+            let output = Command::new("gh").args(["auth", "token"]).output().await.ok()?;
+            let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (output.status.success() && !token.is_empty()).then_some(token)
+        })
+        .await
+        .as_deref()
+}
+
+/// What was read from an answer of the API, with the ETag it came with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Tagged<T> {
+    etag: String,
+    value: T,
+}
+
+/// `GET` an API path such as `/repos/o/r/pulls` and read the answer with
+/// `parse`. What was read is kept with the ETag and asked for
+/// conditionally, so unchanged data costs no rate limit.
+pub async fn get<T: Serialize + DeserializeOwned>(path: &str, parse: impl Fn(&Value) -> Option<T>) -> Result<T> {
+    let cached = cache::get_stale::<Tagged<T>>(CACHE_NAMESPACE, path).map(|(tagged, _)| tagged);
+    let mut request = reqwest::Client::new()
+        .get(format!("{}{}", API, path))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "grinta")
+        .timeout(REQUEST_TIMEOUT);
+    if let Some(token) = token().await {
+        request = request.bearer_auth(token);
+    }
+    if let Some(cached) = &cached {
+        request = request.header("If-None-Match", cached.etag.as_str());
+    }
+    let response = request.send().await?;
+    match (response.status().as_u16(), cached) {
+        (304, Some(cached)) => Ok(cached.value),
+        (200, _) => {
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|etag| etag.to_str().ok())
+                .map(str::to_string);
+            let value = parse(&response.json::<Value>().await?).context("Unexpected answer from GitHub")?;
+            if let Some(etag) = etag {
+                let _ = cache::put(CACHE_NAMESPACE, path, &Tagged { etag, value: &value });
+            }
+            Ok(value)
+        }
+        (status, _) => bail!("GitHub answered {}", status),
+    }
+}
+
+/// `owner/repo` of a GitHub web page such as `https://github.com/o/r`.
+pub fn slug(remote: &str) -> Option<&str> {
+    let slug = remote.strip_prefix("https://github.com/")?;
+    (slug.split('/').count() == 2).then_some(slug)
+}
+
+/// How the last workflow run of a branch went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CiStatus {
+    Passed,
+    Failed,
+    Running,
+}
+
+impl CiStatus {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CiStatus::Passed => "✓",
+            CiStatus::Failed => "✗",
+            CiStatus::Running => "●",
+        }
+    }
+}
+
+/// Open pull requests and CI of a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoStatus {
+    pub open_prs: usize,
+    pub ci: Option<CiStatus>,
+}
+
+impl RepoStatus {
+    /// `3 PRs ✓` for the context column.
+    pub fn badge(&self) -> String {
+        let prs = match self.open_prs {
+            0 => None,
+            1 => Some("1 PR".to_string()),
+            // A page holds at most 100
+            100.. => Some("100+ PRs".to_string()),
+            count => Some(format!("{} PRs", count)),
+        };
+        [prs, self.ci.map(|ci| ci.symbol().to_string())]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The status of the latest run of `/actions/runs`.
+fn parse_ci(runs: &Value) -> Option<CiStatus> {
+    let run = runs.get("workflow_runs")?.get(0)?;
+    if run.get("status")?.as_str()? != "completed" {
+        return Some(CiStatus::Running);
+    }
+    match run.get("conclusion")?.as_str()? {
+        "success" => Some(CiStatus::Passed),
+        "failure" | "timed_out" | "cancelled" | "startup_failure" => Some(CiStatus::Failed),
+        _ => None,
+    }
+}
+
+async fn repo_status(slug: &str, branch: Option<&str>) -> Option<RepoStatus> {
+    let open_prs = get(&format!("/repos/{}/pulls?state=open&per_page=100", slug), |pulls| {
+        Some(pulls.as_array()?.len())
+    })
+    .await
+    .ok()?;
+    let ci = match branch {
+        Some(branch) => {
+            let path = format!("/repos/{}/actions/runs?branch={}&per_page=1", slug, urlencoding::encode(branch));
+            // A branch without runs is remembered as such
+            get(&path, |runs| Some(parse_ci(runs))).await.ok().flatten()
+        }
+        None => None,
+    };
+    Some(RepoStatus { open_prs, ci })
+}
+
+/// Send the status of the GitHub repositories, by path, every few minutes.
+pub async fn watch_repos(tx: mpsc::Sender<HashMap<String, RepoStatus>>) {
+    let minutes = config::get().repos.github_refresh_minutes.max(1);
+    let mut interval = tokio::time::interval(Duration::from_secs(minutes * 60));
+    loop {
+        interval.tick().await;
+        let mut statuses = HashMap::new();
+        for repo in repos::list_repos() {
+            let Some(slug) = repo.remote.as_deref().and_then(slug) else {
+                continue;
+            };
+            if let Some(status) = repo_status(slug, repo.branch.as_deref()).await {
+                statuses.insert(repo.path.to_string_lossy().to_string(), status);
+            }
+        }
+        if tx.send(statuses).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// What `gh <text>` looks for, narrowed with `gh repo`, `gh pr` or
+/// `gh issue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchScope {
+    All,
+    Repos,
+    PullRequests,
+    Issues,
+}
+
+/// The scope and text of a `gh <text>` query.
+pub fn parse_search_query(query: &str) -> Option<(SearchScope, &str)> {
+    let text = query.strip_prefix(SEARCH_PREFIX)?.strip_prefix(' ')?.trim();
+    let (scope, rest) = match text.split_once(' ') {
+        Some((word, rest)) => match word {
+            "repo" | "repos" => (SearchScope::Repos, rest.trim()),
+            "pr" | "prs" => (SearchScope::PullRequests, rest.trim()),
+            "issue" | "issues" => (SearchScope::Issues, rest.trim()),
+            _ => (SearchScope::All, text),
+        },
+        None => (SearchScope::All, text),
+    };
+    (!rest.is_empty()).then_some((scope, rest))
+}
+
+/// Whether `query` searches GitHub. A plugin or REST source declared with
+/// the same prefix takes it over.
+pub fn wants_search(query: &str) -> bool {
+    let settings = config::get();
+    let taken = settings.plugins.iter().any(|plugin| plugin.prefix == SEARCH_PREFIX)
+        || settings.rest.iter().any(|source| source.prefix == SEARCH_PREFIX);
+    !taken && parse_search_query(query).is_some()
+}
+
+/// A repository, issue or pull request found on GitHub.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub scope: SearchScope,
+    pub title: String,
+    pub url: String,
+    /// The description of a repository, or the repository of an issue
+    pub detail: Option<String>,
+}
+
+/// The repositories of `/user/repos`.
+fn parse_repos(repos: &Value) -> Option<Vec<SearchHit>> {
+    let hits = repos
+        .as_array()?
+        .iter()
+        .filter_map(|repo| {
+            Some(SearchHit {
+                scope: SearchScope::Repos,
+                title: repo.get("full_name")?.as_str()?.to_string(),
+                url: repo.get("html_url")?.as_str()?.to_string(),
+                detail: repo.get("description").and_then(Value::as_str).map(str::to_string),
+            })
+        })
+        .collect();
+    Some(hits)
+}
+
+/// The issues and pull requests of `/search/issues`.
+fn parse_issues(results: &Value) -> Option<Vec<SearchHit>> {
+    let hits = results
+        .get("items")?
+        .as_array()?
+        .iter()
+        .filter_map(|issue| {
+            let number = issue.get("number")?.as_u64()?;
+            let title = issue.get("title")?.as_str()?;
+            let closed = issue.get("state").and_then(Value::as_str) == Some("closed");
+            let repo = issue
+                .get("repository_url")
+                .and_then(Value::as_str)
+                .and_then(|url| url.strip_prefix("https://api.github.com/repos/"));
+            Some(SearchHit {
+                scope: match issue.get("pull_request") {
+                    Some(_) => SearchScope::PullRequests,
+                    None => SearchScope::Issues,
+                },
+                title: format!("#{} {}{}", number, title, if closed { " (closed)" } else { "" }),
+                url: issue.get("html_url")?.as_str()?.to_string(),
+                detail: repo.map(str::to_string),
+            })
+        })
+        .collect();
+    Some(hits)
+}
+
+/// Repositories whose name has every word of `text`.
+fn matching_repos(repos: Vec<SearchHit>, text: &str) -> Vec<SearchHit> {
+    let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+    repos
+        .into_iter()
+        .filter(|repo| {
+            let name = repo.title.to_lowercase();
+            words.iter().all(|word| name.contains(word.as_str()))
+        })
+        .take(MAX_REPOS)
+        .collect()
+}
+
+fn search_item(hit: &SearchHit) -> CommandItem {
+    let label = match (&hit.scope, &hit.detail) {
+        (SearchScope::Repos, _) | (_, None) => hit.title.clone(),
+        (_, Some(repo)) => format!("{} · {}", hit.title, repo),
+    };
+    let mut item = CommandItem::new(&label, Handler::Url, &hit.url);
+    item.icon = match hit.scope {
+        SearchScope::PullRequests => "🔀",
+        SearchScope::Issues => "🐞",
+        _ => "📦",
+    }
+    .to_string();
+    if let (SearchScope::Repos, Some(description)) = (&hit.scope, &hit.detail) {
+        item.metadata.insert("preview".to_string(), description.clone());
+    }
+    item
+}
+
+async fn search(scope: SearchScope, text: &str) -> Result<Vec<SearchHit>> {
+    let key = format!("{:?}:{}", scope, text.to_lowercase());
+    let ttl = chrono::Duration::minutes(SEARCH_TTL_MINUTES);
+    if let Some(hits) = cache::get::<Vec<SearchHit>>(SEARCH_CACHE_NAMESPACE, &key, ttl) {
+        return Ok(hits);
+    }
+    let repos = async {
+        if !matches!(scope, SearchScope::All | SearchScope::Repos) {
+            return Ok(Vec::new());
+        }
+        // Every repository of the user, kept with its ETag and matched here
+        let repos = get("/user/repos?per_page=100&sort=pushed", parse_repos).await?;
+        Ok::<_, anyhow::Error>(matching_repos(repos, text))
+    };
+    let issues = async {
+        let kind = match scope {
+            SearchScope::Repos => return Ok(Vec::new()),
+            SearchScope::PullRequests => " is:pr",
+            SearchScope::Issues => " is:issue",
+            SearchScope::All => "",
+        };
+        let q = format!("{} involves:@me{}", text, kind);
+        let path = format!("/search/issues?q={}&sort=updated&per_page={}", urlencoding::encode(&q), MAX_ISSUES);
+        get(&path, parse_issues).await
+    };
+    let (repos, issues) = tokio::join!(repos, issues);
+    let mut hits = repos?;
+    hits.extend(issues?);
+    let _ = cache::put(SEARCH_CACHE_NAMESPACE, &key, &hits);
+    Ok(hits)
+}
+
+/// Repositories, issues and pull requests of the user matching a `gh`
+/// query, opened in the browser.
+pub async fn get_search_items(query: &str) -> Vec<CommandItem> {
+    let Some((scope, text)) = parse_search_query(query) else {
+        return Vec::new();
+    };
+    if token().await.is_none() {
+        let label = "Sign in with `gh auth login` to search GitHub";
+        let mut item = CommandItem::new(label, Handler::Copy, "gh auth login");
+        item.icon = "🔑".to_string();
+        return vec![item];
+    }
+    match search(scope, text).await {
+        Ok(hits) => hits.iter().map(search_item).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("https://github.com/getgrinta/grinta-rs"), Some("getgrinta/grinta-rs"));
+        assert_eq!(slug("https://gitlab.com/o/r"), None);
+        assert_eq!(slug("https://github.com/o"), None);
+    }
+
+    #[test]
+    fn test_parse_ci() {
+        let run = |status: &str, conclusion: Value| json!({"workflow_runs": [{"status": status, "conclusion": conclusion}]});
+        assert_eq!(parse_ci(&run("completed", json!("success"))), Some(CiStatus::Passed));
+        assert_eq!(parse_ci(&run("completed", json!("failure"))), Some(CiStatus::Failed));
+        assert_eq!(parse_ci(&run("in_progress", Value::Null)), Some(CiStatus::Running));
+        assert_eq!(parse_ci(&run("completed", json!("skipped"))), None);
+        assert_eq!(parse_ci(&json!({"workflow_runs": []})), None);
+    }
+
+    #[test]
+    fn test_badge() {
+        let status = |open_prs, ci| RepoStatus { open_prs, ci };
+        assert_eq!(status(3, Some(CiStatus::Passed)).badge(), "3 PRs ✓");
+        assert_eq!(status(1, None).badge(), "1 PR");
+        assert_eq!(status(0, Some(CiStatus::Failed)).badge(), "✗");
+        assert_eq!(status(100, Some(CiStatus::Running)).badge(), "100+ PRs ●");
+        assert_eq!(status(0, None).badge(), "");
+    }
+
+    #[test]
+    fn test_parse_search_query() {
+        assert_eq!(parse_search_query("gh grinta"), Some((SearchScope::All, "grinta")));
+        assert_eq!(parse_search_query("gh pr  flaky test "), Some((SearchScope::PullRequests, "flaky test")));
+        assert_eq!(parse_search_query("gh issues crash"), Some((SearchScope::Issues, "crash")));
+        assert_eq!(parse_search_query("gh repo"), Some((SearchScope::All, "repo")));
+        assert_eq!(parse_search_query("gh pr "), Some((SearchScope::All, "pr")));
+        assert_eq!(parse_search_query("gh"), None);
+        assert_eq!(parse_search_query("ghost"), None);
+    }
+
+    #[test]
+    fn test_parse_issues() {
+        let results = json!({"items": [
+            {
+                "number": 12, "title": "Fix flaky test", "state": "open",
+                "html_url": "https://github.com/o/r/pull/12",
+                "repository_url": "https://api.github.com/repos/o/r",
+                "pull_request": {"url": "…"}
+            },
+            {
+                "number": 7, "title": "Crash on start", "state": "closed",
+                "html_url": "https://github.com/o/r/issues/7",
+                "repository_url": "https://api.github.com/repos/o/r"
+            },
+            {"title": "No number"}
+        ]});
+        let hits = parse_issues(&results).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].scope, SearchScope::PullRequests);
+        assert_eq!(hits[0].title, "#12 Fix flaky test");
+        assert_eq!(hits[1].scope, SearchScope::Issues);
+        assert_eq!(hits[1].title, "#7 Crash on start (closed)");
+
+        let item = search_item(&hits[0]);
+        assert_eq!(item.label, "#12 Fix flaky test · o/r");
+        assert_eq!(item.handler, Handler::Url);
+        assert_eq!(item.value, "https://github.com/o/r/pull/12");
+    }
+
+    #[test]
+    fn test_matching_repos() {
+        let repos = parse_repos(&json!([
+            {"full_name": "getgrinta/grinta-rs", "html_url": "https://github.com/getgrinta/grinta-rs", "description": "Launcher"},
+            {"full_name": "ada/dotfiles", "html_url": "https://github.com/ada/dotfiles", "description": null}
+        ]))
+        .unwrap();
+        let found = matching_repos(repos.clone(), "Grinta RS");
+        assert_eq!(found.len(), 1);
+        assert_eq!(search_item(&found[0]).label, "getgrinta/grinta-rs");
+        assert_eq!(search_item(&found[0]).metadata["preview"], "Launcher");
+        assert!(matching_repos(repos, "nothing").is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use crate::keychain;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Queries starting with this word search the issue tracker.
+const SEARCH_PREFIX: &str = "issue";
+const LINEAR_API: &str = "https://api.linear.app/graphql";
+const CACHE_NAMESPACE: &str = "issues";
+/// Searches are answered from the cache for this long while a query is
+/// refined.
+const CACHE_TTL_MINUTES: i64 = 2;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ISSUES: usize = 15;
+
+const LINEAR_QUERY: &str = "query($term: String!, $first: Int) {
+  searchIssues(term: $term, first: $first) {
+    nodes { identifier title url priorityLabel state { name } assignee { name } team { name } }
+  }
+}";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Linear,
+    Jira,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IssuesConfig {
+    pub backend: Backend,
+    /// An API token, when not kept in the keychain
+    pub token: Option<String>,
+    /// Keychain service holding the API token
+    pub keychain: Option<String>,
+    /// The Jira site, such as `https://acme.atlassian.net`
+    pub url: Option<String>,
+    /// The Atlassian account the Jira token belongs to
+    pub email: Option<String>,
+}
+
+impl IssuesConfig {
+    fn is_configured(&self) -> bool {
+        self.token.is_some() || self.keychain.is_some()
+    }
+
+    async fn token(&self) -> Result<String> {
+        if let Some(token) = self.token.clone().filter(|token| !token.is_empty()) {
+            return Ok(token);
+        }
+        let service = self.keychain.as_deref().context("No issue tracker token configured")?;
+        keychain::secret(service).await
+    }
+}
+
+/// A ticket found in the issue tracker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    pub key: String,
+    pub title: String,
+    pub url: String,
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    pub priority: Option<String>,
+    /// The Linear team or Jira project
+    pub project: Option<String>,
+}
+
+/// The text of an `issue <text>` query.
+pub fn parse_query(query: &str) -> Option<&str> {
+    let text = query.strip_prefix(SEARCH_PREFIX)?.strip_prefix(' ')?.trim();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Whether `query` searches the issue tracker: one is configured and no
+/// plugin or REST source claims the prefix.
+pub fn wants_query(query: &str) -> bool {
+    let settings = config::get();
+    let taken = settings.plugins.iter().any(|plugin| plugin.prefix == SEARCH_PREFIX)
+        || settings.rest.iter().any(|source| source.prefix == SEARCH_PREFIX);
+    !taken && settings.issues.is_configured() && parse_query(query).is_some()
+}
+
+fn name_of(value: &Value, field: &str) -> Option<String> {
+    value.get(field)?.get("name")?.as_str().map(str::to_string)
+}
+
+/// The issues of a Linear `searchIssues` answer.
+fn parse_linear(response: &Value) -> Option<Vec<Issue>> {
+    let issues = response
+        .pointer("/data/searchIssues/nodes")?
+        .as_array()?
+        .iter()
+        .filter_map(|issue| {
+            Some(Issue {
+                key: issue.get("identifier")?.as_str()?.to_string(),
+                title: issue.get("title")?.as_str()?.to_string(),
+                url: issue.get("url")?.as_str()?.to_string(),
+                status: name_of(issue, "state"),
+                assignee: name_of(issue, "assignee"),
+                priority: issue
+                    .get("priorityLabel")
+                    .and_then(Value::as_str)
+                    .filter(|priority| *priority != "No priority")
+                    .map(str::to_string),
+                project: name_of(issue, "team"),
+            })
+        })
+        .collect();
+    Some(issues)
+}
+
+/// The issues of a Jira `search/jql` answer, linked under `site`.
+fn parse_jira(response: &Value, site: &str) -> Option<Vec<Issue>> {
+    let issues = response
+        .get("issues")?
+        .as_array()?
+        .iter()
+        .filter_map(|issue| {
+            let key = issue.get("key")?.as_str()?;
+            let fields = issue.get("fields")?;
+            Some(Issue {
+                key: key.to_string(),
+                title: fields.get("summary")?.as_str()?.to_string(),
+                url: format!("{}/browse/{}", site.trim_end_matches('/'), key),
+                status: name_of(fields, "status"),
+                assignee: fields
+                    .get("assignee")
+                    .and_then(|assignee| assignee.get("displayName")?.as_str())
+                    .map(str::to_string),
+                priority: name_of(fields, "priority"),
+                project: name_of(fields, "project"),
+            })
+        })
+        .collect();
+    Some(issues)
+}
+
+/// A JQL full-text search for `text`, most recently updated first.
+fn jql(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("text ~ \"{}\" ORDER BY updated DESC", escaped)
+}
+
+async fn search_linear(token: &str, text: &str) -> Result<Vec<Issue>> {
+    let body = json!({"query": LINEAR_QUERY, "variables": {"term": text, "first": MAX_ISSUES}});
+    let response = reqwest::Client::new()
+        .post(LINEAR_API)
+        .header("Authorization", token)
+        .json(&body)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    if let Some(message) = response.pointer("/errors/0/message").and_then(Value::as_str) {
+        bail!("Linear: {}", message);
+    }
+    parse_linear(&response).context("Unexpected answer from Linear")
+}
+
+async fn search_jira(settings: &IssuesConfig, token: &str, text: &str) -> Result<Vec<Issue>> {
+    let site = settings.url.as_deref().context("Set `url` under [issues] to your Jira site")?;
+    let email = settings.email.as_deref().context("Set `email` under [issues] to your Atlassian account")?;
+    let max_results = MAX_ISSUES.to_string();
+    let response = reqwest::Client::new()
+        .get(format!("{}/rest/api/3/search/jql", site.trim_end_matches('/')))
+        .query(&[
+            ("jql", jql(text).as_str()),
+            ("fields", "summary,status,assignee,priority,project"),
+            ("maxResults", max_results.as_str()),
+        ])
+        .basic_auth(email, Some(token))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    parse_jira(&response, site).context("Unexpected answer from Jira")
+}
+
+async fn search(text: &str) -> Result<Vec<Issue>> {
+    let settings = &config::get().issues;
+    let key = format!("{:?}:{}", settings.backend, text.to_lowercase());
+    let ttl = chrono::Duration::minutes(CACHE_TTL_MINUTES);
+    if let Some(issues) = cache::get::<Vec<Issue>>(CACHE_NAMESPACE, &key, ttl) {
+        return Ok(issues);
+    }
+    let token = settings.token().await?;
+    let issues = match settings.backend {
+        Backend::Linear => search_linear(&token, text).await?,
+        Backend::Jira => search_jira(settings, &token, text).await?,
+    };
+    let _ = cache::put(CACHE_NAMESPACE, &key, &issues);
+    Ok(issues)
+}
+
+/// `ENG-42 Fix login · In Progress`, opening the ticket, with the status
+/// and assignee in the preview pane.
+fn issue_item(issue: &Issue) -> CommandItem {
+    let label = match &issue.status {
+        Some(status) => format!("{} {} · {}", issue.key, issue.title, status),
+        None => format!("{} {}", issue.key, issue.title),
+    };
+    let mut item = CommandItem::new(&label, Handler::Url, &issue.url);
+    item.icon = "🎫".to_string();
+    let unassigned = Some("Unassigned".to_string());
+    let fields = [
+        ("status", "Status", &issue.status),
+        ("assignee", "Assignee", if issue.assignee.is_some() { &issue.assignee } else { &unassigned }),
+        ("priority", "Priority", &issue.priority),
+        ("project", "Project", &issue.project),
+    ];
+    let mut preview = format!("{} {}\n", issue.key, issue.title);
+    for (key, name, value) in fields {
+        if let Some(value) = value {
+            item.metadata.insert(key.to_string(), value.clone());
+            preview.push_str(&format!("\n{}: {}", name, value));
+        }
+    }
+    item.metadata.insert("preview".to_string(), preview);
+    item.metadata.insert("keywords".to_string(), issue.key.clone());
+    item
+}
+
+/// Tickets matching an `issue` query, or the error in a single item.
+pub async fn get_issue_items(query: &str) -> Vec<CommandItem> {
+    let Some(text) = parse_query(query) else {
+        return Vec::new();
+    };
+    match search(text).await {
+        Ok(issues) => issues.iter().map(issue_item).collect(),
+        Err(e) => {
+            let mut item = CommandItem::new(&format!("Issue search failed: {}", e), Handler::Copy, &e.to_string());
+            item.icon = "⚠️".to_string();
+            vec![item]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("issue login bug"), Some("login bug"));
+        assert_eq!(parse_query("issue "), None);
+        assert_eq!(parse_query("issues login"), None);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config: IssuesConfig = toml::from_str(
+            "backend = \"jira\"\nkeychain = \"grinta-jira\"\nurl = \"https://acme.atlassian.net\"\nemail = \"me@acme.com\"",
+        )
+        .unwrap();
+        assert_eq!(config.backend, Backend::Jira);
+        assert!(config.is_configured());
+        assert!(!IssuesConfig::default().is_configured());
+    }
+
+    #[test]
+    fn test_parse_linear() {
+        let response = json!({"data": {"searchIssues": {"nodes": [
+            {
+                "identifier": "ENG-42",
+                "title": "Fix login",
+                "url": "https://linear.app/acme/issue/ENG-42/fix-login",
+                "priorityLabel": "High",
+                "state": {"name": "In Progress"},
+                "assignee": {"name": "Sam"},
+                "team": {"name": "Engineering"}
+            },
+            {
+                "identifier": "ENG-7",
+                "title": "Dark mode",
+                "url": "https://linear.app/acme/issue/ENG-7/dark-mode",
+                "priorityLabel": "No priority",
+                "state": {"name": "Backlog"},
+                "assignee": null,
+                "team": {"name": "Engineering"}
+            }
+        ]}}});
+        let issues = parse_linear(&response).unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].status.as_deref(), Some("In Progress"));
+        assert_eq!(issues[0].assignee.as_deref(), Some("Sam"));
+        assert_eq!(issues[1].assignee, None);
+        assert_eq!(issues[1].priority, None);
+        assert!(parse_linear(&json!({"errors": [{"message": "Authentication required"}]})).is_none());
+    }
+
+    #[test]
+    fn test_parse_jira() {
+        let response = json!({"issues": [{
+            "key": "OPS-12",
+            "fields": {
+                "summary": "Rotate certificates",
+                "status": {"name": "To Do"},
+                "assignee": {"displayName": "Alex Doe"},
+                "priority": {"name": "Medium"},
+                "project": {"name": "Operations"}
+            }
+        }]});
+        let issues = parse_jira(&response, "https://acme.atlassian.net/").unwrap();
+        assert_eq!(issues[0].url, "https://acme.atlassian.net/browse/OPS-12");
+        assert_eq!(issues[0].assignee.as_deref(), Some("Alex Doe"));
+        assert_eq!(issues[0].project.as_deref(), Some("Operations"));
+    }
+
+    #[test]
+    fn test_jql() {
+        assert_eq!(jql("login \"sso\""), "text ~ \"login \\\"sso\\\"\" ORDER BY updated DESC");
+    }
+
+    #[test]
+    fn test_issue_item() {
+        let issue = Issue {
+            key: "ENG-42".to_string(),
+            title: "Fix login".to_string(),
+            url: "https://linear.app/acme/issue/ENG-42".to_string(),
+            status: Some("In Progress".to_string()),
+            assignee: None,
+            priority: Some("High".to_string()),
+            project: None,
+        };
+        let item = issue_item(&issue);
+        assert_eq!(item.label, "ENG-42 Fix login · In Progress");
+        assert_eq!(item.handler, Handler::Url);
+        assert_eq!(item.value, issue.url);
+        assert_eq!(item.metadata["status"], "In Progress");
+        assert_eq!(item.metadata["assignee"], "Unassigned");
+        assert_eq!(
+            item.metadata["preview"],
+            "ENG-42 Fix login\n\nStatus: In Progress\nAssignee: Unassigned\nPriority: High"
+        );
+    }
+}
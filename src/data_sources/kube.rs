@@ -0,0 +1,271 @@
+use crate::core::{CommandItem, Handler};
+use crate::events::{self, Event};
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+/// A context of a kubeconfig: a cluster, the user to reach it as and the
+/// namespace commands default to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KubeContext {
+    pub name: String,
+    pub cluster: Option<String>,
+    pub namespace: Option<String>,
+}
+
+/// The kubeconfig files kubectl reads: those of `$KUBECONFIG`, or
+/// `~/.kube/config`.
+fn config_paths() -> Vec<PathBuf> {
+    match std::env::var_os("KUBECONFIG").filter(|paths| !paths.is_empty()) {
+        Some(paths) => std::env::split_paths(&paths).collect(),
+        None => dirs::home_dir().map(|home| home.join(".kube/config")).into_iter().collect(),
+    }
+}
+
+/// A YAML scalar without its quotes.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|rest| rest.strip_suffix(quote)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// The contexts of a kubeconfig written in YAML's block style, as kubectl
+/// writes it. Only the `contexts` list is read.
+fn parse_yaml_contexts(config: &str) -> Vec<KubeContext> {
+    let mut contexts: Vec<KubeContext> = Vec::new();
+    let mut in_contexts = false;
+    // Indentation of the keys of the current entry, whether the lines below
+    // are inside its `context` mapping and the indentation of that mapping
+    let mut entry_indent = 0;
+    let mut in_context = false;
+    let mut context_indent = None;
+    for line in config.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if indent(line) == 0 && !trimmed.starts_with('-') {
+            in_contexts = trimmed == "contexts:";
+            continue;
+        }
+        if !in_contexts {
+            continue;
+        }
+        let mut content = trimmed;
+        let mut column = indent(line);
+        if let Some(rest) = trimmed.strip_prefix('-') {
+            if column < entry_indent || contexts.is_empty() {
+                // A new entry of the list
+                contexts.push(KubeContext::default());
+                content = rest.trim_start();
+                column += trimmed.len() - content.len();
+                entry_indent = column;
+                in_context = false;
+                context_indent = None;
+            }
+        }
+        let Some(context) = contexts.last_mut() else {
+            continue;
+        };
+        let Some((key, value)) = content.split_once(':') else {
+            continue;
+        };
+        let value = unquote(value);
+        if column == entry_indent {
+            in_context = key == "context";
+            context_indent = None;
+            if key == "name" {
+                context.name = value;
+            }
+        } else if in_context && column > entry_indent && *context_indent.get_or_insert(column) == column {
+            match key {
+                "cluster" if !value.is_empty() => context.cluster = Some(value),
+                "namespace" if !value.is_empty() => context.namespace = Some(value),
+                _ => {}
+            }
+        }
+    }
+    contexts.retain(|context| !context.name.is_empty());
+    contexts
+}
+
+/// The contexts of a kubeconfig written as JSON.
+fn parse_json_contexts(config: &str) -> Vec<KubeContext> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(config) else {
+        return Vec::new();
+    };
+    let field = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    value
+        .get("contexts")
+        .and_then(|contexts| contexts.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let context = entry.get("context");
+            Some(KubeContext {
+                name: field(entry, "name")?,
+                cluster: context.and_then(|context| field(context, "cluster")),
+                namespace: context.and_then(|context| field(context, "namespace")),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_contexts(config: &str) -> Vec<KubeContext> {
+    if config.trim_start().starts_with('{') {
+        parse_json_contexts(config)
+    } else {
+        parse_yaml_contexts(config)
+    }
+}
+
+fn context_item(context: &KubeContext) -> CommandItem {
+    let mut item = CommandItem::new(&format!("kube {}", context.name), Handler::Kube, &context.name);
+    let mut keywords = Vec::new();
+    if let Some(cluster) = &context.cluster {
+        item.metadata.insert("cluster".to_string(), cluster.clone());
+        keywords.push(cluster.as_str());
+    }
+    if let Some(namespace) = &context.namespace {
+        item.metadata.insert("namespace".to_string(), namespace.clone());
+        keywords.push(namespace.as_str());
+    }
+    if !keywords.is_empty() {
+        item.metadata.insert("keywords".to_string(), keywords.join(" "));
+    }
+    item
+}
+
+/// `cluster · namespace` for the subtitle of a context item.
+pub fn describe(item: &CommandItem) -> Option<String> {
+    let parts: Vec<String> = [
+        item.metadata.get("cluster").filter(|cluster| **cluster != item.value).cloned(),
+        item.metadata.get("namespace").map(|namespace| format!("namespace {}", namespace)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Items switching to each context of the kubeconfig files. A context in
+/// several files is listed once, as kubectl merges them.
+pub fn get_kube_items() -> Vec<CommandItem> {
+    let mut seen = HashSet::new();
+    config_paths()
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|config| parse_contexts(&config))
+        .filter(|context| seen.insert(context.name.clone()))
+        .map(|context| context_item(&context))
+        .collect()
+}
+
+/// Make `context` kubectl's current context, telling so in the status bar.
+pub async fn use_context(context: &str) -> Result<()> {
+    let output = Command::new("kubectl")
+        .args(["config", "use-context", context])
+        .output()
+        .await
+        .context("Failed to run kubectl")?;
+    if !output.status.success() {
+        bail!("kubectl: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    events::publish(Event::Notified(format!("Switched to context {}", context)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"apiVersion: v1
+clusters:
+- cluster:
+    server: https://prod.example.com
+  name: prod-cluster
+contexts:
+- context:
+    cluster: prod-cluster
+    namespace: web
+    user: admin
+  name: prod
+- name: "minikube"
+  context:
+    cluster: minikube
+    extensions:
+    - extension:
+        namespace: ignored
+      name: context_info
+    namespace: default
+  # comment
+-   name: 'empty'
+    context: {}
+current-context: prod
+kind: Config
+users:
+- name: admin
+"#;
+
+    #[test]
+    fn test_parse_yaml_contexts() {
+        let contexts = parse_contexts(CONFIG);
+        assert_eq!(
+            contexts,
+            vec![
+                KubeContext {
+                    name: "prod".to_string(),
+                    cluster: Some("prod-cluster".to_string()),
+                    namespace: Some("web".to_string()),
+                },
+                KubeContext {
+                    name: "minikube".to_string(),
+                    cluster: Some("minikube".to_string()),
+                    namespace: Some("default".to_string()),
+                },
+                KubeContext {
+                    name: "empty".to_string(),
+                    ..KubeContext::default()
+                },
+            ]
+        );
+        // Indented lists
+        let indented = "contexts:\n  - name: dev\n    context:\n      namespace: api\nusers: []\n";
+        assert_eq!(parse_contexts(indented)[0].namespace.as_deref(), Some("api"));
+        assert!(parse_contexts("contexts: []\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_json_contexts() {
+        let config = r#"{"contexts": [{"name": "dev", "context": {"cluster": "dev", "namespace": "api"}}, {"context": {}}]}"#;
+        let contexts = parse_contexts(config);
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].name, "dev");
+        assert_eq!(contexts[0].namespace.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_context_item() {
+        let item = context_item(&parse_contexts(CONFIG)[0]);
+        assert_eq!(item.label, "kube prod");
+        assert_eq!(item.handler, Handler::Kube);
+        assert_eq!(item.value, "prod");
+        assert_eq!(item.metadata["keywords"], "prod-cluster web");
+        assert_eq!(describe(&item).as_deref(), Some("prod-cluster · namespace web"));
+
+        // A cluster named like its context isn't repeated
+        let minikube = context_item(&parse_contexts(CONFIG)[1]);
+        assert_eq!(describe(&minikube).as_deref(), Some("namespace default"));
+        assert_eq!(describe(&context_item(&KubeContext { name: "x".to_string(), ..KubeContext::default() })), None);
+    }
+}
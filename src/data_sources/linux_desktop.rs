@@ -0,0 +1,230 @@
+use crate::core::{CommandItem, CommandType, Handler};
+use crate::data_sources::desktop_entries::{application_dirs, parse_hidden_desktop_entry};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Recent documents listed, most recently used first.
+const MAX_RECENT: usize = 30;
+
+/// Desktop environments with settings panels Grinta can list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Desktop {
+    Gnome,
+    Kde,
+}
+
+/// The desktop from `XDG_CURRENT_DESKTOP`, a colon separated list such as
+/// `ubuntu:GNOME`.
+pub fn parse_desktop(value: &str) -> Option<Desktop> {
+    value.split(':').find_map(|name| match name.trim().to_ascii_lowercase().as_str() {
+        "gnome" | "gnome-classic" | "gnome-flashback" | "unity" => Some(Desktop::Gnome),
+        "kde" => Some(Desktop::Kde),
+        _ => None,
+    })
+}
+
+fn current_desktop() -> Option<Desktop> {
+    parse_desktop(&env::var("XDG_CURRENT_DESKTOP").ok()?)
+}
+
+/// Whether the `.desktop` file opens a panel of the desktop's settings app:
+/// `gnome-wifi-panel.desktop` on GNOME, `kcm_bluetooth.desktop` on KDE.
+pub fn is_settings_panel(desktop: Desktop, file_name: &str) -> bool {
+    match desktop {
+        Desktop::Gnome => file_name.starts_with("gnome-") && file_name.ends_with("-panel.desktop"),
+        Desktop::Kde => file_name.starts_with("kcm_") && file_name.ends_with(".desktop"),
+    }
+}
+
+/// Settings panels of the running desktop. The panels are `NoDisplay`
+/// entries, so they are missing from the applications.
+fn get_settings_panels(desktop: Desktop) -> Vec<CommandItem> {
+    let mut panels = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for dir in application_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let Some(id) = path.file_name().and_then(|name| name.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if !is_settings_panel(desktop, &id) || !seen_ids.insert(id) {
+                continue;
+            }
+            let Some(entry) = fs::read_to_string(&path).ok().and_then(|contents| parse_hidden_desktop_entry(&contents)) else {
+                continue;
+            };
+            let mut item = CommandItem::new(&format!("{} Settings", entry.name), Handler::App, path.to_str().unwrap_or(""));
+            item.kind = CommandType::App;
+            item.icon = "⚙️".to_string();
+            item.metadata.insert("settings".to_string(), "true".to_string());
+            panels.push(item);
+        }
+    }
+    panels
+}
+
+/// Replace the XML entities `recently-used.xbel` escapes in attributes.
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+/// Local path of a `file://` URI.
+fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    Some(PathBuf::from(urlencoding::decode(path).ok()?.into_owned()))
+}
+
+/// `(path, modified)` of the local documents in a GTK `recently-used.xbel`
+/// file. Timestamps are ISO 8601, so they sort as strings.
+pub fn parse_xbel(contents: &str) -> Vec<(PathBuf, String)> {
+    contents
+        .split("<bookmark ")
+        .skip(1)
+        .filter_map(|rest| {
+            // Keep the space the split removed, which `attribute` looks for
+            let tag = format!(" {}", &rest[..rest.find('>')?]);
+            let path = file_uri_path(&unescape_xml(attribute(&tag, "href")?))?;
+            let modified = attribute(&tag, "visited")
+                .or_else(|| attribute(&tag, "modified"))
+                .unwrap_or_default();
+            Some((path, modified.to_string()))
+        })
+        .collect()
+}
+
+/// The document a KDE `RecentDocuments/*.desktop` link points to.
+pub fn parse_recent_link(contents: &str) -> Option<PathBuf> {
+    contents.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "URL" | "URL[$e]").then_some(())?;
+        file_uri_path(value.trim())
+    })
+}
+
+fn gtk_recent_documents() -> Vec<PathBuf> {
+    let Some(file) = dirs::data_dir().map(|dir| dir.join("recently-used.xbel")) else {
+        return Vec::new();
+    };
+    let mut documents = fs::read_to_string(file).map(|c| parse_xbel(&c)).unwrap_or_default();
+    documents.sort_by(|a, b| b.1.cmp(&a.1));
+    documents.into_iter().map(|(path, _)| path).collect()
+}
+
+fn kde_recent_documents() -> Vec<PathBuf> {
+    let Some(Ok(entries)) = dirs::data_dir().map(|dir| fs::read_dir(dir.join("RecentDocuments"))) else {
+        return Vec::new();
+    };
+    let mut documents: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            let target = parse_recent_link(&fs::read_to_string(entry.path()).ok()?)?;
+            Some((modified, target))
+        })
+        .collect();
+    documents.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    documents.into_iter().map(|(_, path)| path).collect()
+}
+
+fn recent_item(path: &Path) -> Option<CommandItem> {
+    let label = path.file_name()?.to_str()?;
+    let is_dir = path.is_dir();
+    let handler = if is_dir { Handler::Folder } else { Handler::File };
+    let mut item = CommandItem::new(label, handler, path.to_str()?);
+    item.metadata.insert("type".to_string(), if is_dir { "folder" } else { "file" }.to_string());
+    item.metadata.insert("recent".to_string(), "true".to_string());
+    Some(item)
+}
+
+/// Recently used documents that still exist, from GTK's
+/// `recently-used.xbel` and, on KDE, Plasma's recent documents.
+fn get_recent_documents(desktop: Option<Desktop>) -> Vec<CommandItem> {
+    let mut paths = gtk_recent_documents();
+    if desktop == Some(Desktop::Kde) {
+        paths.extend(kde_recent_documents());
+    }
+    let mut seen = HashSet::new();
+    paths
+        .into_iter()
+        .filter(|path| path.exists() && seen.insert(path.clone()))
+        .filter_map(|path| recent_item(&path))
+        .take(MAX_RECENT)
+        .collect()
+}
+
+/// Settings panels of the GNOME or KDE session and its recent documents.
+pub async fn get_desktop_items() -> Vec<CommandItem> {
+    tokio::task::spawn_blocking(|| {
+        let desktop = current_desktop();
+        let mut items = desktop.map(get_settings_panels).unwrap_or_default();
+        items.extend(get_recent_documents(desktop));
+        items
+    })
+    .await
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop() {
+        assert_eq!(parse_desktop("ubuntu:GNOME"), Some(Desktop::Gnome));
+        assert_eq!(parse_desktop("KDE"), Some(Desktop::Kde));
+        assert_eq!(parse_desktop("XFCE"), None);
+        assert_eq!(parse_desktop(""), None);
+    }
+
+    #[test]
+    fn test_is_settings_panel() {
+        assert!(is_settings_panel(Desktop::Gnome, "gnome-wifi-panel.desktop"));
+        assert!(!is_settings_panel(Desktop::Gnome, "gnome-terminal.desktop"));
+        assert!(is_settings_panel(Desktop::Kde, "kcm_bluetooth.desktop"));
+        assert!(!is_settings_panel(Desktop::Kde, "gnome-wifi-panel.desktop"));
+    }
+
+    #[test]
+    fn test_parse_xbel() {
+        let xbel = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark href="file:///home/jane/Documents/Q3%20report.pdf" added="2024-05-01T09:00:00Z" modified="2024-05-02T10:00:00Z" visited="2024-05-03T11:00:00Z">
+    <info><metadata owner="http://freedesktop.org"><mime:mime-type type="application/pdf"/></metadata></info>
+  </bookmark>
+  <bookmark href="https://example.com/remote" added="2024-05-01T09:00:00Z" modified="2024-05-01T09:00:00Z">
+  </bookmark>
+  <bookmark href="file:///home/jane/a&amp;b.txt" added="2024-04-01T09:00:00Z" modified="2024-04-01T09:00:00Z">
+  </bookmark>
+</xbel>"#;
+        assert_eq!(
+            parse_xbel(xbel),
+            vec![
+                (PathBuf::from("/home/jane/Documents/Q3 report.pdf"), "2024-05-03T11:00:00Z".to_string()),
+                (PathBuf::from("/home/jane/a&b.txt"), "2024-04-01T09:00:00Z".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_recent_link() {
+        let link = "[Desktop Entry]\nIcon=text-plain\nName=notes.txt\nType=Link\nURL[$e]=file:///home/jane/notes.txt\n";
+        assert_eq!(parse_recent_link(link), Some(PathBuf::from("/home/jane/notes.txt")));
+        assert_eq!(parse_recent_link("[Desktop Entry]\nURL=https://example.com\n"), None);
+    }
+}
@@ -1,48 +1,183 @@
+use chrono::{Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 
 use crate::core::{CommandItem, Handler};
+use crate::internal::InternalCommand;
+
+/// Query prefix listing the folders new notes can go to.
+const FOLDER_PREFIX: &str = "note-folder:";
+
+/// Apple Notes settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotesConfig {
+    /// Read the plaintext of every note so queries match note content.
+    /// Slower to index with many notes
+    pub bodies: bool,
+    /// Account new notes are created in, the default account when unset
+    pub account: Option<String>,
+    /// Folder of that account new notes go to, its default folder when unset
+    pub folder: Option<String>,
+}
+
+impl NotesConfig {
+    /// Where new notes go unless another folder is picked.
+    pub fn note_folder(&self) -> NoteFolder {
+        NoteFolder {
+            account: self.account.clone(),
+            folder: self.folder.clone(),
+        }
+    }
+}
+
+/// An account and one of its folders; unset parts stand for the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteFolder {
+    pub account: Option<String>,
+    pub folder: Option<String>,
+}
+
+impl NoteFolder {
+    /// `account/folder`, as carried by `InternalCommand::SetNoteFolder`.
+    /// Account names can't contain a slash, folder names can.
+    pub fn parse(value: &str) -> NoteFolder {
+        let (account, folder) = value.split_once('/').unwrap_or((value, ""));
+        NoteFolder {
+            account: (!account.is_empty()).then(|| account.to_string()),
+            folder: (!folder.is_empty()).then(|| folder.to_string()),
+        }
+    }
+
+    pub fn to_value(&self) -> String {
+        format!(
+            "{}/{}",
+            self.account.as_deref().unwrap_or(""),
+            self.folder.as_deref().unwrap_or("")
+        )
+    }
+}
+
+impl std::fmt::Display for NoteFolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} · {}",
+            self.folder.as_deref().unwrap_or("default folder"),
+            self.account.as_deref().unwrap_or("default account")
+        )
+    }
+}
+
+/// Characters of a note body kept for searching and the preview pane.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const MAX_BODY_CHARS: usize = 4000;
+
+/// Characters of context shown around a match in a note body.
+const SNIPPET_CONTEXT: usize = 40;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Note {
     pub id: String,
     pub title: String,
     pub folder: String,
+    #[serde(default)]
+    pub account: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    /// Plaintext, when bodies are read
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// The item for a note: its title and folder, with the body (when read)
+/// searchable and shown in the preview pane.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn note_item(note: Note) -> CommandItem {
+    let label = format!("{} ({})", note.title, note.folder);
+    let mut item = CommandItem::new(&label, Handler::Note, &note.id);
+    item.metadata.insert("folder".to_string(), note.folder);
+    if let Some(account) = note.account {
+        item.metadata.insert("account".to_string(), account);
+    }
+    if let Some(body) = note.body.filter(|body| !body.trim().is_empty()) {
+        let body: String = body.chars().take(MAX_BODY_CHARS).collect();
+        item.metadata.insert("preview".to_string(), body.clone());
+        item.metadata.insert("body".to_string(), body);
+    }
+    item
+}
+
+/// `text` lowercased, along with the offset in `text` of the char each
+/// byte comes from, as lowercasing can change the length of a char.
+fn lowercase_with_offsets(text: &str) -> (String, Vec<usize>) {
+    let mut lower = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        lower.extend(c.to_lowercase());
+        offsets.resize(lower.len(), offset);
+    }
+    (lower, offsets)
+}
+
+/// The words around the first place `query` (or one of its words) occurs
+/// in `body`, on a single line, such as `…Oat milk, lemons and basil`.
+pub fn body_snippet(body: &str, query: &str) -> Option<String> {
+    let (lower_body, offsets) = lowercase_with_offsets(body);
+    let lower_query: String = query.chars().flat_map(char::to_lowercase).collect();
+    let found = std::iter::once(lower_query.trim())
+        .chain(lower_query.split_whitespace())
+        .filter(|word| !word.is_empty())
+        .find_map(|word| lower_body.find(word))?;
+    let start = offsets[found];
+
+    let before: Vec<char> = body[..start].chars().collect();
+    let from = before.len().saturating_sub(SNIPPET_CONTEXT);
+    let after: String = body[start..].chars().take(SNIPPET_CONTEXT * 2).collect();
+    let snippet = format!("{}{}", before[from..].iter().collect::<String>(), after);
+    let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+    let prefix = if from > 0 { "…" } else { "" };
+    let suffix = if body[start..].chars().count() > SNIPPET_CONTEXT * 2 { "…" } else { "" };
+    Some(format!("{}{}{}", prefix, snippet, suffix))
 }
 
 #[cfg(target_os = "macos")]
 pub async fn get_notes() -> Vec<CommandItem> {
     let mut notes = Vec::new();
-    
+    let read_bodies = crate::config::get().notes.bodies;
+
     // JavaScript to fetch notes from the Notes app
     let script = r#"
+        const readBodies = READ_BODIES;
         const Notes = Application("Notes");
         Notes.includeStandardAdditions = true;
 
-        const folders = Notes.folders();
         const notes = [];
 
-        folders.forEach(function(folder) {
-            return folder.notes().forEach(function(note) {
-                notes.push({
-                    id: note.id(),
-                    title: note.name(),
-                    folder: folder.name(),
-                    createdAt: note.creationDate(),
-                    updatedAt: note.modificationDate()
-                })
+        Notes.accounts().forEach(function(account) {
+            account.folders().forEach(function(folder) {
+                folder.notes().forEach(function(note) {
+                    notes.push({
+                        id: note.id(),
+                        title: note.name(),
+                        folder: folder.name(),
+                        account: account.name(),
+                        createdAt: note.creationDate(),
+                        updatedAt: note.modificationDate(),
+                        body: readBodies ? note.plaintext() : null
+                    });
+                });
             });
         });
         console.log(JSON.stringify(notes));
-    "#;
-    
+    "#
+    .replace("READ_BODIES", if read_bodies { "true" } else { "false" });
+
     // Run osascript to execute the JavaScript
     if let Ok(output) = Command::new("osascript")
-        .args(["-l", "JavaScript", "-e", script])
+        .args(["-l", "JavaScript", "-e", &script])
         .output()
         .await
     {
@@ -50,12 +185,8 @@ pub async fn get_notes() -> Vec<CommandItem> {
         if let Ok(output_str) = String::from_utf8(output.stderr) {
             // Parse the JSON output
             if let Ok(parsed_notes) = serde_json::from_str::<Vec<Note>>(&output_str) {
-                for note in parsed_notes {
-                    // Create a command item for each note
-                    // Store the note ID in the value field
-                    let label = format!("{} ({})", note.title, note.folder);
-                    notes.push(CommandItem::new(&label, Handler::Note, &note.id));
-                }
+                // The note ID is stored in the value field
+                notes.extend(parsed_notes.into_iter().map(note_item));
             }
         }
     }
@@ -82,33 +213,50 @@ pub async fn open_note(note_id: &str) -> std::io::Result<()> {
         .map(|_| ())
 }
 
+/// Create a note in `target` and return its id. Fails with the account
+/// or folder name when it doesn't exist.
 #[cfg(target_os = "macos")]
-pub async fn create_note(name: &str, body: Option<&str>) -> std::io::Result<String> {
+pub async fn create_note(name: &str, body: Option<&str>, target: &NoteFolder) -> std::io::Result<String> {
     // Format the note body with title
     let formatted_body = format_note_body(name, body.unwrap_or(""));
-    
+    let js_option = |name: &Option<String>| name.as_deref().map_or("null".to_string(), js_string);
+
     // JavaScript to create a new note
     let script = format!(r#"
         const Notes = Application("Notes");
         Notes.includeStandardAdditions = true;
-        const accountName = "iCloud";
-        const folderName = "Notes";
-        const account = Notes.accounts.byName(accountName);
-        const folder = account.folders.byName(folderName);
-        const newNote = Notes.Note({{  
-            body: `{}`
-        }});
+        const accountName = {};
+        const folderName = {};
+        let account = Notes.defaultAccount();
+        if (accountName !== null) {{
+            const accounts = Notes.accounts.whose({{ name: accountName }})();
+            if (accounts.length === 0) throw new Error("No Notes account named " + accountName);
+            account = accounts[0];
+        }}
+        let folder = account.defaultFolder();
+        if (folderName !== null) {{
+            const folders = account.folders.whose({{ name: folderName }})();
+            if (folders.length === 0) throw new Error("No folder named " + folderName + " in " + account.name());
+            folder = folders[0];
+        }}
+        const newNote = Notes.Note({{ body: {} }});
         folder.notes.push(newNote);
         const noteId = newNote.id().trim();
         console.log(noteId);
-    "#, formatted_body);
-    
+    "#, js_option(&target.account), js_option(&target.folder), js_string(&formatted_body));
+
     // Run osascript to execute the JavaScript
     let output = Command::new("osascript")
         .args(["-l", "JavaScript", "-e", &script])
         .output()
         .await?;
-    
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        // osascript prefixes the thrown message with the script position
+        let message = message.rsplit("Error: ").next().unwrap_or(&message);
+        return Err(std::io::Error::other(message.trim().trim_end_matches(" (-2700)").to_string()));
+    }
+
     // Get the note ID from stderr
     if let Ok(note_id) = String::from_utf8(output.stderr) {
         // Remove any newlines
@@ -139,6 +287,129 @@ pub async fn delete_note(note_id: &str) -> std::io::Result<()> {
         .map(|_| ())
 }
 
+/// The note's content as plain text, its title on the first line.
+#[cfg(target_os = "macos")]
+pub async fn get_note_text(note_id: &str) -> std::io::Result<String> {
+    let script = format!(r#"
+        const Notes = Application("Notes");
+        Notes.notes.byId({}).plaintext();
+    "#, js_string(note_id));
+
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", &script])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+}
+
+/// The note's content as plain text for the editor. Notes with
+/// formatting or attachments are refused, since saving plain text would
+/// drop them.
+#[cfg(target_os = "macos")]
+pub async fn get_editable_text(note_id: &str) -> std::io::Result<String> {
+    check_editable(note_id).await?;
+    get_note_text(note_id).await
+}
+
+/// Fail unless the note's body is plain text that `text_to_html` can
+/// write back.
+#[cfg(target_os = "macos")]
+async fn check_editable(note_id: &str) -> std::io::Result<()> {
+    let script = format!(r#"
+        const Notes = Application("Notes");
+        const note = Notes.notes.byId({});
+        note.attachments.length + "\n" + note.body();
+    "#, js_string(note_id));
+
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", &script])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (attachments, body) = stdout.split_once('\n').unwrap_or((stdout.as_ref(), ""));
+    if attachments.trim() != "0" || !is_plain_html(body) {
+        return Err(std::io::Error::other(
+            "it has formatting or attachments that plain text would lose; edit it in Notes",
+        ));
+    }
+    Ok(())
+}
+
+/// Replace the note's content with `text`, its first line becoming the
+/// title. Refused for notes with formatting or attachments.
+#[cfg(target_os = "macos")]
+pub async fn set_note_text(note_id: &str, text: &str) -> std::io::Result<()> {
+    // The note may have been formatted in Notes since it was opened
+    check_editable(note_id).await?;
+    let script = format!(r#"
+        const Notes = Application("Notes");
+        Notes.notes.byId({}).body = {};
+    "#, js_string(note_id), js_string(&text_to_html(text)));
+
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", &script])
+        .output()
+        .await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// `text` as a JavaScript string literal.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn js_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Whether note HTML only has the markup `text_to_html` writes: lines,
+/// blank lines and the heading.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn is_plain_html(html: &str) -> bool {
+    html.split('<').skip(1).all(|tag| {
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        matches!(name.to_ascii_lowercase().as_str(), "div" | "h1" | "br")
+    })
+}
+
+/// Note HTML for plain text: the first line as the heading, then one
+/// `<div>` per line.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn text_to_html(text: &str) -> String {
+    let escape = |line: &str| {
+        line.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let mut lines = text.lines();
+    let mut html = format!("<div><h1>{}</h1></div>", escape(lines.next().unwrap_or("")));
+    for line in lines {
+        if line.is_empty() {
+            html.push_str("\n<div><br></div>");
+        } else {
+            html.push_str(&format!("\n<div>{}</div>", escape(line)));
+        }
+    }
+    html
+}
+
 // Helper function to format note body with title
 #[cfg(target_os = "macos")]
 fn format_note_body(title: &str, body: &str) -> String {
@@ -155,3 +426,231 @@ fn format_note_body(title: &str, body: &str) -> String {
 pub async fn get_notes() -> Vec<CommandItem> {
     Vec::new()
 }
+
+#[cfg(not(target_os = "macos"))]
+fn notes_unsupported() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Apple Notes is only available on macOS",
+    )
+}
+
+/// There is no Notes app to show the note in.
+#[cfg(not(target_os = "macos"))]
+pub async fn open_note(_note_id: &str) -> std::io::Result<()> {
+    Err(notes_unsupported())
+}
+
+/// There is no Notes app to create the note in.
+#[cfg(not(target_os = "macos"))]
+pub async fn create_note(_name: &str, _body: Option<&str>, _target: &NoteFolder) -> std::io::Result<String> {
+    Err(notes_unsupported())
+}
+
+/// There is no Notes app to delete the note from.
+#[cfg(not(target_os = "macos"))]
+pub async fn delete_note(_note_id: &str) -> std::io::Result<()> {
+    Err(notes_unsupported())
+}
+
+/// There is no Notes app to read the note from.
+#[cfg(not(target_os = "macos"))]
+pub async fn get_note_text(_note_id: &str) -> std::io::Result<String> {
+    Err(notes_unsupported())
+}
+
+/// There is no Notes app to read the note from, so nothing is editable.
+#[cfg(not(target_os = "macos"))]
+pub async fn get_editable_text(_note_id: &str) -> std::io::Result<String> {
+    Err(notes_unsupported())
+}
+
+/// There is no Notes app to save the note to.
+#[cfg(not(target_os = "macos"))]
+pub async fn set_note_text(_note_id: &str, _text: &str) -> std::io::Result<()> {
+    Err(notes_unsupported())
+}
+
+/// Items of a `note-folder: work` query: the folders of the indexed notes
+/// matching the rest of the query, each choosing where new notes go.
+pub fn folder_items(query: &str, items: &[CommandItem]) -> Option<Vec<CommandItem>> {
+    let filter = query.strip_prefix(FOLDER_PREFIX)?.trim().to_lowercase();
+    let mut folders: Vec<NoteFolder> = items
+        .iter()
+        .filter(|item| item.handler == Handler::Note)
+        .filter_map(|item| {
+            Some(NoteFolder {
+                account: item.metadata.get("account").cloned(),
+                folder: Some(item.metadata.get("folder")?.clone()),
+            })
+        })
+        .collect();
+    folders.sort_by(|a, b| (&a.account, &a.folder).cmp(&(&b.account, &b.folder)));
+    folders.dedup();
+    folders.insert(0, NoteFolder::default());
+
+    let items = folders
+        .into_iter()
+        .filter(|folder| folder.to_string().to_lowercase().contains(&filter))
+        .map(|folder| {
+            let mut item = InternalCommand::SetNoteFolder(folder.to_value())
+                .to_item(&format!("New notes go to {}", folder));
+            item.icon = "🗂️".to_string();
+            item
+        })
+        .collect();
+    Some(items)
+}
+
+/// Title of the daily note for `date`, such as `2024-03-18`.
+pub fn daily_note_title(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// The note titled `title` among the indexed items, whose labels read
+/// `<title> (<folder>)`.
+fn find_note<'a>(items: &'a [CommandItem], title: &str) -> Option<&'a CommandItem> {
+    let prefix = format!("{} (", title);
+    items
+        .iter()
+        .find(|item| item.handler == Handler::Note && item.label.starts_with(&prefix))
+}
+
+/// Items for the `today` command: today's daily note, or an item creating
+/// it, then yesterday's note when there is one. None for other queries.
+pub fn daily_note_items(query: &str, items: &[CommandItem], today: NaiveDate) -> Option<Vec<CommandItem>> {
+    if query != "today" {
+        return None;
+    }
+    let title = daily_note_title(today);
+    let mut daily = vec![match find_note(items, &title) {
+        Some(note) => CommandItem::new(&format!("Today's note · {}", title), Handler::Note, &note.value),
+        None => InternalCommand::CreateNote(title.clone()).to_item(&format!("Create today's note · {}", title)),
+    }];
+    let yesterday = daily_note_title(today - Duration::days(1));
+    if let Some(note) = find_note(items, &yesterday) {
+        daily.push(CommandItem::new(&format!("Yesterday's note · {}", yesterday), Handler::Note, &note.value));
+    }
+    for item in &mut daily {
+        item.icon = "📅".to_string();
+    }
+    Some(daily)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 18).unwrap()
+    }
+
+    #[test]
+    fn test_note_item_keeps_body() {
+        let json = r#"[{"id": "x-coredata://1", "title": "Groceries", "folder": "Notes", "createdAt": "", "updatedAt": "", "body": "Groceries\nOat milk"},
+                       {"id": "x-coredata://2", "title": "Empty", "folder": "Notes", "createdAt": "", "updatedAt": ""}]"#;
+        let notes: Vec<Note> = serde_json::from_str(json).unwrap();
+        let items: Vec<CommandItem> = notes.into_iter().map(note_item).collect();
+        assert_eq!(items[0].label, "Groceries (Notes)");
+        assert_eq!(items[0].metadata["body"], "Groceries\nOat milk");
+        assert_eq!(items[0].metadata["preview"], "Groceries\nOat milk");
+        assert!(!items[1].metadata.contains_key("body"));
+    }
+
+    #[test]
+    fn test_text_to_html() {
+        assert_eq!(
+            text_to_html("Groceries\nOat milk & <lemons>\n\nBasil"),
+            "<div><h1>Groceries</h1></div>\n<div>Oat milk &amp; &lt;lemons&gt;</div>\n<div><br></div>\n<div>Basil</div>"
+        );
+        assert_eq!(text_to_html(""), "<div><h1></h1></div>");
+        assert!(is_plain_html(&text_to_html("Groceries\nOat milk & <lemons>\n\nBasil")));
+        assert!(!is_plain_html("<div><h1>Trip</h1></div><div><b>Bold</b></div>"));
+        assert!(!is_plain_html("<div><ul><li>One</li></ul></div>"));
+        assert!(!is_plain_html("<div><img src=\"data:image/png;base64,AA\"></div>"));
+        assert_eq!(js_string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+    }
+
+    #[test]
+    fn test_note_folder_round_trip() {
+        let folder = NoteFolder {
+            account: Some("iCloud".to_string()),
+            folder: Some("Work/Clients".to_string()),
+        };
+        assert_eq!(NoteFolder::parse(&folder.to_value()), folder);
+        assert_eq!(NoteFolder::parse(&NoteFolder::default().to_value()), NoteFolder::default());
+        assert_eq!(folder.to_string(), "Work/Clients · iCloud");
+    }
+
+    #[test]
+    fn test_folder_items() {
+        let json = r#"[{"id": "1", "title": "Standup", "folder": "Work", "account": "Exchange", "createdAt": "", "updatedAt": ""},
+                       {"id": "2", "title": "Retro", "folder": "Work", "account": "Exchange", "createdAt": "", "updatedAt": ""},
+                       {"id": "3", "title": "Groceries", "folder": "Notes", "account": "iCloud", "createdAt": "", "updatedAt": ""}]"#;
+        let notes: Vec<Note> = serde_json::from_str(json).unwrap();
+        let items: Vec<CommandItem> = notes.into_iter().map(note_item).collect();
+
+        assert_eq!(folder_items("groceries", &items), None);
+        let all = folder_items("note-folder:", &items).unwrap();
+        let labels: Vec<&str> = all.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "New notes go to default folder · default account",
+                "New notes go to Work · Exchange",
+                "New notes go to Notes · iCloud",
+            ]
+        );
+        let work = folder_items("note-folder: exch", &items).unwrap();
+        assert_eq!(work.len(), 1);
+        assert_eq!(
+            InternalCommand::parse(&work[0].value),
+            Some(InternalCommand::SetNoteFolder("Exchange/Work".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_body_snippet() {
+        let body = format!("{}Oat milk, lemons and basil\n{}", "intro ".repeat(20), "more ".repeat(20));
+        let snippet = body_snippet(&body, "LEMONS").unwrap();
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("Oat milk, lemons and basil more"));
+        assert_eq!(body_snippet("Zest two lemons", "lemons").unwrap(), "Zest two lemons");
+        // Any word of the query
+        assert_eq!(body_snippet("Zest two lemons", "tart lemons").unwrap(), "Zest two lemons");
+        assert_eq!(body_snippet("Zest two lemons", "basil"), None);
+        // Lowercasing "ẞİ" keeps its length but moves the char boundaries
+        assert_eq!(body_snippet("ẞİ", "i").unwrap(), "ẞİ");
+        assert_eq!(body_snippet("Straße İstanbul", "STANBUL").unwrap(), "Straße İstanbul");
+    }
+
+    #[test]
+    fn test_daily_note_items_create_today() {
+        assert_eq!(daily_note_items("todo", &[], today()), None);
+
+        let items = daily_note_items("today", &[], today()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Create today's note · 2024-03-18");
+        assert_eq!(
+            InternalCommand::parse(&items[0].value),
+            Some(InternalCommand::CreateNote("2024-03-18".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_daily_note_items_open_existing() {
+        let notes = vec![
+            CommandItem::new("2024-03-17 (Journal)", Handler::Note, "x-coredata://17"),
+            CommandItem::new("2024-03-18 (Journal)", Handler::Note, "x-coredata://18"),
+            CommandItem::new("2024-03-18 (Downloads)", Handler::File, "/tmp/2024-03-18 (Downloads)"),
+        ];
+        let items = daily_note_items("today", &notes, today()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].label, "Today's note · 2024-03-18");
+        assert_eq!(items[0].handler, Handler::Note);
+        assert_eq!(items[0].value, "x-coredata://18");
+        assert_eq!(items[1].label, "Yesterday's note · 2024-03-17");
+        assert_eq!(items[1].value, "x-coredata://17");
+    }
+}
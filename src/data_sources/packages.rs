@@ -0,0 +1,249 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const TRACK_COMMAND: &str = "track";
+const CACHE_NAMESPACE: &str = "packages";
+const CACHE_TTL_MINUTES: i64 = 15;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Optional shipment status lookups through Ship24.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackagesConfig {
+    pub ship24_api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Carrier {
+    Ups,
+    Fedex,
+    Dhl,
+    Usps,
+}
+
+impl Carrier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Carrier::Ups => "UPS",
+            Carrier::Fedex => "FedEx",
+            Carrier::Dhl => "DHL",
+            Carrier::Usps => "USPS",
+        }
+    }
+
+    pub fn tracking_url(&self, number: &str) -> String {
+        match self {
+            Carrier::Ups => format!("https://www.ups.com/track?tracknum={}", number),
+            Carrier::Fedex => format!("https://www.fedex.com/fedextrack/?trknbr={}", number),
+            Carrier::Dhl => format!(
+                "https://www.dhl.com/global-en/home/tracking.html?tracking-id={}",
+                number
+            ),
+            Carrier::Usps => format!("https://tools.usps.com/go/TrackConfirmAction?tLabels={}", number),
+        }
+    }
+}
+
+/// Return the normalized tracking number for a `track <number>` query.
+/// `track start` / `track stop` belong to time tracking.
+pub fn parse_query(query: &str) -> Option<String> {
+    let rest = query.strip_prefix(TRACK_COMMAND)?.strip_prefix(' ')?.trim();
+    let number: String = rest
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+    let looks_like_number = number.len() >= 8
+        && number.chars().all(|c| c.is_ascii_alphanumeric())
+        && number.chars().any(|c| c.is_ascii_digit());
+    looks_like_number.then_some(number)
+}
+
+fn all_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Carriers whose tracking number format matches, most likely first.
+/// Some formats are ambiguous (22 digits are used by both USPS and FedEx).
+pub fn detect_carriers(number: &str) -> Vec<Carrier> {
+    let len = number.len();
+    let mut carriers = Vec::new();
+
+    if number.starts_with("1Z") && len == 18 {
+        carriers.push(Carrier::Ups);
+    }
+    // USPS domestic (94..., 92..., 93..., 95...) and international (EA123456789US)
+    let usps_international = len == 13
+        && number[..2].chars().all(|c| c.is_ascii_alphabetic())
+        && all_digits(&number[2..11])
+        && number.ends_with("US");
+    if (all_digits(number) && (20..=22).contains(&len) && number.starts_with('9')) || usps_international {
+        carriers.push(Carrier::Usps);
+    }
+    if all_digits(number) && matches!(len, 12 | 15 | 20 | 22) {
+        carriers.push(Carrier::Fedex);
+    }
+    if (all_digits(number) && len == 10) || number.starts_with("JJD") || number.starts_with("JVGL") {
+        carriers.push(Carrier::Dhl);
+    }
+    carriers
+}
+
+/// Items opening the tracking page of each matching carrier.
+pub fn tracking_items(query: &str) -> Vec<CommandItem> {
+    let Some(number) = parse_query(query) else {
+        return Vec::new();
+    };
+    detect_carriers(&number)
+        .into_iter()
+        .map(|carrier| {
+            let label = format!("Track {} package {}", carrier.name(), number);
+            let mut item = CommandItem::new(&label, Handler::Url, &carrier.tracking_url(&number));
+            item.icon = "📦".to_string();
+            item
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackingStatus {
+    pub status: String,
+    /// Most recent first, as "<time>  <location>  <description>"
+    pub events: Vec<String>,
+}
+
+fn parse_ship24(response: &Value) -> Option<TrackingStatus> {
+    let tracking = &response["data"]["trackings"][0];
+    let status = tracking["shipment"]["statusMilestone"].as_str()?;
+    let events = tracking["events"]
+        .as_array()
+        .map(|events| {
+            events
+                .iter()
+                .map(|event| {
+                    let time = event["occurrenceDatetime"].as_str().unwrap_or("");
+                    let location = event["location"].as_str().unwrap_or("");
+                    let description = event["status"].as_str().unwrap_or("");
+                    format!("{}  {}  {}", time, location, description).trim().to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(TrackingStatus {
+        status: status.replace('_', " "),
+        events,
+    })
+}
+
+async fn fetch_status(number: &str, api_key: &str) -> Result<TrackingStatus> {
+    let response = reqwest::Client::new()
+        .post("https://api.ship24.com/public/v1/trackers/track")
+        .bearer_auth(api_key)
+        .json(&json!({ "trackingNumber": number }))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    parse_ship24(&response).context("No tracking information")
+}
+
+fn status_item(number: &str, status: &TrackingStatus) -> CommandItem {
+    let url = detect_carriers(number)
+        .first()
+        .map(|carrier| carrier.tracking_url(number))
+        .unwrap_or_else(|| format!("https://www.ship24.com/tracking?p={}", number));
+    let label = format!("{}: {}", number, status.status);
+    let mut item = CommandItem::new(&label, Handler::Url, &url);
+    item.icon = "📦".to_string();
+
+    let mut preview = vec![format!("{} — {}", number, status.status), String::new()];
+    preview.extend(status.events.iter().cloned());
+    item.metadata.insert("preview".to_string(), preview.join("\n"));
+    item
+}
+
+/// Shipment status for a `track <number>` query when a Ship24 API key is
+/// configured.
+pub async fn get_status_items(number: &str) -> Vec<CommandItem> {
+    let Some(api_key) = config::get().packages.ship24_api_key.clone() else {
+        return Vec::new();
+    };
+    let ttl = chrono::Duration::minutes(CACHE_TTL_MINUTES);
+    if let Some(status) = cache::get::<TrackingStatus>(CACHE_NAMESPACE, number, ttl) {
+        return vec![status_item(number, &status)];
+    }
+
+    match fetch_status(number, &api_key).await {
+        Ok(status) => {
+            let _ = cache::put(CACHE_NAMESPACE, number, &status);
+            vec![status_item(number, &status)]
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("track 1Z999AA10123456784"), Some("1Z999AA10123456784".to_string()));
+        assert_eq!(parse_query("track 9400 1000 0000 0000 0000 00"), Some("9400100000000000000000".to_string()));
+        assert_eq!(parse_query("track start"), None);
+        assert_eq!(parse_query("track stop"), None);
+        assert_eq!(parse_query("track writing report"), None);
+        assert_eq!(parse_query("tracking 1Z999AA10123456784"), None);
+    }
+
+    #[test]
+    fn test_detect_carriers() {
+        assert_eq!(detect_carriers("1Z999AA10123456784"), vec![Carrier::Ups]);
+        assert_eq!(detect_carriers("123456789012"), vec![Carrier::Fedex]);
+        assert_eq!(detect_carriers("1234567890"), vec![Carrier::Dhl]);
+        assert_eq!(detect_carriers("EA123456789US"), vec![Carrier::Usps]);
+        assert_eq!(
+            detect_carriers("9400100000000000000000"),
+            vec![Carrier::Usps, Carrier::Fedex]
+        );
+        assert!(detect_carriers("ABCDEFGH1").is_empty());
+    }
+
+    #[test]
+    fn test_tracking_items() {
+        let items = tracking_items("track 1Z999AA10123456784");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].handler, Handler::Url);
+        assert_eq!(items[0].value, "https://www.ups.com/track?tracknum=1Z999AA10123456784");
+        assert!(tracking_items("track stop").is_empty());
+    }
+
+    #[test]
+    fn test_parse_ship24() {
+        let response = json!({
+            "data": { "trackings": [{
+                "shipment": { "statusMilestone": "in_transit" },
+                "events": [
+                    { "occurrenceDatetime": "2024-05-02T10:00:00", "location": "Louisville, KY", "status": "Departed facility" },
+                    { "occurrenceDatetime": "2024-05-01T08:00:00", "location": null, "status": "Label created" }
+                ]
+            }]}
+        });
+        let status = parse_ship24(&response).unwrap();
+        assert_eq!(status.status, "in transit");
+        assert_eq!(status.events.len(), 2);
+        assert_eq!(status.events[1], "2024-05-01T08:00:00    Label created");
+
+        let item = status_item("1Z999AA10123456784", &status);
+        assert_eq!(item.label, "1Z999AA10123456784: in transit");
+        assert!(item.metadata["preview"].contains("Departed facility"));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use sysinfo::{Pid, ProcessRefreshKind, Signal, System};
+
+const MAX_PROCESSES: usize = 20;
+
+/// What Enter does with a listed process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    /// `ps <name>`: bring the process's app to the front
+    Focus,
+    /// `kill <name>`: terminate it (Alt+Enter kills it outright)
+    Kill,
+}
+
+impl ProcessAction {
+    fn name(self) -> &'static str {
+        match self {
+            ProcessAction::Focus => "focus",
+            ProcessAction::Kill => "kill",
+        }
+    }
+}
+
+/// A running process, as listed in the results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub memory_bytes: u64,
+}
+
+/// Parse `ps [name]` or `kill <name>`.
+pub fn parse_query(query: &str) -> Option<(ProcessAction, &str)> {
+    if query == "ps" {
+        return Some((ProcessAction::Focus, ""));
+    }
+    if let Some(filter) = query.strip_prefix("ps ") {
+        return Some((ProcessAction::Focus, filter.trim()));
+    }
+    let filter = query.strip_prefix("kill ")?.trim();
+    (!filter.is_empty()).then_some((ProcessAction::Kill, filter))
+}
+
+fn format_memory(bytes: u64) -> String {
+    let megabytes = bytes as f64 / (1024.0 * 1024.0);
+    if megabytes >= 1024.0 {
+        format!("{:.1} GB", megabytes / 1024.0)
+    } else {
+        format!("{:.0} MB", megabytes)
+    }
+}
+
+/// Processes whose name contains `filter`, largest first.
+pub fn select_processes(mut processes: Vec<ProcessInfo>, filter: &str) -> Vec<ProcessInfo> {
+    let filter = filter.to_lowercase();
+    processes.retain(|process| process.name.to_lowercase().contains(&filter));
+    processes.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes).then_with(|| a.pid.cmp(&b.pid)));
+    processes.truncate(MAX_PROCESSES);
+    processes
+}
+
+pub fn process_item(process: &ProcessInfo, action: ProcessAction) -> CommandItem {
+    let verb = match action {
+        ProcessAction::Focus => "",
+        ProcessAction::Kill => "Quit ",
+    };
+    let label = format!(
+        "{}{} ({}) · {}",
+        verb,
+        process.name,
+        process.pid,
+        format_memory(process.memory_bytes)
+    );
+    let mut item = CommandItem::new(&label, Handler::Process, &process.pid.to_string());
+    item.metadata.insert("process_action".to_string(), action.name().to_string());
+    item
+}
+
+fn running_processes() -> Vec<ProcessInfo> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessRefreshKind::new().with_memory());
+    let own_pid = std::process::id();
+    system
+        .processes()
+        .values()
+        .filter(|process| process.pid().as_u32() != own_pid)
+        .map(|process| ProcessInfo {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string(),
+            memory_bytes: process.memory(),
+        })
+        .collect()
+}
+
+/// The running processes matching `filter`, acting on them with `action`.
+pub async fn get_process_items(action: ProcessAction, filter: String) -> Vec<CommandItem> {
+    let processes = tokio::task::spawn_blocking(running_processes).await.unwrap_or_default();
+    select_processes(processes, &filter)
+        .iter()
+        .map(|process| process_item(process, action))
+        .collect()
+}
+
+/// Send SIGTERM, or SIGKILL when `force` is set, to the process `pid`.
+pub fn kill(pid: &str, force: bool) -> Result<()> {
+    let pid = Pid::from_u32(pid.parse().context("Invalid process id")?);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        anyhow::bail!("Process {} is no longer running", pid);
+    }
+    let process = system.process(pid).context("Process not found")?;
+    let signal = if force { Signal::Kill } else { Signal::Term };
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => anyhow::bail!("Failed to signal {} ({})", process.name(), pid),
+        None => anyhow::bail!("Sending {} is not supported on this platform", signal),
+    }
+}
+
+/// Bring the windows of process `pid` to the front.
+pub fn focus(pid: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        let script = format!(
+            "tell application \"System Events\" to set frontmost of (first process whose unix id is {}) to true",
+            pid.parse::<u32>().context("Invalid process id")?
+        );
+        let status = std::process::Command::new("osascript").args(["-e", &script]).status()?;
+        if !status.success() {
+            anyhow::bail!("Process {} has no window to bring to the front", pid);
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let status = std::process::Command::new("xdotool")
+            .args(["search", "--pid", pid, "windowactivate"])
+            .status()
+            .context("Bringing windows to the front needs xdotool")?;
+        if !status.success() {
+            anyhow::bail!("Process {} has no window to bring to the front", pid);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, megabytes: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            memory_bytes: megabytes * 1024 * 1024,
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("ps"), Some((ProcessAction::Focus, "")));
+        assert_eq!(parse_query("ps fire"), Some((ProcessAction::Focus, "fire")));
+        assert_eq!(parse_query("kill slack"), Some((ProcessAction::Kill, "slack")));
+        assert_eq!(parse_query("kill "), None);
+        assert_eq!(parse_query("psql"), None);
+    }
+
+    #[test]
+    fn test_select_processes() {
+        let processes = vec![
+            process(10, "firefox", 300),
+            process(11, "Firefox Helper", 900),
+            process(12, "slack", 500),
+        ];
+        let selected = select_processes(processes, "fire");
+        assert_eq!(selected.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![11, 10]);
+    }
+
+    #[test]
+    fn test_process_item() {
+        let item = process_item(&process(42, "slack", 2048), ProcessAction::Kill);
+        assert_eq!(item.label, "Quit slack (42) · 2.0 GB");
+        assert_eq!(item.handler, Handler::Process);
+        assert_eq!(item.value, "42");
+        assert_eq!(item.metadata["process_action"], "kill");
+        assert_eq!(format_memory(300 * 1024 * 1024), "300 MB");
+    }
+}
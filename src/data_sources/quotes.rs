@@ -0,0 +1,292 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "quotes";
+const CACHE_TTL_MINUTES: i64 = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Crypto tickers recognised without a `$` prefix, with their CoinGecko ids.
+const CRYPTO_IDS: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("sol", "solana"),
+    ("xrp", "ripple"),
+    ("ada", "cardano"),
+    ("doge", "dogecoin"),
+    ("dot", "polkadot"),
+    ("ltc", "litecoin"),
+    ("xmr", "monero"),
+    ("usdt", "tether"),
+];
+
+/// Where stock quotes come from. Crypto quotes always use CoinGecko.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteProvider {
+    #[default]
+    Yahoo,
+    Finnhub,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotesConfig {
+    pub provider: QuoteProvider,
+    /// API key for providers that need one (Finnhub)
+    pub api_key: Option<String>,
+    /// Currency crypto prices are quoted in
+    pub currency: String,
+}
+
+impl Default for QuotesConfig {
+    fn default() -> Self {
+        Self {
+            provider: QuoteProvider::default(),
+            api_key: None,
+            currency: "usd".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol {
+    Stock(String),
+    /// CoinGecko id and ticker
+    Crypto(String, String),
+}
+
+impl Symbol {
+    fn cache_key(&self) -> String {
+        match self {
+            Symbol::Stock(ticker) => format!("stock:{}", ticker),
+            Symbol::Crypto(id, _) => format!("crypto:{}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub symbol: String,
+    pub price: f64,
+    pub change_percent: f64,
+    pub currency: String,
+}
+
+fn crypto_id(ticker: &str) -> Option<&'static str> {
+    CRYPTO_IDS
+        .iter()
+        .find(|(symbol, _)| *symbol == ticker)
+        .map(|(_, id)| *id)
+}
+
+/// Recognise `$AAPL` style stock queries and known crypto tickers (`btc`,
+/// `$eth`).
+pub fn parse_query(query: &str) -> Option<Symbol> {
+    let (prefixed, ticker) = match query.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, query),
+    };
+    let lower = ticker.to_lowercase();
+    if let Some(id) = crypto_id(&lower) {
+        return Some(Symbol::Crypto(id.to_string(), lower.to_uppercase()));
+    }
+
+    let valid = !ticker.is_empty()
+        && ticker.len() <= 10
+        && ticker.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    (prefixed && valid).then(|| Symbol::Stock(ticker.to_uppercase()))
+}
+
+pub fn format_quote(quote: &Quote) -> String {
+    let arrow = if quote.change_percent >= 0.0 { "▲" } else { "▼" };
+    format!(
+        "{} {:.2} {} {} {:.2}%",
+        quote.symbol,
+        quote.price,
+        quote.currency.to_uppercase(),
+        arrow,
+        quote.change_percent.abs()
+    )
+}
+
+fn quote_item(quote: &Quote, stale_minutes: Option<i64>) -> CommandItem {
+    let mut label = format_quote(quote);
+    if let Some(minutes) = stale_minutes {
+        label.push_str(&format!(" (offline, {} min old)", minutes));
+    }
+    let mut item = CommandItem::new(&label, Handler::Copy, &format!("{:.2}", quote.price));
+    item.icon = "💹".to_string();
+    item
+}
+
+async fn fetch_yahoo(client: &reqwest::Client, ticker: &str) -> Result<Quote> {
+    let response = client
+        .get(format!("https://query1.finance.yahoo.com/v8/finance/chart/{}", ticker))
+        .query(&[("range", "1d"), ("interval", "1d")])
+        .header("User-Agent", "Mozilla/5.0")
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    parse_yahoo(ticker, &response).context("Unknown symbol")
+}
+
+fn parse_yahoo(ticker: &str, response: &Value) -> Option<Quote> {
+    let meta = &response["chart"]["result"][0]["meta"];
+    let price = meta["regularMarketPrice"].as_f64()?;
+    let previous = meta["chartPreviousClose"].as_f64().or(meta["previousClose"].as_f64())?;
+    Some(Quote {
+        symbol: ticker.to_string(),
+        price,
+        change_percent: (price - previous) / previous * 100.0,
+        currency: meta["currency"].as_str().unwrap_or("USD").to_string(),
+    })
+}
+
+async fn fetch_finnhub(client: &reqwest::Client, ticker: &str, api_key: &str) -> Result<Quote> {
+    let response = client
+        .get("https://finnhub.io/api/v1/quote")
+        .query(&[("symbol", ticker), ("token", api_key)])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+
+    let price = response["c"].as_f64().filter(|p| *p > 0.0).context("Unknown symbol")?;
+    Ok(Quote {
+        symbol: ticker.to_string(),
+        price,
+        change_percent: response["dp"].as_f64().unwrap_or(0.0),
+        currency: "USD".to_string(),
+    })
+}
+
+async fn fetch_coingecko(client: &reqwest::Client, id: &str, ticker: &str, currency: &str) -> Result<Quote> {
+    let response = client
+        .get("https://api.coingecko.com/api/v3/simple/price")
+        .query(&[
+            ("ids", id),
+            ("vs_currencies", currency),
+            ("include_24hr_change", "true"),
+        ])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    parse_coingecko(id, ticker, currency, &response).context("Unknown coin")
+}
+
+fn parse_coingecko(id: &str, ticker: &str, currency: &str, response: &Value) -> Option<Quote> {
+    let coin = &response[id];
+    Some(Quote {
+        symbol: ticker.to_string(),
+        price: coin[currency].as_f64()?,
+        change_percent: coin[format!("{}_24h_change", currency)].as_f64().unwrap_or(0.0),
+        currency: currency.to_string(),
+    })
+}
+
+async fn fetch_quote(symbol: &Symbol) -> Result<Quote> {
+    let settings = &config::get().quotes;
+    let client = reqwest::Client::new();
+    match symbol {
+        Symbol::Crypto(id, ticker) => fetch_coingecko(&client, id, ticker, &settings.currency).await,
+        Symbol::Stock(ticker) => match (settings.provider, &settings.api_key) {
+            (QuoteProvider::Finnhub, Some(key)) => fetch_finnhub(&client, ticker, key).await,
+            (QuoteProvider::Finnhub, None) => anyhow::bail!("Finnhub needs an api_key"),
+            (QuoteProvider::Yahoo, _) => fetch_yahoo(&client, ticker).await,
+        },
+    }
+}
+
+/// Quote for a `$AAPL` / `btc` query. Fresh quotes are cached for a few
+/// minutes; when the provider can't be reached the last known quote is
+/// shown instead, marked as offline.
+pub async fn get_quote_items(symbol: &Symbol) -> Vec<CommandItem> {
+    let key = symbol.cache_key();
+    let ttl = chrono::Duration::minutes(CACHE_TTL_MINUTES);
+    if let Some(quote) = cache::get::<Quote>(CACHE_NAMESPACE, &key, ttl) {
+        return vec![quote_item(&quote, None)];
+    }
+
+    match fetch_quote(symbol).await {
+        Ok(quote) => {
+            let _ = cache::put(CACHE_NAMESPACE, &key, &quote);
+            vec![quote_item(&quote, None)]
+        }
+        Err(_) => cache::get_stale::<Quote>(CACHE_NAMESPACE, &key)
+            .map(|(quote, age)| vec![quote_item(&quote, Some(age.num_minutes()))])
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("$AAPL"), Some(Symbol::Stock("AAPL".to_string())));
+        assert_eq!(parse_query("$brk.b"), Some(Symbol::Stock("BRK.B".to_string())));
+        assert_eq!(
+            parse_query("btc"),
+            Some(Symbol::Crypto("bitcoin".to_string(), "BTC".to_string()))
+        );
+        assert_eq!(
+            parse_query("$ETH"),
+            Some(Symbol::Crypto("ethereum".to_string(), "ETH".to_string()))
+        );
+        assert_eq!(parse_query("aapl"), None);
+        assert_eq!(parse_query("$"), None);
+        assert_eq!(parse_query("$ not a ticker"), None);
+    }
+
+    #[test]
+    fn test_parse_yahoo() {
+        let response = json!({
+            "chart": { "result": [{ "meta": {
+                "regularMarketPrice": 110.0,
+                "chartPreviousClose": 100.0,
+                "currency": "USD"
+            }}]}
+        });
+        let quote = parse_yahoo("AAPL", &response).unwrap();
+        assert_eq!(quote.price, 110.0);
+        assert!((quote.change_percent - 10.0).abs() < 1e-9);
+        assert!(parse_yahoo("NOPE", &json!({"chart": {"result": null}})).is_none());
+    }
+
+    #[test]
+    fn test_parse_coingecko() {
+        let response = json!({ "bitcoin": { "usd": 65000.5, "usd_24h_change": -2.5 } });
+        let quote = parse_coingecko("bitcoin", "BTC", "usd", &response).unwrap();
+        assert_eq!(quote.price, 65000.5);
+        assert_eq!(quote.change_percent, -2.5);
+    }
+
+    #[test]
+    fn test_quote_item() {
+        let quote = Quote {
+            symbol: "BTC".to_string(),
+            price: 65000.5,
+            change_percent: -2.5,
+            currency: "usd".to_string(),
+        };
+        let item = quote_item(&quote, None);
+        assert_eq!(item.label, "BTC 65000.50 USD ▼ 2.50%");
+        assert_eq!(item.value, "65000.50");
+        assert_eq!(item.handler, Handler::Copy);
+
+        let stale = quote_item(&quote, Some(42));
+        assert!(stale.label.ends_with("(offline, 42 min old)"));
+    }
+}
@@ -0,0 +1,243 @@
+#[cfg(target_os = "macos")]
+use chrono::{Datelike, Timelike};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+#[cfg(target_os = "macos")]
+use tokio::process::Command;
+
+use crate::core::{CommandItem, Handler};
+use crate::internal::InternalCommand;
+use crate::natural_date;
+
+/// Query command adding a reminder, as in `todo buy milk tomorrow 5pm`.
+const QUICK_ADD_PREFIX: &str = "todo ";
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct Reminder {
+    id: String,
+    name: String,
+    list: String,
+    /// Local time, `YYYY-MM-DD HH:MM`, when the reminder is due
+    #[serde(default)]
+    due: Option<String>,
+}
+
+/// The item for an incomplete reminder, with its due time if it has one.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn reminder_item(reminder: Reminder) -> CommandItem {
+    let label = format!("{} ({})", reminder.name, reminder.list);
+    let mut item = CommandItem::new(&label, Handler::Reminder, &reminder.id);
+    item.metadata.insert("list".to_string(), reminder.list);
+    if let Some(due) = reminder.due {
+        item.metadata.insert("due".to_string(), due);
+    }
+    item
+}
+
+/// A reminder typed as `todo <title> [when]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub due: Option<NaiveDateTime>,
+}
+
+/// Split `buy milk tomorrow 5pm` into the title and the longest trailing
+/// words that read as a date or time. The title keeps at least one word.
+pub fn parse_quick_add(text: &str, now: NaiveDateTime) -> Option<QuickAdd> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return None;
+    }
+    for split in 1..words.len() {
+        if let Some(due) = natural_date::parse_datetime(&words[split..].join(" "), now) {
+            return Some(QuickAdd {
+                title: words[..split].join(" "),
+                due: Some(due),
+            });
+        }
+    }
+    Some(QuickAdd {
+        title: words.join(" "),
+        due: None,
+    })
+}
+
+/// Items of a `todo buy milk tomorrow 5pm` query: adding the reminder.
+pub fn quick_add_items(query: &str, now: NaiveDateTime) -> Option<Vec<CommandItem>> {
+    let text = query.strip_prefix(QUICK_ADD_PREFIX)?.trim();
+    let quick_add = parse_quick_add(text, now)?;
+    let label = match quick_add.due {
+        Some(due) => format!("Add reminder: {} · {}", quick_add.title, due.format("%a %b %-d, %H:%M")),
+        None => format!("Add reminder: {}", quick_add.title),
+    };
+    let mut item = InternalCommand::CreateReminder(text.to_string()).to_item(&label);
+    item.icon = Handler::Reminder.to_icon().to_string();
+    Some(vec![item])
+}
+
+/// `text` as a JavaScript string literal.
+#[cfg(target_os = "macos")]
+fn js_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Incomplete reminders of every list.
+#[cfg(target_os = "macos")]
+pub async fn get_reminders() -> Vec<CommandItem> {
+    let script = r#"
+        const Reminders = Application("Reminders");
+        const pad = (n) => String(n).padStart(2, "0");
+        const reminders = [];
+        Reminders.lists().forEach(function(list) {
+            list.reminders.whose({ completed: false })().forEach(function(reminder) {
+                const due = reminder.dueDate();
+                reminders.push({
+                    id: reminder.id(),
+                    name: reminder.name(),
+                    list: list.name(),
+                    due: due ? `${due.getFullYear()}-${pad(due.getMonth() + 1)}-${pad(due.getDate())} ${pad(due.getHours())}:${pad(due.getMinutes())}` : null
+                });
+            });
+        });
+        console.log(JSON.stringify(reminders));
+    "#;
+
+    let Ok(output) = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    // console.log writes to stderr
+    let json = String::from_utf8_lossy(&output.stderr);
+    match serde_json::from_str::<Vec<Reminder>>(json.trim()) {
+        Ok(reminders) => reminders.into_iter().map(reminder_item).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Show the reminder in the Reminders app.
+#[cfg(target_os = "macos")]
+pub async fn open_reminder(reminder_id: &str) -> std::io::Result<()> {
+    let script = format!(r#"
+        const Reminders = Application("Reminders");
+        Reminders.activate();
+        Reminders.show(Reminders.reminders.byId({}));
+    "#, js_string(reminder_id));
+    run_script(&script).await
+}
+
+/// Add a reminder to the default list.
+#[cfg(target_os = "macos")]
+pub async fn create_reminder(quick_add: &QuickAdd) -> std::io::Result<()> {
+    let due = match quick_add.due {
+        // JavaScript months start at 0
+        Some(due) => format!(
+            "new Date({}, {}, {}, {}, {})",
+            due.year(),
+            due.month0(),
+            due.day(),
+            due.hour(),
+            due.minute()
+        ),
+        None => "null".to_string(),
+    };
+    let script = format!(r#"
+        const Reminders = Application("Reminders");
+        const properties = {{ name: {} }};
+        const due = {};
+        if (due !== null) properties.dueDate = due;
+        Reminders.defaultList().reminders.push(Reminders.Reminder(properties));
+    "#, js_string(&quick_add.title), due);
+    run_script(&script).await
+}
+
+#[cfg(target_os = "macos")]
+async fn run_script(script: &str) -> std::io::Result<()> {
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script])
+        .output()
+        .await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn reminders_unsupported() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Apple Reminders is only available on macOS",
+    )
+}
+
+/// There is no Reminders app to show the reminder in.
+#[cfg(not(target_os = "macos"))]
+pub async fn open_reminder(_reminder_id: &str) -> std::io::Result<()> {
+    Err(reminders_unsupported())
+}
+
+/// There is no Reminders app to add the reminder to.
+#[cfg(not(target_os = "macos"))]
+pub async fn create_reminder(_quick_add: &QuickAdd) -> std::io::Result<()> {
+    Err(reminders_unsupported())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 5, 6).unwrap().and_hms_opt(18, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_quick_add() {
+        let quick_add = parse_quick_add("buy milk tomorrow 5pm", now()).unwrap();
+        assert_eq!(quick_add.title, "buy milk");
+        assert_eq!(
+            quick_add.due,
+            NaiveDate::from_ymd_opt(2026, 5, 7).unwrap().and_hms_opt(17, 0, 0)
+        );
+
+        let quick_add = parse_quick_add("call the bank friday", now()).unwrap();
+        assert_eq!(quick_add.title, "call the bank");
+        assert_eq!(quick_add.due.unwrap().date(), NaiveDate::from_ymd_opt(2026, 5, 8).unwrap());
+
+        // The title is never read as a date
+        assert_eq!(parse_quick_add("tomorrow", now()).unwrap().due, None);
+        assert_eq!(parse_quick_add("water the plants", now()).unwrap().due, None);
+        assert_eq!(parse_quick_add("  ", now()), None);
+    }
+
+    #[test]
+    fn test_quick_add_items() {
+        assert_eq!(quick_add_items("buy milk", now()), None);
+        let items = quick_add_items("todo buy milk tomorrow 5pm", now()).unwrap();
+        assert_eq!(items[0].label, "Add reminder: buy milk · Thu May 7, 17:00");
+        assert_eq!(
+            InternalCommand::parse(&items[0].value),
+            Some(InternalCommand::CreateReminder("buy milk tomorrow 5pm".to_string()))
+        );
+        assert_eq!(quick_add_items("todo  ", now()), None);
+    }
+
+    #[test]
+    fn test_reminder_item() {
+        let json = r#"[{"id": "x-apple-reminder://1", "name": "Buy milk", "list": "Groceries", "due": "2026-05-07 17:00"},
+                       {"id": "x-apple-reminder://2", "name": "Call the bank", "list": "Reminders", "due": null}]"#;
+        let reminders: Vec<Reminder> = serde_json::from_str(json).unwrap();
+        let items: Vec<CommandItem> = reminders.into_iter().map(reminder_item).collect();
+        assert_eq!(items[0].label, "Buy milk (Groceries)");
+        assert_eq!(items[0].handler, Handler::Reminder);
+        assert_eq!(items[0].metadata["due"], "2026-05-07 17:00");
+        assert!(!items[1].metadata.contains_key("due"));
+    }
+}
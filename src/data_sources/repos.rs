@@ -0,0 +1,576 @@
+use crate::clipboard;
+use crate::commands;
+use crate::config::{self, expand_tilde};
+use crate::core::{CommandItem, Handler};
+use crate::events::{self, Event};
+use crate::shell;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+/// How many `git status` run at once.
+const STATUS_CONCURRENCY: usize = 4;
+/// A repository whose status takes longer is shown without it.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Git repositories found under `[repos]` roots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReposConfig {
+    /// Folders searched for repositories, such as `~/Projects`
+    pub roots: Vec<String>,
+    /// How many folders deep repositories are looked for under a root
+    pub depth: usize,
+    /// Command opening a repository in an editor, such as `code` or `zed`.
+    /// `$VISUAL` or `$EDITOR` in a terminal when unset
+    pub editor: Option<String>,
+    /// Show the open pull requests and CI status of GitHub repositories
+    pub github_status: bool,
+    /// How often the GitHub status is fetched again
+    pub github_refresh_minutes: u64,
+}
+
+impl Default for ReposConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            depth: 3,
+            editor: None,
+            github_status: true,
+            github_refresh_minutes: 10,
+        }
+    }
+}
+
+/// A repository and what is read from its `.git` folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    pub path: PathBuf,
+    /// The branch checked out, or the short commit of a detached `HEAD`
+    pub branch: Option<String>,
+    /// Web page of the `origin` remote
+    pub remote: Option<String>,
+}
+
+/// Whether an item is a repository rather than a plain folder.
+pub fn is_repo(item: &CommandItem) -> bool {
+    item.handler == Handler::Folder && item.metadata.contains_key("repo")
+}
+
+/// Folders under `root` holding a `.git`, at most `depth` levels down.
+/// Repositories aren't searched for nested ones, nor are hidden folders.
+fn find_repos(root: &Path, depth: usize, repos: &mut Vec<PathBuf>) {
+    if root.join(".git").exists() {
+        repos.push(root.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    let mut folders: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .collect();
+    folders.sort();
+    for folder in folders {
+        find_repos(&folder, depth - 1, repos);
+    }
+}
+
+/// The repository's git folder: `.git` itself, or the folder a worktree's
+/// `.git` file points to.
+fn git_dir(repo: &Path) -> Option<PathBuf> {
+    let dot_git = repo.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let pointer = fs::read_to_string(&dot_git).ok()?;
+    let dir = pointer.trim().strip_prefix("gitdir:")?.trim();
+    Some(repo.join(dir))
+}
+
+/// The folder shared by a repository's worktrees, where its config is.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+/// The branch named by `HEAD`, or the short commit it is detached at.
+fn parse_head(head: &str) -> Option<String> {
+    let head = head.trim();
+    match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            Some(reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string())
+        }
+        None if head.len() >= 7 && head.chars().all(|c| c.is_ascii_hexdigit()) => Some(head[..7].to_string()),
+        None => None,
+    }
+}
+
+/// The `url` of `[remote "origin"]` in a git config.
+fn parse_origin_url(config: &str) -> Option<String> {
+    let mut in_origin = false;
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+            continue;
+        }
+        if !in_origin {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "url" {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// The web page of a remote such as `git@github.com:owner/repo.git` or
+/// `ssh://git@host:22/owner/repo`, or `None` for a local one.
+fn web_url(remote: &str) -> Option<String> {
+    let (host, path) = if let Some(rest) = remote.strip_prefix("https://").or_else(|| remote.strip_prefix("http://")) {
+        rest.split_once('/')?
+    } else if let Some(rest) = remote.strip_prefix("ssh://").or_else(|| remote.strip_prefix("git://")) {
+        rest.split_once('/')?
+    } else if !remote.contains("://") {
+        // scp-like `user@host:path`
+        remote.split_once(':')?
+    } else {
+        return None;
+    };
+    let host = host.rsplit('@').next()?;
+    // A port belongs to the ssh server, not the web one
+    let host = host.split(':').next()?;
+    let path = path.trim_start_matches('/').trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("https://{}/{}", host, path))
+}
+
+/// Read a repository's branch and remote, without running git.
+fn read_repo(path: &Path) -> Repo {
+    let git_dir = git_dir(path);
+    let branch = git_dir
+        .as_ref()
+        .and_then(|dir| fs::read_to_string(dir.join("HEAD")).ok())
+        .and_then(|head| parse_head(&head));
+    let remote = git_dir
+        .as_ref()
+        .and_then(|dir| fs::read_to_string(common_dir(dir).join("config")).ok())
+        .and_then(|config| parse_origin_url(&config))
+        .and_then(|url| web_url(&url));
+    Repo {
+        path: path.to_path_buf(),
+        branch,
+        remote,
+    }
+}
+
+/// Whether the work tree has uncommitted changes, `None` when git can't
+/// tell in time.
+async fn is_dirty(path: &Path) -> Option<bool> {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["status", "--porcelain"])
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(STATUS_TIMEOUT, status).await.ok()?.ok()?;
+    output.status.success().then(|| !output.stdout.iter().all(u8::is_ascii_whitespace))
+}
+
+fn repo_item(repo: &Repo) -> CommandItem {
+    let name = repo.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let mut item = CommandItem::new(&name, Handler::Folder, &repo.path.to_string_lossy());
+    item.metadata.insert("repo".to_string(), "true".to_string());
+    item.metadata.insert("keywords".to_string(), "git repo".to_string());
+    if let Some(branch) = &repo.branch {
+        item.metadata.insert("branch".to_string(), branch.clone());
+    }
+    if let Some(remote) = &repo.remote {
+        item.metadata.insert("remote".to_string(), remote.clone());
+    }
+    item
+}
+
+/// Show whether a repository item has uncommitted changes.
+pub fn set_dirty(item: &mut CommandItem, dirty: bool) {
+    if dirty {
+        item.metadata.insert("dirty".to_string(), "true".to_string());
+    } else {
+        item.metadata.remove("dirty");
+    }
+}
+
+/// `main · modified` for the subtitle of a repository item.
+pub fn status_line(item: &CommandItem) -> Option<String> {
+    let branch = item.metadata.get("branch")?;
+    Some(if item.metadata.contains_key("dirty") {
+        format!("{} · modified", branch)
+    } else {
+        branch.clone()
+    })
+}
+
+fn repo_paths() -> Vec<PathBuf> {
+    let settings = &config::get().repos;
+    let mut paths = Vec::new();
+    for root in &settings.roots {
+        find_repos(&expand_tilde(root), settings.depth, &mut paths);
+    }
+    paths.dedup();
+    paths
+}
+
+/// The repositories under the configured roots, without their status.
+pub fn list_repos() -> Vec<Repo> {
+    repo_paths().iter().map(|path| read_repo(path)).collect()
+}
+
+/// Items for the repositories under the configured roots. Whether they
+/// have changes is found out afterwards, by `dirty_repos`.
+pub fn get_repo_items() -> Vec<CommandItem> {
+    list_repos().iter().map(repo_item).collect()
+}
+
+/// The paths of the repositories with uncommitted changes, running a few
+/// `git status` at a time.
+pub async fn dirty_repos() -> HashSet<String> {
+    let permits = Arc::new(Semaphore::new(STATUS_CONCURRENCY));
+    let mut statuses = tokio::task::JoinSet::new();
+    for path in repo_paths() {
+        let permits = permits.clone();
+        statuses.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            is_dirty(&path).await?.then(|| path.to_string_lossy().to_string())
+        });
+    }
+    let mut dirty = HashSet::new();
+    while let Some(status) = statuses.join_next().await {
+        if let Ok(Some(path)) = status {
+            dirty.insert(path);
+        }
+    }
+    dirty
+}
+
+/// Open the repository in the configured editor, or in `$VISUAL` or
+/// `$EDITOR` in a terminal.
+pub fn open_in_editor(path: &str) -> Result<()> {
+    let settings = config::get();
+    if let Some(editor) = &settings.repos.editor {
+        let mut words = editor.split_whitespace();
+        let program = words.next().context("The repos editor is empty")?;
+        std::process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .envs(commands::workspace_env())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", program))?;
+        return Ok(());
+    }
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|editor| !editor.is_empty()))
+        .context("Set `editor` under [repos], or $EDITOR")?;
+    commands::open_in_terminal(settings.terminal, &format!("cd {} && {} .", shell::quote(path), editor))
+}
+
+/// Open a terminal window in the repository, or any folder.
+pub fn open_in_terminal(path: &str) -> Result<()> {
+    commands::open_in_terminal(config::get().terminal, &format!("cd {}", shell::quote(path)))
+}
+
+/// The output of `git` run in the repository, failing with its error.
+async fn git(path: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The branch checked out, or the short commit of a detached `HEAD`.
+async fn current_branch(path: &str) -> Result<String> {
+    match git(path, &["branch", "--show-current"]).await? {
+        branch if branch.is_empty() => git(path, &["rev-parse", "--short", "HEAD"]).await,
+        branch => Ok(branch),
+    }
+}
+
+/// Copy the branch checked out, telling so in the status bar.
+pub async fn copy_branch(path: &str) -> Result<()> {
+    let branch = current_branch(path).await?;
+    clipboard::copy(&branch)?;
+    events::publish(Event::Notified(format!("Copied {}", branch)));
+    Ok(())
+}
+
+/// The web page of the `origin` remote, as git sees it now.
+async fn remote_web_url(path: &str) -> Result<String> {
+    let url = git(path, &["remote", "get-url", "origin"]).await?;
+    web_url(&url).with_context(|| format!("origin ({}) has no web page", url))
+}
+
+/// Open the `origin` remote in the browser.
+pub async fn open_remote(path: &str) -> Result<()> {
+    commands::open_target(&remote_web_url(path).await?)
+}
+
+/// The CI runs of `branch` on GitHub, GitLab or Bitbucket.
+fn ci_url(remote: &str, branch: &str) -> Option<String> {
+    let host = remote.strip_prefix("https://")?.split('/').next()?;
+    let branch = urlencoding::encode(branch);
+    if host == "github.com" {
+        Some(format!("{}/actions?query=branch%3A{}", remote, branch))
+    } else if host.contains("gitlab") {
+        Some(format!("{}/-/pipelines?ref={}", remote, branch))
+    } else if host == "bitbucket.org" {
+        Some(format!("{}/pipelines", remote))
+    } else {
+        None
+    }
+}
+
+/// Open the last CI run of the branch checked out: the run itself with the
+/// GitHub CLI, or else the list of the branch's runs.
+pub async fn open_last_ci_run(path: &str) -> Result<()> {
+    let branch = current_branch(path).await?;
+    let remote = remote_web_url(path).await?;
+    let last_run = Command::new("gh")
+        .current_dir(path)
+        .args(["run", "list", "--branch", &branch, "--limit", "1", "--json", "url", "--jq", ".[0].url"])
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|url| url.starts_with("https://"));
+    match last_run.or_else(|| ci_url(&remote, &branch)) {
+        Some(url) => commands::open_target(&url),
+        None => bail!("No known CI for {}", remote),
+    }
+}
+
+/// Ask for a folder with the system picker, starting in `start`. `None`
+/// when cancelled.
+#[cfg(target_os = "macos")]
+async fn pick_folder(start: &Path) -> Result<Option<PathBuf>> {
+    let script = format!(
+        "POSIX path of (choose folder with prompt \"Create the worktree in\" default location POSIX file {})",
+        commands::applescript_string(&start.to_string_lossy())
+    );
+    let output = Command::new("osascript").args(["-e", &script]).output().await?;
+    let folder = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((output.status.success() && !folder.is_empty()).then(|| PathBuf::from(folder)))
+}
+
+/// Ask for a folder with zenity or kdialog, starting in `start`. `None`
+/// when cancelled.
+#[cfg(not(target_os = "macos"))]
+async fn pick_folder(start: &Path) -> Result<Option<PathBuf>> {
+    let start = format!("{}/", start.display());
+    let pickers: [(&str, Vec<&str>); 2] = [
+        ("zenity", vec!["--file-selection", "--directory", "--title=Create the worktree in", "--filename", &start]),
+        ("kdialog", vec!["--getexistingdirectory", &start, "--title", "Create the worktree in"]),
+    ];
+    for (program, args) in pickers {
+        // Not installed, try the next one
+        let Ok(output) = Command::new(program).args(&args).output().await else {
+            continue;
+        };
+        let folder = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok((output.status.success() && !folder.is_empty()).then(|| PathBuf::from(folder)));
+    }
+    bail!("Install zenity or kdialog to pick a folder")
+}
+
+/// `<folder>/<repo>-<branch>`, numbered when taken.
+fn worktree_path(folder: &Path, repo: &str, branch: &str) -> PathBuf {
+    let name = format!("{}-{}", repo, branch.replace('/', "-"));
+    let mut path = folder.join(&name);
+    let mut number = 2;
+    while path.exists() {
+        path = folder.join(format!("{}-{}", name, number));
+        number += 1;
+    }
+    path
+}
+
+/// Add a worktree at the current commit in a folder picked by the user.
+pub async fn create_worktree(path: &str) -> Result<()> {
+    let repo = Path::new(path);
+    let start = repo.parent().unwrap_or(repo);
+    let Some(folder) = pick_folder(start).await? else {
+        return Ok(());
+    };
+    let branch = current_branch(path).await?;
+    let name = repo.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let worktree = worktree_path(&folder, &name, &branch);
+    // Detached, since the branch is already checked out in the repository
+    git(path, &["worktree", "add", "--detach", &worktree.to_string_lossy()]).await?;
+    events::publish(Event::Notified(format!("Created worktree {}", worktree.display())));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_repos() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for repo in ["grinta", "work/api", "work/api/vendor/lib", ".hidden/secret", "a/b/c/deep"] {
+            fs::create_dir_all(root.join(repo).join(".git")).unwrap();
+        }
+        fs::create_dir_all(root.join("notes")).unwrap();
+
+        let mut repos = Vec::new();
+        find_repos(root, 3, &mut repos);
+        let names: Vec<PathBuf> = repos.iter().map(|repo| repo.strip_prefix(root).unwrap().to_path_buf()).collect();
+        assert_eq!(names, vec![PathBuf::from("grinta"), PathBuf::from("work/api")]);
+    }
+
+    #[test]
+    fn test_parse_head() {
+        assert_eq!(parse_head("ref: refs/heads/feature/login\n").as_deref(), Some("feature/login"));
+        assert_eq!(parse_head("0123456789abcdef0123456789abcdef01234567\n").as_deref(), Some("0123456"));
+        assert_eq!(parse_head("garbage"), None);
+    }
+
+    #[test]
+    fn test_parse_origin_url() {
+        let config = "\
+[core]
+\tbare = false
+[remote \"upstream\"]
+\turl = https://github.com/other/repo.git
+[remote \"origin\"]
+\turl = git@github.com:getgrinta/grinta-rs.git
+\tfetch = +refs/heads/*:refs/remotes/origin/*
+";
+        assert_eq!(parse_origin_url(config).as_deref(), Some("git@github.com:getgrinta/grinta-rs.git"));
+        assert_eq!(parse_origin_url("[core]\n\tbare = false\n"), None);
+    }
+
+    #[test]
+    fn test_web_url() {
+        let expected = Some("https://github.com/getgrinta/grinta-rs".to_string());
+        assert_eq!(web_url("git@github.com:getgrinta/grinta-rs.git"), expected);
+        assert_eq!(web_url("https://github.com/getgrinta/grinta-rs.git"), expected);
+        assert_eq!(web_url("https://user@github.com/getgrinta/grinta-rs/"), expected);
+        assert_eq!(web_url("ssh://git@github.com:22/getgrinta/grinta-rs.git"), expected);
+        assert_eq!(
+            web_url("git@gitlab.com:group/sub/project.git").as_deref(),
+            Some("https://gitlab.com/group/sub/project")
+        );
+        assert_eq!(web_url("/srv/git/project.git"), None);
+        assert_eq!(web_url("file:///srv/git/project.git"), None);
+    }
+
+    #[test]
+    fn test_read_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("grinta");
+        fs::create_dir_all(repo.join(".git/worktrees/fix")).unwrap();
+        fs::write(repo.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(repo.join(".git/config"), "[remote \"origin\"]\n\turl = git@github.com:o/grinta.git\n").unwrap();
+
+        let read = read_repo(&repo);
+        assert_eq!(read.branch.as_deref(), Some("main"));
+        assert_eq!(read.remote.as_deref(), Some("https://github.com/o/grinta"));
+
+        // A worktree's `.git` file points into the main repository
+        let worktree = dir.path().join("grinta-fix");
+        fs::create_dir_all(&worktree).unwrap();
+        let git_dir = repo.join(".git/worktrees/fix");
+        fs::write(worktree.join(".git"), format!("gitdir: {}\n", git_dir.display())).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/fix\n").unwrap();
+        fs::write(git_dir.join("commondir"), "../..\n").unwrap();
+        let read = read_repo(&worktree);
+        assert_eq!(read.branch.as_deref(), Some("fix"));
+        assert_eq!(read.remote.as_deref(), Some("https://github.com/o/grinta"));
+    }
+
+    #[test]
+    fn test_repo_item() {
+        let repo = Repo {
+            path: PathBuf::from("/home/me/Projects/grinta"),
+            branch: Some("main".to_string()),
+            remote: None,
+        };
+        let mut item = repo_item(&repo);
+        set_dirty(&mut item, true);
+        assert_eq!(item.label, "grinta");
+        assert_eq!(item.value, "/home/me/Projects/grinta");
+        assert!(is_repo(&item));
+        assert_eq!(status_line(&item).as_deref(), Some("main · modified"));
+        assert!(!item.metadata.contains_key("remote"));
+
+        set_dirty(&mut item, false);
+        assert_eq!(status_line(&item).as_deref(), Some("main"));
+        assert!(!is_repo(&CommandItem::new("src", Handler::Folder, "/tmp/src")));
+    }
+
+    #[test]
+    fn test_ci_url() {
+        assert_eq!(
+            ci_url("https://github.com/o/grinta", "feat/x").as_deref(),
+            Some("https://github.com/o/grinta/actions?query=branch%3Afeat%2Fx")
+        );
+        assert_eq!(
+            ci_url("https://gitlab.example.com/o/grinta", "main").as_deref(),
+            Some("https://gitlab.example.com/o/grinta/-/pipelines?ref=main")
+        );
+        assert_eq!(ci_url("https://bitbucket.org/o/grinta", "main").as_deref(), Some("https://bitbucket.org/o/grinta/pipelines"));
+        assert_eq!(ci_url("https://git.example.com/o/grinta", "main"), None);
+    }
+
+    #[test]
+    fn test_worktree_path() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(worktree_path(dir.path(), "grinta", "feat/x"), dir.path().join("grinta-feat-x"));
+        fs::create_dir(dir.path().join("grinta-main")).unwrap();
+        assert_eq!(worktree_path(dir.path(), "grinta", "main"), dir.path().join("grinta-main-2"));
+    }
+
+    #[tokio::test]
+    async fn test_git_subcommands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        if git(&path, &["init", "--quiet", "--initial-branch=trunk"]).await.is_err() {
+            // git isn't installed
+            return;
+        }
+        assert_eq!(current_branch(&path).await.unwrap(), "trunk");
+        assert!(remote_web_url(&path).await.is_err());
+        git(&path, &["remote", "add", "origin", "git@github.com:o/grinta.git"]).await.unwrap();
+        assert_eq!(remote_web_url(&path).await.unwrap(), "https://github.com/o/grinta");
+    }
+}
@@ -0,0 +1,349 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use crate::keychain;
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT_MS: u64 = 3000;
+const DEFAULT_MIN_INTERVAL_MS: u64 = 1000;
+
+/// A search API queried with `<prefix> <query>`, such as an internal wiki
+/// or inventory. Declared under `[[rest]]`.
+///
+/// `items`, `label` and `value` are JSONPath expressions: `items` selects
+/// the results in the response, and `label` and `value` are read from
+/// each result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestConfig {
+    pub name: String,
+    /// Word the query starts with, which is stripped
+    pub prefix: String,
+    /// Requested with `{query}` replaced by the URL-encoded query
+    pub url: String,
+    #[serde(default = "default_items")]
+    pub items: String,
+    pub label: String,
+    /// A URL is opened in the browser; anything else is copied
+    pub value: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub auth: Option<RestAuth>,
+    /// Requests are at least this far apart, however fast the query changes
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_items() -> String {
+    "$".to_string()
+}
+
+fn default_min_interval_ms() -> u64 {
+    DEFAULT_MIN_INTERVAL_MS
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+/// A header carrying a secret kept in the system keychain rather than in
+/// the config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestAuth {
+    /// Keychain item holding the secret: the service of a generic password
+    /// on macOS, the `service` attribute for `secret-tool` on Linux
+    pub keychain: String,
+    #[serde(default = "default_header")]
+    pub header: String,
+    /// Header value with `{secret}` replaced by the secret
+    #[serde(default = "default_header_value")]
+    pub value: String,
+}
+
+fn default_header() -> String {
+    "Authorization".to_string()
+}
+
+fn default_header_value() -> String {
+    "Bearer {secret}".to_string()
+}
+
+/// One step of a JSONPath expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    /// `*`: every element or field
+    Wildcard,
+}
+
+/// Parse the supported part of JSONPath: `$`, `.key`, `['key']`, `[0]`
+/// and `*`. A path without a leading `$` is read from the current value,
+/// so `title` is `$.title`.
+fn parse_path(path: &str) -> Result<Vec<Step>> {
+    let path = path.trim();
+    let mut rest = path.strip_prefix('$').or_else(|| path.strip_prefix('@')).unwrap_or(path);
+    if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+        // A bare key
+        return parse_path(&format!("$.{}", path));
+    }
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let key = &after[..end];
+            match key {
+                "" => bail!("Empty key in {}", path),
+                "*" => steps.push(Step::Wildcard),
+                key => steps.push(Step::Key(key.to_string())),
+            }
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').with_context(|| format!("Unclosed [ in {}", path))?;
+            let inner = after[..end].trim();
+            let step = if inner == "*" {
+                Step::Wildcard
+            } else if let Some(key) = inner
+                .strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|key| key.strip_suffix('"')))
+            {
+                Step::Key(key.to_string())
+            } else {
+                Step::Index(inner.parse().with_context(|| format!("Invalid index [{}] in {}", inner, path))?)
+            };
+            steps.push(step);
+            rest = &after[end + 1..];
+        } else {
+            bail!("Unexpected {} in {}", rest, path);
+        }
+    }
+    Ok(steps)
+}
+
+/// The values `steps` lead to from `value`.
+fn select<'a>(value: &'a Value, steps: &[Step]) -> Vec<&'a Value> {
+    let mut current = vec![value];
+    for step in steps {
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&Value> {
+                match (step, value) {
+                    (Step::Key(key), Value::Object(object)) => object.get(key).into_iter().collect(),
+                    (Step::Index(index), Value::Array(array)) => array.get(*index).into_iter().collect(),
+                    (Step::Wildcard, Value::Array(array)) => array.iter().collect(),
+                    (Step::Wildcard, Value::Object(object)) => object.values().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// `template` with `{query}` replaced by the URL-encoded query.
+fn request_url(template: &str, query: &str) -> String {
+    template.replace("{query}", &urlencoding::encode(query))
+}
+
+/// How long to wait before a request, when the last one was sent at
+/// `last` and requests are at least `interval` apart.
+fn wait_time(last: Option<Instant>, interval: Duration, now: Instant) -> Duration {
+    last.map(|last| (last + interval).saturating_duration_since(now)).unwrap_or_default()
+}
+
+/// When each source last sent a request.
+static LAST_REQUESTS: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+impl RestConfig {
+    /// The query to send, if this source handles `query`.
+    pub fn argument<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let rest = query.strip_prefix(self.prefix.as_str())?;
+        let argument = rest.strip_prefix(' ')?.trim();
+        (!argument.is_empty()).then_some(argument)
+    }
+
+    /// Items of a response.
+    fn parse_response(&self, response: &Value) -> Result<Vec<CommandItem>> {
+        let label = parse_path(&self.label)?;
+        let value = parse_path(&self.value)?;
+        let mut results = select(response, &parse_path(&self.items)?);
+        // `$.results` selects the array rather than its elements
+        if let [Value::Array(array)] = results.as_slice() {
+            results = array.iter().collect();
+        }
+        let items = results
+            .into_iter()
+            .filter_map(|result| {
+                let label = select(result, &label).into_iter().find_map(scalar)?;
+                let value = select(result, &value).into_iter().find_map(scalar)?;
+                let handler = if value.starts_with("http://") || value.starts_with("https://") {
+                    Handler::Url
+                } else {
+                    Handler::Copy
+                };
+                let mut item = CommandItem::new(&label, handler, &value);
+                if let Some(icon) = &self.icon {
+                    item.icon = icon.clone();
+                }
+                item.metadata.insert("rest".to_string(), self.name.clone());
+                // Configured like the config's own items
+                item.metadata.insert("custom".to_string(), "true".to_string());
+                Some(item)
+            })
+            .collect();
+        Ok(items)
+    }
+
+    async fn fetch(&self, argument: &str) -> Result<Vec<CommandItem>> {
+        let interval = Duration::from_millis(self.min_interval_ms);
+        let last = LAST_REQUESTS.lock().ok().and_then(|requests| requests.get(&self.name).copied());
+        // A newer query aborts the lookup while it waits
+        tokio::time::sleep(wait_time(last, interval, Instant::now())).await;
+        if let Ok(mut requests) = LAST_REQUESTS.lock() {
+            requests.insert(self.name.clone(), Instant::now());
+        }
+
+        let mut request = reqwest::Client::new()
+            .get(request_url(&self.url, argument))
+            .timeout(Duration::from_millis(self.timeout_ms));
+        if let Some(auth) = &self.auth {
+            let secret = keychain::secret(&auth.keychain).await?;
+            request = request.header(auth.header.as_str(), auth.value.replace("{secret}", &secret));
+        }
+        let response = request.send().await?.error_for_status()?;
+        let response: Value = response.json().await?;
+        self.parse_response(&response)
+    }
+}
+
+/// Whether a configured REST source handles `query`.
+pub fn wants_query(query: &str) -> bool {
+    config::get().rest.iter().any(|source| source.argument(query).is_some())
+}
+
+/// Items from every REST source handling `query`, in config order. Sources
+/// that fail contribute nothing.
+pub async fn get_rest_items(query: &str) -> Vec<CommandItem> {
+    let handles: Vec<_> = config::get()
+        .rest
+        .iter()
+        .filter_map(|source| {
+            let argument = source.argument(query)?.to_string();
+            Some(tokio::spawn(async move { source.fetch(&argument).await }))
+        })
+        .collect();
+
+    let mut items = Vec::new();
+    for handle in handles {
+        if let Ok(Ok(source_items)) = handle.await {
+            items.extend(source_items);
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn wiki() -> RestConfig {
+        RestConfig {
+            name: "wiki".to_string(),
+            prefix: "wiki".to_string(),
+            url: "https://wiki.example.com/api/search?q={query}".to_string(),
+            items: "$.data.results".to_string(),
+            label: "title".to_string(),
+            value: "$.links['web ui']".to_string(),
+            icon: None,
+            auth: None,
+            min_interval_ms: DEFAULT_MIN_INTERVAL_MS,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        }
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path("$").unwrap(), vec![]);
+        assert_eq!(
+            parse_path("$.results[*].name").unwrap(),
+            vec![Step::Key("results".to_string()), Step::Wildcard, Step::Key("name".to_string())]
+        );
+        assert_eq!(
+            parse_path("@['display name'][0]").unwrap(),
+            vec![Step::Key("display name".to_string()), Step::Index(0)]
+        );
+        assert_eq!(parse_path("title").unwrap(), vec![Step::Key("title".to_string())]);
+        assert!(parse_path("$.results[").is_err());
+        assert!(parse_path("$..deep").is_err());
+        assert!(parse_path("$[first]").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let value = json!({"hosts": [{"name": "web"}, {"name": "db"}, {"id": 3}]});
+        let names: Vec<&Value> = select(&value, &parse_path("$.hosts[*].name").unwrap());
+        assert_eq!(names, vec![&json!("web"), &json!("db")]);
+        assert_eq!(select(&value, &parse_path("$.hosts[1].name").unwrap()), vec![&json!("db")]);
+        assert!(select(&value, &parse_path("$.missing[0]").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_argument() {
+        let source = wiki();
+        assert_eq!(source.argument("wiki  onboarding "), Some("onboarding"));
+        assert_eq!(source.argument("wiki"), None);
+        assert_eq!(source.argument("wikipedia"), None);
+        assert_eq!(request_url(&source.url, "a&b c"), "https://wiki.example.com/api/search?q=a%26b%20c");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response = json!({"data": {"results": [
+            {"title": "Onboarding", "links": {"web ui": "https://wiki.example.com/x/1"}},
+            {"title": "No link"},
+            {"title": 42, "links": {"web ui": "PAGE-42"}},
+        ]}});
+        let items = wiki().parse_response(&response).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].label, "Onboarding");
+        assert_eq!(items[0].handler, Handler::Url);
+        assert_eq!(items[0].metadata["rest"], "wiki");
+        assert_eq!(items[1].label, "42");
+        assert_eq!(items[1].handler, Handler::Copy);
+
+        // `items` may select the elements themselves
+        let source = RestConfig { items: "$.data.results[*]".to_string(), ..wiki() };
+        assert_eq!(source.parse_response(&response).unwrap().len(), 2);
+        let source = RestConfig { label: "$.title[".to_string(), ..wiki() };
+        assert!(source.parse_response(&response).is_err());
+    }
+
+    #[test]
+    fn test_wait_time() {
+        let interval = Duration::from_millis(1000);
+        let start = Instant::now();
+        assert_eq!(wait_time(None, interval, start), Duration::ZERO);
+        let soon = start + Duration::from_millis(200);
+        assert_eq!(wait_time(Some(start), interval, soon), Duration::from_millis(800));
+        let later = start + Duration::from_secs(10);
+        assert_eq!(wait_time(Some(start), interval, later), Duration::ZERO);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Words of the text used as the name of a snippet saved without one.
+const NAME_WORDS: usize = 5;
+
+/// One file per snippet, named after the snippet.
+pub fn snippets_dir() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("snippets"))
+}
+
+/// Snippets kept together as `name = "text"` pairs.
+fn snippets_file() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("snippets.toml"))
+}
+
+/// Read `(name, text)` pairs from the snippets directory and file.
+fn read_snippets(dir: &Path, file: &Path) -> Vec<(String, String)> {
+    let mut snippets = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if name.starts_with('.') || !path.is_file() {
+                continue;
+            }
+            if let Ok(text) = fs::read_to_string(&path) {
+                // Editors add a final newline the user did not mean to paste
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+                snippets.push((name.to_string(), text.to_string()));
+            }
+        }
+    }
+    if let Some(table) = fs::read_to_string(file)
+        .ok()
+        .and_then(|contents| toml::from_str::<BTreeMap<String, String>>(&contents).ok())
+    {
+        snippets.extend(table);
+    }
+    snippets.sort();
+    snippets
+}
+
+fn snippet_item(name: &str, text: &str) -> CommandItem {
+    let mut item = CommandItem::new(name, Handler::Copy, text);
+    item.icon = "✂️".to_string();
+    item.metadata.insert("snippet".to_string(), "true".to_string());
+    item.metadata.insert("preview".to_string(), text.to_string());
+    item
+}
+
+/// Snippets from `~/.config/grinta/snippets/` and `snippets.toml`. Enter
+/// copies the text.
+pub fn get_snippet_items() -> Vec<CommandItem> {
+    let (Ok(dir), Ok(file)) = (snippets_dir(), snippets_file()) else {
+        return Vec::new();
+    };
+    read_snippets(&dir, &file)
+        .iter()
+        .map(|(name, text)| snippet_item(name, text))
+        .collect()
+}
+
+/// Split `name: text` into its parts. Without a name, the first words of
+/// the text are used.
+pub fn parse_new(query: &str) -> Option<(String, String)> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    if let Some((name, text)) = query.split_once(": ") {
+        if !name.trim().is_empty() && !text.trim().is_empty() {
+            return Some((name.trim().to_string(), text.trim().to_string()));
+        }
+    }
+    let name: Vec<&str> = query.split_whitespace().take(NAME_WORDS).collect();
+    Some((name.join(" "), query.to_string()))
+}
+
+/// A file name for the snippet, keeping the name readable.
+fn file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '\0') { '-' } else { c })
+        .collect();
+    format!("{}.txt", cleaned.trim_start_matches('.'))
+}
+
+/// Save the query as a new snippet in `dir`.
+fn create_in(dir: &Path, query: &str) -> Result<String> {
+    let (name, text) = parse_new(query).context("Type the snippet text first")?;
+    fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(&name));
+    if path.exists() {
+        anyhow::bail!("A snippet named \"{}\" already exists", name);
+    }
+    fs::write(&path, format!("{}\n", text))?;
+    Ok(name)
+}
+
+/// Save the query (`name: text`, or just the text) as a new snippet and
+/// return its name.
+pub fn create(query: &str) -> Result<String> {
+    create_in(&snippets_dir()?, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(
+            parse_new("sig: Best regards, Jane"),
+            Some(("sig".to_string(), "Best regards, Jane".to_string()))
+        );
+        assert_eq!(
+            parse_new("see you at the standup tomorrow morning"),
+            Some(("see you at the standup".to_string(), "see you at the standup tomorrow morning".to_string()))
+        );
+        assert_eq!(parse_new("  "), None);
+    }
+
+    #[test]
+    fn test_create_and_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("snippets");
+        let file = temp_dir.path().join("snippets.toml");
+        fs::write(&file, "addr = \"1 Main St\"").unwrap();
+
+        assert_eq!(create_in(&dir, "sig: Best regards").unwrap(), "sig");
+        assert!(create_in(&dir, "sig: Cheers").is_err());
+
+        let snippets = read_snippets(&dir, &file);
+        assert_eq!(
+            snippets,
+            vec![
+                ("addr".to_string(), "1 Main St".to_string()),
+                ("sig".to_string(), "Best regards".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snippet_item() {
+        let item = snippet_item("sig", "Best regards");
+        assert_eq!(item.handler, Handler::Copy);
+        assert_eq!(item.value, "Best regards");
+        assert_eq!(item.metadata["snippet"], "true");
+        assert_eq!(file_name("a/b: c"), "a-b- c.txt");
+    }
+}
@@ -0,0 +1,227 @@
+use crate::core::{CommandItem, Handler};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const SPELL_COMMAND: &str = "spell";
+const SYSTEM_DICTIONARY: &str = "/usr/share/dict/words";
+const MAX_SUGGESTIONS: usize = 8;
+const MAX_SYNONYMS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Spelling {
+    Correct,
+    Suggestions(Vec<String>),
+}
+
+/// Return the word for a `spell <word>` query.
+pub fn parse_query(query: &str) -> Option<&str> {
+    let word = query.strip_prefix(SPELL_COMMAND)?.strip_prefix(' ')?.trim();
+    let is_word = !word.is_empty() && word.chars().all(|c| c.is_alphabetic() || c == '\'' || c == '-');
+    is_word.then_some(word)
+}
+
+/// Parse the reply of `aspell -a` (ispell pipe mode) for a single word:
+/// `*` when it is correct, `& word count offset: a, b` with suggestions,
+/// `# word offset` when there are none.
+pub fn parse_aspell(output: &str) -> Option<Spelling> {
+    let line = output.lines().find(|line| !line.starts_with('@') && !line.is_empty())?;
+    match line.chars().next()? {
+        '*' | '+' | '-' => Some(Spelling::Correct),
+        '&' => {
+            let (_, suggestions) = line.split_once(": ")?;
+            Some(Spelling::Suggestions(
+                suggestions.split(", ").map(str::to_string).take(MAX_SUGGESTIONS).collect(),
+            ))
+        }
+        '#' => Some(Spelling::Suggestions(Vec::new())),
+        _ => None,
+    }
+}
+
+async fn aspell(word: &str) -> Option<Spelling> {
+    let mut child = Command::new("aspell")
+        .arg("-a")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // `^` keeps a word that looks like a pipe-mode command from being
+    // interpreted as one
+    let mut stdin = child.stdin.take()?;
+    stdin.write_all(format!("^{}\n", word).as_bytes()).await.ok()?;
+    drop(stdin);
+    let output = child.wait_with_output().await.ok()?;
+    parse_aspell(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Check `word` against a word list, suggesting the closest entries when
+/// it is not there.
+pub fn check_word_list(word: &str, words: &str) -> Spelling {
+    let lower = word.to_lowercase();
+    let length = lower.chars().count();
+    let mut candidates = Vec::new();
+    for entry in words.lines() {
+        let entry_lower = entry.to_lowercase();
+        if entry_lower == lower {
+            return Spelling::Correct;
+        }
+        if entry_lower.chars().count().abs_diff(length) > 2 {
+            continue;
+        }
+        let distance = edit_distance(&lower, &entry_lower);
+        if distance <= 2 {
+            candidates.push((distance, entry.to_string()));
+        }
+    }
+    candidates.sort();
+    candidates.dedup_by(|a, b| a.1.eq_ignore_ascii_case(&b.1));
+    Spelling::Suggestions(candidates.into_iter().map(|(_, w)| w).take(MAX_SUGGESTIONS).collect())
+}
+
+/// Synonyms from WordNet's `wn <word> -syns*` output: the line after each
+/// `Sense N` heading lists the words of that sense.
+pub fn parse_wordnet(output: &str, word: &str) -> Vec<String> {
+    let mut synonyms: Vec<String> = Vec::new();
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if !line.starts_with("Sense ") {
+            continue;
+        }
+        let Some(sense) = lines.next() else {
+            break;
+        };
+        for synonym in sense.split(',').map(str::trim) {
+            let known = synonyms.iter().any(|s| s.eq_ignore_ascii_case(synonym));
+            if !synonym.is_empty() && !synonym.eq_ignore_ascii_case(word) && !known {
+                synonyms.push(synonym.to_string());
+            }
+        }
+    }
+    synonyms.truncate(MAX_SYNONYMS);
+    synonyms
+}
+
+async fn wordnet_synonyms(word: &str) -> Vec<String> {
+    let output = Command::new("wn")
+        .arg(word)
+        .args(["-synsn", "-synsv", "-synsa", "-synsr"])
+        .output()
+        .await;
+    match output {
+        Ok(output) => parse_wordnet(&String::from_utf8_lossy(&output.stdout), word),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn copy_item(label: &str, value: &str, icon: &str) -> CommandItem {
+    let mut item = CommandItem::new(label, Handler::Copy, value);
+    item.icon = icon.to_string();
+    item
+}
+
+/// Spelling suggestions and synonyms for `word`, each copyable. Uses
+/// `aspell` when installed and the system word list otherwise.
+pub async fn get_spell_items(word: &str) -> Vec<CommandItem> {
+    let spelling = match aspell(word).await {
+        Some(spelling) => Some(spelling),
+        None => tokio::fs::read_to_string(SYSTEM_DICTIONARY)
+            .await
+            .ok()
+            .map(|words| check_word_list(word, &words)),
+    };
+
+    let mut items = Vec::new();
+    match spelling {
+        Some(Spelling::Correct) => {
+            items.push(copy_item(&format!("{} is spelled correctly", word), word, "✅"));
+        }
+        Some(Spelling::Suggestions(suggestions)) if suggestions.is_empty() => {
+            items.push(copy_item(&format!("No suggestions for {}", word), word, "❓"));
+        }
+        Some(Spelling::Suggestions(suggestions)) => {
+            items.extend(
+                suggestions
+                    .iter()
+                    .map(|s| copy_item(&format!("Did you mean: {}", s), s, "✏️")),
+            );
+        }
+        None => {}
+    }
+    items.extend(
+        wordnet_synonyms(word)
+            .await
+            .iter()
+            .map(|s| copy_item(&format!("Synonym: {}", s), s, "📖")),
+    );
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("spell recieve"), Some("recieve"));
+        assert_eq!(parse_query("spell don't"), Some("don't"));
+        assert_eq!(parse_query("spell two words"), None);
+        assert_eq!(parse_query("spelling"), None);
+    }
+
+    #[test]
+    fn test_parse_aspell() {
+        let banner = "@(#) International Ispell Version 3.1.20 (but really Aspell 0.60.8)\n";
+        assert_eq!(parse_aspell(&format!("{}*\n\n", banner)), Some(Spelling::Correct));
+        assert_eq!(
+            parse_aspell(&format!("{}& recieve 3 0: receive, relieve, recite\n\n", banner)),
+            Some(Spelling::Suggestions(vec![
+                "receive".to_string(),
+                "relieve".to_string(),
+                "recite".to_string()
+            ]))
+        );
+        assert_eq!(parse_aspell(&format!("{}# xqzt 0\n", banner)), Some(Spelling::Suggestions(vec![])));
+        assert_eq!(parse_aspell(banner), None);
+    }
+
+    #[test]
+    fn test_check_word_list() {
+        let words = "believe\nreceive\nrecipe\nrelieve\nzebra\n";
+        assert_eq!(check_word_list("Receive", words), Spelling::Correct);
+        // Closest first, then alphabetical
+        assert_eq!(
+            check_word_list("recieve", words),
+            Spelling::Suggestions(vec![
+                "relieve".to_string(),
+                "believe".to_string(),
+                "receive".to_string(),
+                "recipe".to_string()
+            ])
+        );
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_parse_wordnet() {
+        let output = "\nSynonyms/Hypernyms (Ordered by Estimated Frequency) of adj happy\n\n\
+            3 senses of happy\n\nSense 1\nhappy\n       => ...\n\nSense 2\nfelicitous, happy\n\n\
+            Sense 3\nglad, happy\n";
+        assert_eq!(parse_wordnet(output, "happy"), vec!["felicitous", "glad"]);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::core::{CommandItem, Handler};
+use crate::shell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nested `Include`s followed before giving up, as ssh does.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A host to connect to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshHost {
+    /// What is passed to `ssh`: an alias from the config or a host name
+    pub name: String,
+    /// `HostName`, when the alias stands for another host
+    pub host_name: Option<String>,
+    pub user: Option<String>,
+    /// Port of a known host on another port. Ports in the config are
+    /// applied by ssh itself.
+    pub port: Option<u16>,
+}
+
+impl SshHost {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            host_name: None,
+            user: None,
+            port: None,
+        }
+    }
+}
+
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh"))
+}
+
+/// Split a config line into its keyword and arguments. Keywords are case
+/// insensitive and may be followed by `=`.
+fn keyword_and_args(line: &str) -> Option<(String, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(line.len());
+    let (keyword, rest) = line.split_at(end);
+    let args = rest.trim_start().trim_start_matches('=').trim();
+    Some((keyword.to_lowercase(), args))
+}
+
+/// Hosts declared in an ssh config. Patterns such as `*.internal` can't be
+/// connected to and are skipped; `Include`d files are read with `read`,
+/// given paths relative to `~/.ssh`.
+fn parse_config(config: &str, read: &dyn Fn(&str) -> Vec<String>, depth: usize) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = Vec::new();
+    // Indices of the hosts the current `Host` block applies to
+    let mut current: Vec<usize> = Vec::new();
+    for line in config.lines() {
+        let Some((keyword, args)) = keyword_and_args(line) else {
+            continue;
+        };
+        match keyword.as_str() {
+            "host" => {
+                current.clear();
+                for name in args.split_whitespace() {
+                    if name.contains(['*', '?', '!']) {
+                        continue;
+                    }
+                    current.push(hosts.len());
+                    hosts.push(SshHost::named(name));
+                }
+            }
+            "match" => current.clear(),
+            "hostname" | "user" => {
+                for &index in &current {
+                    let host = &mut hosts[index];
+                    let field = if keyword == "hostname" { &mut host.host_name } else { &mut host.user };
+                    // The first value given wins
+                    field.get_or_insert_with(|| args.to_string());
+                }
+            }
+            "include" if depth < MAX_INCLUDE_DEPTH => {
+                for path in args.split_whitespace() {
+                    for included in read(path) {
+                        hosts.extend(parse_config(&included, read, depth + 1));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    hosts
+}
+
+/// Hosts in a `known_hosts` file. Hashed entries can't be read back and
+/// are skipped, as are CA and revoked keys.
+fn parse_known_hosts(known_hosts: &str) -> Vec<SshHost> {
+    let mut hosts = Vec::new();
+    for line in known_hosts.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('@') || line.starts_with('|') {
+            continue;
+        }
+        let Some(names) = line.split_whitespace().next() else {
+            continue;
+        };
+        // `name,address`: the name is enough
+        let Some(name) = names.split(',').next() else {
+            continue;
+        };
+        if name.contains(['*', '?', '!']) {
+            continue;
+        }
+        // `[name]:port` for servers on other ports
+        let host = match name.strip_prefix('[').and_then(|rest| rest.split_once("]:")) {
+            Some((name, port)) => SshHost {
+                port: port.parse().ok(),
+                ..SshHost::named(name)
+            },
+            None => SshHost::named(name),
+        };
+        hosts.push(host);
+    }
+    hosts
+}
+
+/// The files an `Include` argument names, relative to `~/.ssh`, with `*`
+/// in the file name.
+fn read_include(path: &str) -> Vec<String> {
+    let Some(ssh_dir) = ssh_dir() else {
+        return Vec::new();
+    };
+    let path = if path.starts_with('~') {
+        crate::config::expand_tilde(path)
+    } else {
+        ssh_dir.join(path)
+    };
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+    let Some((prefix, suffix)) = file_name.split_once('*') else {
+        return fs::read_to_string(&path).into_iter().collect();
+    };
+    let Some(Ok(entries)) = path.parent().map(fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(suffix))
+        })
+        .collect();
+    paths.sort();
+    paths.iter().filter_map(|path| fs::read_to_string(path).ok()).collect()
+}
+
+/// Hosts from the config first, then the other known hosts, each once.
+fn merge_hosts(configured: Vec<SshHost>, known: Vec<SshHost>) -> Vec<SshHost> {
+    let mut seen = HashSet::new();
+    configured
+        .into_iter()
+        .chain(known)
+        .filter(|host| seen.insert(host.name.clone()))
+        .collect()
+}
+
+/// The command connecting to `host`.
+pub fn command(host: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) => format!("ssh -p {} {}", port, shell::quote(host)),
+        None => format!("ssh {}", shell::quote(host)),
+    }
+}
+
+fn host_item(host: &SshHost) -> CommandItem {
+    let mut item = CommandItem::new(&format!("ssh {}", host.name), Handler::Ssh, &host.name);
+    let target = host.host_name.as_deref().unwrap_or(&host.name);
+    let destination = match &host.user {
+        Some(user) => format!("{}@{}", user, target),
+        None => target.to_string(),
+    };
+    item.metadata.insert("destination".to_string(), destination);
+    if let Some(port) = host.port {
+        item.metadata.insert("port".to_string(), port.to_string());
+    }
+    item
+}
+
+fn read_hosts(ssh_dir: &Path) -> Vec<SshHost> {
+    let configured = fs::read_to_string(ssh_dir.join("config"))
+        .map(|config| parse_config(&config, &read_include, 0))
+        .unwrap_or_default();
+    let known = fs::read_to_string(ssh_dir.join("known_hosts"))
+        .map(|known_hosts| parse_known_hosts(&known_hosts))
+        .unwrap_or_default();
+    merge_hosts(configured, known)
+}
+
+/// Items opening `ssh <host>` in a terminal, for the hosts in
+/// `~/.ssh/config` and `~/.ssh/known_hosts`.
+pub fn get_ssh_items() -> Vec<CommandItem> {
+    match ssh_dir() {
+        Some(dir) => read_hosts(&dir).iter().map(host_item).collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_includes(_: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = "\
+# Work
+Host work-box bastion
+    HostName 10.0.0.5
+    User deploy
+    Port 2222
+    User ignored
+
+Host *.internal !secret
+    User admin
+
+Match host gitlab.com
+    User git
+
+host=dev
+  hostname = dev.example.com
+";
+        let hosts = parse_config(config, &no_includes, 0);
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts[0].name, "work-box");
+        assert_eq!(hosts[0].host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(hosts[0].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[0].port, None);
+        assert_eq!(hosts[1].name, "bastion");
+        assert_eq!(hosts[1].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[2].name, "dev");
+        assert_eq!(hosts[2].host_name.as_deref(), Some("dev.example.com"));
+        assert_eq!(hosts[2].user, None);
+    }
+
+    #[test]
+    fn test_parse_config_include() {
+        let read = |path: &str| match path {
+            "config.d/*" => vec!["Host included\n".to_string(), "Include loop\n".to_string()],
+            "loop" => vec!["Include loop\n".to_string()],
+            _ => Vec::new(),
+        };
+        let hosts = parse_config("Include config.d/*\nHost main\n", &read, 0);
+        let names: Vec<&str> = hosts.iter().map(|host| host.name.as_str()).collect();
+        assert_eq!(names, vec!["included", "main"]);
+    }
+
+    #[test]
+    fn test_parse_known_hosts() {
+        let known_hosts = "\
+github.com,140.82.121.4 ssh-ed25519 AAAA
+[git.example.com]:2222 ssh-ed25519 AAAA
+|1|hash=|salt= ssh-ed25519 AAAA
+@cert-authority *.example.com ssh-ed25519 AAAA
+# comment
+";
+        let hosts = parse_known_hosts(known_hosts);
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0], SshHost::named("github.com"));
+        assert_eq!(hosts[1].name, "git.example.com");
+        assert_eq!(hosts[1].port, Some(2222));
+    }
+
+    #[test]
+    fn test_merge_and_items() {
+        let configured = vec![SshHost {
+            host_name: Some("10.0.0.5".to_string()),
+            user: Some("deploy".to_string()),
+            ..SshHost::named("work-box")
+        }];
+        let known = vec![
+            SshHost { port: Some(2222), ..SshHost::named("work-box") },
+            SshHost { port: Some(2200), ..SshHost::named("git.example.com") },
+        ];
+        let hosts = merge_hosts(configured, known);
+        assert_eq!(hosts.len(), 2);
+
+        let items: Vec<CommandItem> = hosts.iter().map(host_item).collect();
+        assert_eq!(items[0].label, "ssh work-box");
+        assert_eq!(items[0].value, "work-box");
+        assert_eq!(items[0].metadata["destination"], "deploy@10.0.0.5");
+        assert!(!items[0].metadata.contains_key("port"));
+        assert_eq!(items[1].metadata["port"], "2200");
+        assert_eq!(command("git.example.com", Some(2200)), "ssh -p 2200 'git.example.com'");
+        assert_eq!(command("work-box", None), "ssh 'work-box'");
+        assert_eq!(command("box; rm -rf ~", None), "ssh 'box; rm -rf ~'");
+    }
+
+    #[test]
+    fn test_read_hosts() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config"), "Host box\n  HostName box.lan\n").unwrap();
+        fs::write(dir.path().join("known_hosts"), "box ssh-ed25519 AAAA\nother ssh-ed25519 AAAA\n").unwrap();
+        let names: Vec<String> = read_hosts(dir.path()).into_iter().map(|host| host.name).collect();
+        assert_eq!(names, vec!["box", "other"]);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::core::{CommandItem, Handler};
+use anyhow::{bail, Result};
+use tokio::process::Command;
+
+/// A built-in system command, listed with the apps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemCommand {
+    Sleep,
+    Restart,
+    ShutDown,
+    LockScreen,
+    EmptyTrash,
+    ToggleDarkMode,
+    EjectAllDisks,
+}
+
+impl SystemCommand {
+    pub const ALL: [SystemCommand; 7] = [
+        SystemCommand::Sleep,
+        SystemCommand::Restart,
+        SystemCommand::ShutDown,
+        SystemCommand::LockScreen,
+        SystemCommand::EmptyTrash,
+        SystemCommand::ToggleDarkMode,
+        SystemCommand::EjectAllDisks,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SystemCommand::Sleep => "Sleep",
+            SystemCommand::Restart => "Restart",
+            SystemCommand::ShutDown => "Shut Down",
+            SystemCommand::LockScreen => "Lock Screen",
+            SystemCommand::EmptyTrash => "Empty Trash",
+            SystemCommand::ToggleDarkMode => "Toggle Dark Mode",
+            SystemCommand::EjectAllDisks => "Eject All Disks",
+        }
+    }
+
+    /// The item value, kept in the history.
+    pub fn name(self) -> &'static str {
+        match self {
+            SystemCommand::Sleep => "sleep",
+            SystemCommand::Restart => "restart",
+            SystemCommand::ShutDown => "shut-down",
+            SystemCommand::LockScreen => "lock-screen",
+            SystemCommand::EmptyTrash => "empty-trash",
+            SystemCommand::ToggleDarkMode => "toggle-dark-mode",
+            SystemCommand::EjectAllDisks => "eject-all-disks",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|command| command.name() == name)
+    }
+
+    /// Commands that can't be undone ask before running.
+    pub fn needs_confirmation(self) -> bool {
+        matches!(self, SystemCommand::Restart | SystemCommand::ShutDown | SystemCommand::EmptyTrash)
+    }
+
+    /// The program and arguments running the command on this platform.
+    #[cfg(target_os = "macos")]
+    fn invocation(self) -> Option<(&'static str, Vec<&'static str>)> {
+        let script = match self {
+            SystemCommand::Sleep => return Some(("pmset", vec!["sleepnow"])),
+            SystemCommand::Restart => r#"tell application "System Events" to restart"#,
+            SystemCommand::ShutDown => r#"tell application "System Events" to shut down"#,
+            SystemCommand::LockScreen => {
+                r#"tell application "System Events" to keystroke "q" using {control down, command down}"#
+            }
+            SystemCommand::EmptyTrash => r#"tell application "Finder" to empty trash"#,
+            SystemCommand::ToggleDarkMode => {
+                r#"tell application "System Events" to tell appearance preferences to set dark mode to not dark mode"#
+            }
+            SystemCommand::EjectAllDisks => r#"tell application "Finder" to eject (every disk whose ejectable is true)"#,
+        };
+        Some(("osascript", vec!["-e", script]))
+    }
+
+    /// The program and arguments running the command on this platform.
+    #[cfg(target_os = "linux")]
+    fn invocation(self) -> Option<(&'static str, Vec<&'static str>)> {
+        match self {
+            SystemCommand::Sleep => Some(("systemctl", vec!["suspend"])),
+            SystemCommand::Restart => Some(("systemctl", vec!["reboot"])),
+            SystemCommand::ShutDown => Some(("systemctl", vec!["poweroff"])),
+            SystemCommand::LockScreen => Some(("loginctl", vec!["lock-session"])),
+            SystemCommand::EmptyTrash => Some(("gio", vec!["trash", "--empty"])),
+            SystemCommand::ToggleDarkMode | SystemCommand::EjectAllDisks => None,
+        }
+    }
+
+    /// The program and arguments running the command on this platform.
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    fn invocation(self) -> Option<(&'static str, Vec<&'static str>)> {
+        None
+    }
+}
+
+/// Items for the system commands available on this platform.
+pub fn get_system_items() -> Vec<CommandItem> {
+    SystemCommand::ALL
+        .into_iter()
+        .filter(|command| command.invocation().is_some())
+        .map(|command| CommandItem::new(command.label(), Handler::System, command.name()))
+        .collect()
+}
+
+/// Whether the item is a system command that asks before running.
+pub fn needs_confirmation(item: &CommandItem) -> bool {
+    item.handler == Handler::System
+        && SystemCommand::from_name(&item.value).is_some_and(SystemCommand::needs_confirmation)
+}
+
+/// Run the system command named `name`.
+pub async fn run(name: &str) -> Result<()> {
+    let Some(command) = SystemCommand::from_name(name) else {
+        bail!("Unknown system command: {}", name);
+    };
+    let Some((program, args)) = command.invocation() else {
+        bail!("{} is not available on this platform", command.label());
+    };
+    let output = Command::new(program).args(&args).output().await?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            command.label(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for command in SystemCommand::ALL {
+            assert_eq!(SystemCommand::from_name(command.name()), Some(command));
+        }
+        assert_eq!(SystemCommand::from_name("reboot-now"), None);
+    }
+
+    #[test]
+    fn test_needs_confirmation() {
+        let restart = CommandItem::new("Restart", Handler::System, "restart");
+        assert!(needs_confirmation(&restart));
+        let lock = CommandItem::new("Lock Screen", Handler::System, "lock-screen");
+        assert!(!needs_confirmation(&lock));
+        // Only system commands are confirmed
+        let app = CommandItem::new("Restart", Handler::App, "restart");
+        assert!(!needs_confirmation(&app));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_get_system_items() {
+        let items = get_system_items();
+        assert!(items.iter().any(|item| item.label == "Sleep" && item.handler == Handler::System));
+        // Nothing to run them with on Linux
+        assert!(!items.iter().any(|item| item.value == "toggle-dark-mode"));
+    }
+}
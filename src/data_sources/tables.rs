@@ -0,0 +1,273 @@
+use crate::config::{self, expand_tilde};
+use crate::core::{CommandItem, Handler};
+use crate::events::{self, Event};
+use crate::shell;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A row of a table, by column name.
+type Row = HashMap<String, String>;
+
+/// A CSV or JSON file whose rows are listed as items, such as a list of
+/// servers or of internal dashboards. Declared under `[[tables]]`.
+///
+/// `label` and the targets are templates: `{column}` is replaced by the
+/// row's value, and a bare column name stands for that column.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableConfig {
+    /// `.csv` file with a header row, or `.json` array of objects; `~`
+    /// allowed
+    pub path: String,
+    pub label: String,
+    /// Opened in the browser
+    pub url: Option<String>,
+    /// Run in the user's shell
+    pub command: Option<String>,
+    /// Host opened with `ssh` in a terminal
+    pub ssh: Option<String>,
+    /// Copied to the clipboard, when there is no other target
+    pub value: Option<String>,
+    pub icon: Option<String>,
+    /// Columns the items are also found by
+    pub keywords: Vec<String>,
+}
+
+/// `template` filled in from `row`. A template without placeholders that
+/// names a column is that column's value.
+fn render(template: &str, row: &Row) -> String {
+    render_with(template, row, str::to_string)
+}
+
+/// `template` filled in from `row`, with each value passed through
+/// `escape`; the template's own text is kept as is.
+fn render_with(template: &str, row: &Row, escape: impl Fn(&str) -> String) -> String {
+    if !template.contains('{') {
+        if let Some(value) = row.get(template) {
+            return escape(value);
+        }
+    }
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let column = &rest[start + 1..start + end];
+        rendered.push_str(&escape(row.get(column.trim()).map(String::as_str).unwrap_or_default()));
+        rest = &rest[start + end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Row>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|header| header.trim().to_string()).collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(
+            headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(|field| field.trim().to_string()))
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+/// Rows of a JSON array of objects. Strings, numbers and booleans are
+/// kept; nested values are left out.
+fn parse_json(text: &str) -> Result<Vec<Row>> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    let Some(objects) = value.as_array() else {
+        bail!("Expected an array of objects");
+    };
+    let rows = objects
+        .iter()
+        .filter_map(|object| object.as_object())
+        .map(|object| {
+            object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(text) => text.clone(),
+                        serde_json::Value::Number(number) => number.to_string(),
+                        serde_json::Value::Bool(flag) => flag.to_string(),
+                        _ => return None,
+                    };
+                    Some((key.clone(), value))
+                })
+                .collect()
+        })
+        .collect();
+    Ok(rows)
+}
+
+impl TableConfig {
+    fn read_rows(&self, path: &Path) -> Result<Vec<Row>> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let is_json = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        if is_json {
+            parse_json(&text)
+        } else {
+            parse_csv(&text)
+        }
+    }
+
+    /// The item of `row`, or `None` when its label or target is empty.
+    fn to_item(&self, row: &Row) -> Option<CommandItem> {
+        let label = render(&self.label, row);
+        if label.trim().is_empty() {
+            return None;
+        }
+        let targets = [
+            (&self.url, Handler::Url),
+            (&self.command, Handler::Shell),
+            (&self.ssh, Handler::Ssh),
+            (&self.value, Handler::Copy),
+        ];
+        let (template, handler) = targets.into_iter().find_map(|(template, handler)| Some((template.as_ref()?, handler)))?;
+        if render(template, row).trim().is_empty() {
+            return None;
+        }
+        // Rows are data, often exported or shared: commands get them as
+        // quoted words
+        let value = if handler == Handler::Shell {
+            render_with(template, row, shell::quote)
+        } else {
+            render(template, row)
+        };
+        let mut item = CommandItem::new(&label, handler, &value);
+        if let Some(icon) = &self.icon {
+            item.icon = icon.clone();
+        }
+        let keywords: Vec<&str> = self
+            .keywords
+            .iter()
+            .filter_map(|column| row.get(column).map(String::as_str))
+            .collect();
+        if !keywords.is_empty() {
+            item.metadata.insert("keywords".to_string(), keywords.join(" "));
+        }
+        // Read along with the config's own items
+        item.metadata.insert("custom".to_string(), "true".to_string());
+        Some(item)
+    }
+
+    /// The items of every row of the file.
+    pub fn items(&self) -> Result<Vec<CommandItem>> {
+        let rows = self.read_rows(&expand_tilde(&self.path))?;
+        Ok(rows.iter().filter_map(|row| self.to_item(row)).collect())
+    }
+}
+
+/// Items of the tables declared under `[[tables]]`. A table that can't be
+/// read is reported and skipped.
+pub fn get_table_items() -> Vec<CommandItem> {
+    let mut items = Vec::new();
+    for table in &config::get().tables {
+        match table.items() {
+            Ok(rows) => items.extend(rows),
+            Err(e) => events::publish(Event::ErrorRaised(format!("Error reading table {}: {:#}", table.path, e))),
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> Row {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let row = row(&[("name", "db-1"), ("region", "eu")]);
+        assert_eq!(render("name", &row), "db-1");
+        assert_eq!(render("{name} ({ region })", &row), "db-1 (eu)");
+        assert_eq!(render("https://grafana/{name}?x={missing}", &row), "https://grafana/db-1?x=");
+        // Not a column, and no placeholders
+        assert_eq!(render("Servers", &row), "Servers");
+        assert_eq!(render("{unclosed", &row), "{unclosed");
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("name, host\n\"web, eu\",web.example.com\ndb,\"db.\"\"x\"\"\"\nshort\n").unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["name"], "web, eu");
+        assert_eq!(rows[0]["host"], "web.example.com");
+        assert_eq!(rows[1]["host"], "db.\"x\"");
+        assert!(!rows[2].contains_key("host"));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let rows = parse_json(r#"[{"name": "web", "port": 22, "tags": ["a"]}, 3]"#).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["port"], "22");
+        assert!(!rows[0].contains_key("tags"));
+        assert!(parse_json(r#"{"name": "web"}"#).is_err());
+    }
+
+    #[test]
+    fn test_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("servers.csv");
+        std::fs::write(&path, "name,host,team\nWeb,web.example.com,platform\nNo host,,\n").unwrap();
+        let table = TableConfig {
+            path: path.to_string_lossy().to_string(),
+            label: "{name} server".to_string(),
+            ssh: Some("host".to_string()),
+            keywords: vec!["team".to_string()],
+            ..TableConfig::default()
+        };
+        let items = table.items().unwrap();
+        // Rows without a target are left out
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Web server");
+        assert_eq!(items[0].handler, Handler::Ssh);
+        assert_eq!(items[0].value, "web.example.com");
+        assert_eq!(items[0].metadata["keywords"], "platform");
+
+        let json = dir.path().join("dashboards.json");
+        std::fs::write(&json, r#"[{"title": "Latency", "id": "abc"}]"#).unwrap();
+        let table = TableConfig {
+            path: json.to_string_lossy().to_string(),
+            label: "title".to_string(),
+            url: Some("https://grafana.example.com/d/{id}".to_string()),
+            ..TableConfig::default()
+        };
+        let items = table.items().unwrap();
+        assert_eq!(items[0].handler, Handler::Url);
+        assert_eq!(items[0].value, "https://grafana.example.com/d/abc");
+
+        let csv = dir.path().join("hosts.csv");
+        std::fs::write(&csv, "host\n\"x; rm -rf ~\"\nit's\n").unwrap();
+        let table = TableConfig {
+            path: csv.to_string_lossy().to_string(),
+            label: "host".to_string(),
+            command: Some("ping -c 1 {host}".to_string()),
+            ..TableConfig::default()
+        };
+        let items = table.items().unwrap();
+        assert_eq!(items[0].handler, Handler::Shell);
+        assert_eq!(items[0].label, "x; rm -rf ~");
+        assert_eq!(items[0].value, "ping -c 1 'x; rm -rf ~'");
+        assert_eq!(items[1].value, "ping -c 1 'it'\\''s'");
+
+        let missing = TableConfig {
+            path: dir.path().join("missing.csv").to_string_lossy().to_string(),
+            ..TableConfig::default()
+        };
+        assert!(missing.items().is_err());
+    }
+}
@@ -0,0 +1,359 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::repos;
+use crate::shell;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Recipes of the justfiles and Makefiles of the repositories.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TasksConfig {
+    pub enabled: bool,
+}
+
+impl Default for TasksConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// The tool running the tasks of a folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    Just,
+    Make,
+}
+
+impl Runner {
+    /// The file names each tool reads, in the order it looks for them.
+    fn file_names(&self) -> &'static [&'static str] {
+        match self {
+            Runner::Just => &["justfile", "Justfile", ".justfile"],
+            Runner::Make => &["GNUmakefile", "makefile", "Makefile"],
+        }
+    }
+
+    fn command(&self) -> &'static str {
+        match self {
+            Runner::Just => "just",
+            Runner::Make => "make",
+        }
+    }
+
+    fn parse(&self, text: &str) -> Vec<Task> {
+        match self {
+            Runner::Just => parse_justfile(text),
+            Runner::Make => parse_makefile(text),
+        }
+    }
+}
+
+/// A target or recipe, with the comment describing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Names that can be passed to the tool as they are.
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+}
+
+/// The text of a `# comment` or `## comment` line.
+fn comment_text(line: &str) -> Option<String> {
+    let text = line.strip_prefix('#')?.trim_start_matches('#').trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The targets of a Makefile. A target is described by the comment right
+/// above it or by a `## description` after its prerequisites. Special
+/// targets such as `.PHONY`, pattern rules and variables are left out.
+pub fn parse_makefile(text: &str) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut comment = None;
+    for line in text.lines() {
+        let line = line.trim_end();
+        // Recipes and indented conditionals
+        if line.starts_with(['\t', ' ']) || line.is_empty() {
+            comment = None;
+            continue;
+        }
+        if line.starts_with('#') {
+            comment = comment_text(line);
+            continue;
+        }
+        let described = comment.take();
+        let Some((targets, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `NAME := value`, `NAME ::= value` and `NAME = a:b`
+        if rest.starts_with('=') || rest.starts_with(":=") || targets.contains(['=', '$']) {
+            continue;
+        }
+        let description = match rest.split_once("##") {
+            Some((_, description)) => Some(description.trim().to_string()).filter(|d| !d.is_empty()),
+            None => described,
+        };
+        for name in targets.split_whitespace() {
+            if name.starts_with('.') || name.contains('%') || !is_plain_name(name) {
+                continue;
+            }
+            if !tasks.iter().any(|task| task.name == name) {
+                tasks.push(Task {
+                    name: name.to_string(),
+                    description: description.clone(),
+                });
+            }
+        }
+    }
+    tasks
+}
+
+/// Split a recipe line at its colon, outside of quoted defaults such as
+/// `url='http://localhost'`.
+fn split_recipe(line: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, ':') => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a recipe parameter must be given, as `target` must but
+/// `mode='debug'` and `*args` needn't.
+fn is_required(parameter: &str) -> bool {
+    let parameter = parameter.trim_start_matches('$');
+    !parameter.contains('=') && !parameter.starts_with('*')
+}
+
+/// The recipes of a justfile that run without arguments. A recipe is
+/// described by the comment right above it or a `[doc("…")]` attribute;
+/// `_private` and `[private]` recipes are left out.
+pub fn parse_justfile(text: &str) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut comment = None;
+    let mut private = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.starts_with(['\t', ' ']) || line.is_empty() {
+            comment = None;
+            private = false;
+            continue;
+        }
+        if line.starts_with('#') {
+            comment = comment_text(line);
+            continue;
+        }
+        // Attributes sit between a recipe's comment and the recipe
+        if let Some(attributes) = line.strip_prefix('[') {
+            private |= attributes.contains("private");
+            if let Some((_, doc)) = attributes.split_once("doc(") {
+                let doc = doc.split(')').next().unwrap_or_default().trim_matches(['"', '\'']);
+                comment = Some(doc.to_string()).filter(|doc| !doc.is_empty());
+            }
+            continue;
+        }
+        let described = comment.take();
+        let is_private = std::mem::take(&mut private);
+        let Some((head, rest)) = split_recipe(line) else {
+            continue;
+        };
+        // `name := value`
+        if rest.starts_with('=') {
+            continue;
+        }
+        let mut words = head.trim_start_matches('@').split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        if ["set", "alias", "export", "import", "mod"].contains(&name) {
+            continue;
+        }
+        let takes_arguments = words.any(is_required);
+        if is_private || takes_arguments || name.starts_with('_') || !is_plain_name(name) {
+            continue;
+        }
+        if !tasks.iter().any(|task| task.name == name) {
+            tasks.push(Task {
+                name: name.to_string(),
+                description: described,
+            });
+        }
+    }
+    tasks
+}
+
+/// An item running `task` in `dir`, labelled like `make build (grinta)`.
+fn task_item(runner: Runner, task: &Task, dir: &Path) -> CommandItem {
+    let project = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    let label = format!("{} {} ({})", runner.command(), task.name, project);
+    let command = format!(
+        "cd {} && {} {}",
+        shell::quote(&dir.to_string_lossy()),
+        runner.command(),
+        task.name
+    );
+    let mut item = CommandItem::new(&label, Handler::Shell, &command);
+    item.icon = "🛠️".to_string();
+    // Builds and test runs are worth watching, so they get a terminal
+    item.metadata.insert("terminal".to_string(), "true".to_string());
+    if let Some(description) = &task.description {
+        item.metadata.insert("description".to_string(), description.clone());
+        item.metadata.insert("keywords".to_string(), description.clone());
+    }
+    item
+}
+
+/// The tasks of the justfile and Makefile of `dir`, if it has them.
+pub fn dir_task_items(dir: &Path) -> Vec<CommandItem> {
+    let mut items = Vec::new();
+    for runner in [Runner::Just, Runner::Make] {
+        let Some(text) = runner
+            .file_names()
+            .iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+        else {
+            continue;
+        };
+        items.extend(runner.parse(&text).iter().map(|task| task_item(runner, task, dir)));
+    }
+    items
+}
+
+/// The tasks of the repositories under the configured roots.
+pub fn get_task_items() -> Vec<CommandItem> {
+    if !config::get().tasks.enabled {
+        return Vec::new();
+    }
+    let mut seen = HashSet::new();
+    repos::list_repos()
+        .into_iter()
+        .filter(|repo| seen.insert(repo.path.clone()))
+        .flat_map(|repo| dir_task_items(&repo.path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, description: Option<&str>) -> Task {
+        Task {
+            name: name.to_string(),
+            description: description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_makefile() {
+        let makefile = "\
+CC := gcc
+VERSION = 1:2
+.PHONY: build test
+
+# Build the binary
+build: deps
+\t$(CC) -o app main.c
+
+test: build ## Run the tests
+\t./app --test
+
+%.o: %.c
+\t$(CC) -c $<
+
+install uninstall:
+\t./install.sh
+
+# Not a description
+
+clean:
+\trm -f app
+";
+        assert_eq!(
+            parse_makefile(makefile),
+            vec![
+                task("build", Some("Build the binary")),
+                task("test", Some("Run the tests")),
+                task("install", None),
+                task("uninstall", None),
+                task("clean", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_justfile() {
+        let justfile = "\
+set shell := [\"bash\", \"-c\"]
+version := \"1.0\"
+alias b := build
+
+# Build everything
+build:
+    cargo build
+
+[private]
+helper:
+    echo hidden
+
+_internal:
+    echo hidden
+
+# Deploy to an environment
+deploy env:
+    ./deploy.sh {{env}}
+
+[doc('Serve the docs')]
+@serve port='8080' url='http://localhost:8080' *flags:
+    mdbook serve -p {{port}}
+
+test: build
+    cargo test
+";
+        assert_eq!(
+            parse_justfile(justfile),
+            vec![
+                task("build", Some("Build everything")),
+                task("serve", Some("Serve the docs")),
+                task("test", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_item() {
+        let item = task_item(Runner::Make, &task("build", Some("Build it")), Path::new("/home/ada/src/grinta"));
+        assert_eq!(item.label, "make build (grinta)");
+        assert_eq!(item.handler, Handler::Shell);
+        assert_eq!(item.value, "cd '/home/ada/src/grinta' && make build");
+        assert_eq!(item.metadata["description"], "Build it");
+        assert!(shell::runs_in_terminal(&item));
+
+        let item = task_item(Runner::Just, &task("test", None), Path::new("/tmp/it's"));
+        assert_eq!(item.value, "cd '/tmp/it'\\''s' && just test");
+        assert!(!item.metadata.contains_key("description"));
+    }
+
+    #[test]
+    fn test_dir_task_items() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("justfile"), "# Lint\nlint:\n    cargo clippy\n").unwrap();
+        std::fs::write(dir.path().join("Makefile"), "all:\n\tcc main.c\n").unwrap();
+        let labels: Vec<String> = dir_task_items(dir.path()).into_iter().map(|item| item.label).collect();
+        let project = dir.path().file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(labels, vec![format!("just lint ({})", project), format!("make all ({})", project)]);
+
+        assert!(dir_task_items(&dir.path().join("missing")).is_empty());
+    }
+}
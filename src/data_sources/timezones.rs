@@ -0,0 +1,366 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use serde::{Deserialize, Serialize};
+
+const TIME_COMMAND: &str = "time";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimezonesConfig {
+    /// Places listed by the `time` query, as cities (`tokyo`), time zone
+    /// names (`Europe/Paris`) or abbreviations (`PST`)
+    pub clocks: Vec<String>,
+}
+
+/// Abbreviations and the zone they are taken for, with its daylight
+/// saving time, so `9am PST` in July is 9am in Los Angeles.
+const ABBREVIATIONS: &[(&str, Tz)] = &[
+    ("utc", Tz::UTC),
+    ("gmt", Tz::Etc__GMT),
+    ("est", Tz::America__New_York),
+    ("edt", Tz::America__New_York),
+    ("et", Tz::America__New_York),
+    ("cst", Tz::America__Chicago),
+    ("cdt", Tz::America__Chicago),
+    ("ct", Tz::America__Chicago),
+    ("mst", Tz::America__Denver),
+    ("mdt", Tz::America__Denver),
+    ("mt", Tz::America__Denver),
+    ("pst", Tz::America__Los_Angeles),
+    ("pdt", Tz::America__Los_Angeles),
+    ("pt", Tz::America__Los_Angeles),
+    ("akst", Tz::America__Anchorage),
+    ("hst", Tz::Pacific__Honolulu),
+    ("brt", Tz::America__Sao_Paulo),
+    ("wet", Tz::Europe__Lisbon),
+    ("bst", Tz::Europe__London),
+    ("cet", Tz::Europe__Paris),
+    ("cest", Tz::Europe__Paris),
+    ("eet", Tz::Europe__Athens),
+    ("eest", Tz::Europe__Athens),
+    ("msk", Tz::Europe__Moscow),
+    ("ist", Tz::Asia__Kolkata),
+    ("sgt", Tz::Asia__Singapore),
+    ("hkt", Tz::Asia__Hong_Kong),
+    ("jst", Tz::Asia__Tokyo),
+    ("kst", Tz::Asia__Seoul),
+    ("aest", Tz::Australia__Sydney),
+    ("aedt", Tz::Australia__Sydney),
+    ("nzst", Tz::Pacific__Auckland),
+    ("nzdt", Tz::Pacific__Auckland),
+];
+
+/// Cities without a zone of their own name.
+const CITIES: &[(&str, &str, Tz)] = &[
+    ("san francisco", "San Francisco", Tz::America__Los_Angeles),
+    ("sf", "San Francisco", Tz::America__Los_Angeles),
+    ("seattle", "Seattle", Tz::America__Los_Angeles),
+    ("boston", "Boston", Tz::America__New_York),
+    ("washington", "Washington", Tz::America__New_York),
+    ("miami", "Miami", Tz::America__New_York),
+    ("nyc", "New York", Tz::America__New_York),
+    ("austin", "Austin", Tz::America__Chicago),
+    ("dallas", "Dallas", Tz::America__Chicago),
+    ("houston", "Houston", Tz::America__Chicago),
+    ("munich", "Munich", Tz::Europe__Berlin),
+    ("frankfurt", "Frankfurt", Tz::Europe__Berlin),
+    ("barcelona", "Barcelona", Tz::Europe__Madrid),
+    ("milan", "Milan", Tz::Europe__Rome),
+    ("beijing", "Beijing", Tz::Asia__Shanghai),
+    ("mumbai", "Mumbai", Tz::Asia__Kolkata),
+    ("delhi", "Delhi", Tz::Asia__Kolkata),
+    ("bangalore", "Bangalore", Tz::Asia__Kolkata),
+    ("osaka", "Osaka", Tz::Asia__Tokyo),
+];
+
+/// Where a time is read or shown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Place {
+    Local,
+    Zone { tz: Tz, name: String },
+}
+
+impl Place {
+    fn name(&self) -> &str {
+        match self {
+            Place::Local => "local",
+            Place::Zone { name, .. } => name,
+        }
+    }
+
+    fn at(&self, instant: DateTime<Utc>, local: FixedOffset) -> DateTime<FixedOffset> {
+        match self {
+            Place::Local => instant.with_timezone(&local),
+            Place::Zone { tz, .. } => instant.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    /// `time` of the day this place is in at `now`.
+    fn today_at(&self, time: NaiveTime, now: DateTime<Utc>, local: FixedOffset) -> Option<DateTime<Utc>> {
+        let date = self.at(now, local).date_naive().and_time(time);
+        let instant = match self {
+            Place::Local => local.from_local_datetime(&date).single()?.with_timezone(&Utc),
+            // The first of the two hours repeated when clocks go back
+            Place::Zone { tz, .. } => tz.from_local_datetime(&date).earliest()?.with_timezone(&Utc),
+        };
+        Some(instant)
+    }
+}
+
+/// The last part of a zone name, as `New York` for `America/New_York`.
+fn city_name(tz: Tz) -> String {
+    tz.name().rsplit('/').next().unwrap_or_default().replace('_', " ")
+}
+
+/// A place written as an abbreviation, a city or a time zone name.
+pub fn parse_place(text: &str) -> Option<Place> {
+    let lower = text.trim().to_lowercase();
+    if let Some((abbreviation, tz)) = ABBREVIATIONS.iter().find(|(abbreviation, _)| *abbreviation == lower) {
+        return Some(Place::Zone { tz: *tz, name: abbreviation.to_uppercase() });
+    }
+    if let Some((_, name, tz)) = CITIES.iter().find(|(city, _, _)| *city == lower) {
+        return Some(Place::Zone { tz: *tz, name: name.to_string() });
+    }
+    let tz = TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(&lower))
+        .or_else(|| TZ_VARIANTS.iter().find(|tz| city_name(**tz).eq_ignore_ascii_case(&lower)))?;
+    Some(Place::Zone { tz: *tz, name: city_name(*tz) })
+}
+
+/// `9am`, `9:30 pm`, `14:00`, `noon` or `midnight`.
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let text = text.to_lowercase().replace(' ', "");
+    match text.as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+    let (clock, meridiem) = match text.strip_suffix("am") {
+        Some(clock) => (clock, Some(0)),
+        None => match text.strip_suffix("pm") {
+            Some(clock) => (clock, Some(12)),
+            None => (text.as_str(), None),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse().ok()?),
+        // A bare number is only a time with am or pm
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(offset) => hour % 12 + offset,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeQuery {
+    /// `time`: the configured clocks
+    Clocks,
+    /// `time in tokyo`
+    Now(Place),
+    /// `9am PST in CET`, or local time with `9am in tokyo`
+    Convert { time: NaiveTime, from: Place, to: Place },
+}
+
+pub fn parse_query(query: &str) -> Option<TimeQuery> {
+    let query = query.trim();
+    if query.eq_ignore_ascii_case(TIME_COMMAND) {
+        return Some(TimeQuery::Clocks);
+    }
+    let lower = query.to_lowercase();
+    if let Some(place) = lower.strip_prefix("time in ").or_else(|| lower.strip_prefix("time at ")) {
+        return Some(TimeQuery::Now(parse_place(place)?));
+    }
+    let (source, target) = lower.rsplit_once(" in ").or_else(|| lower.rsplit_once(" to "))?;
+    let to = parse_place(target)?;
+    let words: Vec<&str> = source.split_whitespace().collect();
+    // `9am PST` or `9 am PST`
+    (1..=words.len().min(2)).rev().find_map(|count| {
+        let time = parse_clock(&words[..count].join(" "))?;
+        let from = match &words[count..] {
+            [] => Place::Local,
+            rest => parse_place(&rest.join(" "))?,
+        };
+        Some(TimeQuery::Convert { time, from, to: to.clone() })
+    })
+}
+
+/// `+9`, `+5:30` or `-3` hours.
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    match minutes % 60 {
+        0 => format!("{}{}", sign, minutes / 60),
+        rest => format!("{}{}:{:02}", sign, minutes / 60, rest),
+    }
+}
+
+fn time_item(label: &str, time: &DateTime<FixedOffset>) -> CommandItem {
+    let mut item = CommandItem::new(label, Handler::Copy, &time.format("%H:%M").to_string());
+    item.icon = "🕐".to_string();
+    item
+}
+
+/// The day `time` is on relative to `reference`, as ` (next day)`.
+fn day_shift(time: &DateTime<FixedOffset>, reference: &DateTime<FixedOffset>) -> &'static str {
+    match (time.date_naive() - reference.date_naive()).num_days() {
+        0 => "",
+        1 => " (next day)",
+        -1 => " (previous day)",
+        _ => "",
+    }
+}
+
+pub fn time_items(query: &str, now: DateTime<Utc>, local: FixedOffset, clocks: &[String]) -> Vec<CommandItem> {
+    match parse_query(query) {
+        Some(TimeQuery::Clocks) => {
+            let here = Place::Local.at(now, local);
+            let label = format!("{} local · {}", here.format("%H:%M"), here.format("%a, %b %-d"));
+            let mut items = vec![time_item(&label, &here)];
+            items.extend(clocks.iter().filter_map(|clock| parse_place(clock)).map(|place| {
+                let there = place.at(now, local);
+                let difference = there.offset().fix().local_minus_utc() - local.local_minus_utc();
+                let label = format!(
+                    "{} {} · {} h{}",
+                    there.format("%H:%M"),
+                    place.name(),
+                    format_offset(difference),
+                    day_shift(&there, &here)
+                );
+                time_item(&label, &there)
+            }));
+            items
+        }
+        Some(TimeQuery::Now(place)) => {
+            let there = place.at(now, local);
+            let label = format!(
+                "{} in {} · {} (UTC{})",
+                there.format("%H:%M"),
+                place.name(),
+                there.format("%a, %b %-d"),
+                format_offset(there.offset().local_minus_utc())
+            );
+            vec![time_item(&label, &there)]
+        }
+        Some(TimeQuery::Convert { time, from, to }) => {
+            let Some(instant) = from.today_at(time, now, local) else {
+                return Vec::new();
+            };
+            let source = from.at(instant, local);
+            let target = to.at(instant, local);
+            let label = format!(
+                "{} {} = {} {}{}",
+                source.format("%H:%M"),
+                from.name(),
+                target.format("%H:%M"),
+                to.name(),
+                day_shift(&target, &source)
+            );
+            vec![time_item(&label, &target)]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// `time_items` at the current time.
+pub fn get_time_items(query: &str) -> Vec<CommandItem> {
+    let now = Utc::now();
+    let local = now.with_timezone(&Local).offset().fix();
+    time_items(query, now, local, &config::get().timezones.clocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap()
+    }
+
+    fn berlin() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn labels(query: &str, clocks: &[&str]) -> Vec<String> {
+        let clocks: Vec<String> = clocks.iter().map(|clock| clock.to_string()).collect();
+        time_items(query, now(), berlin(), &clocks).into_iter().map(|item| item.label).collect()
+    }
+
+    #[test]
+    fn test_parse_place() {
+        let zone = |query: &str| match parse_place(query) {
+            Some(Place::Zone { tz, name }) => Some((tz, name)),
+            _ => None,
+        };
+        assert_eq!(zone("PST"), Some((Tz::America__Los_Angeles, "PST".to_string())));
+        assert_eq!(zone("tokyo"), Some((Tz::Asia__Tokyo, "Tokyo".to_string())));
+        assert_eq!(zone("new york"), Some((Tz::America__New_York, "New York".to_string())));
+        assert_eq!(zone("europe/paris"), Some((Tz::Europe__Paris, "Paris".to_string())));
+        assert_eq!(zone("sf"), Some((Tz::America__Los_Angeles, "San Francisco".to_string())));
+        assert_eq!(zone("atlantis"), None);
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0);
+        assert_eq!(parse_clock("9am"), time(9, 0));
+        assert_eq!(parse_clock("9:30 pm"), time(21, 30));
+        assert_eq!(parse_clock("12am"), time(0, 0));
+        assert_eq!(parse_clock("12pm"), time(12, 0));
+        assert_eq!(parse_clock("14:00"), time(14, 0));
+        assert_eq!(parse_clock("noon"), time(12, 0));
+        assert_eq!(parse_clock("14"), None);
+        assert_eq!(parse_clock("13pm"), None);
+        assert_eq!(parse_clock("25:00"), None);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("time"), Some(TimeQuery::Clocks));
+        assert!(matches!(parse_query("time in tokyo"), Some(TimeQuery::Now(_))));
+        assert!(matches!(
+            parse_query("9 am pst in cet"),
+            Some(TimeQuery::Convert { from: Place::Zone { .. }, .. })
+        ));
+        assert!(matches!(
+            parse_query("3pm to tokyo"),
+            Some(TimeQuery::Convert { from: Place::Local, .. })
+        ));
+        // Units and currencies are left alone
+        assert_eq!(parse_query("5 min to sec"), None);
+        assert_eq!(parse_query("100 usd to eur"), None);
+        assert_eq!(parse_query("time in atlantis"), None);
+    }
+
+    #[test]
+    fn test_time_items() {
+        assert_eq!(labels("time in tokyo", &[]), vec!["21:00 in Tokyo · Thu, Jan 15 (UTC+9)"]);
+        assert_eq!(labels("time in kolkata", &[]), vec!["17:30 in Kolkata · Thu, Jan 15 (UTC+5:30)"]);
+        assert_eq!(labels("9am PST in CET", &[]), vec!["09:00 PST = 18:00 CET"]);
+        assert_eq!(labels("8pm EST in tokyo", &[]), vec!["20:00 EST = 10:00 Tokyo (next day)"]);
+        assert_eq!(labels("9:00 to new york", &[]), vec!["09:00 local = 03:00 New York"]);
+
+        let items = time_items("4pm pst in utc", now(), berlin(), &[]);
+        assert_eq!(items[0].value, "00:00");
+        assert_eq!(items[0].handler, Handler::Copy);
+    }
+
+    #[test]
+    fn test_clocks() {
+        assert_eq!(
+            labels("time", &["tokyo", "PST", "nowhere"]),
+            vec!["13:00 local · Thu, Jan 15", "21:00 Tokyo · +8 h", "04:00 PST · -9 h"]
+        );
+        // Daylight saving time is followed
+        let summer = Utc.with_ymd_and_hms(2026, 7, 15, 12, 0, 0).unwrap();
+        let items = time_items("time in pst", summer, berlin(), &[]);
+        assert_eq!(items[0].value, "05:00");
+    }
+}
@@ -0,0 +1,268 @@
+use crate::cache;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const TRANSLATE_COMMAND: &str = "tr";
+const CACHE_NAMESPACE: &str = "translations";
+const CACHE_TTL_DAYS: i64 = 30;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const LABEL_PREVIEW_CHARS: usize = 60;
+
+/// Which translation service to use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslateProvider {
+    /// A LibreTranslate-compatible server, such as a local argos-translate
+    #[default]
+    Libre,
+    Deepl,
+    Google,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslateConfig {
+    pub provider: TranslateProvider,
+    /// API key (required for DeepL and Google)
+    pub api_key: Option<String>,
+    /// Server for the libre provider
+    pub url: String,
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        Self {
+            provider: TranslateProvider::default(),
+            api_key: None,
+            url: "http://localhost:5000".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslateQuery {
+    /// `None` lets the service detect the language
+    pub source: Option<String>,
+    pub target: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    pub text: String,
+    pub detected_source: Option<String>,
+}
+
+fn is_language_code(code: &str) -> bool {
+    let (language, region) = code.split_once('-').unwrap_or((code, ""));
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && region.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Parse `tr en>de hello world` (or `tr >de ...` to detect the source).
+pub fn parse_query(query: &str) -> Option<TranslateQuery> {
+    let rest = query.strip_prefix(TRANSLATE_COMMAND)?.strip_prefix(' ')?.trim_start();
+    let (languages, text) = rest.split_once(' ')?;
+    let (source, target) = languages.split_once('>')?;
+    let text = text.trim();
+    if text.is_empty() || !is_language_code(target) || !(source.is_empty() || is_language_code(source)) {
+        return None;
+    }
+    Some(TranslateQuery {
+        source: (!source.is_empty()).then(|| source.to_lowercase()),
+        target: target.to_lowercase(),
+        text: text.to_string(),
+    })
+}
+
+fn parse_deepl(response: &Value) -> Option<Translation> {
+    let translation = &response["translations"][0];
+    Some(Translation {
+        text: translation["text"].as_str()?.to_string(),
+        detected_source: translation["detected_source_language"].as_str().map(str::to_lowercase),
+    })
+}
+
+fn parse_google(response: &Value) -> Option<Translation> {
+    let translation = &response["data"]["translations"][0];
+    Some(Translation {
+        text: translation["translatedText"].as_str()?.to_string(),
+        detected_source: translation["detectedSourceLanguage"].as_str().map(str::to_string),
+    })
+}
+
+fn parse_libre(response: &Value) -> Option<Translation> {
+    Some(Translation {
+        text: response["translatedText"].as_str()?.to_string(),
+        detected_source: response["detectedLanguage"]["language"].as_str().map(str::to_string),
+    })
+}
+
+async fn post(request: reqwest::RequestBuilder, body: Value) -> Result<Value> {
+    Ok(request
+        .json(&body)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?)
+}
+
+async fn fetch_translation(query: &TranslateQuery, config: &config::Config) -> Result<Translation> {
+    let settings = &config.translate;
+    let client = reqwest::Client::new();
+    let translation = match settings.provider {
+        TranslateProvider::Deepl => {
+            let api_key = settings.api_key.as_deref().context("DeepL needs an API key")?;
+            // Free-tier keys end in ":fx" and use their own host
+            let host = if api_key.ends_with(":fx") { "api-free.deepl.com" } else { "api.deepl.com" };
+            let mut body = json!({ "text": [query.text], "target_lang": query.target.to_uppercase() });
+            if let Some(source) = &query.source {
+                body["source_lang"] = json!(source.to_uppercase());
+            }
+            let request = client
+                .post(format!("https://{}/v2/translate", host))
+                .header("Authorization", format!("DeepL-Auth-Key {}", api_key));
+            parse_deepl(&post(request, body).await?)
+        }
+        TranslateProvider::Google => {
+            let api_key = settings.api_key.as_deref().context("Google Translate needs an API key")?;
+            let mut body = json!({ "q": query.text, "target": query.target, "format": "text" });
+            if let Some(source) = &query.source {
+                body["source"] = json!(source);
+            }
+            let request = client
+                .post("https://translation.googleapis.com/language/translate/v2")
+                .query(&[("key", api_key)]);
+            parse_google(&post(request, body).await?)
+        }
+        TranslateProvider::Libre => {
+            let mut body = json!({
+                "q": query.text,
+                "source": query.source.as_deref().unwrap_or("auto"),
+                "target": query.target,
+                "format": "text",
+            });
+            if let Some(api_key) = &settings.api_key {
+                body["api_key"] = json!(api_key);
+            }
+            let request = client.post(format!("{}/translate", settings.url.trim_end_matches('/')));
+            parse_libre(&post(request, body).await?)
+        }
+    };
+    translation.context("Unexpected translation response")
+}
+
+fn translation_item(query: &TranslateQuery, translation: &Translation) -> CommandItem {
+    let mut shown: String = translation.text.chars().take(LABEL_PREVIEW_CHARS).collect();
+    if shown.len() < translation.text.len() {
+        shown.push('…');
+    }
+    let mut item = CommandItem::new(
+        &format!("{}: {}", query.target.to_uppercase(), shown),
+        Handler::Copy,
+        &translation.text,
+    );
+    item.icon = "🌐".to_string();
+
+    let source = query
+        .source
+        .clone()
+        .or_else(|| translation.detected_source.clone())
+        .unwrap_or_else(|| "auto".to_string());
+    let preview = format!(
+        "{} → {}\n\n{}\n\n{}",
+        source.to_uppercase(),
+        query.target.to_uppercase(),
+        query.text,
+        translation.text
+    );
+    item.metadata.insert("preview".to_string(), preview);
+    item
+}
+
+/// The translation of a `tr en>de ...` query, copied on Enter.
+pub async fn get_translation_items(query: &TranslateQuery) -> Vec<CommandItem> {
+    let key = format!(
+        "{}>{}:{}",
+        query.source.as_deref().unwrap_or(""),
+        query.target,
+        query.text
+    );
+    let ttl = chrono::Duration::days(CACHE_TTL_DAYS);
+    if let Some(translation) = cache::get::<Translation>(CACHE_NAMESPACE, &key, ttl) {
+        return vec![translation_item(query, &translation)];
+    }
+
+    match fetch_translation(query, config::get()).await {
+        Ok(translation) => {
+            let _ = cache::put(CACHE_NAMESPACE, &key, &translation);
+            vec![translation_item(query, &translation)]
+        }
+        Err(e) => {
+            let mut item = CommandItem::new(&format!("Translation failed: {}", e), Handler::Copy, "");
+            item.icon = "⚠️".to_string();
+            vec![item]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("tr en>de hello world"),
+            Some(TranslateQuery {
+                source: Some("en".to_string()),
+                target: "de".to_string(),
+                text: "hello world".to_string(),
+            })
+        );
+        let detected = parse_query("tr >pt-BR good morning").unwrap();
+        assert_eq!(detected.source, None);
+        assert_eq!(detected.target, "pt-br");
+
+        assert_eq!(parse_query("tr en>de"), None);
+        assert_eq!(parse_query("tr hello world"), None);
+        assert_eq!(parse_query("tr en>german hello"), None);
+        assert_eq!(parse_query("track start"), None);
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let deepl = json!({ "translations": [{ "detected_source_language": "EN", "text": "Hallo Welt" }] });
+        assert_eq!(
+            parse_deepl(&deepl),
+            Some(Translation { text: "Hallo Welt".to_string(), detected_source: Some("en".to_string()) })
+        );
+
+        let google = json!({ "data": { "translations": [{ "translatedText": "Hallo Welt" }] } });
+        assert_eq!(parse_google(&google).unwrap().text, "Hallo Welt");
+
+        let libre = json!({ "translatedText": "Hallo Welt", "detectedLanguage": { "confidence": 90, "language": "en" } });
+        assert_eq!(parse_libre(&libre).unwrap().detected_source.as_deref(), Some("en"));
+        assert_eq!(parse_libre(&json!({ "error": "bad" })), None);
+    }
+
+    #[test]
+    fn test_translation_item() {
+        let query = parse_query("tr >de hello world").unwrap();
+        let translation = Translation {
+            text: "Hallo Welt".to_string(),
+            detected_source: Some("en".to_string()),
+        };
+        let item = translation_item(&query, &translation);
+        assert_eq!(item.label, "DE: Hallo Welt");
+        assert_eq!(item.value, "Hallo Welt");
+        assert_eq!(item.metadata["preview"], "EN → DE\n\nhello world\n\nHallo Welt");
+    }
+}
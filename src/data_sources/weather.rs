@@ -0,0 +1,273 @@
+use crate::cache;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+const WEATHER_COMMAND: &str = "weather";
+const CACHE_NAMESPACE: &str = "weather";
+const CACHE_TTL_MINUTES: i64 = 15;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayForecast {
+    pub date: String,
+    pub weather_code: u8,
+    pub max: f64,
+    pub min: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    pub location: String,
+    pub temperature: f64,
+    pub weather_code: u8,
+    pub wind_speed: f64,
+    pub humidity: f64,
+    pub daily: Vec<DayForecast>,
+}
+
+/// Return the city when the query is a `weather [city]` command (empty for
+/// the current location).
+pub fn parse_query(query: &str) -> Option<&str> {
+    if query == WEATHER_COMMAND {
+        return Some("");
+    }
+    query
+        .strip_prefix(WEATHER_COMMAND)
+        .and_then(|rest| rest.strip_prefix(' '))
+        .map(str::trim)
+}
+
+/// Icon and description for a WMO weather interpretation code.
+pub fn describe(code: u8) -> (&'static str, &'static str) {
+    match code {
+        0 => ("☀️", "Clear sky"),
+        1 => ("🌤", "Mainly clear"),
+        2 => ("⛅", "Partly cloudy"),
+        3 => ("☁️", "Overcast"),
+        45 | 48 => ("🌫", "Fog"),
+        51 | 53 | 55 | 56 | 57 => ("🌦", "Drizzle"),
+        61 | 63 | 65 | 66 | 67 | 80 | 81 | 82 => ("🌧", "Rain"),
+        71 | 73 | 75 | 77 | 85 | 86 => ("🌨", "Snow"),
+        95 | 96 | 99 => ("⛈", "Thunderstorm"),
+        _ => ("🌡", "Unknown"),
+    }
+}
+
+/// Current conditions and a short forecast for the preview pane.
+pub fn format_preview(weather: &Weather) -> String {
+    let (icon, description) = describe(weather.weather_code);
+    let mut lines = vec![
+        weather.location.clone(),
+        String::new(),
+        format!("{} {}  {:.0}°C", icon, description, weather.temperature),
+        format!("Wind {:.0} km/h · Humidity {:.0}%", weather.wind_speed, weather.humidity),
+        String::new(),
+    ];
+    for day in &weather.daily {
+        let (icon, description) = describe(day.weather_code);
+        lines.push(format!(
+            "{}  {} {:>3.0}° / {:>3.0}°  {}",
+            day.date, icon, day.max, day.min, description
+        ));
+    }
+    lines.join("\n")
+}
+
+fn weather_item(weather: &Weather) -> CommandItem {
+    let (icon, description) = describe(weather.weather_code);
+    let summary = format!(
+        "{}: {:.0}°C, {}",
+        weather.location, weather.temperature, description
+    );
+    let mut item = CommandItem::new(&summary, Handler::Copy, &summary);
+    item.icon = icon.to_string();
+    item.metadata.insert("preview".to_string(), format_preview(weather));
+    item
+}
+
+fn parse_forecast(location: &str, response: &Value) -> Option<Weather> {
+    let current = &response["current"];
+    let daily = &response["daily"];
+    let dates = daily["time"].as_array()?;
+
+    let days = dates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, date)| {
+            Some(DayForecast {
+                date: date.as_str()?.to_string(),
+                weather_code: daily["weather_code"][i].as_u64()? as u8,
+                max: daily["temperature_2m_max"][i].as_f64()?,
+                min: daily["temperature_2m_min"][i].as_f64()?,
+            })
+        })
+        .collect();
+
+    Some(Weather {
+        location: location.to_string(),
+        temperature: current["temperature_2m"].as_f64()?,
+        weather_code: current["weather_code"].as_u64()? as u8,
+        wind_speed: current["wind_speed_10m"].as_f64().unwrap_or(0.0),
+        humidity: current["relative_humidity_2m"].as_f64().unwrap_or(0.0),
+        daily: days,
+    })
+}
+
+/// Resolve a city name with the Open-Meteo geocoding API.
+async fn geocode(client: &reqwest::Client, city: &str) -> Result<(String, f64, f64)> {
+    let response = client
+        .get("https://geocoding-api.open-meteo.com/v1/search")
+        .query(&[("name", city), ("count", "1")])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+
+    let place = &response["results"][0];
+    let name = place["name"].as_str().context("Unknown location")?;
+    let location = match place["country"].as_str() {
+        Some(country) => format!("{}, {}", name, country),
+        None => name.to_string(),
+    };
+    Ok((
+        location,
+        place["latitude"].as_f64().context("Missing latitude")?,
+        place["longitude"].as_f64().context("Missing longitude")?,
+    ))
+}
+
+/// Approximate the current location from the public IP address.
+async fn locate(client: &reqwest::Client) -> Result<(String, f64, f64)> {
+    let response = client
+        .get("https://ipapi.co/json/")
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+
+    Ok((
+        response["city"].as_str().unwrap_or("Current location").to_string(),
+        response["latitude"].as_f64().context("Could not determine location")?,
+        response["longitude"].as_f64().context("Could not determine location")?,
+    ))
+}
+
+async fn fetch_weather(city: &str) -> Result<Weather> {
+    let client = reqwest::Client::new();
+    let (location, latitude, longitude) = if city.is_empty() {
+        locate(&client).await?
+    } else {
+        geocode(&client, city).await?
+    };
+
+    let response = client
+        .get("https://api.open-meteo.com/v1/forecast")
+        .query(&[
+            ("latitude", latitude.to_string()),
+            ("longitude", longitude.to_string()),
+            (
+                "current",
+                "temperature_2m,weather_code,wind_speed_10m,relative_humidity_2m".to_string(),
+            ),
+            (
+                "daily",
+                "weather_code,temperature_2m_max,temperature_2m_min".to_string(),
+            ),
+            ("forecast_days", "3".to_string()),
+            ("timezone", "auto".to_string()),
+        ])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+
+    parse_forecast(&location, &response).context("Unexpected forecast response")
+}
+
+/// Weather for a `weather [city]` query, served from a 15 minute cache.
+pub async fn get_weather_items(city: &str) -> Vec<CommandItem> {
+    let key = city.to_lowercase();
+    let ttl = chrono::Duration::minutes(CACHE_TTL_MINUTES);
+    if let Some(weather) = cache::get::<Weather>(CACHE_NAMESPACE, &key, ttl) {
+        return vec![weather_item(&weather)];
+    }
+
+    match fetch_weather(city).await {
+        Ok(weather) => {
+            let _ = cache::put(CACHE_NAMESPACE, &key, &weather);
+            vec![weather_item(&weather)]
+        }
+        Err(e) => {
+            let mut item = CommandItem::new(&format!("Weather lookup failed: {}", e), Handler::Copy, &e.to_string());
+            item.icon = "⚠️".to_string();
+            vec![item]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_weather() -> Weather {
+        let response = json!({
+            "current": {
+                "temperature_2m": 12.4,
+                "weather_code": 2,
+                "wind_speed_10m": 14.0,
+                "relative_humidity_2m": 71
+            },
+            "daily": {
+                "time": ["2024-05-01", "2024-05-02", "2024-05-03"],
+                "weather_code": [2, 61, 0],
+                "temperature_2m_max": [15.1, 12.0, 18.3],
+                "temperature_2m_min": [7.2, 6.5, 8.0]
+            }
+        });
+        parse_forecast("Berlin, Germany", &response).unwrap()
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("weather"), Some(""));
+        assert_eq!(parse_query("weather berlin"), Some("berlin"));
+        assert_eq!(parse_query("weather  new york "), Some("new york"));
+        assert_eq!(parse_query("weatherproof"), None);
+        assert_eq!(parse_query("the weather"), None);
+    }
+
+    #[test]
+    fn test_parse_forecast() {
+        let weather = sample_weather();
+        assert_eq!(weather.temperature, 12.4);
+        assert_eq!(weather.weather_code, 2);
+        assert_eq!(weather.daily.len(), 3);
+        assert_eq!(weather.daily[1].weather_code, 61);
+
+        assert!(parse_forecast("Nowhere", &json!({})).is_none());
+    }
+
+    #[test]
+    fn test_weather_item() {
+        let item = weather_item(&sample_weather());
+        assert_eq!(item.label, "Berlin, Germany: 12°C, Partly cloudy");
+        assert_eq!(item.handler, Handler::Copy);
+
+        let preview = item.metadata.get("preview").unwrap();
+        assert!(preview.contains("Wind 14 km/h · Humidity 71%"));
+        assert!(preview.contains("2024-05-02"));
+        assert_eq!(preview.lines().count(), 8);
+    }
+
+    #[test]
+    fn test_describe_unknown_code() {
+        assert_eq!(describe(42).1, "Unknown");
+    }
+}
@@ -1,17 +1,187 @@
+use crate::config;
 use crate::core::{CommandItem, CommandType, Handler};
+use crate::internal::InternalCommand;
 use anyhow::Result;
-use open;
 use reqwest;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::time::Duration;
 use urlencoding;
 
+/// Engine used when the configured default is unknown.
+const FALLBACK_ENGINE: &str = "ddg";
+
+/// Engines available without configuration, by bang.
+const BUILTIN_ENGINES: [(&str, &str); 8] = [
+    ("ddg", "https://duckduckgo.com/?q={query}"),
+    ("g", "https://www.google.com/search?q={query}"),
+    ("bing", "https://www.bing.com/search?q={query}"),
+    ("yt", "https://www.youtube.com/results?search_query={query}"),
+    ("gh", "https://github.com/search?q={query}"),
+    ("w", "https://en.wikipedia.org/w/index.php?search={query}"),
+    ("so", "https://stackoverflow.com/search?q={query}"),
+    ("crates", "https://crates.io/search?q={query}"),
+];
+
+/// Web search engines, picked with `!bang` in the query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Bang of the engine used without one
+    pub default: String,
+    /// URL templates by bang, `{query}` standing for the search. Added to
+    /// (or replacing) the built-in engines
+    pub engines: BTreeMap<String, String>,
+    /// Fetch DuckDuckGo's instant answer for the top suggestion and show
+    /// it in its row
+    pub instant_answers: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            default: FALLBACK_ENGINE.to_string(),
+            engines: BTreeMap::new(),
+            instant_answers: false,
+        }
+    }
+}
+
+impl SearchConfig {
+    fn engines(&self) -> BTreeMap<String, String> {
+        let mut engines: BTreeMap<String, String> = BUILTIN_ENGINES
+            .iter()
+            .map(|(bang, template)| (bang.to_string(), template.to_string()))
+            .collect();
+        engines.extend(self.engines.clone());
+        engines
+    }
+
+    fn default_template(&self) -> String {
+        let engines = self.engines();
+        engines
+            .get(&self.default)
+            .or_else(|| engines.get(FALLBACK_ENGINE))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The `!bang` at the start or end of the query, its engine template
+    /// and the query without it.
+    fn parse_bang(&self, query: &str) -> Option<(String, String, String)> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let (index, bang) = [0, words.len().checked_sub(1)?]
+            .into_iter()
+            .find_map(|index| Some((index, words[index].strip_prefix('!')?)))?;
+        let template = self.engines().get(bang)?.clone();
+        let rest: Vec<&str> = words
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, word)| *word)
+            .collect();
+        Some((bang.to_string(), template, rest.join(" ")))
+    }
+
+    /// The URL searching for `query`, with its bang's engine or the default.
+    pub fn url_for(&self, query: &str) -> String {
+        match self.parse_bang(query) {
+            Some((_, template, rest)) => fill_template(&template, &rest),
+            None => fill_template(&self.default_template(), query),
+        }
+    }
+}
+
+fn fill_template(template: &str, query: &str) -> String {
+    template.replace("{query}", &urlencoding::encode(query))
+}
+
+/// `www.google.com` for a template on that host.
+fn engine_host(template: &str) -> &str {
+    let rest = template.split_once("://").map_or(template, |(_, rest)| rest);
+    let host = rest.split('/').next().unwrap_or(rest);
+    host.strip_prefix("www.").unwrap_or(host)
+}
+
+/// An item searching `rest` with a bang's engine. The bang is kept in the
+/// metadata, so its launches in the history count as its usage.
+fn bang_search_item(bang: &str, template: &str, rest: &str) -> CommandItem {
+    let label = format!("Search {} for \"{}\"", engine_host(template), rest);
+    let mut cmd = CommandItem::new(&label, Handler::Url, &fill_template(template, rest));
+    cmd.icon = "🔎".to_string();
+    cmd.kind = CommandType::WebSearch;
+    cmd.metadata.insert("bang".to_string(), bang.to_string());
+    cmd
+}
+
+/// An item searching with the engine picked by a `!bang`, as in `!gh ratatui`.
+pub fn bang_items(query: &str) -> Vec<CommandItem> {
+    let search = &config::get().search;
+    match search.parse_bang(query) {
+        Some((bang, template, rest)) if !rest.is_empty() => vec![bang_search_item(&bang, &template, &rest)],
+        _ => Vec::new(),
+    }
+}
+
+/// How many times each bang's searches were launched, from the history.
+pub fn bang_usage(history: &[CommandItem]) -> BTreeMap<String, u32> {
+    let mut usage = BTreeMap::new();
+    for item in history {
+        if let Some(bang) = item.metadata.get("bang") {
+            *usage.entry(bang.clone()).or_insert(0) += item.launch_count.max(1);
+        }
+    }
+    usage
+}
+
+/// A search with a bang used before but typed without its `!`, as in
+/// `gh ratatui` once `!gh` was used.
+pub fn used_bang_items(query: &str, usage: &BTreeMap<String, u32>) -> Vec<CommandItem> {
+    let Some((bang, rest)) = query.split_once(' ') else {
+        return Vec::new();
+    };
+    let rest = rest.trim();
+    if rest.is_empty() || !usage.contains_key(bang) {
+        return Vec::new();
+    }
+    let engines = config::get().search.engines();
+    match engines.get(bang) {
+        Some(template) => vec![bang_search_item(bang, template, rest)],
+        None => Vec::new(),
+    }
+}
+
+/// Most completions offered for a partly typed bang.
+const MAX_BANG_COMPLETIONS: usize = 3;
+
+/// Completions of a single word into the used bangs it starts, such as
+/// `gh` into `!gh `: an exact match first, then by usage.
+pub fn bang_completions(query: &str, usage: &BTreeMap<String, u32>) -> Vec<CommandItem> {
+    if query.is_empty() || query.contains(char::is_whitespace) || query.starts_with('!') {
+        return Vec::new();
+    }
+    let engines = config::get().search.engines();
+    let mut used: Vec<(&String, &u32)> = usage
+        .iter()
+        .filter(|(bang, _)| bang.starts_with(query) && engines.contains_key(*bang))
+        .collect();
+    used.sort_by_key(|(bang, count)| (bang.as_str() != query, std::cmp::Reverse(**count)));
+    used.into_iter()
+        .take(MAX_BANG_COMPLETIONS)
+        .map(|(bang, count)| {
+            let searches = if *count == 1 { "search" } else { "searches" };
+            let label = format!("!{} · Search {} ({} {})", bang, engine_host(&engines[bang]), count, searches);
+            let mut item = InternalCommand::SetQuery(format!("!{} ", bang)).to_item(&label);
+            item.icon = "🔎".to_string();
+            item
+        })
+        .collect()
+}
+
 fn create_suggestion_command(suggestion: &str) -> CommandItem {
-    let mut cmd = CommandItem::new(
-        suggestion,
-        Handler::Url,
-        &format!("https://duckduckgo.com/?q={}", suggestion),
-    );
+    let url = fill_template(&config::get().search.default_template(), suggestion);
+    let mut cmd = CommandItem::new(suggestion, Handler::Url, &url);
     cmd.icon = "🔎".to_string();
     cmd.kind = CommandType::WebSuggestion;
     cmd
@@ -38,27 +208,89 @@ pub async fn get_web_search_suggestions(query: String) -> Result<Vec<CommandItem
         .iter()
         .filter_map(|item| item["phrase"].as_str())
         .map(create_suggestion_command)
-        .collect();
+        .collect::<Vec<_>>();
 
     Ok(suggestions)
 }
 
+/// Show DuckDuckGo's instant answer on the top suggestion, when enabled
+/// under `[search]`. Returns whether one was added; the suggestions are
+/// worth showing before it arrives.
+pub async fn add_instant_answer(suggestions: &mut [CommandItem]) -> bool {
+    if !config::get().search.instant_answers {
+        return false;
+    }
+    let Some(top) = suggestions.first_mut() else {
+        return false;
+    };
+    match get_instant_answer(&top.label).await {
+        Some(answer) => {
+            top.metadata.insert("answer".to_string(), answer);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Longest instant answer shown in a suggestion row, in characters.
+const MAX_ANSWER_CHARS: usize = 100;
+
+/// The one-line answer of a DuckDuckGo instant-answer response: a direct
+/// answer (calculation, conversion, ...), else the definition, else the
+/// first sentence of the abstract.
+pub fn parse_instant_answer(response: &Value) -> Option<String> {
+    let text = ["Answer", "Definition", "AbstractText"]
+        .into_iter()
+        .filter_map(|field| response[field].as_str())
+        .map(str::trim)
+        .find(|text| !text.is_empty())?;
+    let line = text.lines().next().unwrap_or(text);
+    let sentence = match line.find(". ") {
+        Some(end) => &line[..=end],
+        None => line,
+    };
+    if sentence.chars().count() <= MAX_ANSWER_CHARS {
+        return Some(sentence.to_string());
+    }
+    let truncated: String = sentence.chars().take(MAX_ANSWER_CHARS - 1).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+/// DuckDuckGo's instant answer for `query`, if it has one.
+pub async fn get_instant_answer(query: &str) -> Option<String> {
+    let response = reqwest::Client::new()
+        .get("https://api.duckduckgo.com/")
+        .query(&[("q", query), ("format", "json"), ("no_html", "1"), ("skip_disambig", "1")])
+        .timeout(Duration::from_millis(800))
+        .send()
+        .await
+        .ok()?
+        .json::<Value>()
+        .await
+        .ok()?;
+    parse_instant_answer(&response)
+}
+
 fn open_url(url: &str) {
-    if let Err(e) = open::that(url) {
+    if let Err(e) = crate::commands::open_target(url) {
         eprintln!("Failed to open URL: {}", e);
     }
 }
 
+fn search_url(query: &str) -> String {
+    config::get().search.url_for(query)
+}
+
 pub fn search_web(query: &str) {
-    let encoded_query = urlencoding::encode(query);
-    let url = format!("https://duckduckgo.com/?q={}", encoded_query);
-    open_url(&url);
+    open_url(&search_url(query));
 }
 
-pub fn open_chat_gpt(query: &str) {
-    let encoded_query = urlencoding::encode(query);
-        let url = format!("https://chatgpt.com/?q={}", encoded_query);
-    open_url(&url);
+/// An item searching the web for `query`.
+pub fn search_item(query: &str) -> CommandItem {
+    let mut cmd = CommandItem::new(&format!("Search the web for \"{}\"", query), Handler::Url, &search_url(query));
+    cmd.icon = "🔎".to_string();
+    cmd.kind = CommandType::WebSearch;
+    cmd
 }
 
 #[cfg(test)]
@@ -73,7 +305,7 @@ mod tests {
         
         assert_eq!(cmd.label, "test query");
         assert_eq!(cmd.handler, Handler::Url);
-        assert_eq!(cmd.value, "https://duckduckgo.com/?q=test query");
+        assert_eq!(cmd.value, "https://duckduckgo.com/?q=test%20query");
         assert_eq!(cmd.icon, "🔎");
         assert_eq!(cmd.kind, CommandType::WebSuggestion);
     }
@@ -84,7 +316,7 @@ mod tests {
         let cmd = create_suggestion_command(suggestion);
         
         assert_eq!(cmd.label, "test & query");
-        assert_eq!(cmd.value, "https://duckduckgo.com/?q=test & query");
+        assert_eq!(cmd.value, "https://duckduckgo.com/?q=test%20%26%20query");
     }
 
     #[tokio::test]
@@ -200,7 +432,7 @@ mod tests {
         let cmd = create_suggestion_command(unicode_query);
         
         assert_eq!(cmd.label, unicode_query);
-        assert!(cmd.value.contains(unicode_query));
+        assert!(cmd.value.contains(&*urlencoding::encode(unicode_query)));
     }
 
     #[tokio::test]
@@ -236,4 +468,94 @@ mod tests {
         let encoded_url = format!("{}{}", base_url, encoded_query);
         assert_eq!(encoded_url, "https://duckduckgo.com/?q=rust%20programming");
     }
+
+    fn launched_bang(bang: &str, rest: &str, launches: u32) -> CommandItem {
+        let mut item = bang_search_item(bang, "https://example.com/?q={query}", rest);
+        item.launch_count = launches;
+        item
+    }
+
+    #[test]
+    fn test_bang_usage() {
+        let history = vec![
+            launched_bang("gh", "ratatui", 2),
+            launched_bang("gh", "tokio", 1),
+            launched_bang("so", "lifetimes", 1),
+            CommandItem::new("Firefox", Handler::App, "/usr/bin/firefox"),
+        ];
+        let usage = bang_usage(&history);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage["gh"], 3);
+        assert_eq!(usage["so"], 1);
+    }
+
+    #[test]
+    fn test_used_bang_items() {
+        let usage = BTreeMap::from([("gh".to_string(), 3)]);
+        let items = used_bang_items("gh ratatui", &usage);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Search github.com for \"ratatui\"");
+        assert_eq!(items[0].value, "https://github.com/search?q=ratatui");
+        assert_eq!(items[0].metadata["bang"], "gh");
+
+        // Bangs never used are not guessed
+        assert!(used_bang_items("so lifetimes", &usage).is_empty());
+        assert!(used_bang_items("gh ", &usage).is_empty());
+    }
+
+    #[test]
+    fn test_bang_completions() {
+        let usage = BTreeMap::from([("g".to_string(), 1), ("gh".to_string(), 5), ("so".to_string(), 2)]);
+        let values: Vec<String> = bang_completions("g", &usage).into_iter().map(|item| item.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                InternalCommand::SetQuery("!g ".to_string()).to_value(),
+                InternalCommand::SetQuery("!gh ".to_string()).to_value(),
+            ]
+        );
+        let gh = bang_completions("gh", &usage);
+        assert_eq!(gh.len(), 1);
+        assert_eq!(gh[0].label, "!gh · Search github.com (5 searches)");
+        assert!(bang_completions("gh ratatui", &usage).is_empty());
+        assert!(bang_completions("!g", &usage).is_empty());
+    }
+
+    #[test]
+    fn test_parse_instant_answer() {
+        let calculation = serde_json::json!({ "Answer": "2 + 2 = 4", "AbstractText": "" });
+        assert_eq!(parse_instant_answer(&calculation).unwrap(), "2 + 2 = 4");
+
+        let definition = serde_json::json!({ "Answer": "", "Definition": "ferris definition: a crab." });
+        assert_eq!(parse_instant_answer(&definition).unwrap(), "ferris definition: a crab.");
+
+        let abstract_text = serde_json::json!({
+            "Answer": { "from": "weather" },
+            "AbstractText": "Rust is a general-purpose programming language. It emphasizes performance."
+        });
+        assert_eq!(parse_instant_answer(&abstract_text).unwrap(), "Rust is a general-purpose programming language.");
+
+        let long = serde_json::json!({ "AbstractText": "a".repeat(300) });
+        let answer = parse_instant_answer(&long).unwrap();
+        assert_eq!(answer.chars().count(), MAX_ANSWER_CHARS);
+        assert!(answer.ends_with('…'));
+
+        assert_eq!(parse_instant_answer(&serde_json::json!({ "Answer": "" })), None);
+    }
+
+    #[test]
+    fn test_search_engines() {
+        let mut search = SearchConfig::default();
+        assert_eq!(search.url_for("rust traits"), "https://duckduckgo.com/?q=rust%20traits");
+        assert_eq!(search.url_for("!gh ratatui"), "https://github.com/search?q=ratatui");
+        assert_eq!(search.url_for("borrow checker !so"), "https://stackoverflow.com/search?q=borrow%20checker");
+        // Unknown bangs are searched as typed
+        assert_eq!(search.url_for("!nope x"), "https://duckduckgo.com/?q=%21nope%20x");
+
+        search.default = "g".to_string();
+        search.engines.insert("mdn".to_string(), "https://developer.mozilla.org/search?q={query}".to_string());
+        assert_eq!(search.url_for("flexbox"), "https://www.google.com/search?q=flexbox");
+        assert_eq!(search.url_for("!mdn flexbox"), "https://developer.mozilla.org/search?q=flexbox");
+        assert_eq!(engine_host("https://www.google.com/search?q={query}"), "google.com");
+    }
 }
@@ -0,0 +1,298 @@
+use crate::core::{CommandItem, Handler};
+use anyhow::Result;
+
+/// What Enter does with a Wi-Fi item.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WifiAction {
+    /// Turn Wi-Fi on or off
+    Toggle,
+    /// Join a known network, with the password kept in the Keychain
+    Join(String),
+    /// Copy a network's password from the Keychain, which asks first
+    CopyPassword(String),
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl WifiAction {
+    pub fn to_value(&self) -> String {
+        match self {
+            WifiAction::Toggle => "toggle".to_string(),
+            WifiAction::Join(network) => format!("join:{}", network),
+            WifiAction::CopyPassword(network) => format!("password:{}", network),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "toggle" {
+            return Some(WifiAction::Toggle);
+        }
+        if let Some(network) = value.strip_prefix("join:") {
+            return Some(WifiAction::Join(network.to_string()));
+        }
+        value
+            .strip_prefix("password:")
+            .map(|network| WifiAction::CopyPassword(network.to_string()))
+    }
+}
+
+/// The Wi-Fi interface as it is now.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WifiStatus {
+    pub powered: bool,
+    /// The network joined, if any
+    pub current: Option<String>,
+    /// Known networks, in order of preference
+    pub known: Vec<String>,
+}
+
+/// Parse `wifi [network]`.
+pub fn parse_query(query: &str) -> Option<&str> {
+    if query == "wifi" {
+        return Some("");
+    }
+    query.strip_prefix("wifi ").map(str::trim)
+}
+
+/// The device of the `Wi-Fi` port in `networksetup -listallhardwareports`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_wifi_device(output: &str) -> Option<String> {
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if matches!(line.trim(), "Hardware Port: Wi-Fi" | "Hardware Port: AirPort") {
+            return lines
+                .next()?
+                .trim()
+                .strip_prefix("Device: ")
+                .map(|device| device.trim().to_string());
+        }
+    }
+    None
+}
+
+/// `Wi-Fi Power (en0): On` from `networksetup -getairportpower`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_power(output: &str) -> bool {
+    output.trim().ends_with(": On")
+}
+
+/// The network in `networksetup -getairportnetwork` output, or the
+/// ` SSID: ` line of `airport -I`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_current_network(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        line.strip_prefix("Current Wi-Fi Network: ")
+            .or_else(|| line.strip_prefix("SSID: "))
+            .map(|network| network.trim().to_string())
+            .filter(|network| !network.is_empty())
+    })
+}
+
+/// The indented networks of `networksetup -listpreferredwirelessnetworks`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_known_networks(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.starts_with('\t') || line.starts_with(' '))
+        .map(|line| line.trim().to_string())
+        .filter(|network| !network.is_empty())
+        .collect()
+}
+
+/// Items for `wifi [filter]`: the toggle, the current network's password
+/// and the known networks matching `filter`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn wifi_items(status: &WifiStatus, filter: &str) -> Vec<CommandItem> {
+    let filter = filter.to_lowercase();
+    let item = |label: String, action: WifiAction| {
+        let mut item = CommandItem::new(&label, Handler::Wifi, &action.to_value());
+        item.icon = "📶".to_string();
+        item
+    };
+    let mut items = Vec::new();
+    if filter.is_empty() {
+        let toggle = if status.powered { "Turn Wi-Fi Off" } else { "Turn Wi-Fi On" };
+        items.push(item(toggle.to_string(), WifiAction::Toggle));
+        if let Some(current) = &status.current {
+            items.push(item(
+                format!("Copy Wi-Fi password of {}", current),
+                WifiAction::CopyPassword(current.clone()),
+            ));
+        }
+    }
+    if status.powered {
+        for network in &status.known {
+            if !network.to_lowercase().contains(&filter) {
+                continue;
+            }
+            if status.current.as_ref() == Some(network) {
+                items.push(item(format!("{} · connected", network), WifiAction::CopyPassword(network.clone())));
+            } else {
+                items.push(item(format!("Join {}", network), WifiAction::Join(network.clone())));
+            }
+        }
+    }
+    items
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::*;
+    use anyhow::{bail, Context};
+    use tokio::process::Command;
+
+    /// The deprecated `airport` tool, which still reports the network when
+    /// `networksetup` can't.
+    const AIRPORT: &str =
+        "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/Resources/airport";
+
+    async fn output(program: &str, args: &[&str]) -> Result<String> {
+        let output = Command::new(program).args(args).output().await?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    pub async fn device() -> Result<String> {
+        let ports = output("networksetup", &["-listallhardwareports"]).await?;
+        parse_wifi_device(&ports).context("This Mac has no Wi-Fi interface")
+    }
+
+    pub async fn status() -> Result<WifiStatus> {
+        let device = device().await?;
+        let powered = parse_power(&output("networksetup", &["-getairportpower", &device]).await?);
+        let mut current = output("networksetup", &["-getairportnetwork", &device])
+            .await
+            .ok()
+            .and_then(|output| parse_current_network(&output));
+        if current.is_none() && powered {
+            current = output(AIRPORT, &["-I"]).await.ok().and_then(|output| parse_current_network(&output));
+        }
+        let known = output("networksetup", &["-listpreferredwirelessnetworks", &device])
+            .await
+            .map(|output| parse_known_networks(&output))
+            .unwrap_or_default();
+        Ok(WifiStatus { powered, current, known })
+    }
+
+    pub async fn run(action: WifiAction) -> Result<()> {
+        let device = device().await?;
+        match action {
+            WifiAction::Toggle => {
+                let powered = parse_power(&output("networksetup", &["-getairportpower", &device]).await?);
+                let power = if powered { "off" } else { "on" };
+                output("networksetup", &["-setairportpower", &device, power]).await?;
+            }
+            WifiAction::Join(network) => {
+                // networksetup prints failures but exits with 0
+                let printed = output("networksetup", &["-setairportnetwork", &device, &network]).await?;
+                if !printed.trim().is_empty() {
+                    bail!("Failed to join {}: {}", network, printed.trim());
+                }
+            }
+            WifiAction::CopyPassword(network) => {
+                let password = output("security", &["find-generic-password", "-wa", &network])
+                    .await
+                    .with_context(|| format!("No password for {} in the Keychain", network))?;
+                crate::clipboard::copy(password.trim_end_matches('\n'))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Items for `wifi [filter]`.
+#[cfg(target_os = "macos")]
+pub async fn get_wifi_items(filter: String) -> Vec<CommandItem> {
+    match macos::status().await {
+        Ok(status) => wifi_items(&status, &filter),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Run a Wi-Fi item's action.
+#[cfg(target_os = "macos")]
+pub async fn run(value: &str) -> Result<()> {
+    let action = WifiAction::parse(value).ok_or_else(|| anyhow::anyhow!("Unknown Wi-Fi action: {}", value))?;
+    macos::run(action).await
+}
+
+/// Networks are read with `networksetup`, so there are none off macOS.
+#[cfg(not(target_os = "macos"))]
+pub async fn get_wifi_items(_filter: String) -> Vec<CommandItem> {
+    Vec::new()
+}
+
+/// Wi-Fi actions go through `networksetup`, which only macOS has.
+#[cfg(not(target_os = "macos"))]
+pub async fn run(_value: &str) -> Result<()> {
+    anyhow::bail!("Wi-Fi actions are only available on macOS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("wifi"), Some(""));
+        assert_eq!(parse_query("wifi  office "), Some("office"));
+        assert_eq!(parse_query("wifis"), None);
+    }
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in [
+            WifiAction::Toggle,
+            WifiAction::Join("Café: Guest".to_string()),
+            WifiAction::CopyPassword("Home".to_string()),
+        ] {
+            assert_eq!(WifiAction::parse(&action.to_value()), Some(action));
+        }
+        assert_eq!(WifiAction::parse("forget:Home"), None);
+    }
+
+    #[test]
+    fn test_parse_networksetup() {
+        let ports = "\nHardware Port: Thunderbolt Bridge\nDevice: bridge0\nEthernet Address: N/A\n\nHardware Port: Wi-Fi\nDevice: en0\nEthernet Address: aa:bb\n";
+        assert_eq!(parse_wifi_device(ports).as_deref(), Some("en0"));
+        assert_eq!(parse_wifi_device("Hardware Port: Ethernet\nDevice: en1\n"), None);
+
+        assert!(parse_power("Wi-Fi Power (en0): On\n"));
+        assert!(!parse_power("Wi-Fi Power (en0): Off\n"));
+
+        assert_eq!(parse_current_network("Current Wi-Fi Network: Home\n").as_deref(), Some("Home"));
+        assert_eq!(parse_current_network("You are not associated with an AirPort network.\n"), None);
+        assert_eq!(parse_current_network("     agrCtlRSSI: -50\n           SSID: Office\n").as_deref(), Some("Office"));
+
+        let preferred = "Preferred networks on en0:\n\tHome\n\tOffice Guest\n";
+        assert_eq!(parse_known_networks(preferred), vec!["Home", "Office Guest"]);
+    }
+
+    #[test]
+    fn test_wifi_items() {
+        let status = WifiStatus {
+            powered: true,
+            current: Some("Home".to_string()),
+            known: vec!["Home".to_string(), "Office".to_string()],
+        };
+        let labels: Vec<String> = wifi_items(&status, "").into_iter().map(|item| item.label).collect();
+        assert_eq!(
+            labels,
+            vec!["Turn Wi-Fi Off", "Copy Wi-Fi password of Home", "Home · connected", "Join Office"]
+        );
+
+        let items = wifi_items(&status, "off");
+        assert_eq!(items.len(), 1);
+        assert_eq!(WifiAction::parse(&items[0].value), Some(WifiAction::Join("Office".to_string())));
+
+        // Nothing to join while Wi-Fi is off
+        let off = WifiStatus { powered: false, ..status };
+        let labels: Vec<String> = wifi_items(&off, "").into_iter().map(|item| item.label).collect();
+        assert_eq!(labels[0], "Turn Wi-Fi On");
+        assert!(!labels.iter().any(|label| label.starts_with("Join")));
+    }
+}
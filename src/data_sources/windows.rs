@@ -0,0 +1,199 @@
+// Compiled everywhere so the query builders and parsers are tested on
+// every platform; only Windows calls into the module
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::core::{CommandItem, CommandType, Handler};
+
+/// Budget for each Windows search backend, like the other file searches.
+const SEARCH_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Arguments for Everything's command-line client (`es.exe`): names
+/// starting with the query, up to `limit` full paths.
+pub fn everything_args(query: &str, limit: usize) -> Vec<String> {
+    vec![
+        "-n".to_string(),
+        limit.to_string(),
+        format!("startwith:\"{}\"", query.replace('"', "")),
+    ]
+}
+
+/// Quote a value for a Windows Search SQL string literal, escaping the
+/// `LIKE` wildcards so the query is matched literally.
+fn sql_like_prefix(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        match c {
+            '\'' => escaped.push_str("''"),
+            '%' | '_' | '[' => {
+                escaped.push('[');
+                escaped.push(c);
+                escaped.push(']');
+            }
+            _ => escaped.push(c),
+        }
+    }
+    format!("'{}%'", escaped)
+}
+
+/// The Windows Search (`SYSTEMINDEX`) query for names starting with
+/// `query` under `roots`.
+pub fn windows_search_sql(query: &str, roots: &[PathBuf], limit: usize) -> String {
+    let scopes: Vec<String> = roots
+        .iter()
+        .map(|root| format!("SCOPE='file:{}'", root.to_string_lossy().replace('\'', "''")))
+        .collect();
+    let mut sql = format!(
+        "SELECT TOP {} System.ItemPathDisplay FROM SYSTEMINDEX WHERE System.FileName LIKE {}",
+        limit,
+        sql_like_prefix(query)
+    );
+    if !scopes.is_empty() {
+        sql.push_str(&format!(" AND ({})", scopes.join(" OR ")));
+    }
+    sql
+}
+
+/// A single-quoted PowerShell string.
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// PowerShell printing one path per line for the Windows Search `sql`,
+/// through the OLE DB provider.
+pub fn windows_search_script(sql: &str) -> String {
+    format!(
+        "$c = New-Object -ComObject ADODB.Connection; \
+         $c.Open(\"Provider=Search.CollatorDSO;Extended Properties='Application=Windows';\"); \
+         $r = $c.Execute({}); \
+         while (-not $r.EOF) {{ $r.Fields.Item(0).Value; $r.MoveNext() }}",
+        powershell_quote(sql)
+    )
+}
+
+fn output_paths(stdout: &[u8], roots: &[PathBuf], limit: usize) -> Vec<String> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|path| roots.iter().any(|root| Path::new(path).starts_with(root)))
+        .take(limit)
+        .map(str::to_string)
+        .collect()
+}
+
+async fn run(program: &str, args: &[String]) -> Option<Vec<u8>> {
+    let output = timeout(SEARCH_TIMEOUT, Command::new(program).args(args).output())
+        .await
+        .ok()?
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// Search with Everything, which answers from its own index in a few
+/// milliseconds. Returns None when `es.exe` or the Everything service is
+/// not available.
+pub async fn run_everything(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    // Everything searches every volume; results outside the roots are dropped
+    let stdout = run("es", &everything_args(query, limit * 10)).await?;
+    Some(output_paths(&stdout, roots, limit))
+}
+
+/// Search the Windows Search index. Returns None when the indexer cannot
+/// be queried.
+pub async fn run_windows_search(query: &str, roots: &[PathBuf], limit: usize) -> Option<Vec<String>> {
+    let script = windows_search_script(&windows_search_sql(query, roots, limit));
+    let args = ["-NoProfile", "-NonInteractive", "-Command", &script].map(str::to_string);
+    let stdout = run("powershell", &args).await?;
+    Some(output_paths(&stdout, roots, limit))
+}
+
+/// `(name, app id)` pairs printed by the `Get-StartApps` script below.
+pub fn parse_start_apps(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, app_id) = line.trim_end_matches('\r').split_once('\t')?;
+            (!name.trim().is_empty() && !app_id.trim().is_empty())
+                .then(|| (name.trim().to_string(), app_id.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Directories whose changes mean the Start menu apps changed.
+pub fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for var in ["APPDATA", "ProgramData"] {
+        if let Some(base) = std::env::var_os(var) {
+            dirs.push(PathBuf::from(base).join("Microsoft\\Windows\\Start Menu\\Programs"));
+        }
+    }
+    if let Some(program_files) = std::env::var_os("ProgramFiles") {
+        dirs.push(PathBuf::from(program_files).join("WindowsApps"));
+    }
+    dirs
+}
+
+/// Apps of the `shell:AppsFolder` virtual folder, which lists UWP apps
+/// along with the desktop apps of the Start menu. The item value is the
+/// `shell:AppsFolder\<app id>` URI that launches the app.
+pub async fn get_start_apps() -> Vec<CommandItem> {
+    let script = "Get-StartApps | ForEach-Object { \"$($_.Name)`t$($_.AppID)\" }";
+    let args = ["-NoProfile", "-NonInteractive", "-Command", script].map(str::to_string);
+    let Some(stdout) = run("powershell", &args).await else {
+        return Vec::new();
+    };
+    parse_start_apps(&String::from_utf8_lossy(&stdout))
+        .into_iter()
+        .map(|(name, app_id)| {
+            let mut item = CommandItem::new(&name, Handler::App, &format!("shell:AppsFolder\\{}", app_id));
+            item.kind = CommandType::App;
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_everything_args() {
+        assert_eq!(everything_args("re\"port", 20), vec!["-n", "20", "startwith:\"report\""]);
+    }
+
+    #[test]
+    fn test_windows_search_sql() {
+        let roots = vec![PathBuf::from("C:\\Users\\jane")];
+        assert_eq!(
+            windows_search_sql("50%_o'clock", &roots, 10),
+            "SELECT TOP 10 System.ItemPathDisplay FROM SYSTEMINDEX WHERE System.FileName LIKE '50[%][_]o''clock%' \
+             AND (SCOPE='file:C:\\Users\\jane')"
+        );
+        let script = windows_search_script("WHERE a = 'b'");
+        assert!(script.contains("$c.Execute('WHERE a = ''b''')"));
+    }
+
+    #[test]
+    fn test_parse_start_apps() {
+        let output = "Calculator\tMicrosoft.WindowsCalculator_8wekyb3d8bbwe!App\r\nNo id\t\r\nVisual Studio Code\tMicrosoft.VisualStudioCode\r\n";
+        assert_eq!(
+            parse_start_apps(output),
+            vec![
+                ("Calculator".to_string(), "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App".to_string()),
+                ("Visual Studio Code".to_string(), "Microsoft.VisualStudioCode".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_paths_keeps_roots() {
+        let roots = vec![PathBuf::from("/home/jane")];
+        let stdout = b"/home/jane/report.pdf\n/srv/report.pdf\n\n/home/jane/reports\n";
+        assert_eq!(output_paths(stdout, &roots, 1), vec!["/home/jane/report.pdf"]);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+/// Directories visited often, as ranked by zoxide.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoxideConfig {
+    pub enabled: bool,
+    /// How many of the top directories are listed
+    pub limit: usize,
+    /// Enter opens a terminal in the directory rather than the file manager
+    pub open_in_terminal: bool,
+}
+
+impl Default for ZoxideConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            limit: 200,
+            open_in_terminal: false,
+        }
+    }
+}
+
+/// `(score, path)` of the lines of `zoxide query --list --score`, best
+/// first as zoxide prints them.
+pub fn parse_scores(output: &str) -> Vec<(f64, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (score, path) = line.trim_start().split_once(' ')?;
+            let path = path.trim();
+            Some((score.parse().ok()?, path.to_string())).filter(|_| !path.is_empty())
+        })
+        .collect()
+}
+
+/// `~/src/grinta` for paths in the home directory.
+fn shorten(path: &str, home: Option<&Path>) -> String {
+    match home.and_then(|home| Path::new(path).strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.to_string(),
+    }
+}
+
+fn zoxide_item(score: f64, path: &str, home: Option<&Path>) -> CommandItem {
+    let name = Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    let mut item = CommandItem::new(&name, Handler::Folder, path);
+    item.metadata.insert("zoxide".to_string(), "true".to_string());
+    // Zoxide counts visits with aging, much like launches in the history
+    item.metadata.insert("frecency".to_string(), format!("{:.1}", score));
+    item.metadata.insert("keywords".to_string(), shorten(path, home));
+    item
+}
+
+pub fn is_zoxide(item: &CommandItem) -> bool {
+    item.handler == Handler::Folder && item.metadata.contains_key("zoxide")
+}
+
+/// Whether Enter opens `item` in a terminal.
+pub fn opens_in_terminal(item: &CommandItem) -> bool {
+    is_zoxide(item) && config::get().zoxide.open_in_terminal
+}
+
+/// The directories zoxide ranks highest that still exist. Without zoxide
+/// there are none.
+pub async fn get_zoxide_items() -> Vec<CommandItem> {
+    let settings = &config::get().zoxide;
+    if !settings.enabled {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("zoxide").args(["query", "--list", "--score"]).output().await else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    let home = dirs::home_dir();
+    parse_scores(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter(|(_, path)| Path::new(path).is_dir())
+        .take(settings.limit)
+        .map(|(score, path)| zoxide_item(score, &path, home.as_deref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scores() {
+        let output = "  48.0 /home/ada/src/grinta\n   4.5 /home/ada/My Documents\n   0.2 /tmp\nnot a score\n";
+        assert_eq!(
+            parse_scores(output),
+            vec![
+                (48.0, "/home/ada/src/grinta".to_string()),
+                (4.5, "/home/ada/My Documents".to_string()),
+                (0.2, "/tmp".to_string()),
+            ]
+        );
+        assert!(parse_scores("").is_empty());
+    }
+
+    #[test]
+    fn test_zoxide_item() {
+        let home = Path::new("/home/ada");
+        let item = zoxide_item(48.0, "/home/ada/src/grinta", Some(home));
+        assert_eq!(item.label, "grinta");
+        assert_eq!(item.handler, Handler::Folder);
+        assert_eq!(item.value, "/home/ada/src/grinta");
+        assert_eq!(item.metadata["keywords"], "~/src/grinta");
+        assert_eq!(item.metadata["frecency"], "48.0");
+        assert!(is_zoxide(&item));
+
+        assert_eq!(zoxide_item(1.0, "/", Some(home)).label, "/");
+        assert_eq!(zoxide_item(1.0, "/home/ada", Some(home)).metadata["keywords"], "~");
+        assert_eq!(zoxide_item(1.0, "/srv/www", Some(home)).metadata["keywords"], "/srv/www");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::audit;
+use crate::config;
+use crate::core::CommandItem;
+use crate::hooks;
+use crate::workspace::Source;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Events a slow subscriber can fall behind by before it misses some.
+const CAPACITY: usize = 64;
+
+/// Something that happened in Grinta, for the subsystems that react to it
+/// (audit log, hooks, notifications) without being wired into the code
+/// that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// An item was executed, with the error if it failed
+    ItemExecuted { item: CommandItem, error: Option<String> },
+    /// The query was edited
+    QueryChanged(String),
+    /// The items of a source were read again, `None` for all of them
+    SourceRefreshed(Option<Source>),
+    /// An error for the error bar
+    ErrorRaised(String),
+    /// A message for the status bar, such as the outcome of an action
+    Notified(String),
+    /// How far along a long task is, in percent; `None` once it is over
+    Progress { task: String, percent: Option<u8> },
+}
+
+static BUS: Lazy<broadcast::Sender<Event>> = Lazy::new(|| broadcast::channel(CAPACITY).0);
+
+/// Send `event` to every current subscriber. Without subscribers it is
+/// dropped.
+pub fn publish(event: Event) {
+    let _ = BUS.send(event);
+}
+
+/// Receive the events published from now on.
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    BUS.subscribe()
+}
+
+/// Record an execution in the audit log, if enabled, and run the
+/// `after_execute` hook. Called where the item ran, before Grinta may exit.
+pub async fn record_execution(item: &CommandItem, result: &Result<()>) {
+    let config = config::get();
+    if config.audit_log {
+        let _ = audit::record(item, result);
+    }
+    let _ = hooks::after_execute(&config.hooks, item, result).await;
+}
+
+/// Executions still running or being recorded, waited for on exit.
+static IN_FLIGHT: Lazy<Mutex<Vec<JoinHandle<()>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Run an execution in the background. Grinta waits for it on exit, so
+/// closing right after Enter still records it.
+pub fn spawn_execution(task: impl Future<Output = ()> + Send + 'static) {
+    let handle = tokio::spawn(task);
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        in_flight.retain(|handle| !handle.is_finished());
+        in_flight.push(handle);
+    }
+}
+
+/// Wait up to `limit` for the executions still in flight.
+pub async fn wait_for_executions(limit: Duration) {
+    let handles = match IN_FLIGHT.lock() {
+        Ok(mut in_flight) => std::mem::take(&mut *in_flight),
+        Err(_) => return,
+    };
+    let all = async {
+        for handle in handles {
+            let _ = handle.await;
+        }
+    };
+    let _ = tokio::time::timeout(limit, all).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+
+    #[tokio::test]
+    async fn test_wait_for_executions() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        spawn_execution(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let _ = tx.send(()).await;
+        });
+        wait_for_executions(Duration::from_secs(5)).await;
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers() {
+        let mut first = subscribe();
+        let mut second = subscribe();
+        let item = CommandItem::new("Docs", Handler::Url, "https://docs.rs");
+        publish(Event::ItemExecuted { item: item.clone(), error: None });
+
+        // Other tests publish too; skip what they sent
+        let executed = |event: &Event| matches!(event, Event::ItemExecuted { item, .. } if item.label == "Docs");
+        for events in [&mut first, &mut second] {
+            loop {
+                let event = events.recv().await.unwrap();
+                if executed(&event) {
+                    assert_eq!(event, Event::ItemExecuted { item: item.clone(), error: None });
+                    break;
+                }
+            }
+        }
+    }
+}
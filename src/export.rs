@@ -0,0 +1,204 @@
+use crate::clipboard;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    Clipboard,
+    /// A timestamped file in the downloads directory
+    File,
+}
+
+/// Choices offered by the export menu, in order.
+pub const EXPORT_OPTIONS: [(ExportFormat, ExportTarget); 6] = [
+    (ExportFormat::Markdown, ExportTarget::Clipboard),
+    (ExportFormat::Json, ExportTarget::Clipboard),
+    (ExportFormat::Csv, ExportTarget::Clipboard),
+    (ExportFormat::Markdown, ExportTarget::File),
+    (ExportFormat::Json, ExportTarget::File),
+    (ExportFormat::Csv, ExportTarget::File),
+];
+
+pub fn option_label(format: ExportFormat, target: ExportTarget) -> String {
+    match target {
+        ExportTarget::Clipboard => format!("Copy as {}", format.name()),
+        ExportTarget::File => format!("Save as {} file", format.name()),
+    }
+}
+
+/// The export menu opened on the results with Ctrl+S.
+#[derive(Debug, Clone, Default)]
+pub struct ExportMenu {
+    pub selected: usize,
+}
+
+impl ExportMenu {
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % EXPORT_OPTIONS.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = (self.selected + EXPORT_OPTIONS.len() - 1) % EXPORT_OPTIONS.len();
+    }
+
+    pub fn selected_option(&self) -> (ExportFormat, ExportTarget) {
+        EXPORT_OPTIONS[self.selected]
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedItem<'a> {
+    label: &'a str,
+    value: &'a str,
+    kind: &'static str,
+}
+
+impl<'a> ExportedItem<'a> {
+    fn new(item: &'a CommandItem) -> Self {
+        // Generated secrets stay out of shared lists
+        let value = if item.metadata.contains_key("masked") { "<masked>" } else { &item.value };
+        Self {
+            label: &item.label,
+            value,
+            kind: item.handler.to_string(),
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Render `items` as a Markdown list, a JSON array or CSV with a header.
+pub fn render(items: &[CommandItem], format: ExportFormat) -> Result<String> {
+    let exported: Vec<ExportedItem> = items.iter().map(ExportedItem::new).collect();
+    let text = match format {
+        ExportFormat::Markdown => exported
+            .iter()
+            .zip(items)
+            .map(|(entry, item)| match item.handler {
+                Handler::Url => format!("- [{}]({})\n", entry.label, entry.value),
+                _ => format!("- {}: `{}`\n", entry.label, entry.value),
+            })
+            .collect(),
+        ExportFormat::Json => serde_json::to_string_pretty(&exported)? + "\n",
+        ExportFormat::Csv => {
+            let mut csv = String::from("label,value,kind\n");
+            for entry in &exported {
+                csv.push_str(&format!(
+                    "{},{},{}\n",
+                    csv_field(entry.label),
+                    csv_field(entry.value),
+                    entry.kind
+                ));
+            }
+            csv
+        }
+    };
+    Ok(text)
+}
+
+/// Export `items` and describe where they went.
+pub fn export(items: &[CommandItem], format: ExportFormat, target: ExportTarget) -> Result<String> {
+    let text = render(items, format)?;
+    match target {
+        ExportTarget::Clipboard => {
+            clipboard::copy(&text)?;
+            Ok(format!("Copied {} results as {}", items.len(), format.name()))
+        }
+        ExportTarget::File => {
+            let dir = dirs::download_dir()
+                .or_else(dirs::home_dir)
+                .context("Failed to find the downloads directory")?;
+            let path: PathBuf = dir.join(format!(
+                "grinta-results-{}.{}",
+                Local::now().format("%Y%m%d-%H%M%S"),
+                format.extension()
+            ));
+            std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(format!("Saved {} results to {}", items.len(), path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<CommandItem> {
+        let mut password = CommandItem::new("Password", Handler::Copy, "hunter2");
+        password.metadata.insert("masked".to_string(), "true".to_string());
+        vec![
+            CommandItem::new("Invoice, 2024.pdf", Handler::File, "/tmp/Invoice, 2024.pdf"),
+            CommandItem::new("Grinta", Handler::Url, "https://grinta.app"),
+            password,
+        ]
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = render(&items(), ExportFormat::Markdown).unwrap();
+        assert_eq!(
+            markdown,
+            "- Invoice, 2024.pdf: `/tmp/Invoice, 2024.pdf`\n- [Grinta](https://grinta.app)\n- Password: `<masked>`\n"
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = render(&items(), ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "label,value,kind");
+        assert_eq!(lines[1], "\"Invoice, 2024.pdf\",\"/tmp/Invoice, 2024.pdf\",File");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value = serde_json::from_str(&render(&items(), ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[1]["value"], "https://grinta.app");
+        assert_eq!(json[1]["kind"], "Website");
+        assert_eq!(json[2]["value"], "<masked>");
+    }
+
+    #[test]
+    fn test_export_menu_wraps() {
+        let mut menu = ExportMenu::default();
+        menu.previous();
+        assert_eq!(menu.selected_option(), (ExportFormat::Csv, ExportTarget::File));
+        menu.next();
+        assert_eq!(option_label(menu.selected_option().0, menu.selected_option().1), "Copy as Markdown");
+    }
+}
@@ -1,21 +1,38 @@
-use crate::core::CommandItem;
+use crate::config;
+use crate::core::{CommandItem, Handler};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 const HISTORY_FILE: &str = "grinta_history.json";
 
-fn history_file_path() -> Result<PathBuf> {
+/// History file of a workspace; `None` is the global history.
+fn workspace_history_file_path(workspace: Option<&str>) -> Result<PathBuf> {
     let mut path = dirs::data_dir().context("Failed to get data directory")?;
     path.push("grinta-rs");
     fs::create_dir_all(&path)?;
-    path.push(HISTORY_FILE);
+    match workspace {
+        Some(name) => {
+            let safe_name: String = name
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+                .collect();
+            path.push(format!("grinta_history.{}.json", safe_name));
+        }
+        None => path.push(HISTORY_FILE),
+    }
     Ok(path)
 }
 
 pub fn load_history() -> Result<Vec<CommandItem>> {
-    let path = history_file_path()?;
+    load_workspace_history(None)
+}
+
+pub fn load_workspace_history(workspace: Option<&str>) -> Result<Vec<CommandItem>> {
+    let path = workspace_history_file_path(workspace)?;
     if !path.exists() {
         return Ok(Vec::new());
     }
@@ -26,21 +43,165 @@ pub fn load_history() -> Result<Vec<CommandItem>> {
     Ok(history)
 }
 
+#[allow(dead_code)]
 pub fn save_history(history: &[CommandItem]) -> Result<()> {
-    let path = history_file_path()?;
+    save_workspace_history(history, None)
+}
+
+pub fn save_workspace_history(history: &[CommandItem], workspace: Option<&str>) -> Result<()> {
+    let path = workspace_history_file_path(workspace)?;
     let mut file = File::create(path)?;
     let json = serde_json::to_string_pretty(history)?;
     file.write_all(json.as_bytes())?;
     Ok(())
 }
 
-pub fn add_to_history(history: &mut Vec<CommandItem>, mut item: CommandItem) -> Result<()> {
+#[allow(dead_code)]
+pub fn add_to_history(history: &mut Vec<CommandItem>, item: CommandItem) -> Result<()> {
+    add_to_workspace_history(history, item, None)
+}
+
+pub fn add_to_workspace_history(
+    history: &mut Vec<CommandItem>,
+    mut item: CommandItem,
+    workspace: Option<&str>,
+) -> Result<()> {
     item.mark_executed();
-    
-    history.retain(|h| h.label != item.label || h.handler != item.handler || h.value != item.value);
+
+    // Entries of the same item under an older value (an app that moved,
+    // a URL with a trailing slash) are merged into this one
+    let key = canonical_key(&item);
+    let same_item = |h: &CommandItem| h.label == item.label && canonical_key(h) == key;
+    let previous_count = history
+        .iter()
+        .filter(|h| same_item(h))
+        .map(|h| h.launch_count)
+        .max()
+        .unwrap_or(0);
+    item.launch_count = previous_count.max(item.launch_count) + 1;
+
+    history.retain(|h| !same_item(h));
     history.push(item);
 
-    save_history(history)
+    save_workspace_history(history, workspace)
+}
+
+/// `path` with `~` expanded and `.`/`..` resolved, without touching the
+/// file system.
+fn normalize_path(path: &str) -> String {
+    let expanded = match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    };
+    let mut normalized = PathBuf::new();
+    for component in expanded.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized.to_string_lossy().to_string()
+}
+
+/// `url` without its scheme, `www.`, default port, fragment and trailing
+/// slash, and with a lowercase host.
+fn normalize_url(url: &str) -> String {
+    let Some((_, rest)) = url.trim().split_once("://") else {
+        return url.trim().to_string();
+    };
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let (host, path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let host = host.strip_suffix(":443").or_else(|| host.strip_suffix(":80")).unwrap_or(host);
+    let (path, query) = path.split_once('?').map_or((path, None), |(path, query)| (path, Some(query)));
+    let mut normalized = format!("{}{}", host, path.trim_end_matches('/'));
+    if let Some(query) = query {
+        normalized.push('?');
+        normalized.push_str(query);
+    }
+    normalized
+}
+
+/// What identifies an item across value changes: the bundle identifier
+/// (or bundle name) of apps, the normalized path of files and the
+/// normalized URL of links. History entries and frecency are merged by it.
+pub fn canonical_key(item: &CommandItem) -> (Handler, String) {
+    let key = match item.handler {
+        Handler::App => match item.metadata.get("bundle_id") {
+            Some(bundle_id) => bundle_id.clone(),
+            None => Path::new(&item.value)
+                .file_name()
+                .map_or_else(|| item.value.clone(), |name| name.to_string_lossy().to_string()),
+        },
+        Handler::File | Handler::Folder => normalize_path(&item.value),
+        Handler::Url => normalize_url(&item.value),
+        _ => item.value.clone(),
+    };
+    (item.handler, key)
+}
+
+/// Whether a history entry or pin points at something that is gone: an
+/// app bundle, file or folder that was deleted, or a note missing from
+/// `note_ids`. Notes are only checked once some were read.
+pub fn is_broken(item: &CommandItem, note_ids: &HashSet<&str>) -> bool {
+    match item.handler {
+        Handler::App | Handler::File | Handler::Folder => {
+            let path = PathBuf::from(normalize_path(&item.value));
+            path.is_absolute() && !path.exists()
+        }
+        Handler::Note => !note_ids.is_empty() && !note_ids.contains(item.value.as_str()),
+        _ => false,
+    }
+}
+
+/// Launch count weighted by how recently the item was last launched, as
+/// configured under `[ranking]`. Entries recorded before launch counts
+/// existed count as one launch.
+pub fn frecency(item: &CommandItem, now: DateTime<Local>) -> f64 {
+    let Some(ran_at) = item.ran_at else {
+        return 0.0;
+    };
+    let age_days = (now - ran_at).num_seconds().max(0) as f64 / 86_400.0;
+    item.launch_count.max(1) as f64 * config::get().ranking.decay(age_days)
+}
+
+/// Frecency of every history entry, keyed by `canonical_key`. Entries
+/// sharing a key add up.
+pub fn frecency_scores(history: &[CommandItem]) -> HashMap<(Handler, String), f64> {
+    let now = Local::now();
+    let mut scores = HashMap::new();
+    for item in history {
+        *scores.entry(canonical_key(item)).or_insert(0.0) += frecency(item, now);
+    }
+    scores
+}
+
+/// Frecency of `item` from `frecency_scores`, plus any it brings itself,
+/// such as zoxide directories.
+pub fn item_frecency(scores: &HashMap<(Handler, String), f64>, item: &CommandItem) -> f64 {
+    let own = item.metadata.get("frecency").and_then(|f| f.parse::<f64>().ok()).unwrap_or(0.0);
+    scores.get(&canonical_key(item)).copied().unwrap_or(0.0) + own
+}
+
+/// The entry usually launched after typing `prefix`: the most frecent
+/// one whose label starts with it, ignoring case.
+pub fn likely_completion<'a>(history: &'a [CommandItem], prefix: &str) -> Option<&'a CommandItem> {
+    let prefix = prefix.to_lowercase();
+    if prefix.is_empty() {
+        return None;
+    }
+    let now = Local::now();
+    history
+        .iter()
+        .filter(|item| item.label.to_lowercase().starts_with(&prefix))
+        .map(|item| (frecency(item, now), item))
+        .filter(|(score, _)| *score > 0.0)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, item)| item)
 }
 
 #[cfg(test)]
@@ -145,6 +306,128 @@ mod tests {
         assert_eq!(history[1].label, "App 3");
     }
 
+    #[test]
+    fn test_add_to_history_counts_launches() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        env::set_var("HOME", temp_path);
+
+        let mut history = vec![];
+        let item = create_test_item("App", Handler::App, "/Applications/App.app");
+        for _ in 0..3 {
+            add_to_history(&mut history, item.clone()).unwrap();
+        }
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].launch_count, 3);
+    }
+
+    #[test]
+    fn test_frecency_decays_with_age() {
+        let now = Local::now();
+        let mut recent = create_test_item("Recent", Handler::App, "recent");
+        recent.ran_at = Some(now);
+        recent.launch_count = 4;
+
+        let mut old = recent.clone();
+        let half_life = config::get().ranking.half_life_days;
+        old.ran_at = Some(now - chrono::Duration::seconds((half_life * 86_400.0) as i64));
+
+        let never = create_test_item("Never", Handler::App, "never");
+
+        assert!((frecency(&recent, now) - 4.0).abs() < 1e-9);
+        assert!((frecency(&old, now) - 2.0).abs() < 1e-6);
+        assert_eq!(frecency(&never, now), 0.0);
+    }
+
+    #[test]
+    fn test_frecency_frequent_beats_single_launch() {
+        let now = Local::now();
+        let mut frequent = create_test_item("Frequent", Handler::App, "frequent");
+        frequent.ran_at = Some(now - chrono::Duration::days(3));
+        frequent.launch_count = 50;
+
+        let mut once = create_test_item("Once", Handler::App, "once");
+        once.ran_at = Some(now);
+        once.launch_count = 1;
+
+        assert!(frecency(&frequent, now) > frecency(&once, now));
+
+        let history = vec![frequent, once];
+        let scores = frecency_scores(&history);
+        assert!(scores[&(Handler::App, "frequent".to_string())] > scores[&(Handler::App, "once".to_string())]);
+    }
+
+    #[test]
+    fn test_likely_completion() {
+        let now = Local::now();
+        let mut safari = create_test_item("Safari", Handler::App, "/Applications/Safari.app");
+        safari.ran_at = Some(now);
+        safari.launch_count = 12;
+        let mut slides = create_test_item("slides.key", Handler::File, "/Users/jane/slides.key");
+        slides.ran_at = Some(now);
+        let history = vec![slides, safari];
+
+        assert_eq!(likely_completion(&history, "s").unwrap().label, "Safari");
+        assert_eq!(likely_completion(&history, "SL").unwrap().label, "slides.key");
+        assert!(likely_completion(&history, "x").is_none());
+        assert!(likely_completion(&history, "").is_none());
+    }
+
+    #[test]
+    fn test_canonical_key() {
+        let key = |handler, value: &str| canonical_key(&create_test_item("Item", handler, value)).1;
+
+        assert_eq!(key(Handler::App, "/Applications/Slack.app"), key(Handler::App, "/Users/jane/Applications/Slack.app"));
+        let mut bundled = create_test_item("Slack", Handler::App, "/Applications/Slack.app");
+        bundled.metadata.insert("bundle_id".to_string(), "com.tinyspeck.slackmacgap".to_string());
+        assert_eq!(canonical_key(&bundled).1, "com.tinyspeck.slackmacgap");
+
+        assert_eq!(key(Handler::File, "/tmp/./docs/../report.pdf"), "/tmp/report.pdf");
+        assert_eq!(key(Handler::Folder, "/tmp/docs/"), "/tmp/docs");
+
+        assert_eq!(key(Handler::Url, "https://www.GitHub.com/rust-lang/"), "github.com/rust-lang");
+        assert_eq!(key(Handler::Url, "http://github.com:80/rust-lang#readme"), "github.com/rust-lang");
+        assert_eq!(key(Handler::Url, "https://example.com/search/?q=a/b"), "example.com/search?q=a/b");
+        assert_ne!(key(Handler::Url, "https://example.com/a"), key(Handler::Url, "https://example.com/b"));
+        assert_eq!(key(Handler::Note, "x-coredata://1"), "x-coredata://1");
+    }
+
+    #[test]
+    fn test_is_broken() {
+        let dir = TempDir::new().unwrap();
+        let existing = dir.path().join("report.pdf");
+        std::fs::write(&existing, "").unwrap();
+        let no_notes = HashSet::new();
+
+        let file = create_test_item("report.pdf", Handler::File, existing.to_str().unwrap());
+        assert!(!is_broken(&file, &no_notes));
+        let deleted = create_test_item("old.pdf", Handler::File, dir.path().join("old.pdf").to_str().unwrap());
+        assert!(is_broken(&deleted, &no_notes));
+        // Apps launched by name are not checked
+        assert!(!is_broken(&create_test_item("Firefox", Handler::App, "firefox"), &no_notes));
+        assert!(!is_broken(&create_test_item("Site", Handler::Url, "https://example.com"), &no_notes));
+
+        let note = create_test_item("Groceries", Handler::Note, "x-coredata://2");
+        assert!(!is_broken(&note, &no_notes));
+        assert!(is_broken(&note, &HashSet::from(["x-coredata://1"])));
+        assert!(!is_broken(&note, &HashSet::from(["x-coredata://2"])));
+    }
+
+    #[test]
+    fn test_moved_app_merges_history() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path().to_str().unwrap());
+
+        let mut history = vec![];
+        add_to_history(&mut history, create_test_item("Slack", Handler::App, "/Applications/Slack.app")).unwrap();
+        add_to_history(&mut history, create_test_item("Slack", Handler::App, "/Users/jane/Applications/Slack.app")).unwrap();
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].value, "/Users/jane/Applications/Slack.app");
+        assert_eq!(history[0].launch_count, 2);
+    }
+
     #[test]
     fn test_add_to_history_different_handlers() {
         let temp_dir = TempDir::new().unwrap();
@@ -171,13 +454,34 @@ mod tests {
         let temp_path = temp_dir.path().to_str().unwrap();
         env::set_var("HOME", temp_path);
 
-        let path_result = history_file_path();
+        let path_result = workspace_history_file_path(None);
         assert!(path_result.is_ok());
         
         let path = path_result.unwrap();
         assert!(path.to_string_lossy().contains("grinta_history.json"));
     }
 
+    #[test]
+    fn test_workspace_history_is_separate() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        env::set_var("HOME", temp_path);
+
+        let mut global = vec![];
+        add_to_history(&mut global, create_test_item("Global", Handler::App, "global")).unwrap();
+
+        let mut slice = vec![];
+        add_to_workspace_history(&mut slice, create_test_item("Client", Handler::App, "client"), Some("client/x")).unwrap();
+
+        let loaded = load_workspace_history(Some("client/x")).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].label, "Client");
+
+        let path = workspace_history_file_path(Some("client/x")).unwrap();
+        assert!(path.to_string_lossy().ends_with("grinta_history.client_x.json"));
+        assert!(load_workspace_history(Some("other")).unwrap().is_empty());
+    }
+
     #[test]
     fn test_load_corrupted_history() {
         let temp_dir = TempDir::new().unwrap();
@@ -185,7 +489,7 @@ mod tests {
         env::set_var("HOME", temp_path);
 
         // Create a corrupted history file
-        let path = history_file_path().unwrap();
+        let path = workspace_history_file_path(None).unwrap();
         std::fs::create_dir_all(path.parent().unwrap()).unwrap();
         std::fs::write(&path, "invalid json content").unwrap();
 
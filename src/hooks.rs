@@ -0,0 +1,136 @@
+use crate::core::CommandItem;
+use crate::shell;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+
+/// Shell commands run on Grinta's events, for logging, window manager
+/// scripts or statistics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Run after every execution, with the item and its outcome as JSON on
+    /// stdin
+    pub after_execute: Option<String>,
+    /// Run when the launcher window closes
+    pub on_exit: Option<String>,
+}
+
+/// What `after_execute` reads on stdin.
+#[derive(Debug, Serialize)]
+struct Execution<'a> {
+    item: &'a CommandItem,
+    outcome: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The JSON `after_execute` receives. Generated secrets are masked, as in
+/// the audit log.
+fn execution_json(item: &CommandItem, result: &Result<()>) -> Result<String> {
+    let mut item = item.clone();
+    if item.metadata.contains_key("masked") {
+        item.value = "<masked>".to_string();
+    }
+    let execution = Execution {
+        item: &item,
+        outcome: if result.is_ok() { "ok" } else { "error" },
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    Ok(serde_json::to_string(&execution)?)
+}
+
+/// Start `command` with `$SHELL -c`.
+fn spawn_hook(command: &str, stdin: Stdio) -> Result<Child> {
+    Command::new(shell::user_shell())
+        .args(["-c", command])
+        .stdin(stdin)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run hook `{}`", command))
+}
+
+/// Run `command` with `$SHELL -c`, writing `input` to its stdin, and wait
+/// for it.
+async fn run_hook(command: &str, input: Option<String>) -> Result<()> {
+    let mut child = spawn_hook(command, if input.is_some() { Stdio::piped() } else { Stdio::null() })?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A hook that ignores its input may close stdin early
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+    child.wait().await?;
+    Ok(())
+}
+
+/// Run the `after_execute` hook, if one is configured, for `item`.
+pub async fn after_execute(hooks: &HooksConfig, item: &CommandItem, result: &Result<()>) -> Result<()> {
+    let Some(command) = &hooks.after_execute else {
+        return Ok(());
+    };
+    run_hook(command, Some(execution_json(item, result)?)).await
+}
+
+/// Start the `on_exit` hook, if one is configured. It is left running so
+/// a slow hook doesn't keep the closed launcher around.
+pub fn on_exit(hooks: &HooksConfig) -> Result<()> {
+    match &hooks.on_exit {
+        Some(command) => spawn_hook(command, Stdio::null()).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+
+    #[test]
+    fn test_execution_json() {
+        let item = CommandItem::new("Docs", Handler::Url, "https://docs.rs");
+        let json: serde_json::Value = serde_json::from_str(&execution_json(&item, &Ok(())).unwrap()).unwrap();
+        assert_eq!(json["item"]["value"], "https://docs.rs");
+        assert_eq!(json["outcome"], "ok");
+        assert!(json.get("error").is_none());
+
+        let mut secret = CommandItem::new("Password", Handler::Copy, "hunter2");
+        secret.metadata.insert("masked".to_string(), "true".to_string());
+        let failed = Err(anyhow::anyhow!("no clipboard"));
+        let json: serde_json::Value = serde_json::from_str(&execution_json(&secret, &failed).unwrap()).unwrap();
+        assert_eq!(json["item"]["value"], "<masked>");
+        assert_eq!(json["outcome"], "error");
+        assert_eq!(json["error"], "no clipboard");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_after_execute_reads_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("executed.json");
+        let hooks = HooksConfig {
+            after_execute: Some(format!("cat > '{}'", out.display())),
+            on_exit: None,
+        };
+        let item = CommandItem::new("Docs", Handler::Url, "https://docs.rs");
+        after_execute(&hooks, &item, &Ok(())).await.unwrap();
+        let written = std::fs::read_to_string(&out).unwrap();
+        assert!(written.contains("\"outcome\":\"ok\""));
+
+        // Nothing configured, nothing run
+        assert!(on_exit(&HooksConfig::default()).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_on_exit_does_not_wait() {
+        let hooks = HooksConfig {
+            after_execute: None,
+            on_exit: Some("sleep 5".to_string()),
+        };
+        let started = std::time::Instant::now();
+        on_exit(&hooks).unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    }
+}
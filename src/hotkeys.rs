@@ -0,0 +1,275 @@
+use crate::core::CommandItem;
+use crate::workspace::Workspace;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Keys a pin or a configurable action can be bound to: F1–F12, or a
+/// character with Ctrl and/or Alt, such as `alt+1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Hotkey {
+    /// Parse `F1`, `alt+s` or `ctrl+alt+n`, case-insensitively. Bare
+    /// characters are refused since they are typed into the query, and so
+    /// is Ctrl+C, which always quits.
+    pub fn parse(text: &str) -> Option<Hotkey> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<String> = text.split('+').map(|part| part.trim().to_lowercase()).collect();
+        let key = parts.pop()?;
+        for part in parts {
+            modifiers |= match part.as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "option" => KeyModifiers::ALT,
+                _ => return None,
+            };
+        }
+
+        let code = match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => {
+                let mut chars = key.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return None;
+                };
+                if modifiers.is_empty() || (modifiers == KeyModifiers::CONTROL && c == 'c') {
+                    return None;
+                }
+                KeyCode::Char(c)
+            }
+        };
+        Some(Hotkey { code, modifiers })
+    }
+
+    /// Whether `key` is this hotkey. Terminals differ in reporting Shift
+    /// with characters, so it is ignored for them.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        match (self.code, key.code) {
+            (KeyCode::Char(expected), KeyCode::Char(pressed)) => {
+                expected == pressed.to_ascii_lowercase() && self.modifiers == key.modifiers - KeyModifiers::SHIFT
+            }
+            (expected, pressed) => expected == pressed && self.modifiers == key.modifiers,
+        }
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Keys of the built-in actions that can be rebound.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeysConfig {
+    /// Copy the selected item's value
+    pub copy: String,
+    /// Join the call of the next meeting
+    pub join: String,
+}
+
+impl Default for KeysConfig {
+    fn default() -> Self {
+        Self {
+            copy: "ctrl+y".to_string(),
+            join: "alt+j".to_string(),
+        }
+    }
+}
+
+impl KeysConfig {
+    /// The copy key, None when it is not a valid hotkey.
+    pub fn copy_key(&self) -> Option<Hotkey> {
+        Hotkey::parse(&self.copy)
+    }
+
+    /// The join key, None when it is not a valid hotkey.
+    pub fn join_key(&self) -> Option<Hotkey> {
+        Hotkey::parse(&self.join)
+    }
+}
+
+/// Grinta's own key bindings, as listed by the help overlay.
+const KEY_BINDINGS: [(&str, &str); 13] = [
+    ("Enter", "Open the selected item"),
+    ("Alt+Enter", "Reveal the file, or run the command in a terminal"),
+    ("Up / Down", "Move the selection"),
+    ("Tab", "Ask the AI provider"),
+    ("Ctrl+K / Right", "Actions for the selected item"),
+    ("Ctrl+S", "Export the results"),
+    ("Ctrl+T", "Save the query as a snippet"),
+    ("Ctrl+N", "Create a note from the query"),
+    ("Ctrl+D", "Delete the selected note"),
+    ("Ctrl+P", "Preview the selected file (Quick Look on macOS) or note"),
+    ("Ctrl+Backspace", "Move the selected file to the Trash (or Ctrl+H)"),
+    ("Ctrl+E", "Dismiss the error, or edit the selected note"),
+    ("Esc / Ctrl+C", "Quit"),
+];
+
+/// Text of the help overlay: the built-in bindings, then the pin hotkeys.
+pub fn help_text(keys: &KeysConfig, bindings: &[(Hotkey, CommandItem)]) -> String {
+    let mut lines: Vec<String> = KEY_BINDINGS
+        .iter()
+        .map(|(keys, description)| format!("{:<16}{}", keys, description))
+        .collect();
+    if let Some(copy) = keys.copy_key() {
+        lines.push(format!("{:<16}{}", copy.to_string(), "Copy the selected item's value"));
+    }
+    if let Some(join) = keys.join_key() {
+        lines.push(format!("{:<16}{}", join.to_string(), "Join the next meeting's call"));
+    }
+    if !bindings.is_empty() {
+        lines.push(String::new());
+        lines.push("Pinned items".to_string());
+        lines.extend(
+            bindings
+                .iter()
+                .map(|(hotkey, item)| format!("{:<16}{}", hotkey.to_string(), item.label)),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Workspaces in lookup order: the active one first, so its pins win a
+/// hotkey also bound in another workspace.
+fn ordered<'a>(workspaces: &'a BTreeMap<String, Workspace>, active: Option<&str>) -> Vec<&'a Workspace> {
+    let mut ordered: Vec<&Workspace> = active.and_then(|name| workspaces.get(name)).into_iter().collect();
+    ordered.extend(
+        workspaces
+            .iter()
+            .filter(|(name, _)| Some(name.as_str()) != active)
+            .map(|(_, workspace)| workspace),
+    );
+    ordered
+}
+
+/// Every pin with a valid hotkey, as `(hotkey, item)`, without the ones
+/// shadowed by an earlier binding.
+pub fn bindings(workspaces: &BTreeMap<String, Workspace>, active: Option<&str>) -> Vec<(Hotkey, CommandItem)> {
+    let mut bindings: Vec<(Hotkey, CommandItem)> = Vec::new();
+    for workspace in ordered(workspaces, active) {
+        for pin in &workspace.pins {
+            let Some(hotkey) = pin.hotkey.as_deref().and_then(Hotkey::parse) else {
+                continue;
+            };
+            if !bindings.iter().any(|(bound, _)| *bound == hotkey) {
+                bindings.push((hotkey, pin.to_item()));
+            }
+        }
+    }
+    bindings
+}
+
+/// The pinned item bound to `key`, from any workspace.
+pub fn pinned_item(workspaces: &BTreeMap<String, Workspace>, active: Option<&str>, key: &KeyEvent) -> Option<CommandItem> {
+    bindings(workspaces, active)
+        .into_iter()
+        .find(|(hotkey, _)| hotkey.matches(key))
+        .map(|(_, item)| item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+    use crate::workspace::Pin;
+
+    fn pin(label: &str, hotkey: &str) -> Pin {
+        Pin {
+            label: label.to_string(),
+            value: format!("https://example.com/{}", label),
+            handler: Handler::Url,
+            hotkey: Some(hotkey.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert_eq!(Hotkey::parse("F1").unwrap().to_string(), "F1");
+        assert_eq!(Hotkey::parse("alt+s").unwrap().to_string(), "Alt+S");
+        assert_eq!(Hotkey::parse("Ctrl + Alt + 1").unwrap().to_string(), "Ctrl+Alt+1");
+        assert_eq!(Hotkey::parse("s"), None);
+        assert_eq!(Hotkey::parse("ctrl+c"), None);
+        assert_eq!(Hotkey::parse("F13"), None);
+        assert_eq!(Hotkey::parse("hyper+x"), None);
+    }
+
+    #[test]
+    fn test_copy_key() {
+        let ctrl_y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert!(KeysConfig::default().copy_key().unwrap().matches(&ctrl_y));
+        let keys = KeysConfig {
+            copy: "alt+c".to_string(),
+            ..KeysConfig::default()
+        };
+        assert!(!keys.copy_key().unwrap().matches(&ctrl_y));
+        let keys = KeysConfig {
+            copy: "y".to_string(),
+            ..KeysConfig::default()
+        };
+        assert_eq!(keys.copy_key(), None);
+    }
+
+    #[test]
+    fn test_join_key() {
+        let alt_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::ALT);
+        assert!(KeysConfig::default().join_key().unwrap().matches(&alt_j));
+    }
+
+    #[test]
+    fn test_matches() {
+        let alt_s = Hotkey::parse("alt+s").unwrap();
+        assert!(alt_s.matches(&KeyEvent::new(KeyCode::Char('s'), KeyModifiers::ALT)));
+        assert!(alt_s.matches(&KeyEvent::new(KeyCode::Char('S'), KeyModifiers::ALT | KeyModifiers::SHIFT)));
+        assert!(!alt_s.matches(&KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)));
+        assert!(Hotkey::parse("f2").unwrap().matches(&KeyEvent::new(KeyCode::F(2), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_active_workspace_wins() {
+        let mut workspaces = BTreeMap::new();
+        workspaces.insert(
+            "home".to_string(),
+            Workspace {
+                pins: vec![pin("Recipes", "F1")],
+                ..Default::default()
+            },
+        );
+        workspaces.insert(
+            "work".to_string(),
+            Workspace {
+                pins: vec![pin("Standup notes", "F1"), pin("Board", "alt+b")],
+                ..Default::default()
+            },
+        );
+
+        let f1 = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+        assert_eq!(pinned_item(&workspaces, None, &f1).unwrap().label, "Recipes");
+        assert_eq!(pinned_item(&workspaces, Some("work"), &f1).unwrap().label, "Standup notes");
+        // Pins of other workspaces stay reachable
+        let alt_b = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::ALT);
+        assert_eq!(pinned_item(&workspaces, Some("home"), &alt_b).unwrap().label, "Board");
+        assert_eq!(bindings(&workspaces, Some("work")).len(), 2);
+
+        let help = help_text(&KeysConfig::default(), &bindings(&workspaces, Some("work")));
+        assert!(help.contains("Ctrl+Y          Copy"));
+        assert!(help.contains("F1              Standup notes"));
+        assert!(help.contains("Alt+B           Board"));
+        assert!(!help.contains("Recipes"));
+    }
+}
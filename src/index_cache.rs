@@ -0,0 +1,162 @@
+use crate::core::CommandItem;
+use crate::workspace::Source;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const INDEX_FILE: &str = "index.json";
+
+/// Modification time, in seconds, of each directory an app scan read.
+pub type Stamps = Vec<(PathBuf, u64)>;
+
+/// The application scan, reused while its directories are unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct AppScan {
+    stamps: Stamps,
+    extract_icons: bool,
+    items: Vec<CommandItem>,
+}
+
+/// The last items of each indexed source, shown at startup before the
+/// sources are read again.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Index {
+    sources: HashMap<Source, Vec<CommandItem>>,
+    apps: Option<AppScan>,
+}
+
+fn index_path() -> Result<PathBuf> {
+    let mut path = dirs::data_dir().context("Failed to get data directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(INDEX_FILE);
+    Ok(path)
+}
+
+fn read_index(path: &Path) -> Index {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn update_index(update: impl FnOnce(&mut Index)) -> Result<()> {
+    let path = index_path()?;
+    let mut index = read_index(&path);
+    update(&mut index);
+    // Write then rename, so a TUI starting meanwhile never reads half a file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string(&index)?)?;
+    fs::rename(temp, path)?;
+    Ok(())
+}
+
+/// Modification times of `dirs`, 0 for missing ones.
+pub fn stamps(dirs: &[PathBuf]) -> Stamps {
+    dirs.iter()
+        .map(|dir| {
+            let modified = fs::metadata(dir)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |age| age.as_secs());
+            (dir.clone(), modified)
+        })
+        .collect()
+}
+
+/// Items of every indexed source as last read, in `sources` order.
+pub fn load(sources: &[Source]) -> Vec<CommandItem> {
+    let Ok(path) = index_path() else {
+        return Vec::new();
+    };
+    let mut index = read_index(&path);
+    sources
+        .iter()
+        .filter_map(|source| index.sources.remove(source))
+        .flatten()
+        .collect()
+}
+
+/// Remember the items a source was read as.
+pub fn store_source(source: Source, items: &[CommandItem]) -> Result<()> {
+    update_index(|index| {
+        index.sources.insert(source, items.to_vec());
+    })
+}
+
+fn fresh_scan(scan: &AppScan, stamps: &Stamps, extract_icons: bool) -> bool {
+    // Without directories to watch there is no telling whether apps changed
+    !stamps.is_empty() && scan.stamps == *stamps && (scan.extract_icons || !extract_icons)
+}
+
+/// The cached app scan, when none of the scanned directories changed.
+pub fn cached_apps(stamps: &Stamps, extract_icons: bool) -> Option<Vec<CommandItem>> {
+    let scan = read_index(&index_path().ok()?).apps?;
+    fresh_scan(&scan, stamps, extract_icons).then_some(scan.items)
+}
+
+pub fn store_apps(stamps: Stamps, extract_icons: bool, items: &[CommandItem]) -> Result<()> {
+    update_index(|index| {
+        index.apps = Some(AppScan {
+            stamps,
+            extract_icons,
+            items: items.to_vec(),
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stamps_change_with_the_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let dirs = vec![temp_dir.path().to_path_buf(), temp_dir.path().join("missing")];
+        let before = stamps(&dirs);
+        assert_eq!(before[1].1, 0);
+        assert!(before[0].1 > 0);
+
+        let scan = AppScan {
+            stamps: before.clone(),
+            extract_icons: false,
+            items: Vec::new(),
+        };
+        assert!(fresh_scan(&scan, &before, false));
+        // A scan without icons does not do for a caller wanting them
+        assert!(!fresh_scan(&scan, &before, true));
+        let mut moved = before.clone();
+        moved[0].1 += 1;
+        assert!(!fresh_scan(&scan, &moved, false));
+        assert!(!fresh_scan(&AppScan::default(), &Vec::new(), false));
+    }
+
+    #[test]
+    #[serial]
+    fn test_store_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path());
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+
+        assert!(load(&[Source::Apps]).is_empty());
+
+        let app = CommandItem::new("Safari", Handler::App, "/Applications/Safari.app");
+        let bookmark = CommandItem::new("Docs", Handler::Url, "https://docs.rs");
+        store_source(Source::Bookmarks, std::slice::from_ref(&bookmark)).unwrap();
+        store_source(Source::Apps, std::slice::from_ref(&app)).unwrap();
+        assert_eq!(load(&[Source::Apps, Source::Bookmarks]), vec![app.clone(), bookmark]);
+
+        let stamps = vec![(PathBuf::from("/Applications"), 42)];
+        store_apps(stamps.clone(), true, std::slice::from_ref(&app)).unwrap();
+        assert_eq!(cached_apps(&stamps, false), Some(vec![app]));
+        assert_eq!(cached_apps(&vec![(PathBuf::from("/Applications"), 43)], false), None);
+    }
+}
@@ -1,30 +1,96 @@
 use crate::{
+    actions::{self, Action, Confirmation},
+    ai,
+    catalogs,
+    clipboard,
+    commands,
+    config,
     core::{CommandItem, Handler},
-    data_sources,
+    data_sources::{self, notes::NoteFolder},
+    events::{self, Event},
+    export::{self, ExportMenu},
     history,
-    state::AppState,
+    hotkeys,
+    internal::InternalCommand,
+    ocr,
+    search::{Prefetched, SearchCoordinator, SearchKind},
+    shell,
+    state::{AppState, NoteEditor, Overlay},
+    url_cleaner,
+    workspace::Source,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::mpsc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::path::{Path, PathBuf};
 
-// Global counter to track search generations and cancel old searches
-static SEARCH_GENERATION: AtomicU64 = AtomicU64::new(0);
-static WEB_SEARCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Where background work started from the keyboard sends its results.
+#[derive(Clone)]
+pub struct Channels {
+    pub fs_tx: mpsc::Sender<Vec<CommandItem>>,
+    pub web_tx: mpsc::Sender<Vec<CommandItem>>,
+    pub answer_tx: mpsc::Sender<Vec<CommandItem>>,
+    /// Sources to re-read, `None` for all of them
+    pub refresh_tx: mpsc::Sender<Option<Source>>,
+    pub overlay_tx: mpsc::Sender<Overlay>,
+    /// The AI's answer, or why there is none
+    pub thinking_tx: mpsc::Sender<Result<String, String>>,
+    /// A note and its text, fetched to be edited
+    pub note_tx: mpsc::Sender<(CommandItem, String)>,
+    /// Told when an item was launched, if the window should close then
+    pub launched_tx: Option<mpsc::Sender<()>>,
+}
+
+pub fn handle_key_event(key: KeyEvent, app_state: &mut AppState, channels: &Channels) -> bool {
+    let Channels { fs_tx, web_tx, answer_tx, refresh_tx, .. } = channels.clone();
+
+    // Any key dismisses an overlay
+    if app_state.overlay.is_some() {
+        app_state.overlay = None;
+        app_state.thinking = None;
+        return false;
+    }
+    if app_state.note_editor.is_some() {
+        handle_note_editor_key(key, app_state, channels);
+        return false;
+    }
+    if let Some(confirmation) = app_state.confirmation.take() {
+        if matches!(key.code, KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')) {
+            run_confirmed(app_state, confirmation, channels);
+        }
+        return false;
+    }
+    if app_state.action_menu.is_some() {
+        return handle_action_menu_key(key, app_state, channels);
+    }
+    if app_state.export_menu.is_some() {
+        return handle_export_menu_key(key, app_state);
+    }
+    // Pin hotkeys take precedence over the built-in keys
+    if let Some(item) = hotkeys::pinned_item(&app_state.workspaces, app_state.workspace.as_deref(), &key) {
+        launch_item(app_state, item, false, channels);
+        return false;
+    }
+    if config::get().keys.copy_key().is_some_and(|copy| copy.matches(&key)) {
+        copy_selected(app_state);
+        return false;
+    }
+    if config::get().keys.join_key().is_some_and(|join| join.matches(&key)) {
+        if let Some(item) = app_state.next_join_item() {
+            launch_item(app_state, item, false, channels);
+        }
+        return false;
+    }
 
-pub fn handle_key_event(
-    key: KeyEvent,
-    app_state: &mut AppState,
-    fs_tx: mpsc::Sender<Vec<CommandItem>>,
-    web_tx: mpsc::Sender<Vec<CommandItem>>,
-    refresh_tx: mpsc::Sender<()>,
-    error_tx: Option<mpsc::Sender<String>>,
-) -> bool {
     match key.code {
-        KeyCode::Esc => return true, // Signal to exit
+        // Esc leaves a folder before it closes Grinta
+        KeyCode::Esc => return !app_state.leave_container(),
         KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => return true,
         KeyCode::Char('e') if key.modifiers == KeyModifiers::CONTROL => {
-            app_state.clear_error();
+            if app_state.error_message.is_some() {
+                app_state.clear_error();
+            } else {
+                edit_selected_note(app_state, channels);
+            }
         }
         KeyCode::Char('n') if key.modifiers == KeyModifiers::CONTROL => {
             let query = app_state.query.lines().join("");
@@ -32,16 +98,20 @@ pub fn handle_key_event(
                 app_state.set_error("Cannot create note with empty query".to_string());
             } else {
                 app_state.clear_error();
-                let refresh_tx_clone = refresh_tx.clone();
-                tokio::spawn(async move {
-                    if let Ok(note_id) = data_sources::notes::create_note(&query, None).await {
-                        let _ = data_sources::notes::open_note(&note_id).await;
-                        refresh_tx_clone.try_send(()).ok();
-                    }
-                });
-                app_state.query.delete_line_by_end();
-                app_state.query.delete_line_by_head();
-                app_state.filter_items();
+                spawn_create_note(query, app_state.note_folder.clone(), channels);
+                app_state.clear_query();
+            }
+        }
+        KeyCode::Char('t') if key.modifiers == KeyModifiers::CONTROL => {
+            let query = app_state.query.lines().join("");
+            match data_sources::snippets::create(&query) {
+                Ok(_) => {
+                    app_state.clear_error();
+                    // Snippets are read along with the apps
+                    refresh_tx.try_send(Some(Source::Apps)).ok();
+                    app_state.clear_query();
+                }
+                Err(e) => app_state.set_error(format!("Failed to save snippet: {}", e)),
             }
         }
         KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => {
@@ -53,7 +123,7 @@ pub fn handle_key_event(
                     let refresh_tx_clone = refresh_tx.clone();
                     tokio::spawn(async move {
                         if data_sources::notes::delete_note(&note_value).await.is_ok() {
-                            refresh_tx_clone.try_send(()).ok();
+                            refresh_tx_clone.try_send(Some(Source::Notes)).ok();
                         }
                     });
                 }
@@ -65,33 +135,74 @@ pub fn handle_key_event(
                 }
             }
         }
+        KeyCode::Char('p') if key.modifiers == KeyModifiers::CONTROL => preview_selected(app_state, channels),
+        // Terminals without the keyboard protocol send Ctrl+H for Ctrl+Backspace
+        KeyCode::Backspace | KeyCode::Char('h') if key.modifiers == KeyModifiers::CONTROL => match app_state.get_selected_item() {
+            Some(item) if matches!(item.handler, Handler::File | Handler::Folder) => {
+                app_state.confirmation = Some(Confirmation {
+                    action: Action::MoveToTrash,
+                    item: item.clone(),
+                });
+            }
+            _ => app_state.set_error("Only files and folders can be moved to the Trash".to_string()),
+        },
         KeyCode::Tab => {
             let query = app_state.query.lines().join("");
-            data_sources::web_search::open_chat_gpt(&query);
-            return true;
+            let ai = &config::get().ai;
+            match ai.chat_url(&query) {
+                Ok(Some(url)) => match crate::commands::open_target(&url) {
+                    Ok(()) => return true,
+                    Err(e) => app_state.set_error(format!("Failed to open {}: {}", url, e)),
+                },
+                Ok(None) if query.trim().is_empty() => app_state.set_error("Type a question first".to_string()),
+                Ok(None) => ask_ollama(app_state, query, channels),
+                Err(e) => app_state.set_error(e.to_string()),
+            }
         }
         KeyCode::Enter => {
-            if let Some(item) = app_state.get_selected_item().cloned() {
-                let item_for_exec = item.clone();
-                let alt_modifier = key.modifiers == KeyModifiers::ALT;
-                tokio::spawn(async move {
-                    let _ = crate::commands::execute_command(&item_for_exec, alt_modifier).await;
-                });
-                let _ = history::add_to_history(&mut app_state.history, item);
-                app_state.query.delete_line_by_end();
-                app_state.query.delete_line_by_head();
+            let selected_internal = app_state
+                .get_selected_item()
+                .filter(|item| item.handler == Handler::Internal)
+                .and_then(|item| InternalCommand::parse(&item.value));
+            if let Some(command) = selected_internal {
+                app_state.clear_query();
+                match command {
+                    InternalCommand::Quit => return true,
+                    InternalCommand::ScreenOcr => spawn_screen_ocr(channels),
+                    InternalCommand::CreateNote(title) => {
+                        spawn_create_note(title, app_state.note_folder.clone(), channels)
+                    }
+                    InternalCommand::CreateReminder(text) => spawn_create_reminder(text, channels),
+                    InternalCommand::Refresh(target) => {
+                        if refresh_tx.try_send(target).is_err() {
+                            app_state.set_error("A refresh is already queued".to_string());
+                        }
+                    }
+                    InternalCommand::TrustCatalog(url) => match catalogs::trust(&url) {
+                        // Catalog items are read along with the apps, once fetched
+                        Ok(()) => {
+                            let refresh_tx_clone = refresh_tx.clone();
+                            tokio::spawn(async move {
+                                catalogs::refresh_catalogs().await;
+                                refresh_tx_clone.send(Some(Source::Apps)).await.ok();
+                            });
+                        }
+                        Err(e) => app_state.set_error(format!("Failed to trust catalog: {}", e)),
+                    },
+                    command => app_state.run_internal(command),
+                }
                 app_state.filter_items();
-                // Reset selection to first item
                 if !app_state.filtered_items.is_empty() {
                     app_state.table_state.select(Some(0));
                 }
+            } else if let Some(item) = app_state.get_selected_item().cloned() {
+                let alt_modifier = key.modifiers == KeyModifiers::ALT;
+                launch_item(app_state, item, alt_modifier, channels);
             } else {
                 let query = app_state.query.lines().join("");
                 if !query.is_empty() {
                     data_sources::web_search::search_web(&query);
-                    app_state.query.delete_line_by_end();
-                    app_state.query.delete_line_by_head();
-                    app_state.filter_items();
+                    app_state.clear_query();
                     // Reset selection to first item
                     if !app_state.filtered_items.is_empty() {
                         app_state.table_state.select(Some(0));
@@ -99,6 +210,19 @@ pub fn handle_key_event(
                 }
             }
         }
+        KeyCode::Char('k') if key.modifiers == KeyModifiers::CONTROL => {
+            app_state.open_action_menu();
+        }
+        KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => {
+            if app_state.filtered_items.is_empty() {
+                app_state.set_error("No results to export".to_string());
+            } else {
+                app_state.export_menu = Some(ExportMenu::default());
+            }
+        }
+        KeyCode::Right if cursor_at_end(app_state) && app_state.get_selected_item().is_some() => {
+            app_state.open_action_menu();
+        }
         KeyCode::Down => {
             if !app_state.filtered_items.is_empty() {
                 let i = match app_state.table_state.selected() {
@@ -126,77 +250,567 @@ pub fn handle_key_event(
         _ => {
             app_state.query.input(key);
             app_state.clear_error(); // Clear any errors when user starts typing
-            app_state.filter_items(); // Filter static items immediately
+            app_state.request_filter(); // Filter static items (immediately unless deferred by the profile)
+            events::publish(Event::QueryChanged(app_state.query.lines().join(" ")));
 
-            let query = app_state.query.lines().join("");
+            let (scope, scoped_query) = app_state.scoped_query();
+            let query = if scope.is_some() { scoped_query } else { app_state.query.lines().join("") };
+            let profile = app_state.profile;
+
+            let trimmed = query.trim();
+            if scope.is_none() && data_sources::wants_async_answer(trimmed) {
+                trigger_debounced_answer_lookup(&mut app_state.search, trimmed.to_string(), profile.web_debounce_ms, answer_tx);
+            } else if !app_state.answer_items.is_empty() {
+                app_state.search.cancel(SearchKind::Answers);
+                let _ = answer_tx.try_send(vec![]);
+            }
             
             // Only trigger searches for queries with 2+ characters
             if query.len() >= 2 {
-                trigger_debounced_fs_search(query.clone(), fs_tx, error_tx.clone());
-                trigger_debounced_web_search(query, web_tx);
+                if app_state.searches(Source::Files) {
+                    let roots = app_state.search_roots();
+                    if let Some(items) = prefetched_items(&app_state.search.prefetched, &query) {
+                        let _ = fs_tx.try_send(items);
+                    }
+                    trigger_debounced_fs_search(&mut app_state.search, query.clone(), roots, profile.fs_debounce_ms, fs_tx);
+                }
+                if app_state.searches(Source::Web) && profile.web_suggestions {
+                    trigger_debounced_web_search(&mut app_state.search, query, profile.web_debounce_ms, web_tx);
+                }
             } else {
+                // Searches still running for the longer query are stale
+                if query.trim().is_empty() {
+                    app_state.search.cancel_all();
+                } else {
+                    app_state.search.cancel(SearchKind::Files);
+                    app_state.search.cancel(SearchKind::Web);
+                }
                 // Clear items for short queries by sending empty vecs
                 let _ = fs_tx.try_send(vec![]);
                 let _ = web_tx.try_send(vec![]);
+                if scope.is_none() && query.chars().count() == 1 && app_state.searches(Source::Files) {
+                    prefetch_likely_file(app_state, &query, profile.fs_debounce_ms);
+                }
             }
         }
     }
     false // Do not exit
 }
 
-/// Trigger a debounced file system search that cancels previous searches
-fn trigger_debounced_fs_search(query: String, fs_tx: mpsc::Sender<Vec<CommandItem>>, error_tx: Option<mpsc::Sender<String>>) {
-    // Increment search generation to invalidate previous searches
-    let current_generation = SEARCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
-    
+/// Recognize text in a screen area the user selects, copy it, and show it
+/// in an overlay.
+fn spawn_screen_ocr(channels: &Channels) {
+    let overlay_tx = channels.overlay_tx.clone();
+    tokio::spawn(async move {
+        let copied = match ocr::capture_text().await {
+            Ok(text) => crate::clipboard::copy(&text).map(|_| text),
+            Err(e) => Err(e),
+        };
+        match copied {
+            Ok(text) => {
+                let overlay = Overlay {
+                    title: "Copied text from screen".to_string(),
+                    text,
+                };
+                let _ = overlay_tx.send(overlay).await;
+            }
+            Err(e) => {
+                events::publish(Event::ErrorRaised(e.to_string()));
+            }
+        }
+    });
+}
+
+/// Create a note and open it, then re-read the notes so it can be found.
+fn spawn_create_note(title: String, target: NoteFolder, channels: &Channels) {
+    let refresh_tx = channels.refresh_tx.clone();
+    tokio::spawn(async move {
+        let created = match data_sources::notes::create_note(&title, None, &target).await {
+            Ok(note_id) => data_sources::notes::open_note(&note_id).await,
+            Err(e) => Err(e),
+        };
+        match created {
+            Ok(()) => {
+                refresh_tx.try_send(Some(Source::Notes)).ok();
+            }
+            Err(e) => {
+                events::publish(Event::ErrorRaised(format!("Failed to create note: {}", e)));
+            }
+        }
+    });
+}
+
+/// Add a reminder, then re-read the reminders so it can be found.
+fn spawn_create_reminder(text: String, channels: &Channels) {
+    let refresh_tx = channels.refresh_tx.clone();
+    tokio::spawn(async move {
+        let Some(quick_add) = data_sources::reminders::parse_quick_add(&text, chrono::Local::now().naive_local()) else {
+            return;
+        };
+        match data_sources::reminders::create_reminder(&quick_add).await {
+            Ok(()) => {
+                refresh_tx.try_send(Some(Source::Reminders)).ok();
+            }
+            Err(e) => {
+                events::publish(Event::ErrorRaised(format!("Failed to add reminder: {}", e)));
+            }
+        }
+    });
+}
+
+/// Show the local model's answer in the overlay.
+fn ask_ollama(app_state: &mut AppState, query: String, channels: &Channels) {
+    let title = format!("🦙 {}", config::get().ai.model);
+    app_state.overlay = Some(Overlay {
+        title: title.clone(),
+        text: "Thinking…".to_string(),
+    });
+    app_state.thinking = Some(title);
+    let thinking_tx = channels.thinking_tx.clone();
+    tokio::spawn(async move {
+        let answer = ai::ask_ollama(&config::get().ai, &query).await.map_err(|e| e.to_string());
+        let _ = thinking_tx.send(answer).await;
+    });
+}
+
+fn cursor_at_end(app_state: &AppState) -> bool {
+    let (row, col) = app_state.query.cursor();
+    app_state
+        .query
+        .lines()
+        .get(row)
+        .is_none_or(|line| col >= line.chars().count())
+}
+
+/// Lines of a file shown by the preview overlay.
+#[cfg(not(target_os = "macos"))]
+const PREVIEW_LINES: usize = 200;
+
+/// Peek at the selected file or folder: with Quick Look on macOS, in an
+/// overlay elsewhere. A note's text is shown in the overlay.
+fn preview_selected(app_state: &mut AppState, channels: &Channels) {
+    let Some(item) = app_state
+        .get_selected_item()
+        .filter(|item| matches!(item.handler, Handler::File | Handler::Folder | Handler::Note))
+        .cloned()
+    else {
+        app_state.set_error("Only files, folders and notes can be previewed".to_string());
+        return;
+    };
+    if item.handler == Handler::Note {
+        let overlay_tx = channels.overlay_tx.clone();
+        tokio::spawn(async move {
+            match data_sources::notes::get_note_text(&item.value).await {
+                Ok(text) => {
+                    let _ = overlay_tx.send(Overlay { title: item.label, text }).await;
+                }
+                Err(e) => {
+                    events::publish(Event::ErrorRaised(format!("Cannot preview {}: {}", item.label, e)));
+                }
+            }
+        });
+        return;
+    }
+    #[cfg(target_os = "macos")]
+    if let Err(e) = actions::quick_look(&item.value) {
+        app_state.set_error(format!("Failed to open Quick Look: {}", e));
+    }
+    #[cfg(not(target_os = "macos"))]
+    match actions::text_preview(std::path::Path::new(&item.value), PREVIEW_LINES) {
+        Ok(text) => {
+            app_state.overlay = Some(Overlay {
+                title: item.label.clone(),
+                text,
+            });
+        }
+        Err(e) => app_state.set_error(format!("Cannot preview {}: {}", item.label, e)),
+    }
+}
+
+/// Fetch the selected note's text and open it in the editor.
+fn edit_selected_note(app_state: &mut AppState, channels: &Channels) {
+    let Some(note) = app_state
+        .get_selected_item()
+        .filter(|item| item.handler == Handler::Note)
+        .cloned()
+    else {
+        app_state.set_error("Only notes can be edited with Ctrl+E".to_string());
+        return;
+    };
+    let note_tx = channels.note_tx.clone();
     tokio::spawn(async move {
+        match data_sources::notes::get_editable_text(&note.value).await {
+            Ok(text) => {
+                let _ = note_tx.send((note, text)).await;
+            }
+            Err(e) => {
+                events::publish(Event::ErrorRaised(format!("Cannot edit {}: {}", note.label, e)));
+            }
+        }
+    });
+}
+
+/// Keys of the note editor: Ctrl+S saves the note, Esc discards the
+/// changes, anything else edits the text.
+fn handle_note_editor_key(key: KeyEvent, app_state: &mut AppState, channels: &Channels) {
+    match key.code {
+        KeyCode::Esc => app_state.note_editor = None,
+        KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => {
+            let Some(NoteEditor { note, text }) = app_state.note_editor.take() else {
+                return;
+            };
+            let text = text.into_lines().join("\n");
+            let refresh_tx = channels.refresh_tx.clone();
+            app_state.show_toast(format!("Saving {}", note.label));
+            tokio::spawn(async move {
+                match data_sources::notes::set_note_text(&note.value, &text).await {
+                    // The title may have changed
+                    Ok(()) => {
+                        refresh_tx.try_send(Some(Source::Notes)).ok();
+                    }
+                    Err(e) => {
+                        events::publish(Event::ErrorRaised(format!("Failed to save {}: {}", note.label, e)));
+                    }
+                }
+            });
+        }
+        _ => {
+            if let Some(editor) = &mut app_state.note_editor {
+                editor.text.input(key);
+            }
+        }
+    }
+}
+
+/// Copy the selected item's value (path, URL, note id, ...) and confirm
+/// it with a toast.
+fn copy_selected(app_state: &mut AppState) {
+    let Some(item) = app_state.get_selected_item().cloned() else {
+        app_state.set_error("No item selected to copy".to_string());
+        return;
+    };
+    let value = url_cleaner::url_of(&item);
+    match clipboard::copy(&value) {
+        Ok(()) if item.metadata.contains_key("masked") => app_state.show_toast("Copied".to_string()),
+        Ok(()) => app_state.show_toast(format!("Copied {}", value)),
+        Err(e) => app_state.set_error(format!("Failed to copy: {}", e)),
+    }
+}
+
+/// Execute an item, record it, and reset the query for the next search.
+fn launch_item(app_state: &mut AppState, item: CommandItem, alt_modifier: bool, channels: &Channels) {
+    if app_state.broken.contains(&item.value) {
+        app_state.set_error(format!(
+            "{} no longer exists; \"Clean up broken entries\" removes it from the history",
+            item.label
+        ));
+        return;
+    }
+    // Restarting or emptying the Trash can't be undone
+    if data_sources::system::needs_confirmation(&item) {
+        app_state.confirmation = Some(Confirmation { action: Action::Open, item });
+        return;
+    }
+    let item_for_exec = item.clone();
+    let overlay_tx = channels.overlay_tx.clone();
+    let launched_tx = channels.launched_tx.clone();
+    // Tasks open in a terminal as Alt+Enter does
+    let alt_modifier = alt_modifier || shell::runs_in_terminal(&item);
+    let env = app_state.workspace_env();
+    events::spawn_execution(commands::with_workspace_env(env, async move {
+        // Output shown in an overlay keeps the window open
+        let shows_output = item_for_exec.handler == Handler::Shell && !alt_modifier;
+        let result = match item_for_exec.handler {
+            // Show what the command printed instead of discarding it
+            Handler::Shell if !alt_modifier => match shell::run(&item_for_exec.value).await {
+                Ok(output) => {
+                    let overlay = Overlay {
+                        title: format!("$ {}", item_for_exec.value),
+                        text: if output.is_empty() { "(no output)".to_string() } else { output },
+                    };
+                    let _ = overlay_tx.send(overlay).await;
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+            _ => commands::execute_command(&item_for_exec, alt_modifier).await,
+        };
+        // Recorded before `--close-on-launch` is told to exit
+        events::record_execution(&item_for_exec, &result).await;
+        events::publish(Event::ItemExecuted {
+            item: item_for_exec,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+        match (result, launched_tx) {
+            (Err(e), _) => events::publish(Event::ErrorRaised(e.to_string())),
+            (Ok(()), Some(tx)) if !shows_output => {
+                let _ = tx.send(()).await;
+            }
+            _ => {}
+        }
+    }));
+    // Computed results are copied and process ids and meeting countdowns
+    // go stale, not worth remembering; snippets are. Typed shell commands
+    // would run again on Enter
+    let computed = matches!(item.handler, Handler::Copy | Handler::Transform | Handler::Process)
+        || item.metadata.contains_key("meeting")
+        || shell::is_typed(&item);
+    if !computed || item.metadata.contains_key("snippet") {
+        let _ = app_state.add_to_history(item);
+    }
+    app_state.clear_query();
+    // Reset selection to first item
+    if !app_state.filtered_items.is_empty() {
+        app_state.table_state.select(Some(0));
+    }
+}
+
+/// Run a confirmed destructive action. A trashed path is dropped from the
+/// file results once it is gone.
+fn run_confirmed(app_state: &mut AppState, confirmation: Confirmation, channels: &Channels) {
+    let Confirmation { action, item } = confirmation;
+    let remaining: Vec<CommandItem> = app_state
+        .fs_items
+        .iter()
+        .filter(|fs_item| fs_item.value != item.value)
+        .cloned()
+        .collect();
+    let fs_tx = channels.fs_tx.clone();
+    tokio::spawn(async move {
+        match actions::run(action, &item).await {
+            Err(e) => events::publish(Event::ErrorRaised(e.to_string())),
+            Ok(()) if action == Action::MoveToTrash => {
+                let _ = fs_tx.send(remaining).await;
+            }
+            Ok(()) => {}
+        }
+    });
+}
+
+/// Keys while the action menu is open: navigate, run or close it.
+fn handle_action_menu_key(key: KeyEvent, app_state: &mut AppState, channels: &Channels) -> bool {
+    let Some(menu) = app_state.action_menu.as_mut() else {
+        return false;
+    };
+    match key.code {
+        KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => return true,
+        KeyCode::Esc | KeyCode::Left => app_state.action_menu = None,
+        KeyCode::Char('k') if key.modifiers == KeyModifiers::CONTROL => app_state.action_menu = None,
+        KeyCode::Down => menu.next(),
+        KeyCode::Up => menu.previous(),
+        KeyCode::Enter => {
+            let action = menu.selected_action();
+            let item = menu.item.clone();
+            app_state.action_menu = None;
+            if action == Action::Open {
+                launch_item(app_state, item, false, channels);
+            } else if action == Action::MoveToTrash {
+                app_state.confirmation = Some(Confirmation { action, item });
+            } else if matches!(action, Action::ReadLater | Action::MarkRead | Action::Archive) {
+                match actions::update_reading_list(action, &item) {
+                    Ok(()) => {
+                        app_state.show_toast(format!("{}: {}", action.label(), item.label));
+                        app_state.filter_items();
+                    }
+                    Err(e) => app_state.set_error(e.to_string()),
+                }
+            } else {
+                let refresh_tx = channels.refresh_tx.clone();
+                let env = app_state.workspace_env();
+                tokio::spawn(commands::with_workspace_env(env, async move {
+                    match actions::run(action, &item).await {
+                        Err(e) => events::publish(Event::ErrorRaised(e.to_string())),
+                        // Show the new bookmark right away
+                        Ok(()) if action == Action::AddToChrome => {
+                            refresh_tx.send(Some(Source::Bookmarks)).await.ok();
+                        }
+                        Ok(()) => {}
+                    }
+                }));
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+fn handle_export_menu_key(key: KeyEvent, app_state: &mut AppState) -> bool {
+    let Some(menu) = app_state.export_menu.as_mut() else {
+        return false;
+    };
+    match key.code {
+        KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => return true,
+        KeyCode::Esc | KeyCode::Left => app_state.export_menu = None,
+        KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => app_state.export_menu = None,
+        KeyCode::Down => menu.next(),
+        KeyCode::Up => menu.previous(),
+        KeyCode::Enter => {
+            let (format, target) = menu.selected_option();
+            app_state.export_menu = None;
+            match export::export(&app_state.filtered_items, format, target) {
+                Ok(message) => {
+                    app_state.overlay = Some(Overlay {
+                        title: "Export".to_string(),
+                        text: message,
+                    })
+                }
+                Err(e) => app_state.set_error(format!("Export failed: {}", e)),
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Trigger a debounced file system search that cancels previous searches
+fn trigger_debounced_fs_search(
+    search: &mut SearchCoordinator,
+    query: String,
+    roots: Vec<PathBuf>,
+    debounce_ms: u64,
+    fs_tx: mpsc::Sender<Vec<CommandItem>>,
+) {
+    search.start(SearchKind::Files, |ticket| async move {
         // Debounce delay - wait for user to stop typing
-        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(debounce_ms)).await;
         
         // Check if this search is still the latest (not superseded by newer search)
-        if SEARCH_GENERATION.load(Ordering::SeqCst) != current_generation {
+        if !ticket.is_current() {
             return; // This search was superseded, abort
         }
         
+        // Show hits while the search is still running
+        let (updates_tx, mut updates_rx) = mpsc::channel(4);
+        let search = tokio::spawn(async move {
+            data_sources::fs::streaming_search_with_errors(&query, 8, &roots, &updates_tx).await
+        });
+        while let Some(items) = updates_rx.recv().await {
+            if !ticket.is_current() {
+                search.abort();
+                return;
+            }
+            let _ = fs_tx.send(items).await;
+        }
+
         // Perform the search with error handling
-        let items = match data_sources::fs::spotlight_search_with_errors(&query, 8).await {
+        let items = match search.await.unwrap_or_else(|e| Err(e.to_string())) {
             Ok(items) => items,
             Err(error_msg) => {
-                // Send error to UI error bar if channel is available
-                if let Some(ref tx) = error_tx {
-                    let _ = tx.send(error_msg).await;
-                }
+                // Shown in the error bar
+                events::publish(Event::ErrorRaised(error_msg));
                 vec![]
             }
         };
         
         // Double-check generation before sending results
-        if SEARCH_GENERATION.load(Ordering::SeqCst) == current_generation {
+        if ticket.is_current() {
             let _ = fs_tx.send(items).await;
         }
     });
 }
 
+/// When the user pauses after a single character that usually ends in
+/// a file they opened before, search for that file ahead of time so it
+/// shows up as soon as the query has two characters. Indexed sources are
+/// already in memory and need no warming.
+fn prefetch_likely_file(app_state: &mut AppState, prefix: &str, debounce_ms: u64) {
+    let Some(item) = history::likely_completion(&app_state.history, prefix) else {
+        return;
+    };
+    if Source::of(item) != Source::Files {
+        return;
+    }
+    let query = item.label.to_lowercase();
+    let prefetched = app_state.search.prefetched.clone();
+    if prefetched.lock().is_ok_and(|prefetched| prefetched.as_ref().is_some_and(|(q, _)| *q == query)) {
+        return;
+    }
+    let roots = app_state.search_roots();
+    // Runs alongside the search for the longer query, which would
+    // otherwise cancel it; only another prefetch or clearing the query does
+    app_state.search.start(SearchKind::Prefetch, |ticket| async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(debounce_ms)).await;
+        if !ticket.is_current() {
+            return;
+        }
+        if let Ok(items) = data_sources::fs::scoped_search_with_errors(&query, 8, &roots).await {
+            if let Ok(mut prefetched) = prefetched.lock() {
+                *prefetched = Some((query, items));
+            }
+        }
+    });
+}
+
+/// The prefetched files still matching `query`, shown until its own
+/// search finishes.
+fn prefetched_items(prefetched: &Prefetched, query: &str) -> Option<Vec<CommandItem>> {
+    let query = query.to_lowercase();
+    let prefetched = prefetched.lock().ok()?;
+    let (prefetched_query, items) = prefetched.as_ref()?;
+    if !prefetched_query.starts_with(&query) {
+        return None;
+    }
+    let items: Vec<CommandItem> = items
+        .iter()
+        .filter(|item| {
+            Path::new(&item.value)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.to_lowercase().starts_with(&query))
+        })
+        .cloned()
+        .collect();
+    (!items.is_empty()).then_some(items)
+}
+
 /// Trigger a debounced web search that cancels previous searches
-fn trigger_debounced_web_search(query: String, web_tx: mpsc::Sender<Vec<CommandItem>>) {
-    // Increment search generation to invalidate previous searches
-    let current_generation = WEB_SEARCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
-    
-    tokio::spawn(async move {
+fn trigger_debounced_web_search(
+    search: &mut SearchCoordinator,
+    query: String,
+    debounce_ms: u64,
+    web_tx: mpsc::Sender<Vec<CommandItem>>,
+) {
+    search.start(SearchKind::Web, |ticket| async move {
         // Debounce delay for web search (responsive but not too aggressive)
-        tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(debounce_ms)).await;
         
         // Check if this search is still the latest
-        if WEB_SEARCH_GENERATION.load(Ordering::SeqCst) != current_generation {
+        if !ticket.is_current() {
             return; // This search was superseded, abort
         }
         
         // Perform the web search
-        if let Ok(suggestions) = data_sources::web_search::get_web_search_suggestions(query).await {
+        if let Ok(mut suggestions) = data_sources::web_search::get_web_search_suggestions(query).await {
             // Double-check generation before sending results
-            if WEB_SEARCH_GENERATION.load(Ordering::SeqCst) == current_generation {
+            if !ticket.is_current() {
+                return;
+            }
+            let _ = web_tx.send(suggestions.clone()).await;
+            // The instant answer fills in the top suggestion once it comes
+            if data_sources::web_search::add_instant_answer(&mut suggestions).await && ticket.is_current() {
                 let _ = web_tx.send(suggestions).await;
             }
         }
     });
 }
+
+/// Trigger a debounced lookup for network answers (weather, ...) that
+/// cancels previous lookups
+fn trigger_debounced_answer_lookup(
+    search: &mut SearchCoordinator,
+    query: String,
+    debounce_ms: u64,
+    answer_tx: mpsc::Sender<Vec<CommandItem>>,
+) {
+    search.start(SearchKind::Answers, |ticket| async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(debounce_ms)).await;
+        if !ticket.is_current() {
+            return;
+        }
+
+        let items = data_sources::get_async_query_items(query).await;
+        if ticket.is_current() {
+            let _ = answer_tx.send(items).await;
+        }
+    });
+}
@@ -0,0 +1,226 @@
+use crate::core::{CommandItem, Handler};
+use crate::workspace::Source;
+
+const WORKSPACE_PREFIX: &str = "workspace:";
+const TRACK_START_PREFIX: &str = "track:start:";
+const TRACK_STOP: &str = "track:stop";
+const SCREEN_OCR: &str = "ocr:screen";
+const REFRESH_PREFIX: &str = "refresh:";
+const REFRESH_ALL: &str = "all";
+const TOGGLE_THEME: &str = "grinta:theme";
+const TOGGLE_PROFILE: &str = "grinta:mode";
+const OPEN_CONFIG: &str = "grinta:config";
+const CLEAR_HISTORY: &str = "grinta:clear-history";
+const TOGGLE_INCOGNITO: &str = "grinta:incognito";
+const SHOW_HELP: &str = "grinta:help";
+const CLEAN_UP_BROKEN: &str = "grinta:clean-up";
+const QUIT: &str = "grinta:quit";
+const TRUST_CATALOG_PREFIX: &str = "catalog:trust:";
+const SET_QUERY_PREFIX: &str = "query:";
+const CREATE_NOTE_PREFIX: &str = "note:create:";
+const SET_NOTE_FOLDER_PREFIX: &str = "note:folder:";
+const CREATE_REMINDER_PREFIX: &str = "reminder:create:";
+const ENTER_CONTAINER_PREFIX: &str = "container:";
+
+/// Commands that act on Grinta itself instead of launching something.
+/// They travel as `Handler::Internal` items whose value encodes the command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InternalCommand {
+    /// Switch to the named workspace, or leave the active one with `None`
+    SwitchWorkspace(Option<String>),
+    /// Start a time tracking timer with the given description
+    TrackStart(String),
+    /// Stop the running time tracking timer
+    TrackStop,
+    /// Copy the text recognized in a selected screen area
+    ScreenOcr,
+    /// Re-read one source's items, or every source with `None`
+    Refresh(Option<Source>),
+    /// Switch between the dark and light themes
+    ToggleTheme,
+    /// Switch between the default and low-bandwidth rendering profiles
+    ToggleProfile,
+    /// Open the config file, creating it when missing
+    OpenConfig,
+    /// Forget the active workspace's history
+    ClearHistory,
+    /// Drop the history entries whose app, file or note is gone
+    CleanUpBroken,
+    /// Stop (or resume) recording launched items in the history
+    ToggleIncognito,
+    /// Allow the remote catalog at the given URL to be loaded
+    TrustCatalog(String),
+    /// List the key bindings, including the pin hotkeys
+    ShowHelp,
+    /// Replace the query, as when completing `gh` into `!gh `
+    SetQuery(String),
+    /// Create a note with the given title and open it
+    CreateNote(String),
+    /// Create the next notes in the given `account/folder`
+    SetNoteFolder(String),
+    /// Add a reminder typed as `<title> [when]`
+    CreateReminder(String),
+    /// List only the items of a source's folder, given as its path
+    EnterContainer(Source, String),
+    Quit,
+}
+
+impl InternalCommand {
+    pub fn to_value(&self) -> String {
+        match self {
+            InternalCommand::SwitchWorkspace(name) => {
+                format!("{}{}", WORKSPACE_PREFIX, name.as_deref().unwrap_or(""))
+            }
+            InternalCommand::TrackStart(description) => {
+                format!("{}{}", TRACK_START_PREFIX, description)
+            }
+            InternalCommand::TrackStop => TRACK_STOP.to_string(),
+            InternalCommand::ScreenOcr => SCREEN_OCR.to_string(),
+            InternalCommand::Refresh(source) => format!(
+                "{}{}",
+                REFRESH_PREFIX,
+                source.map_or(REFRESH_ALL, |s| s.name())
+            ),
+            InternalCommand::ToggleTheme => TOGGLE_THEME.to_string(),
+            InternalCommand::ToggleProfile => TOGGLE_PROFILE.to_string(),
+            InternalCommand::OpenConfig => OPEN_CONFIG.to_string(),
+            InternalCommand::ClearHistory => CLEAR_HISTORY.to_string(),
+            InternalCommand::CleanUpBroken => CLEAN_UP_BROKEN.to_string(),
+            InternalCommand::ToggleIncognito => TOGGLE_INCOGNITO.to_string(),
+            InternalCommand::TrustCatalog(url) => format!("{}{}", TRUST_CATALOG_PREFIX, url),
+            InternalCommand::ShowHelp => SHOW_HELP.to_string(),
+            InternalCommand::SetQuery(query) => format!("{}{}", SET_QUERY_PREFIX, query),
+            InternalCommand::CreateNote(title) => format!("{}{}", CREATE_NOTE_PREFIX, title),
+            InternalCommand::SetNoteFolder(folder) => format!("{}{}", SET_NOTE_FOLDER_PREFIX, folder),
+            InternalCommand::CreateReminder(text) => format!("{}{}", CREATE_REMINDER_PREFIX, text),
+            InternalCommand::EnterContainer(source, path) => {
+                format!("{}{}:{}", ENTER_CONTAINER_PREFIX, source.name(), path)
+            }
+            InternalCommand::Quit => QUIT.to_string(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let command = match value {
+            TRACK_STOP => Some(InternalCommand::TrackStop),
+            SCREEN_OCR => Some(InternalCommand::ScreenOcr),
+            TOGGLE_THEME => Some(InternalCommand::ToggleTheme),
+            TOGGLE_PROFILE => Some(InternalCommand::ToggleProfile),
+            OPEN_CONFIG => Some(InternalCommand::OpenConfig),
+            CLEAR_HISTORY => Some(InternalCommand::ClearHistory),
+            CLEAN_UP_BROKEN => Some(InternalCommand::CleanUpBroken),
+            TOGGLE_INCOGNITO => Some(InternalCommand::ToggleIncognito),
+            SHOW_HELP => Some(InternalCommand::ShowHelp),
+            QUIT => Some(InternalCommand::Quit),
+            _ => None,
+        };
+        if command.is_some() {
+            return command;
+        }
+        if let Some(target) = value.strip_prefix(REFRESH_PREFIX) {
+            return match target {
+                REFRESH_ALL => Some(InternalCommand::Refresh(None)),
+                name => Source::from_name(name).map(|source| InternalCommand::Refresh(Some(source))),
+            };
+        }
+        if let Some(url) = value.strip_prefix(TRUST_CATALOG_PREFIX) {
+            return Some(InternalCommand::TrustCatalog(url.to_string()));
+        }
+        if let Some(query) = value.strip_prefix(SET_QUERY_PREFIX) {
+            return Some(InternalCommand::SetQuery(query.to_string()));
+        }
+        if let Some(title) = value.strip_prefix(CREATE_NOTE_PREFIX) {
+            return Some(InternalCommand::CreateNote(title.to_string()));
+        }
+        if let Some(folder) = value.strip_prefix(SET_NOTE_FOLDER_PREFIX) {
+            return Some(InternalCommand::SetNoteFolder(folder.to_string()));
+        }
+        if let Some(text) = value.strip_prefix(CREATE_REMINDER_PREFIX) {
+            return Some(InternalCommand::CreateReminder(text.to_string()));
+        }
+        if let Some(container) = value.strip_prefix(ENTER_CONTAINER_PREFIX) {
+            let (source, path) = container.split_once(':')?;
+            return Some(InternalCommand::EnterContainer(Source::from_name(source)?, path.to_string()));
+        }
+        if let Some(description) = value.strip_prefix(TRACK_START_PREFIX) {
+            return Some(InternalCommand::TrackStart(description.to_string()));
+        }
+        let name = value.strip_prefix(WORKSPACE_PREFIX)?;
+        Some(InternalCommand::SwitchWorkspace(
+            (!name.is_empty()).then(|| name.to_string()),
+        ))
+    }
+
+    pub fn to_item(&self, label: &str) -> CommandItem {
+        CommandItem::new(label, Handler::Internal, &self.to_value())
+    }
+}
+
+/// Searchable items refreshing each indexed source, and everything.
+pub fn refresh_items() -> Vec<CommandItem> {
+    let targets = [
+        (Some(Source::Apps), "Refresh apps"),
+        (Some(Source::Notes), "Reload notes"),
+        (Some(Source::Bookmarks), "Refresh bookmarks"),
+        (Some(Source::Shortcuts), "Refresh shortcuts"),
+        (Some(Source::Reminders), "Reload reminders"),
+        (None, "Refresh everything"),
+    ];
+    targets
+        .into_iter()
+        .map(|(source, label)| {
+            let mut item = InternalCommand::Refresh(source).to_item(label);
+            item.icon = "🔄".to_string();
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let commands = [
+            InternalCommand::SwitchWorkspace(Some("clientx".to_string())),
+            InternalCommand::SwitchWorkspace(None),
+            InternalCommand::TrackStart("writing report".to_string()),
+            InternalCommand::TrackStop,
+            InternalCommand::ScreenOcr,
+            InternalCommand::Refresh(Some(Source::Bookmarks)),
+            InternalCommand::Refresh(None),
+            InternalCommand::ToggleTheme,
+            InternalCommand::ToggleProfile,
+            InternalCommand::OpenConfig,
+            InternalCommand::ClearHistory,
+            InternalCommand::CleanUpBroken,
+            InternalCommand::ToggleIncognito,
+            InternalCommand::TrustCatalog("https://example.com/ops.toml".to_string()),
+            InternalCommand::ShowHelp,
+            InternalCommand::SetQuery("!gh ".to_string()),
+            InternalCommand::CreateNote("2024-03-18".to_string()),
+            InternalCommand::SetNoteFolder("iCloud/Work".to_string()),
+            InternalCommand::CreateReminder("buy milk tomorrow 5pm".to_string()),
+            InternalCommand::EnterContainer(Source::Bookmarks, "Bookmarks bar/Rust: async".to_string()),
+            InternalCommand::Quit,
+        ];
+        for command in commands {
+            assert_eq!(InternalCommand::parse(&command.to_value()), Some(command));
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown() {
+        assert!(InternalCommand::parse("reboot").is_none());
+        assert!(InternalCommand::parse("refresh:everything").is_none());
+        assert!(InternalCommand::parse("grinta:reboot").is_none());
+    }
+
+    #[test]
+    fn test_to_item() {
+        let item = InternalCommand::SwitchWorkspace(None).to_item("Leave workspace");
+        assert_eq!(item.handler, Handler::Internal);
+        assert_eq!(item.value, "workspace:");
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::process::Command;
+
+/// Secrets already read, so the keychain is asked once per service.
+static SECRETS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+async fn output(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+}
+
+/// The password of the `service` item in the login keychain.
+#[cfg(target_os = "macos")]
+async fn read(service: &str) -> Result<String> {
+    output("security", &["find-generic-password", "-s", service, "-w"]).await
+}
+
+/// The secret stored with `secret-tool store --label=... service <service>`.
+#[cfg(target_os = "linux")]
+async fn read(service: &str) -> Result<String> {
+    output("secret-tool", &["lookup", "service", service]).await
+}
+
+/// Other systems have no keychain command to read secrets with.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn read(_service: &str) -> Result<String> {
+    bail!("The keychain is only available on macOS and Linux")
+}
+
+/// A shell command printing the secret of `service`, for commands run in a
+/// terminal to read it themselves rather than have it typed out.
+#[cfg(target_os = "macos")]
+pub fn lookup_command(service: &str) -> Result<String> {
+    Ok(format!("security find-generic-password -s {} -w", crate::shell::quote(service)))
+}
+
+#[cfg(target_os = "linux")]
+pub fn lookup_command(service: &str) -> Result<String> {
+    Ok(format!("secret-tool lookup service {}", crate::shell::quote(service)))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn lookup_command(_service: &str) -> Result<String> {
+    bail!("The keychain is only available on macOS and Linux")
+}
+
+/// The secret stored for `service` in the system keychain.
+pub async fn secret(service: &str) -> Result<String> {
+    if let Some(secret) = SECRETS.lock().ok().and_then(|secrets| secrets.get(service).cloned()) {
+        return Ok(secret);
+    }
+    let secret = read(service)
+        .await
+        .with_context(|| format!("No secret for {} in the keychain", service))?;
+    if secret.is_empty() {
+        bail!("The keychain secret for {} is empty", service);
+    }
+    if let Ok(mut secrets) = SECRETS.lock() {
+        secrets.insert(service.to_string(), secret.clone());
+    }
+    Ok(secret)
+}
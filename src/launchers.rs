@@ -0,0 +1,160 @@
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::generators;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// Result schemas of other launcher frontends `grinta search` can print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LauncherFormat {
+    /// Wox and Flow Launcher JSON-RPC plugin results
+    Wox,
+    /// Albert external extension items
+    Albert,
+    /// PowerToys Run `Result` objects
+    Powertoys,
+}
+
+/// The item as the argument of `grinta run`, which executes it. A masked
+/// value, such as a generated password, would show in `ps` and the
+/// launcher's output: it is left out and generated again when run.
+pub fn run_argument(item: &CommandItem) -> String {
+    let mut item = item.clone();
+    if item.metadata.contains_key("masked") {
+        item.value.clear();
+    }
+    serde_json::to_string(&item).unwrap_or_default()
+}
+
+/// Parse the argument of `grinta run`.
+pub fn parse_run_argument(argument: &str) -> Result<CommandItem> {
+    let item: CommandItem =
+        serde_json::from_str(argument).context("Expected an item printed by `grinta search --format`")?;
+    if !item.metadata.contains_key("masked") || !item.value.is_empty() {
+        return Ok(item);
+    }
+    let generator = item.metadata.get("generator").context("The masked value was left out")?;
+    generators::generate(generator)?
+        .into_iter()
+        .next()
+        .with_context(|| format!("Nothing generated for {}", generator))
+}
+
+fn subtitle(item: &CommandItem) -> String {
+    if item.metadata.contains_key("masked") {
+        return item.handler.to_string().to_string();
+    }
+    format!("{} · {}", item.handler.to_string(), item.value)
+}
+
+/// An icon the frontend can load: the path of files and apps, whose icon
+/// the frontend extracts, or the desktop icon name.
+fn icon_path(item: &CommandItem) -> String {
+    if let Some(icon) = item.metadata.get("icon") {
+        return icon.clone();
+    }
+    match item.handler {
+        Handler::App | Handler::File | Handler::Folder => item.value.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Scores keeping grinta's order, best first.
+fn score(index: usize, count: usize) -> usize {
+    (count - index) * 10
+}
+
+/// Render the results, best first, for `format`. Every result runs
+/// `grinta run <item>` when picked.
+pub fn render(format: LauncherFormat, items: &[CommandItem]) -> Value {
+    let count = items.len();
+    match format {
+        LauncherFormat::Wox => json!({
+            "result": items.iter().enumerate().map(|(index, item)| json!({
+                "Title": item.label,
+                "SubTitle": subtitle(item),
+                "IcoPath": icon_path(item),
+                "Score": score(index, count),
+                "JsonRPCAction": { "method": "run", "parameters": [run_argument(item)] },
+            })).collect::<Vec<_>>()
+        }),
+        LauncherFormat::Albert => json!({
+            "items": items.iter().map(|item| json!({
+                "id": format!("{}:{}", item.handler.to_string(), item.value),
+                "name": item.label,
+                "description": subtitle(item),
+                "icon": icon_path(item),
+                "actions": [{ "name": "Open", "command": "grinta", "arguments": ["run", run_argument(item)] }],
+            })).collect::<Vec<_>>()
+        }),
+        LauncherFormat::Powertoys => json!(items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| json!({
+                "Title": item.label,
+                "SubTitle": subtitle(item),
+                "IcoPath": icon_path(item),
+                "Score": score(index, count),
+                "ContextData": run_argument(item),
+            }))
+            .collect::<Vec<_>>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<CommandItem> {
+        let mut password = CommandItem::new("Password", Handler::Copy, "hunter2");
+        password.metadata.insert("masked".to_string(), "true".to_string());
+        vec![CommandItem::new("report.pdf", Handler::File, "/tmp/report.pdf"), password]
+    }
+
+    #[test]
+    fn test_render_wox() {
+        let output = render(LauncherFormat::Wox, &items());
+        let results = output["result"].as_array().unwrap();
+        assert_eq!(results[0]["Title"], "report.pdf");
+        assert_eq!(results[0]["SubTitle"], "File · /tmp/report.pdf");
+        assert_eq!(results[0]["IcoPath"], "/tmp/report.pdf");
+        assert!(results[0]["Score"].as_u64() > results[1]["Score"].as_u64());
+        assert_eq!(results[1]["SubTitle"], "Copy");
+        assert_eq!(results[1]["JsonRPCAction"]["method"], "run");
+
+        let argument = results[0]["JsonRPCAction"]["parameters"][0].as_str().unwrap();
+        assert_eq!(parse_run_argument(argument).unwrap(), items()[0]);
+    }
+
+    #[test]
+    fn test_render_albert_and_powertoys() {
+        let albert = render(LauncherFormat::Albert, &items());
+        let action = &albert["items"][0]["actions"][0];
+        assert_eq!(action["command"], "grinta");
+        assert_eq!(action["arguments"][0], "run");
+        assert_eq!(albert["items"][0]["id"], "File:/tmp/report.pdf");
+
+        let powertoys = render(LauncherFormat::Powertoys, &items());
+        assert_eq!(powertoys[1]["Title"], "Password");
+        assert_eq!(parse_run_argument(powertoys[0]["ContextData"].as_str().unwrap()).unwrap(), items()[0]);
+        assert!(parse_run_argument("not json").is_err());
+    }
+
+    #[test]
+    fn test_masked_value_is_left_out() {
+        let password = generators::generator_items("password 16 hex").remove(0);
+        let argument = run_argument(&password);
+        assert!(!argument.contains(&password.value));
+
+        // Generated again when run
+        let item = parse_run_argument(&argument).unwrap();
+        assert_eq!(item.value.len(), 16);
+        assert_eq!(item.label, password.label);
+        assert_ne!(item.value, password.value);
+
+        // Without a generator there is nothing to run
+        let argument = run_argument(&items()[1]);
+        assert!(!argument.contains("hunter2"));
+        assert!(parse_run_argument(&argument).is_err());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod core;
+pub mod config;
+pub mod audit;
 pub mod history;
 pub mod state;
 pub mod data_sources;
@@ -6,4 +8,42 @@ pub mod commands;
 pub mod icons;
 pub mod ui;
 pub mod input;
-pub mod cli; 
\ No newline at end of file
+pub mod profile;
+pub mod cli;
+pub mod internal;
+pub mod workspace;
+pub mod time_tracking;
+pub mod clipboard;
+pub mod cache;
+pub mod actions;
+pub mod natural_date;
+pub mod transforms;
+pub mod daemon;
+pub mod rpc;
+pub mod plugins;
+pub mod ocr;
+pub mod palette;
+pub mod theme;
+pub mod matcher;
+pub mod export;
+pub mod shell;
+pub mod catalogs;
+pub mod send;
+pub mod window;
+pub mod ai;
+pub mod index_cache;
+pub mod launchers;
+pub mod pick;
+pub mod hotkeys;
+pub mod ranking;
+pub mod relative_time;
+pub mod osc;
+pub mod hooks;
+pub mod events;
+pub mod search;
+pub mod containers;
+pub mod appearance;
+pub mod keychain;
+pub mod upload;
+pub mod url_cleaner;
+pub mod reading_list;
@@ -1,110 +1,406 @@
+mod actions;
+mod ai;
+mod appearance;
+mod audit;
+mod cache;
+mod catalogs;
 mod cli;
+mod clipboard;
 mod commands;
+mod config;
+mod containers;
 mod core;
+mod daemon;
 mod data_sources;
+mod events;
+mod export;
 mod history;
+mod hooks;
+mod hotkeys;
 mod icons;
+mod index_cache;
 mod input;
+mod internal;
+mod keychain;
+mod launchers;
+mod matcher;
+mod natural_date;
+mod ocr;
+mod osc;
+mod palette;
+mod pick;
+mod plugins;
+mod profile;
+mod reading_list;
+mod ranking;
+mod relative_time;
+mod rpc;
+mod search;
+mod send;
+mod shell;
 mod state;
+mod theme;
+mod time_tracking;
+mod transforms;
 mod ui;
+mod upload;
+mod url_cleaner;
+mod window;
+mod workspace;
 
 use anyhow::Result;
 use clap::Parser;
-use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::event::{
+    self, DisableFocusChange, EnableFocusChange, Event, KeyEventKind, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+    LeaveAlternateScreen,
 };
 use crossterm::ExecutableCommand;
+use profile::Profile;
 use ratatui::prelude::*;
 use state::AppState;
 use std::io::stdout;
 use tokio::sync::mpsc;
+use workspace::Source;
+
+/// How often the history and pins are checked for apps, files and notes
+/// that are gone.
+const VALIDATION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// How long exiting waits for items still being executed and recorded.
+const EXIT_GRACE: std::time::Duration = std::time::Duration::from_secs(3);
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = cli::Cli::parse();
+    match config::load_config() {
+        Ok(config) => {
+            config::init(config);
+        }
+        Err(e) => eprintln!("{:#}", e),
+    }
     if let Some(search_command) = cli.search_command {
-        return cli::run_search_command(search_command).await;
+        let result = cli::run_command(search_command).await;
+        clipboard::release();
+        return result;
     }
 
     // TUI mode
     let (tx, mut rx) = mpsc::channel(1);
     let (fs_tx, mut fs_rx) = mpsc::channel(1);
     let (web_tx, mut web_rx) = mpsc::channel(1);
-    let (refresh_tx, mut refresh_rx) = mpsc::channel(1);
-    let (error_tx, mut error_rx) = mpsc::channel(1);
+    let (answer_tx, mut answer_rx) = mpsc::channel(1);
+    let (refresh_tx, mut refresh_rx) = mpsc::channel(8);
+    let (overlay_tx, mut overlay_rx) = mpsc::channel(1);
+    let (thinking_tx, mut thinking_rx) = mpsc::channel(1);
+    let (note_tx, mut note_rx) = mpsc::channel(1);
+    let (launched_tx, mut launched_rx) = mpsc::channel(1);
+    let mut events_rx = events::subscribe();
+    let channels = input::Channels {
+        fs_tx,
+        web_tx,
+        answer_tx,
+        refresh_tx,
+        overlay_tx,
+        thinking_tx,
+        note_tx,
+        launched_tx: cli.close_on_launch.then_some(launched_tx),
+    };
+
+    // Fetch catalogs and feeds in the background, at startup and while
+    // the window stays open
+    if !config::get().catalogs.is_empty() || !config::get().feeds.is_empty() {
+        let refresh_tx = channels.refresh_tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(catalogs::REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                // The apps are read from the cached copies meanwhile
+                let (catalogs_updated, feeds_updated) =
+                    tokio::join!(catalogs::refresh_catalogs(), data_sources::feeds::refresh_feeds());
+                if catalogs_updated || feeds_updated {
+                    refresh_tx.send(Some(Source::Apps)).await.ok();
+                }
+            }
+        });
+    }
+
+    // File search walks common folders while Spotlight can't answer
+    #[cfg(target_os = "macos")]
+    tokio::spawn(data_sources::fs::check_spotlight());
 
     let tx_clone = tx.clone();
     tokio::spawn(async move {
-        let items = data_sources::get_all_items(false).await;
-        tx_clone.send(items).await.ok();
+        let items = match daemon::request(daemon::Request::Items).await {
+            Some(items) => items,
+            None => data_sources::get_all_items(false).await,
+        };
+        tx_clone.send((None, items)).await.ok();
     });
 
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
-    stdout().execute(crossterm::terminal::SetTitle("Grinta"))?;
+    // The title follows the query and is put back on exit
+    osc::push_title()?;
+    stdout().execute(EnableFocusChange)?;
+    // Terminals with the keyboard protocol tell Ctrl+Backspace from Backspace
+    let enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced_keys {
+        stdout().execute(PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES))?;
+    }
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
     let history = history::load_history()?;
-    let initial_items = vec![];
+    // Show the items of the last run while the sources are read again
+    let initial_items = index_cache::load(&data_sources::INDEXED_SOURCES);
+    let cached = !initial_items.is_empty();
     let mut app_state = AppState::new(history, initial_items);
+    app_state.profile = Profile::detect(cli.remote, cli.low_power);
+    app_state.running_timer = time_tracking::running_entry().unwrap_or_default();
+    if let Some(cwd) = &cli.cwd {
+        // Tasks run from the folder wherever the terminal opens
+        let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.clone());
+        app_state.cwd_tasks = data_sources::tasks::dir_task_items(&cwd);
+    }
+    // Follow the system's dark or light appearance
+    let (theme_tx, mut theme_rx) = mpsc::channel(1);
+    if config::get().theme == theme::ThemeMode::Auto {
+        if let Some(theme) = appearance::detect().await {
+            app_state.theme = theme;
+        }
+        tokio::spawn(appearance::watch(app_state.theme, theme_tx));
+    }
+    // Pull requests and CI of the GitHub repositories, in the background
+    let (repo_status_tx, mut repo_status_rx) = mpsc::channel(1);
+    let (dirty_tx, mut dirty_rx) = mpsc::channel(1);
+    let repos_config = &config::get().repos;
+    if repos_config.github_status && !repos_config.roots.is_empty() {
+        tokio::spawn(data_sources::github::watch_repos(repo_status_tx));
+    }
+    // Keep the meetings about to start up to date
+    let (meeting_tx, mut meeting_rx) = mpsc::channel(1);
+    if config::get().calendar.is_enabled() {
+        tokio::spawn(data_sources::calendar::watch(meeting_tx));
+    }
+    if cached {
+        app_state.start_refresh(None);
+        app_state.filter_items();
+    }
+
+    app_state.validate_entries();
+    app_state.filter_items();
+
+    // Only redraw when something changed; ratatui diffs the buffer, but
+    // skipping idle frames keeps remote sessions quiet.
+    let mut needs_redraw = true;
+    let mut last_draw = std::time::Instant::now();
+    let mut last_validation = std::time::Instant::now();
+    let mut title = String::new();
+    // Background completions notify only while the window is unfocused
+    let mut focused = true;
 
     loop {
         let mut should_filter = false;
         
-        if let Ok(items) = rx.try_recv() {
-            app_state.items = items;
+        if let Ok((target, items)) = rx.try_recv() {
+            app_state.finish_refresh(target, items);
+            // Whether the repositories have changes is checked afterwards,
+            // so a slow one doesn't hold up the apps
+            let apps = matches!(target, None | Some(Source::Apps));
+            if apps && !config::get().repos.roots.is_empty() {
+                let dirty_tx = dirty_tx.clone();
+                tokio::spawn(async move {
+                    dirty_tx.send(data_sources::repos::dirty_repos().await).await.ok();
+                });
+            }
+            events::publish(events::Event::SourceRefreshed(target));
+            app_state.validate_entries();
+            last_validation = std::time::Instant::now();
             should_filter = true;
         }
 
+        // Apps, files and notes can go away while Grinta is open
+        if last_validation.elapsed() >= VALIDATION_INTERVAL {
+            should_filter |= app_state.validate_entries();
+            // A popup kept open follows the system into and out of low-power mode
+            if !cli.low_power {
+                let low_power = profile::is_low_power_mode();
+                if low_power != app_state.profile.low_power {
+                    app_state.profile = Profile::new(app_state.profile.remote, low_power);
+                }
+            }
+            last_validation = std::time::Instant::now();
+        }
+
         if let Ok(items) = fs_rx.try_recv() {
+            app_state.settle_results();
             app_state.fs_items = items;
             should_filter = true;
         }
 
         if let Ok(items) = web_rx.try_recv() {
+            app_state.settle_results();
             app_state.web_items = items;
             should_filter = true;
         }
 
-        if let Ok(error_msg) = error_rx.try_recv() {
-            app_state.set_error(error_msg);
+        if let Ok(items) = answer_rx.try_recv() {
+            app_state.settle_results();
+            app_state.answer_items = items;
+            should_filter = true;
+        }
+
+        while let Ok(event) = events_rx.try_recv() {
+            match event {
+                events::Event::ErrorRaised(error_msg) => {
+                    if !focused {
+                        osc::notify("Grinta", &error_msg).ok();
+                    }
+                    app_state.set_error(error_msg);
+                    needs_redraw = true;
+                }
+                events::Event::Notified(message) => {
+                    app_state.show_toast(message);
+                    needs_redraw = true;
+                }
+                events::Event::Progress { task, percent } => {
+                    match percent {
+                        Some(percent) => app_state.progress.insert(task, percent),
+                        None => app_state.progress.remove(&task),
+                    };
+                    needs_redraw = true;
+                }
+                _ => {}
+            }
+        }
+
+        if launched_rx.try_recv().is_ok() {
+            break;
+        }
+
+        if let Ok(theme) = theme_rx.try_recv() {
+            app_state.theme = theme;
+            needs_redraw = true;
+        }
+
+        if let Ok(dirty) = dirty_rx.try_recv() {
+            app_state.set_dirty_repos(dirty);
+            should_filter = true;
+        }
+
+        if let Ok(statuses) = repo_status_rx.try_recv() {
+            app_state.repo_status = statuses;
+            needs_redraw = true;
+        }
+
+        if let Ok(agenda) = meeting_rx.try_recv() {
+            // The status bar tells when focused
+            if !focused {
+                for meeting in &agenda.reminders {
+                    let now = chrono::Local::now().naive_local();
+                    let message = format!("{} {}", meeting.title, data_sources::calendar::starts_in(meeting, now));
+                    osc::notify("Grinta", &message).ok();
+                }
+            }
+            app_state.meetings = agenda.meetings;
+            app_state.do_not_disturb = agenda.do_not_disturb;
+            should_filter = true;
+        }
+
+        if let Ok(overlay) = overlay_rx.try_recv() {
+            if !focused {
+                osc::notify("Grinta", &overlay.title).ok();
+            }
+            app_state.overlay = Some(overlay);
+            needs_redraw = true;
+        }
+        
+        if let Ok(answer) = thinking_rx.try_recv() {
+            if !focused && app_state.thinking.is_some() {
+                osc::notify("Grinta", &config::get().ai.model).ok();
+            }
+            app_state.finish_thinking(answer);
+            needs_redraw = true;
+        }
+
+        if let Ok((note, text)) = note_rx.try_recv() {
+            app_state.note_editor = Some(state::NoteEditor::new(note, &text));
+            needs_redraw = true;
         }
         
         if should_filter {
             app_state.filter_items();
+            needs_redraw = true;
         }
 
-        if refresh_rx.try_recv().is_ok() {
+        if app_state.flush_pending_filter() {
+            needs_redraw = true;
+        }
+
+        if let Ok(target) = refresh_rx.try_recv() {
+            app_state.start_refresh(target);
+            needs_redraw = true;
             let tx_clone = tx.clone();
             tokio::spawn(async move {
-                let items = data_sources::get_all_items(false).await;
-                tx_clone.send(items).await.ok();
+                let items = match target {
+                    Some(source) => data_sources::get_source_items(source, false).await,
+                    None => match daemon::request(daemon::Request::Refresh).await {
+                        Some(items) => items,
+                        None => data_sources::get_all_items(false).await,
+                    },
+                };
+                tx_clone.send((target, items)).await.ok();
             });
         }
 
-        terminal.draw(|frame| ui::render(frame, &mut app_state))?;
+        // Keep the running timer in the status bar ticking
+        if app_state.running_timer.is_some() && last_draw.elapsed().as_secs() >= 1 {
+            needs_redraw = true;
+        }
+        if app_state.expire_toast() {
+            needs_redraw = true;
+        }
 
-        if event::poll(std::time::Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if input::handle_key_event(
-                        key,
-                        &mut app_state,
-                        fs_tx.clone(),
-                        web_tx.clone(),
-                        refresh_tx.clone(),
-                        Some(error_tx.clone()),
-                    ) {
+        if needs_redraw {
+            terminal.draw(|frame| ui::render(frame, &mut app_state))?;
+            let query = app_state.query.lines().join(" ");
+            let new_title = osc::window_title(&query, app_state.filtered_items.len());
+            if new_title != title {
+                stdout().execute(crossterm::terminal::SetTitle(&new_title))?;
+                title = new_title;
+            }
+            needs_redraw = false;
+            last_draw = std::time::Instant::now();
+        }
+
+        if event::poll(std::time::Duration::from_millis(app_state.profile.tick_ms))? {
+            let event = event::read()?;
+            needs_redraw = true;
+            match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if input::handle_key_event(key, &mut app_state, &channels) {
                         break;
                     }
                 }
+                Event::FocusGained => focused = true,
+                Event::FocusLost => focused = false,
+                _ => {}
             }
         }
     }
 
+    if enhanced_keys {
+        stdout().execute(PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
+    stdout().execute(DisableFocusChange)?;
     stdout().execute(LeaveAlternateScreen)?;
+    osc::pop_title()?;
+    events::wait_for_executions(EXIT_GRACE).await;
+    clipboard::release();
+    let _ = hooks::on_exit(&config::get().hooks);
     Ok(())
 }
@@ -0,0 +1,168 @@
+use crate::core::{CommandItem, Handler};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+
+/// Scores items against the query with nucleo, remembering which indexed
+/// items matched the last query so typing only rescores those.
+pub struct IncrementalMatcher {
+    matcher: Matcher,
+    buf: Vec<char>,
+    query: String,
+    generation: u64,
+    /// Indices of the matching items, with their score
+    matches: Vec<(usize, u32)>,
+}
+
+impl Default for IncrementalMatcher {
+    fn default() -> Self {
+        let mut config = Config::DEFAULT;
+        // Launcher queries are usually typed from the start of the name
+        config.prefer_prefix = true;
+        Self {
+            matcher: Matcher::new(config),
+            buf: Vec::new(),
+            query: String::new(),
+            generation: 0,
+            matches: Vec::new(),
+        }
+    }
+}
+
+/// Words of the query, each matched fuzzily and case-insensitively. Plain
+/// fuzzy atoms (no `!` negation or anchors) mean that extending the query
+/// can only ever narrow the matches.
+pub fn pattern(query: &str) -> Pattern {
+    Pattern::new(query, CaseMatching::Ignore, Normalization::Smart, AtomKind::Fuzzy)
+}
+
+/// The query's words as case-insensitive substrings, for long texts such
+/// as note bodies where fuzzy matches would be meaningless.
+pub fn body_pattern(query: &str) -> Pattern {
+    Pattern::new(query, CaseMatching::Ignore, Normalization::Smart, AtomKind::Substring)
+}
+
+impl IncrementalMatcher {
+    /// The best of the label, value and keyword scores, `None` when none
+    /// matches. Internal commands encode their value, so only the label
+    /// counts.
+    pub fn score(&mut self, pattern: &Pattern, item: &CommandItem) -> Option<u32> {
+        let label = pattern.score(Utf32Str::new(&item.label, &mut self.buf), &mut self.matcher);
+        if item.handler == Handler::Internal {
+            return label;
+        }
+        let value = pattern.score(Utf32Str::new(&item.value, &mut self.buf), &mut self.matcher);
+        let keywords = item
+            .metadata
+            .get("keywords")
+            .and_then(|keywords| pattern.score(Utf32Str::new(keywords, &mut self.buf), &mut self.matcher));
+        label.max(value).max(keywords)
+    }
+
+    /// Score of the item's `body` text, if any, halved so a match in the
+    /// title or path ranks first.
+    pub fn body_score(&mut self, pattern: &Pattern, item: &CommandItem) -> Option<u32> {
+        let body = item.metadata.get("body")?;
+        pattern
+            .score(Utf32Str::new(body, &mut self.buf), &mut self.matcher)
+            .map(|score| score / 2)
+    }
+
+    /// Indices and scores of the `items` matching `query`. `generation`
+    /// must change whenever `items` does; while it stays the same, an
+    /// unchanged query reuses the previous matches and a longer one only
+    /// rescores them.
+    pub fn match_items(&mut self, query: &str, items: &[&CommandItem], generation: u64) -> &[(usize, u32)] {
+        let same_items = generation == self.generation;
+        if same_items && query == self.query {
+            return &self.matches;
+        }
+
+        let pattern = pattern(query);
+        let body_pattern = body_pattern(query);
+        let candidates: Vec<usize> = if same_items && !self.query.is_empty() && query.starts_with(&self.query) {
+            self.matches.iter().map(|(index, _)| *index).collect()
+        } else {
+            (0..items.len()).collect()
+        };
+        let matches = candidates
+            .into_iter()
+            .filter_map(|index| {
+                let score = self.score(&pattern, items[index]);
+                let body_score = self.body_score(&body_pattern, items[index]);
+                Some((index, score.max(body_score)?))
+            })
+            .collect();
+
+        self.matches = matches;
+        self.query = query.to_string();
+        self.generation = generation;
+        &self.matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<CommandItem> {
+        vec![
+            CommandItem::new("Firefox", Handler::App, "/Applications/Firefox.app"),
+            CommandItem::new("Finder", Handler::App, "/System/Library/CoreServices/Finder.app"),
+            CommandItem::new("Notes", Handler::App, "/System/Applications/Notes.app"),
+        ]
+    }
+
+    #[test]
+    fn test_match_items_narrows() {
+        let items = items();
+        let refs: Vec<&CommandItem> = items.iter().collect();
+        let mut matcher = IncrementalMatcher::default();
+
+        let indices = |matches: &[(usize, u32)]| matches.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        assert_eq!(indices(matcher.match_items("fi", &refs, 1)), vec![0, 1]);
+        assert_eq!(indices(matcher.match_items("fire", &refs, 1)), vec![0]);
+        // Shortening the query rescores everything
+        assert_eq!(indices(matcher.match_items("f", &refs, 1)), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_new_generation_rescores() {
+        let items = items();
+        let refs: Vec<&CommandItem> = items.iter().collect();
+        let mut matcher = IncrementalMatcher::default();
+        assert!(matcher.match_items("notes", &refs[..2], 1).is_empty());
+        assert_eq!(matcher.match_items("notes", &refs, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_score_uses_label_and_value() {
+        let mut matcher = IncrementalMatcher::default();
+        let item = CommandItem::new("Firefox", Handler::App, "/Applications/Firefox.app");
+        assert!(matcher.score(&pattern("FIREFOX"), &item).is_some());
+        assert!(matcher.score(&pattern("applications"), &item).is_some());
+        assert!(matcher.score(&pattern("chrome"), &item).is_none());
+
+        let mut intranet = CommandItem::new("Intranet", Handler::Url, "https://intranet.example.com");
+        intranet.metadata.insert("keywords".to_string(), "wiki hr".to_string());
+        assert!(matcher.score(&pattern("wiki"), &intranet).is_some());
+    }
+
+    #[test]
+    fn test_match_items_searches_bodies() {
+        let mut groceries = CommandItem::new("Groceries (Notes)", Handler::Note, "x-coredata://1");
+        groceries.metadata.insert("body".to_string(), "Groceries\nOat milk, lemons and basil".to_string());
+        let mut lemon_tart = CommandItem::new("Lemon tart (Notes)", Handler::Note, "x-coredata://2");
+        lemon_tart.metadata.insert("body".to_string(), "Lemon tart\nZest two lemons".to_string());
+        let items = [groceries, lemon_tart];
+        let refs: Vec<&CommandItem> = items.iter().collect();
+        let mut matcher = IncrementalMatcher::default();
+
+        let matches = matcher.match_items("lemon", &refs, 1).to_vec();
+        assert_eq!(matches.len(), 2);
+        let score_of = |index: usize| matches.iter().find(|(i, _)| *i == index).unwrap().1;
+        // The title match ranks above the body match
+        assert!(score_of(1) > score_of(0));
+        // Bodies are matched by substring, not fuzzily
+        assert!(matcher.match_items("olb", &refs, 1).is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september",
+    "october", "november", "december",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+];
+
+/// Index of the name `word` abbreviates (at least three letters, so
+/// "month" is not mistaken for Monday).
+fn abbreviation_of(word: &str, names: &[&str]) -> Option<usize> {
+    let word = word.trim_end_matches(['.', ',']);
+    if word.len() < 3 {
+        return None;
+    }
+    names.iter().position(|name| name.starts_with(word))
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    abbreviation_of(word, &WEEKDAYS).and_then(|i| Weekday::try_from(i as u8).ok())
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    abbreviation_of(word, &MONTHS).map(|i| i as u32 + 1)
+}
+
+fn parse_number(word: &str) -> Option<i64> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        "five" => Some(5),
+        "six" => Some(6),
+        "seven" => Some(7),
+        "eight" => Some(8),
+        "nine" => Some(9),
+        "ten" => Some(10),
+        _ => word.parse().ok(),
+    }
+}
+
+/// Shift `date` by `amount` units (days, weeks, months or years).
+fn add_units(date: NaiveDate, amount: i64, unit: &str) -> Option<NaiveDate> {
+    let unit = unit.trim_end_matches('s');
+    match unit {
+        "day" => date.checked_add_signed(Duration::days(amount)),
+        "week" => date.checked_add_signed(Duration::weeks(amount)),
+        "month" | "year" => {
+            let months = if unit == "year" { amount * 12 } else { amount };
+            let shift = Months::new(months.unsigned_abs() as u32);
+            if months >= 0 {
+                date.checked_add_months(shift)
+            } else {
+                date.checked_sub_months(shift)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The first `weekday` strictly after `date`.
+fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() as i64 - date.weekday().num_days_from_monday() as i64 - 1) % 7 + 1;
+    date + Duration::days(days)
+}
+
+/// The last `weekday` strictly before `date`.
+fn previous_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + date.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64 - 1) % 7 + 1;
+    date - Duration::days(days)
+}
+
+/// `dec 25`, `25 december`, `dec 25 2026`. Without a year the next
+/// occurrence on or after `today` is used.
+fn parse_month_day(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    let (month, day, year) = match words {
+        [m, d] | [m, d, _] if parse_month(m).is_some() => (parse_month(m)?, *d, words.get(2)),
+        [d, m] | [d, m, _] if parse_month(m).is_some() => (parse_month(m)?, *d, words.get(2)),
+        _ => return None,
+    };
+    let day: u32 = day
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == ',')
+        .parse()
+        .ok()?;
+
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year.parse().ok()?, month, day),
+        None => {
+            let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if this_year >= today {
+                Some(this_year)
+            } else {
+                NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+            }
+        }
+    }
+}
+
+/// Parse a natural-language date relative to `today`: `today`, `tomorrow`,
+/// `friday`, `next friday`, `last monday`, `in 3 days`,
+/// `3 weeks from today`, `2 months ago`, `dec 25`, `2026-12-25`.
+pub fn parse_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let text = text.trim().to_lowercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["today"] | ["now"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        [day] => parse_weekday(day).map(|w| next_weekday(today, w)),
+        ["next" | "this", day] if parse_weekday(day).is_some() => {
+            Some(next_weekday(today, parse_weekday(day)?))
+        }
+        ["last", day] if parse_weekday(day).is_some() => {
+            Some(previous_weekday(today, parse_weekday(day)?))
+        }
+        ["next", unit] => add_units(today, 1, unit),
+        ["last", unit] => add_units(today, -1, unit),
+        ["in", amount, unit] => add_units(today, parse_number(amount)?, unit),
+        [amount, unit, "ago"] => add_units(today, -parse_number(amount)?, unit),
+        [amount, unit, "from" | "after", rest @ ..] => {
+            let base = parse_date(&rest.join(" "), today)?;
+            add_units(base, parse_number(amount)?, unit)
+        }
+        [amount, unit, "before", rest @ ..] => {
+            let base = parse_date(&rest.join(" "), today)?;
+            add_units(base, -parse_number(amount)?, unit)
+        }
+        _ => parse_month_day(&words, today),
+    }
+}
+
+/// Parse a time of day: `17:30`, `5pm`, `5:30 pm`, `noon`, `midnight`.
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    let text = text.trim().to_lowercase().replace(' ', "");
+    match text.as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (clock, offset) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (text.as_str(), None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parse `<date> at <time>`, `<date> <time>`, `<date>` (at 9:00) or a
+/// bare time (today, or tomorrow once that time has passed).
+pub fn parse_datetime(text: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let text = text.trim().to_lowercase();
+    let default_time = NaiveTime::from_hms_opt(9, 0, 0)?;
+
+    if let Some((date, time)) = text.rsplit_once(" at ") {
+        return Some(parse_date(date, now.date())?.and_time(parse_time(time)?));
+    }
+    if let Some(date) = parse_date(&text, now.date()) {
+        return Some(date.and_time(default_time));
+    }
+    if let Some((date, time)) = text.rsplit_once(' ') {
+        if let (Some(date), Some(time)) = (parse_date(date, now.date()), parse_time(time)) {
+            return Some(date.and_time(time));
+        }
+    }
+    let time = parse_time(&text)?;
+    let today = now.date().and_time(time);
+    Some(if today > now { today } else { today + Duration::days(1) })
+}
+
+/// `days until dec 25` / `days since jan 1`: the number of days between
+/// today and the date.
+pub fn parse_interval(text: &str, today: NaiveDate) -> Option<(i64, NaiveDate)> {
+    let text = text.trim().to_lowercase();
+    if let Some(rest) = text.strip_prefix("days until ").or_else(|| text.strip_prefix("days till ")) {
+        let date = parse_date(rest, today)?;
+        return Some(((date - today).num_days(), date));
+    }
+    if let Some(rest) = text.strip_prefix("days since ") {
+        // Without a year, "since" refers to the most recent occurrence
+        let mut date = parse_date(rest, today)?;
+        let has_year = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .any(|digits| digits.len() == 4);
+        if date > today && !has_year {
+            date = date.checked_sub_months(Months::new(12))?;
+        }
+        return Some(((today - date).num_days(), date));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 6).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn test_relative_days() {
+        assert_eq!(parse_date("today", today()), date(2026, 5, 6));
+        assert_eq!(parse_date("Tomorrow", today()), date(2026, 5, 7));
+        assert_eq!(parse_date("yesterday", today()), date(2026, 5, 5));
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(parse_date("friday", today()), date(2026, 5, 8));
+        assert_eq!(parse_date("next friday", today()), date(2026, 5, 8));
+        assert_eq!(parse_date("wednesday", today()), date(2026, 5, 13));
+        assert_eq!(parse_date("last monday", today()), date(2026, 5, 4));
+        assert_eq!(parse_date("next thurs", today()), date(2026, 5, 7));
+        assert_eq!(parse_date("last wednesday", today()), date(2026, 4, 29));
+        assert_eq!(parse_date("last thursday", today()), date(2026, 4, 30));
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(parse_date("in 3 days", today()), date(2026, 5, 9));
+        assert_eq!(parse_date("in eight days", today()), date(2026, 5, 14));
+        assert_eq!(parse_date("nine weeks ago", today()), date(2026, 3, 4));
+        assert_eq!(parse_date("3 weeks from today", today()), date(2026, 5, 27));
+        assert_eq!(parse_date("2 months ago", today()), date(2026, 3, 6));
+        assert_eq!(parse_date("a year from tomorrow", today()), date(2027, 5, 7));
+        assert_eq!(parse_date("next month", today()), date(2026, 6, 6));
+        assert_eq!(parse_date("10 days before dec 25", today()), date(2026, 12, 15));
+    }
+
+    #[test]
+    fn test_month_days() {
+        assert_eq!(parse_date("dec 25", today()), date(2026, 12, 25));
+        assert_eq!(parse_date("25 December", today()), date(2026, 12, 25));
+        assert_eq!(parse_date("jan 1", today()), date(2027, 1, 1));
+        assert_eq!(parse_date("march 3rd 2030", today()), date(2030, 3, 3));
+        assert_eq!(parse_date("2026-12-25", today()), date(2026, 12, 25));
+        assert_eq!(parse_date("feb 30", today()), None);
+        assert_eq!(parse_date("someday", today()), None);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("17:30"), NaiveTime::from_hms_opt(17, 30, 0));
+        assert_eq!(parse_time("5pm"), NaiveTime::from_hms_opt(17, 0, 0));
+        assert_eq!(parse_time("12 am"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(parse_time("9:15am"), NaiveTime::from_hms_opt(9, 15, 0));
+        assert_eq!(parse_time("noon"), NaiveTime::from_hms_opt(12, 0, 0));
+        assert_eq!(parse_time("13pm"), None);
+        assert_eq!(parse_time("5"), None);
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let now = today().and_hms_opt(18, 0, 0).unwrap();
+        assert_eq!(
+            parse_datetime("tomorrow at 5pm", now),
+            date(2026, 5, 7).unwrap().and_hms_opt(17, 0, 0)
+        );
+        assert_eq!(
+            parse_datetime("tomorrow 5pm", now),
+            date(2026, 5, 7).unwrap().and_hms_opt(17, 0, 0)
+        );
+        assert_eq!(parse_datetime("friday", now), date(2026, 5, 8).unwrap().and_hms_opt(9, 0, 0));
+        assert_eq!(parse_datetime("milk", now), None);
+        // A time that already passed today means tomorrow
+        assert_eq!(parse_datetime("5pm", now), date(2026, 5, 7).unwrap().and_hms_opt(17, 0, 0));
+        assert_eq!(parse_datetime("8pm", now), date(2026, 5, 6).unwrap().and_hms_opt(20, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("days until dec 25", today()), Some((233, date(2026, 12, 25).unwrap())));
+        assert_eq!(parse_interval("days since jan 1", today()), Some((125, date(2026, 1, 1).unwrap())));
+        assert_eq!(parse_interval("next friday", today()), None);
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+/// Recognizes the text in an image with the Vision framework, one line per
+/// observation. Run with `swift ocr.swift <image>`.
+#[cfg(target_os = "macos")]
+const OCR_SCRIPT: &str = r#"
+import AppKit
+import Foundation
+import Vision
+
+guard CommandLine.arguments.count > 1,
+      let image = NSImage(contentsOfFile: CommandLine.arguments[1]),
+      let cgImage = image.cgImage(forProposedRect: nil, context: nil, hints: nil) else {
+    FileHandle.standardError.write("Cannot read the screenshot\n".data(using: .utf8)!)
+    exit(1)
+}
+let request = VNRecognizeTextRequest()
+request.recognitionLevel = .accurate
+request.usesLanguageCorrection = true
+try VNImageRequestHandler(cgImage: cgImage, options: [:]).perform([request])
+let lines = (request.results ?? []).compactMap { $0.topCandidates(1).first?.string }
+print(lines.joined(separator: "\n"))
+"#;
+
+/// Let the user select a screen area and return the text recognized in it.
+#[cfg(target_os = "macos")]
+pub async fn capture_text() -> Result<String> {
+    use anyhow::Context;
+    use tokio::process::Command;
+
+    let temp_dir = std::env::temp_dir();
+    let screenshot = temp_dir.join(format!("grinta-ocr-{}.png", std::process::id()));
+    let script = temp_dir.join("grinta-ocr.swift");
+    tokio::fs::write(&script, OCR_SCRIPT).await?;
+
+    // Interactive selection without the shutter sound; Esc cancels and
+    // leaves no file behind
+    Command::new("screencapture")
+        .args(["-i", "-x"])
+        .arg(&screenshot)
+        .status()
+        .await
+        .context("Failed to run screencapture")?;
+    if !screenshot.exists() {
+        anyhow::bail!("Screen capture cancelled");
+    }
+
+    let output = Command::new("swift").arg(&script).arg(&screenshot).output().await;
+    let _ = tokio::fs::remove_file(&screenshot).await;
+    let output = output.context("Failed to run the Swift OCR helper")?;
+    if !output.status.success() {
+        anyhow::bail!("OCR failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        anyhow::bail!("No text found in the selected area");
+    }
+    Ok(text)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub async fn capture_text() -> Result<String> {
+    anyhow::bail!("Copying text from the screen is only supported on macOS")
+}
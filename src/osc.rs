@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+/// Longest query shown in the window title.
+const MAX_TITLE_QUERY: usize = 40;
+
+/// How the terminal shows a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notifications {
+    /// `OSC 9 ; body`: iTerm2, WezTerm, Ghostty and Windows Terminal
+    Osc9,
+    /// `OSC 777 ; notify ; title ; body`: VTE terminals, foot and urxvt
+    Osc777,
+}
+
+impl Notifications {
+    /// The protocol of the terminal Grinta runs in, if it has one.
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Self::from_env(
+            var("TERM_PROGRAM").as_deref(),
+            var("TERM").as_deref(),
+            var("VTE_VERSION").is_some(),
+            var("WT_SESSION").is_some(),
+        )
+    }
+
+    fn from_env(term_program: Option<&str>, term: Option<&str>, vte: bool, windows_terminal: bool) -> Option<Self> {
+        match term_program {
+            Some("iTerm.app" | "WezTerm" | "ghostty") => return Some(Notifications::Osc9),
+            _ if windows_terminal => return Some(Notifications::Osc9),
+            _ => {}
+        }
+        let term = term.unwrap_or_default();
+        if vte || term.starts_with("foot") || term.starts_with("rxvt") {
+            return Some(Notifications::Osc777);
+        }
+        None
+    }
+
+    /// The escape sequence showing `body` under `title`.
+    pub fn sequence(self, title: &str, body: &str) -> String {
+        let title = printable(title);
+        let body = printable(body);
+        match self {
+            Notifications::Osc9 => format!("\x1b]9;{}: {}\x07", title, body),
+            // Fields are separated by semicolons
+            Notifications::Osc777 => format!("\x1b]777;notify;{};{}\x07", title.replace(';', ","), body),
+        }
+    }
+}
+
+/// `text` without control characters, which would end the sequence.
+fn printable(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+/// `Grinta — 12 results for 'invoi'`, or just `Grinta` without a query.
+pub fn window_title(query: &str, results: usize) -> String {
+    let query = printable(query.trim());
+    if query.is_empty() {
+        return "Grinta".to_string();
+    }
+    let mut shown: String = query.chars().take(MAX_TITLE_QUERY).collect();
+    if shown.len() < query.len() {
+        shown.push('…');
+    }
+    let noun = if results == 1 { "result" } else { "results" };
+    format!("Grinta — {} {} for '{}'", results, noun, shown)
+}
+
+/// Save the terminal's title on its title stack (`XTWINOPS`), so
+/// [`pop_title`] can put it back.
+pub fn push_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[22;0t")?;
+    stdout.flush()
+}
+
+/// Restore the title saved by [`push_title`]. Terminals without a title
+/// stack ignore it.
+pub fn pop_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[23;0t")?;
+    stdout.flush()
+}
+
+/// Show a desktop notification, where the terminal supports one.
+pub fn notify(title: &str, body: &str) -> io::Result<()> {
+    let Some(notifications) = Notifications::detect() else {
+        return Ok(());
+    };
+    let mut stdout = io::stdout();
+    stdout.write_all(notifications.sequence(title, body).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_title() {
+        assert_eq!(window_title("", 40), "Grinta");
+        assert_eq!(window_title("invoi", 12), "Grinta — 12 results for 'invoi'");
+        assert_eq!(window_title("slack ", 1), "Grinta — 1 result for 'slack'");
+        let long = "a".repeat(50);
+        assert_eq!(window_title(&long, 0), format!("Grinta — 0 results for '{}…'", "a".repeat(40)));
+        // An escape in the query can't end the title early
+        assert_eq!(window_title("a\x07b", 0), "Grinta — 0 results for 'a b'");
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Notifications::from_env(Some("iTerm.app"), None, false, false), Some(Notifications::Osc9));
+        assert_eq!(Notifications::from_env(None, Some("xterm-256color"), false, true), Some(Notifications::Osc9));
+        assert_eq!(Notifications::from_env(None, Some("xterm-256color"), true, false), Some(Notifications::Osc777));
+        assert_eq!(Notifications::from_env(None, Some("foot"), false, false), Some(Notifications::Osc777));
+        assert_eq!(Notifications::from_env(Some("Apple_Terminal"), Some("xterm-256color"), false, false), None);
+    }
+
+    #[test]
+    fn test_sequence() {
+        assert_eq!(Notifications::Osc9.sequence("Grinta", "Done"), "\x1b]9;Grinta: Done\x07");
+        assert_eq!(
+            Notifications::Osc777.sequence("a;b", "line\nbreak"),
+            "\x1b]777;notify;a,b;line break\x07"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::core::CommandItem;
+use crate::internal::{self, InternalCommand};
+use crate::theme::Theme;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+const PALETTE_PREFIX: &str = "grinta:";
+
+/// Current settings, which decide how the toggles are labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub theme: Theme,
+    pub low_bandwidth: bool,
+    pub incognito: bool,
+}
+
+/// The filter of a `grinta:` query.
+pub fn parse_query(query: &str) -> Option<&str> {
+    query.strip_prefix(PALETTE_PREFIX).map(str::trim)
+}
+
+fn commands(settings: Settings) -> Vec<(InternalCommand, String, &'static str)> {
+    let theme = format!("Switch to {} theme", settings.theme.toggled().name());
+    let mode = if settings.low_bandwidth {
+        "Switch to normal mode"
+    } else {
+        "Switch to low-bandwidth mode"
+    };
+    let incognito = if settings.incognito {
+        "Turn incognito off"
+    } else {
+        "Turn incognito on (stop recording history)"
+    };
+    vec![
+        (InternalCommand::ToggleTheme, theme, "🎨"),
+        (InternalCommand::ToggleProfile, mode.to_string(), "📶"),
+        (InternalCommand::OpenConfig, "Open config file".to_string(), "⚙️"),
+        (InternalCommand::ClearHistory, "Clear history".to_string(), "🧹"),
+        (InternalCommand::CleanUpBroken, "Clean up broken entries".to_string(), "🩹"),
+        (InternalCommand::ToggleIncognito, incognito.to_string(), "🕶"),
+        (InternalCommand::SetQuery("note-folder: ".to_string()), "Choose the folder for new notes".to_string(), "🗂️"),
+        (InternalCommand::ShowHelp, "Show key bindings".to_string(), "⌨️"),
+        (InternalCommand::Quit, "Quit Grinta".to_string(), "🚪"),
+    ]
+}
+
+/// Every command acting on Grinta itself, narrowed down by `filter`.
+pub fn palette_items(filter: &str, settings: Settings) -> Vec<CommandItem> {
+    let mut items: Vec<CommandItem> = commands(settings)
+        .into_iter()
+        .map(|(command, label, icon)| {
+            let mut item = command.to_item(&label);
+            item.icon = icon.to_string();
+            item
+        })
+        .collect();
+    items.extend(internal::refresh_items());
+
+    if filter.is_empty() {
+        return items;
+    }
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, CommandItem)> = items
+        .into_iter()
+        .filter_map(|item| Some((matcher.fuzzy_match(&item.label, filter)?, item)))
+        .collect();
+    // Stable, so equally good matches keep the palette order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            theme: Theme::Dark,
+            low_bandwidth: false,
+            incognito: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("grinta:"), Some(""));
+        assert_eq!(parse_query("grinta: theme "), Some("theme"));
+        assert_eq!(parse_query("grinta"), None);
+    }
+
+    #[test]
+    fn test_palette_items() {
+        let items = palette_items("", settings());
+        assert_eq!(items[0].label, "Switch to light theme");
+        assert!(items.iter().any(|item| item.label == "Refresh everything"));
+
+        let quit = palette_items("quit", settings());
+        assert_eq!(quit.len(), 1);
+        assert_eq!(InternalCommand::parse(&quit[0].value), Some(InternalCommand::Quit));
+    }
+
+    #[test]
+    fn test_labels_follow_settings() {
+        let current = Settings {
+            theme: Theme::Light,
+            low_bandwidth: true,
+            incognito: true,
+        };
+        let labels: Vec<String> = palette_items("", current).into_iter().map(|item| item.label).collect();
+        assert!(labels.contains(&"Switch to dark theme".to_string()));
+        assert!(labels.contains(&"Switch to normal mode".to_string()));
+        assert!(labels.contains(&"Turn incognito off".to_string()));
+    }
+}
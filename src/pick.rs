@@ -0,0 +1,248 @@
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use ratatui::prelude::*;
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::{TerminalOptions, Viewport};
+use std::io::{self, stderr, Read};
+
+/// Options of `grinta pick`, named after dmenu's flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PickOptions {
+    /// Text left of the input (`-p`)
+    pub prompt: Option<String>,
+    /// Entries shown at once (`-l`)
+    pub lines: usize,
+    /// Match case-insensitively (`-i`); dmenu is case-sensitive by default
+    pub ignore_case: bool,
+}
+
+/// Indices of the `entries` matching `query`, best first. All entries, in
+/// input order, for an empty query.
+pub fn rank(entries: &[String], query: &str, ignore_case: bool) -> Vec<usize> {
+    if query.is_empty() {
+        return (0..entries.len()).collect();
+    }
+    let case = if ignore_case { CaseMatching::Ignore } else { CaseMatching::Respect };
+    let pattern = Pattern::new(query, case, Normalization::Smart, AtomKind::Fuzzy);
+    let mut config = Config::DEFAULT;
+    config.prefer_prefix = true;
+    let mut matcher = Matcher::new(config);
+    let mut buf = Vec::new();
+    let mut matches: Vec<(usize, u32)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| Some((index, pattern.score(Utf32Str::new(entry, &mut buf), &mut matcher)?)))
+        .collect();
+    // Stable, so equal scores keep the input order
+    matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(index, _)| index).collect()
+}
+
+/// What ended the picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Selected(String),
+    Cancelled,
+}
+
+struct Picker {
+    entries: Vec<String>,
+    ignore_case: bool,
+    query: String,
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl Picker {
+    fn new(entries: Vec<String>, ignore_case: bool) -> Self {
+        let matches = rank(&entries, "", ignore_case);
+        Self {
+            entries,
+            ignore_case,
+            query: String::new(),
+            matches,
+            selected: 0,
+        }
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = rank(&self.entries, &self.query, self.ignore_case);
+        self.selected = 0;
+    }
+
+    fn selected_entry(&self) -> Option<&String> {
+        self.matches.get(self.selected).map(|index| &self.entries[*index])
+    }
+
+    /// Apply a key press, returning the outcome once the picker is done.
+    /// Like dmenu, Enter prints the typed text when nothing matches, and
+    /// Tab completes the input with the selected entry.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(Outcome::Cancelled),
+            KeyCode::Char('c') if ctrl => return Some(Outcome::Cancelled),
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                return Some(Outcome::Selected(self.query.clone()));
+            }
+            KeyCode::Enter => {
+                let selection = self.selected_entry().cloned().unwrap_or_else(|| self.query.clone());
+                return Some(Outcome::Selected(selection));
+            }
+            KeyCode::Tab => {
+                if let Some(entry) = self.selected_entry().cloned() {
+                    self.query = entry;
+                    self.update_matches();
+                }
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Char('n') if ctrl => self.select_next(),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.update_matches();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.update_matches();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.update_matches();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn select_next(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, prompt: Option<&str>) {
+        let [input_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(frame.area());
+        let mut input = Vec::new();
+        if let Some(prompt) = prompt {
+            input.push(Span::styled(format!("{} ", prompt), Style::default().add_modifier(Modifier::BOLD)));
+        }
+        input.push(Span::raw(self.query.as_str()));
+        frame.render_widget(Paragraph::new(Line::from(input)), input_area);
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|index| ListItem::new(self.entries[*index].as_str()))
+            .collect();
+        let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+    }
+}
+
+/// Let the user pick one of the lines read from stdin, dmenu style. The
+/// picker draws on stderr below the cursor, so the selection printed on
+/// stdout can be captured by the calling script.
+pub fn run(options: &PickOptions) -> Result<Outcome> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let entries: Vec<String> = input.lines().map(str::to_string).collect();
+    let mut picker = Picker::new(entries, options.ignore_case);
+
+    // Keys are read from the terminal even though stdin is a pipe
+    enable_raw_mode()?;
+    let height = u16::try_from(options.lines.max(1)).unwrap_or(u16::MAX).saturating_add(1);
+    let terminal = Terminal::with_options(
+        CrosstermBackend::new(stderr()),
+        TerminalOptions {
+            viewport: Viewport::Inline(height),
+        },
+    );
+    let mut terminal = match terminal {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            disable_raw_mode()?;
+            return Err(e.into());
+        }
+    };
+
+    let outcome = loop {
+        if let Err(e) = terminal.draw(|frame| picker.render(frame, options.prompt.as_deref())) {
+            break Err(e.into());
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                if let Some(outcome) = picker.handle_key(key) {
+                    break Ok(outcome);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+
+    terminal.clear()?;
+    disable_raw_mode()?;
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<String> {
+        ["firefox", "Files", "thunderbird", "foot"].map(str::to_string).to_vec()
+    }
+
+    fn press(picker: &mut Picker, code: KeyCode) -> Option<Outcome> {
+        picker.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_rank_respects_case() {
+        let entries = entries();
+        assert_eq!(rank(&entries, "", false), vec![0, 1, 2, 3]);
+        assert_eq!(rank(&entries, "F", false), vec![1]);
+        let ignoring = rank(&entries, "F", true);
+        assert_eq!(ignoring.len(), 3);
+        assert!(!ignoring.contains(&2));
+    }
+
+    #[test]
+    fn test_picker_selects() {
+        let mut picker = Picker::new(entries(), true);
+        for c in "fo".chars() {
+            assert_eq!(press(&mut picker, KeyCode::Char(c)), None);
+        }
+        assert_eq!(picker.selected_entry().map(String::as_str), Some("foot"));
+        press(&mut picker, KeyCode::Down);
+        press(&mut picker, KeyCode::Down);
+        let selected = picker.selected_entry().cloned().unwrap();
+        assert_eq!(press(&mut picker, KeyCode::Enter), Some(Outcome::Selected(selected)));
+    }
+
+    #[test]
+    fn test_picker_prints_input_without_match() {
+        let mut picker = Picker::new(entries(), false);
+        for c in "xyz".chars() {
+            press(&mut picker, KeyCode::Char(c));
+        }
+        assert_eq!(press(&mut picker, KeyCode::Enter), Some(Outcome::Selected("xyz".to_string())));
+        assert_eq!(press(&mut picker, KeyCode::Esc), Some(Outcome::Cancelled));
+    }
+
+    #[test]
+    fn test_picker_tab_completes() {
+        let mut picker = Picker::new(entries(), false);
+        press(&mut picker, KeyCode::Char('t'));
+        press(&mut picker, KeyCode::Char('h'));
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(picker.query, "thunderbird");
+        assert_eq!(picker.matches, vec![2]);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::commands;
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+/// How long running the picked item may take.
+const EXECUTE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A script filter: an executable run with the query as its argument that
+/// prints one `CommandItem` as JSON per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    /// File name in the plugins directory, or a path
+    pub command: String,
+    /// Only run for queries starting with this word, which is stripped
+    pub prefix: String,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+/// Directory plugin commands are looked up in.
+pub fn plugins_dir() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("plugins"))
+}
+
+impl PluginConfig {
+    fn executable(&self) -> Result<PathBuf> {
+        if self.command.contains('/') {
+            Ok(config::expand_tilde(&self.command))
+        } else {
+            Ok(plugins_dir()?.join(&self.command))
+        }
+    }
+
+    /// The argument to pass for `query`, if this plugin handles it.
+    pub fn argument<'a>(&self, query: &'a str) -> Option<&'a str> {
+        let rest = query.strip_prefix(self.prefix.as_str())?;
+        if rest.is_empty() {
+            Some(rest)
+        } else {
+            rest.strip_prefix(' ').map(str::trim)
+        }
+    }
+}
+
+/// Whether any configured plugin handles `query`.
+pub fn wants_query(query: &str) -> bool {
+    config::get().plugins.iter().any(|plugin| plugin.argument(query).is_some())
+}
+
+/// Turn plugin output into items; lines that are not items are skipped.
+/// Whatever their handler, the items go back to the plugin on Enter.
+pub fn parse_output(plugin: &str, output: &str) -> Vec<CommandItem> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CommandItem>(line).ok())
+        .map(|mut item| {
+            item.handler = Handler::Plugin;
+            item.ran_at = None;
+            item.launch_count = 0;
+            item.metadata.insert("plugin".to_string(), plugin.to_string());
+            item
+        })
+        .collect()
+}
+
+async fn run_filter(plugin: &PluginConfig, argument: &str) -> Result<Vec<CommandItem>> {
+    let output = Command::new(plugin.executable()?)
+        .arg(argument)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(Duration::from_millis(plugin.timeout_ms), output)
+        .await
+        .with_context(|| format!("Plugin {} timed out", plugin.name))??;
+    Ok(parse_output(&plugin.name, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Items from every plugin handling `query`, in config order. Plugins that
+/// fail or time out contribute nothing.
+pub async fn get_plugin_items(query: &str) -> Vec<CommandItem> {
+    let handles: Vec<_> = config::get()
+        .plugins
+        .iter()
+        .filter_map(|plugin| {
+            let argument = plugin.argument(query)?.to_string();
+            Some(tokio::spawn(async move { run_filter(plugin, &argument).await }))
+        })
+        .collect();
+
+    let mut items = Vec::new();
+    for handle in handles {
+        if let Ok(Ok(plugin_items)) = handle.await {
+            items.extend(plugin_items);
+        }
+    }
+    items
+}
+
+/// Run the plugin that produced `item` with `--execute <value>`.
+pub async fn execute(item: &CommandItem) -> Result<()> {
+    let name = item.metadata.get("plugin").context("Item has no plugin")?;
+    let plugin = config::get()
+        .plugins
+        .iter()
+        .find(|plugin| &plugin.name == name)
+        .with_context(|| format!("Plugin {} is not configured", name))?;
+    let status = Command::new(plugin.executable()?)
+        .args(["--execute", &item.value])
+        .envs(commands::workspace_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .status();
+    let status = tokio::time::timeout(EXECUTE_TIMEOUT, status)
+        .await
+        .with_context(|| format!("Plugin {} timed out", plugin.name))??;
+    if !status.success() {
+        anyhow::bail!("Plugin {} failed ({})", plugin.name, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(prefix: &str) -> PluginConfig {
+        PluginConfig {
+            name: "github".to_string(),
+            command: "gh-search".to_string(),
+            prefix: prefix.to_string(),
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        }
+    }
+
+    #[test]
+    fn test_argument() {
+        let prefixed = plugin("gh");
+        assert_eq!(prefixed.argument("gh grinta"), Some("grinta"));
+        assert_eq!(prefixed.argument("gh"), Some(""));
+        assert_eq!(prefixed.argument("ghost"), None);
+        assert_eq!(prefixed.argument("firefox"), None);
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let output = "{\"label\": \"getgrinta/grinta\", \"handler\": \"Url\", \"value\": \"https://github.com/getgrinta/grinta\", \"icon\": \"🐙\"}\n\
+            debug output\n\
+            {\"label\": \"No value\", \"handler\": \"Plugin\", \"icon\": \"\"}\n\
+            {\"label\": \"Readme\", \"handler\": \"Plugin\", \"value\": \"readme\", \"icon\": \"📄\", \"metadata\": {\"preview\": \"# Grinta\"}}\n";
+        let items = parse_output("github", output);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].handler, Handler::Plugin);
+        assert_eq!(items[0].icon, "🐙");
+        assert_eq!(items[0].metadata["plugin"], "github");
+        assert_eq!(items[1].metadata["preview"], "# Grinta");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_filter() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let script = temp_dir.path().join("echo-plugin");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho \"{\\\"label\\\": \\\"Result for $1\\\", \\\"handler\\\": \\\"Plugin\\\", \\\"value\\\": \\\"$1\\\", \\\"icon\\\": \\\"🧩\\\"}\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut config = plugin("echo");
+        config.command = script.to_string_lossy().into_owned();
+        let items = run_filter(&config, "hello").await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Result for hello");
+        assert_eq!(items[0].value, "hello");
+    }
+}
@@ -0,0 +1,188 @@
+use std::env;
+
+/// Rendering and search tunables for the TUI.
+///
+/// The default profile favours responsiveness on a local terminal. The remote
+/// profile trades some of that for fewer redraws and less output, which keeps
+/// Grinta usable over high-latency links such as SSH. On battery in low-power
+/// mode either of them is slowed down further to save energy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Debounce before running a file system search
+    pub fs_debounce_ms: u64,
+    /// Debounce before fetching web suggestions
+    pub web_debounce_ms: u64,
+    /// Delay before re-filtering after a keystroke (0 = filter immediately)
+    pub filter_debounce_ms: u64,
+    /// Whether the icon column is rendered
+    pub show_icons: bool,
+    /// How long the event loop waits for input before checking channels
+    pub tick_ms: u64,
+    /// Whether web suggestions are fetched while typing
+    pub web_suggestions: bool,
+    /// Tuned for high-latency terminals
+    pub remote: bool,
+    /// Slowed down for low-power mode
+    pub low_power: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            fs_debounce_ms: 200,
+            web_debounce_ms: 250,
+            filter_debounce_ms: 0,
+            show_icons: true,
+            tick_ms: 50,
+            web_suggestions: true,
+            remote: false,
+            low_power: false,
+        }
+    }
+}
+
+impl Profile {
+    /// Profile for high-latency terminals: larger debounces, no per-keystroke
+    /// table rebuild and no icon column.
+    pub fn remote() -> Self {
+        Self {
+            fs_debounce_ms: 400,
+            web_debounce_ms: 500,
+            filter_debounce_ms: 150,
+            show_icons: false,
+            tick_ms: 100,
+            web_suggestions: true,
+            remote: true,
+            low_power: false,
+        }
+    }
+
+    /// The remote or default profile, slowed down for low-power mode if
+    /// `low_power`.
+    pub fn new(remote: bool, low_power: bool) -> Self {
+        let profile = if remote { Self::remote() } else { Self::default() };
+        if low_power {
+            profile.with_low_power()
+        } else {
+            profile
+        }
+    }
+
+    /// This profile with longer debounces, no web suggestions and fewer
+    /// wake-ups of the event loop, which also redraws less often.
+    pub fn with_low_power(self) -> Self {
+        Self {
+            fs_debounce_ms: self.fs_debounce_ms.max(500),
+            web_debounce_ms: self.web_debounce_ms.max(800),
+            filter_debounce_ms: self.filter_debounce_ms.max(100),
+            tick_ms: self.tick_ms.max(250),
+            web_suggestions: false,
+            low_power: true,
+            ..self
+        }
+    }
+
+    /// Pick the remote profile when requested explicitly or when running
+    /// inside an SSH session, the default profile otherwise, in low-power
+    /// mode when requested or when the system is.
+    pub fn detect(remote_flag: bool, low_power_flag: bool) -> Self {
+        Self::new(remote_flag || is_ssh_session(), low_power_flag || is_low_power_mode())
+    }
+}
+
+/// Check the environment variables sshd sets for interactive sessions.
+pub fn is_ssh_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|var| env::var(var).map(|v| !v.is_empty()).unwrap_or(false))
+}
+
+/// Whether the system saves energy: macOS' Low Power Mode, or the
+/// `low-power` ACPI platform profile on Linux.
+#[cfg(target_os = "macos")]
+pub fn is_low_power_mode() -> bool {
+    std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .map(|output| parse_pmset(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_low_power_mode() -> bool {
+    std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+        .map(|profile| profile.trim() == "low-power")
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn is_low_power_mode() -> bool {
+    false
+}
+
+/// The `lowpowermode` setting in `pmset -g` output.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> bool {
+    output.lines().any(|line| {
+        let mut fields = line.split_whitespace();
+        fields.next() == Some("lowpowermode") && fields.next() == Some("1")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile() {
+        let profile = Profile::default();
+        assert_eq!(profile.fs_debounce_ms, 200);
+        assert_eq!(profile.web_debounce_ms, 250);
+        assert_eq!(profile.filter_debounce_ms, 0);
+        assert!(profile.show_icons);
+        assert!(profile.web_suggestions);
+        assert!(!profile.low_power);
+    }
+
+    #[test]
+    fn test_remote_profile_is_less_chatty() {
+        let local = Profile::default();
+        let remote = Profile::remote();
+
+        assert!(remote.fs_debounce_ms > local.fs_debounce_ms);
+        assert!(remote.web_debounce_ms > local.web_debounce_ms);
+        assert!(remote.filter_debounce_ms > 0);
+        assert!(remote.tick_ms >= local.tick_ms);
+        assert!(!remote.show_icons);
+    }
+
+    #[test]
+    fn test_detect_with_flag() {
+        assert!(Profile::detect(true, false).remote);
+        assert_eq!(Profile::detect(true, true), Profile::remote().with_low_power());
+    }
+
+    #[test]
+    fn test_low_power_profile() {
+        for profile in [Profile::default(), Profile::remote()] {
+            let low_power = profile.with_low_power();
+            assert!(low_power.fs_debounce_ms > profile.fs_debounce_ms);
+            assert!(low_power.web_debounce_ms > profile.web_debounce_ms);
+            assert!(low_power.tick_ms > profile.tick_ms);
+            assert!(!low_power.web_suggestions);
+            // Rendering is still tuned for the terminal
+            assert_eq!(low_power.show_icons, profile.show_icons);
+            assert_eq!(low_power.remote, profile.remote);
+        }
+        assert_eq!(Profile::new(true, true), Profile::remote().with_low_power());
+        assert_eq!(Profile::new(false, false), Profile::default());
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let output = "System-wide power settings:\nCurrently in use:\n standby              1\n lowpowermode         1\n sleep                1\n";
+        assert!(parse_pmset(output));
+        assert!(!parse_pmset(&output.replace("lowpowermode         1", "lowpowermode         0")));
+        assert!(!parse_pmset("Currently in use:\n sleep 1\n"));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::core::{CommandItem, CommandType};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
+
+/// How results are ordered, shared by the TUI and `grinta search`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// Days after which a launch counts half as much towards frecency
+    pub half_life_days: f64,
+    /// Added to the match score of results, per kind
+    #[serde(deserialize_with = "type_bonus_over_defaults")]
+    pub type_bonus: KindWeights,
+    /// Order of results tied on score and frecency, lowest first
+    #[serde(deserialize_with = "priority_over_defaults")]
+    pub priority: KindWeights,
+    /// Milliseconds after an edit of the query during which file, web and
+    /// answer results are merged into the list; later ones go below it
+    pub latency_budget_ms: u64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            half_life_days: 14.0,
+            type_bonus: KindWeights {
+                app: 200,
+                note: 150,
+                bookmark: 100,
+                other: 50,
+                web_search: 25,
+                web_suggestion: 0,
+            },
+            // Local items come before web suggestions
+            priority: KindWeights {
+                app: 1,
+                note: 1,
+                bookmark: 1,
+                other: 1,
+                web_search: 2,
+                web_suggestion: 2,
+            },
+            latency_budget_ms: 300,
+        }
+    }
+}
+
+/// A value for each kind of result. `other` covers files and everything
+/// without a kind of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KindWeights {
+    pub app: i64,
+    pub note: i64,
+    pub bookmark: i64,
+    pub other: i64,
+    pub web_search: i64,
+    pub web_suggestion: i64,
+}
+
+/// `KindWeights` as written in the config; kinds left out keep their
+/// default.
+#[derive(Deserialize)]
+struct PartialKindWeights {
+    app: Option<i64>,
+    note: Option<i64>,
+    bookmark: Option<i64>,
+    other: Option<i64>,
+    web_search: Option<i64>,
+    web_suggestion: Option<i64>,
+}
+
+impl PartialKindWeights {
+    fn over(self, defaults: KindWeights) -> KindWeights {
+        KindWeights {
+            app: self.app.unwrap_or(defaults.app),
+            note: self.note.unwrap_or(defaults.note),
+            bookmark: self.bookmark.unwrap_or(defaults.bookmark),
+            other: self.other.unwrap_or(defaults.other),
+            web_search: self.web_search.unwrap_or(defaults.web_search),
+            web_suggestion: self.web_suggestion.unwrap_or(defaults.web_suggestion),
+        }
+    }
+}
+
+fn type_bonus_over_defaults<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KindWeights, D::Error> {
+    Ok(PartialKindWeights::deserialize(deserializer)?.over(RankingConfig::default().type_bonus))
+}
+
+fn priority_over_defaults<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KindWeights, D::Error> {
+    Ok(PartialKindWeights::deserialize(deserializer)?.over(RankingConfig::default().priority))
+}
+
+impl KindWeights {
+    pub fn get(&self, kind: &CommandType) -> i64 {
+        match kind {
+            CommandType::App => self.app,
+            CommandType::Note => self.note,
+            CommandType::Bookmark => self.bookmark,
+            CommandType::Unknown => self.other,
+            CommandType::WebSearch => self.web_search,
+            CommandType::WebSuggestion => self.web_suggestion,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// Refuse settings that would make the ranking meaningless.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.half_life_days.is_finite() && self.half_life_days > 0.0) {
+            bail!(
+                "ranking.half_life_days must be a positive number of days, not {}",
+                self.half_life_days
+            );
+        }
+        Ok(())
+    }
+
+    /// Weight of a launch made `age_days` ago: 1 now, halving every
+    /// `half_life_days`.
+    pub fn decay(&self, age_days: f64) -> f64 {
+        0.5f64.powf(age_days / self.half_life_days)
+    }
+
+    /// Where `item`, matched with `match_score` for `query`, goes given
+    /// its frecency.
+    pub fn rank(&self, query: &str, match_score: i64, frecency: f64, item: &CommandItem) -> Rank {
+        // Suggestions repeat the query, so theirs says nothing
+        let exact = item.kind != CommandType::WebSuggestion && item.label.eq_ignore_ascii_case(query.trim());
+        Rank {
+            exact,
+            score: match_score + self.type_bonus.get(&item.kind),
+            frecency,
+            priority: self.priority.get(&item.kind),
+        }
+    }
+}
+
+/// The place of a result in the TUI and `grinta search` alike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rank {
+    /// Whether the label is the query itself, for other than suggestions
+    pub exact: bool,
+    /// Match score plus the bonus of the result's kind
+    pub score: i64,
+    pub frecency: f64,
+    pub priority: i64,
+}
+
+impl Rank {
+    /// Best first: an exact label, then the higher score, the higher
+    /// frecency and the lower priority.
+    pub fn order(&self, other: &Rank) -> Ordering {
+        other
+            .exact
+            .cmp(&self.exact)
+            .then_with(|| other.score.cmp(&self.score))
+            .then_with(|| other.frecency.partial_cmp(&self.frecency).unwrap_or(Ordering::Equal))
+            .then_with(|| self.priority.cmp(&other.priority))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+
+    #[test]
+    fn test_default_weights() {
+        let ranking = RankingConfig::default();
+        assert!(ranking.validate().is_ok());
+        assert_eq!(ranking.type_bonus.get(&CommandType::App), 200);
+        assert_eq!(ranking.type_bonus.get(&CommandType::Unknown), 50);
+        assert!(ranking.priority.get(&CommandType::Note) < ranking.priority.get(&CommandType::WebSuggestion));
+        assert!((ranking.decay(14.0) - 0.5).abs() < 1e-9);
+        assert_eq!(ranking.decay(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_rank_order() {
+        let ranking = RankingConfig::default();
+        let mut app = CommandItem::new("Notes", Handler::App, "/Applications/Notes.app");
+        app.kind = CommandType::App;
+        let file = CommandItem::new("notes.txt", Handler::File, "/tmp/notes.txt");
+        let app_rank = ranking.rank("note", 100, 0.0, &app);
+        let file_rank = ranking.rank("note", 100, 0.0, &file);
+        assert_eq!(app_rank.score, 300);
+        assert_eq!(app_rank.order(&file_rank), Ordering::Less);
+
+        // Frecency breaks ties on score
+        let launched = ranking.rank("note", 100, 3.0, &file);
+        assert_eq!(launched.order(&file_rank), Ordering::Less);
+
+        // The exact label comes first whatever the bonus
+        let exact = ranking.rank("notes.txt", 100, 0.0, &file);
+        assert_eq!(exact.order(&ranking.rank("notes.txt", 100, 0.0, &app)), Ordering::Less);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut ranking = RankingConfig {
+            half_life_days: 0.0,
+            ..Default::default()
+        };
+        assert!(ranking.validate().is_err());
+        ranking.half_life_days = f64::NAN;
+        assert!(ranking.validate().is_err());
+        ranking.half_life_days = -7.0;
+        assert!(ranking.validate().is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::core::{CommandItem, Handler};
+use crate::relative_time::{self, DateStyle};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+const READING_LIST_FILE: &str = "grinta_reading_list.json";
+const LATER_COMMAND: &str = "later";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingState {
+    #[default]
+    Unread,
+    Read,
+    Archived,
+}
+
+/// A link saved with the "Read later" action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingEntry {
+    pub url: String,
+    pub title: String,
+    pub added: DateTime<Local>,
+    #[serde(default)]
+    pub state: ReadingState,
+}
+
+fn reading_list_path() -> Result<PathBuf> {
+    let mut path = dirs::data_dir().context("Failed to get data directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(READING_LIST_FILE);
+    Ok(path)
+}
+
+pub fn load_entries() -> Result<Vec<ReadingEntry>> {
+    let path = reading_list_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path)?;
+    // An unreadable list is an error, so it is never saved over
+    serde_json::from_str(&contents).with_context(|| format!("The reading list {} is damaged", path.display()))
+}
+
+fn save_entries(entries: &[ReadingEntry]) -> Result<()> {
+    fs::write(reading_list_path()?, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+/// Queue `url`; a link saved before goes back to the top as unread.
+fn add_entry(entries: &mut Vec<ReadingEntry>, url: &str, title: &str, now: DateTime<Local>) {
+    entries.retain(|entry| entry.url != url);
+    entries.push(ReadingEntry {
+        url: url.to_string(),
+        title: title.to_string(),
+        added: now,
+        state: ReadingState::Unread,
+    });
+}
+
+fn set_entry_state(entries: &mut [ReadingEntry], url: &str, state: ReadingState) -> Result<()> {
+    let entry = entries
+        .iter_mut()
+        .find(|entry| entry.url == url)
+        .with_context(|| format!("{} is not in the reading list", url))?;
+    entry.state = state;
+    Ok(())
+}
+
+/// Save a link to the reading list.
+pub fn add(url: &str, title: &str) -> Result<()> {
+    let mut entries = load_entries()?;
+    add_entry(&mut entries, url, title, Local::now());
+    save_entries(&entries)
+}
+
+/// Mark a queued link as read or archived.
+pub fn set_state(url: &str, state: ReadingState) -> Result<()> {
+    let mut entries = load_entries()?;
+    set_entry_state(&mut entries, url, state)?;
+    save_entries(&entries)
+}
+
+/// The reading list state of an item listed under `later`.
+pub fn state_of(item: &CommandItem) -> Option<ReadingState> {
+    match item.metadata.get("later").map(String::as_str) {
+        Some("unread") => Some(ReadingState::Unread),
+        Some("read") => Some(ReadingState::Read),
+        _ => None,
+    }
+}
+
+fn entry_item(entry: &ReadingEntry, now: DateTime<Local>, style: DateStyle) -> CommandItem {
+    let title = if entry.title.is_empty() { &entry.url } else { &entry.title };
+    let mut item = CommandItem::new(title, Handler::Url, &entry.url);
+    let (icon, state) = match entry.state {
+        ReadingState::Read => ("✓", "read"),
+        _ => ("🔖", "unread"),
+    };
+    item.icon = icon.to_string();
+    item.metadata.insert("later".to_string(), state.to_string());
+    item.metadata.insert(
+        "added".to_string(),
+        format!("added {}", relative_time::format(entry.added, now, style)),
+    );
+    item
+}
+
+/// The queue for `later [filter]`: unread links newest first, then the
+/// ones already read. Archived links are not listed.
+fn queue_items_from(query: &str, entries: &[ReadingEntry], now: DateTime<Local>) -> Option<Vec<CommandItem>> {
+    let filter = match query.strip_prefix(LATER_COMMAND)? {
+        "" => "",
+        rest => rest.strip_prefix(' ')?.trim(),
+    };
+    let filter = filter.to_lowercase();
+    let mut listed: Vec<&ReadingEntry> = entries
+        .iter()
+        .filter(|entry| entry.state != ReadingState::Archived)
+        .filter(|entry| {
+            entry.title.to_lowercase().contains(&filter) || entry.url.to_lowercase().contains(&filter)
+        })
+        .collect();
+    listed.sort_by_key(|entry| (entry.state == ReadingState::Read, Reverse(entry.added)));
+    let style = DateStyle::current();
+    Some(listed.into_iter().map(|entry| entry_item(entry, now, style)).collect())
+}
+
+/// Items for a `later` query, or None when the query is something else.
+pub fn queue_items(query: &str) -> Option<Vec<CommandItem>> {
+    if !query.starts_with(LATER_COMMAND) {
+        return None;
+    }
+    queue_items_from(query, &load_entries().unwrap_or_default(), Local::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap()
+    }
+
+    fn entry(url: &str, title: &str, added: DateTime<Local>, state: ReadingState) -> ReadingEntry {
+        ReadingEntry {
+            url: url.to_string(),
+            title: title.to_string(),
+            added,
+            state,
+        }
+    }
+
+    #[test]
+    fn test_add_and_set_state() {
+        let now = now();
+        let mut entries = Vec::new();
+        add_entry(&mut entries, "https://a.com", "A", now - Duration::days(2));
+        add_entry(&mut entries, "https://b.com", "B", now - Duration::days(1));
+        set_entry_state(&mut entries, "https://a.com", ReadingState::Read).unwrap();
+        assert_eq!(entries[0].state, ReadingState::Read);
+        assert!(set_entry_state(&mut entries, "https://c.com", ReadingState::Read).is_err());
+
+        // Saving it again queues it as new
+        add_entry(&mut entries, "https://a.com", "A", now);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1], entry("https://a.com", "A", now, ReadingState::Unread));
+    }
+
+    #[test]
+    fn test_queue_items() {
+        let now = now();
+        let entries = vec![
+            entry("https://old.com/post", "Old post", now - Duration::days(3), ReadingState::Unread),
+            entry("https://done.com", "Done", now - Duration::hours(1), ReadingState::Read),
+            entry("https://gone.com", "Gone", now, ReadingState::Archived),
+            entry("https://new.com/rust", "", now - Duration::hours(2), ReadingState::Unread),
+        ];
+
+        let items = queue_items_from("later", &entries, now).unwrap();
+        let labels: Vec<&str> = items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, ["https://new.com/rust", "Old post", "Done"]);
+        assert_eq!(items[0].metadata["added"], "added 2 hours ago");
+        assert_eq!(state_of(&items[0]), Some(ReadingState::Unread));
+        assert_eq!(state_of(&items[2]), Some(ReadingState::Read));
+
+        let items = queue_items_from("later RUST", &entries, now).unwrap();
+        assert_eq!(items.len(), 1);
+        assert!(queue_items_from("lateral", &entries, now).is_none());
+        assert!(queue_items_from("late", &entries, now).is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Datelike, Local};
+
+/// How dates are written once they are too old to be relative, following
+/// the `LC_ALL`, `LC_TIME` or `LANG` locale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateStyle {
+    /// `18 Mar` rather than `Mar 18`
+    pub day_first: bool,
+    /// `3:04 PM` rather than `15:04`
+    pub twelve_hour: bool,
+}
+
+/// Locales writing the month first.
+const MONTH_FIRST: [&str; 3] = ["en_US", "en_PH", "es_US"];
+/// Locales using a 12-hour clock.
+const TWELVE_HOUR: [&str; 8] = ["en_US", "en_PH", "es_US", "en_AU", "en_CA", "en_IN", "en_NZ", "hi_IN"];
+
+impl DateStyle {
+    /// The style of a locale name such as `de_DE.UTF-8`. `C`, `POSIX` and
+    /// unknown names keep the month first and a 24-hour clock.
+    pub fn from_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or_default();
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return Self::default();
+        }
+        Self {
+            day_first: !MONTH_FIRST.contains(&name),
+            twelve_hour: TWELVE_HOUR.contains(&name),
+        }
+    }
+
+    /// The style of the user's locale.
+    pub fn current() -> Self {
+        let locale = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        Self::from_locale(&locale)
+    }
+
+    /// `Mar 18 15:04`, with the year when it isn't this year's.
+    pub fn format(self, when: DateTime<Local>, now: DateTime<Local>) -> String {
+        let date = match (self.day_first, when.year() == now.year()) {
+            (true, true) => "%-d %b",
+            (true, false) => "%-d %b %Y",
+            (false, true) => "%b %-d",
+            (false, false) => "%b %-d %Y",
+        };
+        let time = if self.twelve_hour { "%-I:%M %p" } else { "%H:%M" };
+        when.format(&format!("{} {}", date, time)).to_string()
+    }
+}
+
+/// `just now`, `2 min ago`, `yesterday` or `3 weeks ago`; older and
+/// future times are written out in `style`.
+pub fn format(when: DateTime<Local>, now: DateTime<Local>, style: DateStyle) -> String {
+    let elapsed = now - when;
+    let days = (now.date_naive() - when.date_naive()).num_days();
+    let plural = |count: i64, unit: &str| {
+        format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+    };
+    if elapsed.num_seconds() < 0 {
+        style.format(when, now)
+    } else if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{} min ago", elapsed.num_minutes())
+    } else if days == 0 {
+        plural(elapsed.num_hours(), "hour")
+    } else if days == 1 {
+        "yesterday".to_string()
+    } else if days < 7 {
+        plural(days, "day")
+    } else if days < 35 {
+        plural(days / 7, "week")
+    } else {
+        style.format(when, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 18, 15, 4, 0).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        let style = DateStyle::default();
+        assert_eq!(format(now() - Duration::seconds(20), now(), style), "just now");
+        assert_eq!(format(now() - Duration::minutes(2), now(), style), "2 min ago");
+        assert_eq!(format(now() - Duration::hours(1), now(), style), "1 hour ago");
+        assert_eq!(format(now() - Duration::hours(5), now(), style), "5 hours ago");
+        assert_eq!(format(now() - Duration::hours(20), now(), style), "yesterday");
+        assert_eq!(format(now() - Duration::days(3), now(), style), "3 days ago");
+        assert_eq!(format(now() - Duration::days(21), now(), style), "3 weeks ago");
+        let january = Local.with_ymd_and_hms(2026, 1, 17, 15, 4, 0).unwrap();
+        assert_eq!(format(january, now(), style), "Jan 17 15:04");
+        let last_year = Local.with_ymd_and_hms(2025, 3, 18, 15, 4, 0).unwrap();
+        assert_eq!(format(last_year, now(), style), "Mar 18 2025 15:04");
+        assert_eq!(format(now() + Duration::days(2), now(), style), "Mar 20 15:04");
+    }
+
+    #[test]
+    fn test_date_style() {
+        let old = Local.with_ymd_and_hms(2026, 1, 17, 15, 4, 0).unwrap();
+        assert_eq!(DateStyle::from_locale("en_US.UTF-8").format(old, now()), "Jan 17 3:04 PM");
+        assert_eq!(DateStyle::from_locale("de_DE.UTF-8").format(old, now()), "17 Jan 15:04");
+        assert_eq!(DateStyle::from_locale("en_AU").format(old, now()), "17 Jan 3:04 PM");
+        assert_eq!(DateStyle::from_locale("C.UTF-8"), DateStyle::default());
+        assert_eq!(DateStyle::from_locale(""), DateStyle::default());
+    }
+}
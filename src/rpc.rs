@@ -0,0 +1,169 @@
+use crate::cli::{self, StreamResponse};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// One request line, e.g. `{"id":1,"method":"search","query":"fire"}`.
+/// `id` is optional and echoed back so clients can pipeline requests.
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    query: String,
+}
+
+/// One response line, with the same result objects `grinta search` prints.
+#[derive(Serialize)]
+struct RpcResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    results: Vec<StreamResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RpcResponse {
+    fn error(id: Option<Value>, error: String) -> Self {
+        Self {
+            id,
+            results: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+async fn handle_line(line: &str) -> RpcResponse {
+    let request: RpcRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return RpcResponse::error(None, format!("Invalid request: {}", e)),
+    };
+    match request.method.as_str() {
+        "search" => RpcResponse {
+            id: request.id,
+            results: cli::search_results(&request.query)
+                .await
+                .iter()
+                .map(|(item, result_type)| StreamResponse::new(item, result_type))
+                .collect(),
+            error: None,
+        },
+        "ping" => RpcResponse {
+            id: request.id,
+            results: Vec::new(),
+            error: None,
+        },
+        method => RpcResponse::error(request.id, format!("Unknown method: {}", method)),
+    }
+}
+
+/// Answer newline-delimited JSON requests until the reader is closed.
+async fn serve_lines<R, W>(reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut json = serde_json::to_string(&handle_line(&line).await)?;
+        json.push('\n');
+        writer.write_all(json.as_bytes()).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+/// Serve search requests on stdin/stdout, or on a Unix socket when a path
+/// is given.
+pub async fn run(socket: Option<&Path>) -> Result<()> {
+    match socket {
+        None => serve_lines(BufReader::new(tokio::io::stdin()), tokio::io::stdout()).await,
+        Some(path) => serve_socket(path).await,
+    }
+}
+
+#[cfg(unix)]
+async fn serve_socket(path: &Path) -> Result<()> {
+    remove_stale_socket(path)?;
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind {}", path.display()))?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let (reader, writer) = stream.into_split();
+            let _ = serve_lines(BufReader::new(reader), writer).await;
+        });
+    }
+}
+
+/// Remove a socket left behind by an earlier run. Anything else at `path`
+/// is left alone, so a mistyped `--socket` can't delete a file.
+#[cfg(unix)]
+pub(crate) fn remove_stale_socket(path: &Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            std::fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+async fn serve_socket(_path: &Path) -> Result<()> {
+    anyhow::bail!("Serving on a socket needs Unix domain sockets; omit --socket to use stdin/stdout")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn responses(input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve_lines(input.as_bytes(), &mut output).await.unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_errors() {
+        let responses = responses(
+            "{\"id\":1,\"method\":\"ping\"}\n\n{\"id\":\"b\",\"method\":\"launch\"}\nnot json\n",
+        )
+        .await;
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["results"], serde_json::json!([]));
+        assert!(responses[0].get("error").is_none());
+        assert_eq!(responses[1]["id"], "b");
+        assert_eq!(responses[1]["error"], "Unknown method: launch");
+        assert!(responses[2]["error"].as_str().unwrap().starts_with("Invalid request"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_only_sockets_are_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(remove_stale_socket(&file).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+
+        let socket = dir.path().join("grinta.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+        remove_stale_socket(&socket).unwrap();
+        assert!(!socket.exists());
+        assert!(remove_stale_socket(&socket).is_ok());
+    }
+}
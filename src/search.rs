@@ -0,0 +1,175 @@
+use crate::core::CommandItem;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// The kinds of background search started while typing. A new search of a
+/// kind supersedes the previous one of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    Files,
+    /// The file usually opened, searched ahead of the query that finds it
+    Prefetch,
+    Web,
+    /// Network answers such as the weather
+    Answers,
+}
+
+/// Searches of one kind: the latest generation and the task running it.
+#[derive(Debug, Default)]
+struct Lane {
+    generation: Arc<AtomicU64>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl Lane {
+    /// Supersede the running search, returning the new generation.
+    fn advance(&mut self) -> u64 {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+/// Handed to a search task to check that no newer search of its kind was
+/// started before it sends results.
+#[derive(Debug, Clone)]
+pub struct Ticket {
+    generation: Arc<AtomicU64>,
+    issued: u64,
+}
+
+impl Ticket {
+    pub fn is_current(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) == self.issued
+    }
+}
+
+/// File search run ahead for the query the user usually ends up typing,
+/// as `(query, items)`.
+pub type Prefetched = Arc<Mutex<Option<(String, Vec<CommandItem>)>>>;
+
+/// The background searches of one Grinta instance: starting one cancels
+/// the previous search of its kind, and all of them can be cancelled when
+/// the query is cleared.
+#[derive(Debug, Default)]
+pub struct SearchCoordinator {
+    files: Lane,
+    prefetch: Lane,
+    web: Lane,
+    answers: Lane,
+    pub prefetched: Prefetched,
+}
+
+impl SearchCoordinator {
+    fn lane(&mut self, kind: SearchKind) -> &mut Lane {
+        match kind {
+            SearchKind::Files => &mut self.files,
+            SearchKind::Prefetch => &mut self.prefetch,
+            SearchKind::Web => &mut self.web,
+            SearchKind::Answers => &mut self.answers,
+        }
+    }
+
+    /// Cancel the running search of `kind` and spawn `search` in its place.
+    pub fn start<F>(&mut self, kind: SearchKind, search: impl FnOnce(Ticket) -> F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let lane = self.lane(kind);
+        let ticket = Ticket {
+            generation: lane.generation.clone(),
+            issued: lane.advance(),
+        };
+        lane.task = Some(tokio::spawn(search(ticket)));
+    }
+
+    /// Cancel the running search of `kind`; results it still sends are
+    /// stale.
+    pub fn cancel(&mut self, kind: SearchKind) {
+        self.lane(kind).advance();
+    }
+
+    pub fn cancel_all(&mut self) {
+        for kind in [SearchKind::Files, SearchKind::Prefetch, SearchKind::Web, SearchKind::Answers] {
+            self.cancel(kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_start_supersedes() {
+        let mut coordinator = SearchCoordinator::default();
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let slow_tx = tx.clone();
+        coordinator.start(SearchKind::Web, |ticket| async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if ticket.is_current() {
+                let _ = slow_tx.send("first").await;
+            }
+        });
+        coordinator.start(SearchKind::Web, |ticket| async move {
+            if ticket.is_current() {
+                let _ = tx.send("second").await;
+            }
+        });
+
+        assert_eq!(rx.recv().await, Some("second"));
+        // The first search was aborted and its sender dropped
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all() {
+        let mut coordinator = SearchCoordinator::default();
+        let (ticket_tx, mut ticket_rx) = mpsc::channel(1);
+        coordinator.start(SearchKind::Files, |ticket| async move {
+            let _ = ticket_tx.send(ticket).await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        let ticket = ticket_rx.recv().await.unwrap();
+        assert!(ticket.is_current());
+
+        coordinator.cancel_all();
+        assert!(!ticket.is_current());
+        assert!(coordinator.files.task.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_search_keeps_the_prefetch() {
+        let mut coordinator = SearchCoordinator::default();
+        let (ticket_tx, mut ticket_rx) = mpsc::channel(1);
+        coordinator.start(SearchKind::Prefetch, |ticket| async move {
+            let _ = ticket_tx.send(ticket).await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        let ticket = ticket_rx.recv().await.unwrap();
+
+        coordinator.start(SearchKind::Files, |_| async {});
+        assert!(ticket.is_current());
+        assert!(coordinator.prefetch.task.as_ref().is_some_and(|task| !task.is_finished()));
+        coordinator.cancel_all();
+        assert!(!ticket.is_current());
+    }
+
+    #[test]
+    fn test_instances_are_independent() {
+        let mut first = SearchCoordinator::default();
+        let second = SearchCoordinator::default();
+        let ticket = Ticket {
+            generation: second.files.generation.clone(),
+            issued: 0,
+        };
+        first.cancel(SearchKind::Files);
+        assert!(ticket.is_current());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::expand_tilde;
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::web_search;
+
+/// Top-level domains a bare name is taken as a domain with. File
+/// extensions that are also TLDs, such as `.md`, are left out.
+const KNOWN_TLDS: [&str; 24] = [
+    "com", "org", "net", "io", "dev", "app", "ai", "co", "edu", "gov", "info", "me", "rs", "tv", "uk", "us", "eu",
+    "de", "fr", "nl", "ch", "ca", "au", "jp",
+];
+
+/// Whether `text` is a bare domain such as `docs.rs`, `example.com/a` or
+/// `www.example.museum`.
+fn looks_like_domain(text: &str) -> bool {
+    if text.contains(char::is_whitespace) {
+        return false;
+    }
+    let host = text.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.split(':').next().unwrap_or("").to_lowercase();
+    let Some((name, tld)) = host.rsplit_once('.') else {
+        return false;
+    };
+    if name.is_empty() || tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) {
+        return false;
+    }
+    host.starts_with("www.") || KNOWN_TLDS.contains(&tld)
+}
+
+/// The item `grinta send` runs for a piece of text: URLs are opened,
+/// existing files and folders are opened with their default app, and
+/// anything else is searched on the web.
+pub fn resolve(input: &str) -> Option<CommandItem> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let url_schemes = ["http://", "https://", "mailto:", "file://"];
+    if url_schemes.iter().any(|scheme| input.starts_with(scheme)) {
+        return Some(CommandItem::new(input, Handler::Url, input));
+    }
+    let path = expand_tilde(input);
+    if path.is_absolute() && path.exists() {
+        let handler = if path.is_dir() { Handler::Folder } else { Handler::File };
+        return Some(CommandItem::new(input, handler, &path.to_string_lossy()));
+    }
+    if looks_like_domain(input) {
+        return Some(CommandItem::new(input, Handler::Url, &format!("https://{}", input)));
+    }
+    Some(web_search::search_item(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::CommandType;
+
+    #[test]
+    fn test_resolve_urls() {
+        assert_eq!(resolve("https://docs.rs/serde").unwrap().value, "https://docs.rs/serde");
+        assert_eq!(resolve(" docs.rs/serde ").unwrap().value, "https://docs.rs/serde");
+        assert_eq!(resolve("localhost.dev:8080/a").unwrap().value, "https://localhost.dev:8080/a");
+        assert!(resolve("  ").is_none());
+    }
+
+    #[test]
+    fn test_resolve_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("notes.txt");
+        std::fs::write(&file, "hi").unwrap();
+
+        assert_eq!(resolve(&file.to_string_lossy()).unwrap().handler, Handler::File);
+        assert_eq!(resolve(&temp_dir.path().to_string_lossy()).unwrap().handler, Handler::Folder);
+    }
+
+    #[test]
+    fn test_resolve_phrases() {
+        let item = resolve("rust borrow checker").unwrap();
+        assert_eq!(item.kind, CommandType::WebSearch);
+        assert_eq!(item.value, "https://duckduckgo.com/?q=rust%20borrow%20checker");
+        // Version numbers are not domains
+        assert_eq!(resolve("v1.2").unwrap().kind, CommandType::WebSearch);
+        assert_eq!(resolve("1.5").unwrap().kind, CommandType::WebSearch);
+        // Nor are file names
+        assert_eq!(resolve("README.md").unwrap().kind, CommandType::WebSearch);
+        assert_eq!(resolve("www.example.museum").unwrap().value, "https://www.example.museum");
+    }
+}
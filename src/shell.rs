@@ -0,0 +1,148 @@
+use crate::commands;
+use crate::core::{CommandItem, Handler};
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Longest output kept for the result overlay.
+const MAX_OUTPUT_CHARS: usize = 4000;
+/// How long a command shown in the overlay may run; longer ones belong in
+/// a terminal (Alt+Enter).
+const RUN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The command of a `> cmd` or `$ cmd` query. `$` needs the space so that
+/// `$AAPL` stays a stock quote.
+pub fn parse_query(query: &str) -> Option<&str> {
+    let command = query
+        .strip_prefix('>')
+        .or_else(|| query.strip_prefix("$ "))?
+        .trim();
+    (!command.is_empty()).then_some(command)
+}
+
+/// An item running the typed command in the user's shell.
+pub fn shell_items(query: &str) -> Vec<CommandItem> {
+    match parse_query(query) {
+        Some(command) => {
+            let mut item = CommandItem::new(&format!("Run in shell: {}", command), Handler::Shell, command);
+            item.metadata.insert("typed".to_string(), "true".to_string());
+            vec![item]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Whether `item` is a command typed after `>` or `$`, which is not
+/// remembered so that it can't be run again by a stray Enter.
+pub fn is_typed(item: &CommandItem) -> bool {
+    item.handler == Handler::Shell && item.metadata.contains_key("typed")
+}
+
+/// Whether Enter runs a shell item in a terminal rather than showing its
+/// output, as for Makefile and justfile tasks.
+pub fn runs_in_terminal(item: &CommandItem) -> bool {
+    item.handler == Handler::Shell && item.metadata.contains_key("terminal")
+}
+
+pub fn user_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+/// `text` as a single-quoted word of a POSIX shell command.
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Keep the end of long output, where the interesting part usually is.
+pub fn truncate_output(output: &str) -> String {
+    let count = output.chars().count();
+    if count <= MAX_OUTPUT_CHARS {
+        return output.to_string();
+    }
+    let tail: String = output.chars().skip(count - MAX_OUTPUT_CHARS).collect();
+    format!("…{}", tail)
+}
+
+/// Run `command` with `$SHELL -c` and return its output. A failing command
+/// is an error carrying its stderr.
+pub async fn run(command: &str) -> Result<String> {
+    let output = Command::new(user_shell())
+        .args(["-c", command])
+        .envs(commands::workspace_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(RUN_TIMEOUT, output)
+        .await
+        .with_context(|| format!("`{}` took longer than {} seconds", command, RUN_TIMEOUT.as_secs()))?
+        .context("Failed to start the shell")?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim_end().to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = if stderr.trim().is_empty() { &stdout } else { stderr.trim() };
+        anyhow::bail!("`{}` failed ({}): {}", command, output.status, detail);
+    }
+    Ok(truncate_output(&stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("> ls -la"), Some("ls -la"));
+        assert_eq!(parse_query(">uptime"), Some("uptime"));
+        assert_eq!(parse_query("$ git status"), Some("git status"));
+        assert_eq!(parse_query("$AAPL"), None);
+        assert_eq!(parse_query(">"), None);
+        assert_eq!(parse_query("ls"), None);
+    }
+
+    #[test]
+    fn test_shell_items() {
+        let items = shell_items("> echo hi");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].handler, Handler::Shell);
+        assert_eq!(items[0].value, "echo hi");
+        assert!(is_typed(&items[0]));
+        assert!(!is_typed(&CommandItem::new("make build", Handler::Shell, "make build")));
+        assert!(shell_items("firefox").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_with_workspace_env() {
+        let env = [("GRINTA_TEST_PROJECT".to_string(), "CX".to_string())].into_iter().collect();
+        let output = commands::with_workspace_env(env, run("echo $GRINTA_TEST_PROJECT")).await.unwrap();
+        assert_eq!(output, "CX");
+        // Grinta's own environment is left alone
+        assert!(std::env::var("GRINTA_TEST_PROJECT").is_err());
+        assert_eq!(run("echo \"$GRINTA_TEST_PROJECT\"").await.unwrap(), "");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("/tmp/my repo"), "'/tmp/my repo'");
+        assert_eq!(quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_truncate_output() {
+        assert_eq!(truncate_output("short"), "short");
+        let long = "x".repeat(MAX_OUTPUT_CHARS + 10);
+        assert_eq!(truncate_output(&long).chars().count(), MAX_OUTPUT_CHARS + 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run() {
+        assert_eq!(run("echo hello").await.unwrap(), "hello");
+        let error = run("echo oops >&2; exit 3").await.unwrap_err().to_string();
+        assert!(error.contains("oops"));
+    }
+}
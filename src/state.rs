@@ -1,8 +1,33 @@
-use crate::core::CommandItem;
+use crate::actions::{ActionMenu, Confirmation};
+use crate::catalogs;
+use crate::commands;
+use crate::config;
+use crate::containers::{self, Container};
+use crate::core::{CommandItem, Handler};
+use crate::data_sources::calendar::{self, Meeting};
+use crate::data_sources::github::RepoStatus;
+use crate::data_sources::notes::{self, NoteFolder};
+use crate::data_sources::{self, reminders, repos, web_search};
+use crate::export::ExportMenu;
+use crate::history;
+use crate::hotkeys;
+use crate::internal::{self, InternalCommand};
+use crate::matcher::{self, IncrementalMatcher};
+use crate::palette;
+use crate::profile::Profile;
+use crate::ranking::Rank;
+use crate::reading_list;
+use crate::search::SearchCoordinator;
+use crate::theme::Theme;
+use crate::time_tracking::{self, TimeEntry};
+use crate::transforms;
+use crate::workspace::{self, Source, Workspace};
+use chrono::Local;
 use ratatui::widgets::TableState;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use tui_textarea::TextArea;
-use fuzzy_matcher::FuzzyMatcher;
-use fuzzy_matcher::skim::SkimMatcherV2;
 
 pub struct AppState<'a> {
     pub query: TextArea<'a>,
@@ -12,7 +37,100 @@ pub struct AppState<'a> {
     pub history: Vec<CommandItem>,
     pub fs_items: Vec<CommandItem>,
     pub web_items: Vec<CommandItem>,
+    /// Network answers for the current query (weather, ...)
+    pub answer_items: Vec<CommandItem>,
     pub error_message: Option<String>,
+    pub profile: Profile,
+    /// Time of the last keystroke whose filtering was deferred
+    pub pending_filter: Option<Instant>,
+    pub workspaces: BTreeMap<String, Workspace>,
+    /// Name of the active workspace, if any
+    pub workspace: Option<String>,
+    /// The running time tracking timer, shown in the status bar
+    pub running_timer: Option<TimeEntry>,
+    /// Today's upcoming meetings, from the configured calendars
+    pub meetings: Vec<Meeting>,
+    /// Whether Do Not Disturb is on, holding meeting reminders back
+    pub do_not_disturb: bool,
+    /// Tasks of the folder given with `--cwd`
+    pub cwd_tasks: Vec<CommandItem>,
+    /// Secondary actions for the selected item, while the menu is open
+    pub action_menu: Option<ActionMenu>,
+    /// Export choices for the current results, while the menu is open
+    pub export_menu: Option<ExportMenu>,
+    /// Text shown over the results until the next key press
+    pub overlay: Option<Overlay>,
+    /// Title of the overlay waiting for the AI's answer, until it comes
+    /// or the overlay is dismissed
+    pub thinking: Option<String>,
+    /// Refreshes in flight, `None` standing for every source
+    pub refreshing: Vec<Option<Source>>,
+    /// Long tasks in flight, such as uploads, and their progress in percent
+    pub progress: BTreeMap<String, u8>,
+    /// Pull requests and CI of the GitHub repositories, by path
+    pub repo_status: HashMap<String, RepoStatus>,
+    /// Paths of the repositories with uncommitted changes, checked after
+    /// the apps are read
+    dirty_repos: HashSet<String>,
+    pub theme: Theme,
+    /// Scores of the indexed items for the current query
+    matcher: IncrementalMatcher,
+    /// Bumped whenever `items` or the workspace pins change
+    items_generation: u64,
+    /// Launched items are not recorded in the history while set
+    pub incognito: bool,
+    /// Values of the history entries and pins whose app, file or note is
+    /// gone, as of the last `validate_entries`
+    pub broken: HashSet<String>,
+    /// Destructive action asking to be confirmed, shown over the results
+    pub confirmation: Option<Confirmation>,
+    /// Short confirmation shown in the status bar, and when it was shown
+    pub toast: Option<(String, Instant)>,
+    /// Note being edited in place of the results
+    pub note_editor: Option<NoteEditor<'a>>,
+    /// Where new notes go, picked with `note-folder:` over the config's
+    pub note_folder: NoteFolder,
+    /// When the query was last edited, to tell which results come late
+    query_edited: Instant,
+    /// Values of the results on screen once the latency budget ran out;
+    /// results arriving after that are listed below them
+    settled: Option<HashSet<String>>,
+    /// Index of the first result that came late, shown below a divider
+    pub late_from: Option<usize>,
+    /// The query changed since the last filter, so the first result is
+    /// selected rather than the one selected before
+    query_changed: bool,
+    /// File, web and answer searches started while typing
+    pub search: SearchCoordinator,
+    /// Folders drilled into, innermost last; only its items are searched
+    pub containers: Vec<Container>,
+    /// Every folder of the indexed items, as of an `items_generation`
+    container_index: Option<(u64, Vec<Container>)>,
+}
+
+/// How long a toast stays in the status bar.
+pub const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// A titled block of text shown over the results, such as recognized text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    pub title: String,
+    pub text: String,
+}
+
+/// A note's text being edited, saved back to Apple Notes with Ctrl+S.
+pub struct NoteEditor<'a> {
+    pub note: CommandItem,
+    pub text: TextArea<'a>,
+}
+
+impl NoteEditor<'_> {
+    pub fn new(note: CommandItem, text: &str) -> Self {
+        let mut text = TextArea::new(text.lines().map(|line| line.to_string()).collect());
+        text.move_cursor(tui_textarea::CursorMove::Bottom);
+        text.move_cursor(tui_textarea::CursorMove::End);
+        Self { note, text }
+    }
 }
 
 impl<'a> AppState<'a> {
@@ -28,122 +146,602 @@ impl<'a> AppState<'a> {
             history,
             fs_items: vec![],
             web_items: vec![],
+            answer_items: vec![],
             error_message: None,
+            profile: Profile::default(),
+            pending_filter: None,
+            workspaces: config::get().workspaces.clone(),
+            workspace: None,
+            running_timer: None,
+            meetings: Vec::new(),
+            do_not_disturb: false,
+            cwd_tasks: vec![],
+            action_menu: None,
+            export_menu: None,
+            overlay: None,
+            thinking: None,
+            refreshing: vec![],
+            progress: BTreeMap::new(),
+            repo_status: HashMap::new(),
+            dirty_repos: HashSet::new(),
+            theme: config::get().theme.initial(),
+            matcher: IncrementalMatcher::default(),
+            items_generation: 0,
+            incognito: false,
+            broken: HashSet::new(),
+            confirmation: None,
+            toast: None,
+            note_editor: None,
+            note_folder: config::get().notes.note_folder(),
+            query_edited: Instant::now(),
+            settled: None,
+            late_from: None,
+            query_changed: false,
+            search: SearchCoordinator::default(),
+            containers: vec![],
+            container_index: None,
         };
         state.filter_items();
         state
     }
 
+    /// Join items of the meetings starting soon or under way.
+    fn join_items(&self) -> Vec<CommandItem> {
+        calendar::join_items(&self.meetings, Local::now().naive_local(), &config::get().calendar)
+    }
+
+    /// The join item of the meeting under way or next, if it has a call
+    /// link.
+    pub fn next_join_item(&self) -> Option<CommandItem> {
+        let now = Local::now().naive_local();
+        let meeting = calendar::next_meeting(&self.meetings, now)?;
+        calendar::join_item(meeting, now, &config::get().calendar)
+    }
+
     pub fn filter_items(&mut self) {
+        // Results arriving while the user navigates keep the same item
+        // selected, wherever it moved
+        let anchor = match std::mem::take(&mut self.query_changed) {
+            true => None,
+            false => self.get_selected_item().map(|item| (item.handler, item.value.clone())),
+        };
+        // Only searches list late results below a divider
+        self.late_from = None;
         let query = self.query.lines().join(" ").trim().to_string();
-        if query.is_empty() {
-            self.filtered_items = self.history.clone();
-            self.filtered_items.reverse();
+        let pinned = self.active_workspace().map(|w| w.pinned_items()).unwrap_or_default();
+        if let Some(filter) = workspace::parse_command(&query) {
+            self.filtered_items =
+                workspace::switch_items(filter, &self.workspaces, self.workspace.as_deref());
+        } else if let Some(items) = time_tracking::command_items(&query, self.running_timer.as_ref()) {
+            self.filtered_items = items;
+        } else if let Some(items) = notes::folder_items(&query, &self.items) {
+            self.filtered_items = items;
+        } else if let Some(items) = reminders::quick_add_items(&query, Local::now().naive_local()) {
+            self.filtered_items = items;
+        } else if let Some(items) = reading_list::queue_items(&query) {
+            self.filtered_items = items;
+        } else if let Some(filter) = palette::parse_query(&query) {
+            self.filtered_items = palette::palette_items(filter, self.palette_settings());
+        } else if let Some(container) = self.containers.last().cloned() {
+            self.filtered_items = self.container_items(&container, &query);
+        } else if query.is_empty() {
+            let mut recent = self.history.clone();
+            recent.reverse();
+            // Meetings about to start come first, then the tasks of the
+            // `--cwd` folder and subscribed catalogs waiting for the
+            // user's go-ahead
+            self.filtered_items = self.join_items();
+            self.filtered_items.extend(self.cwd_tasks.iter().cloned());
+            self.filtered_items.extend(catalogs::trust_items());
+            self.filtered_items.extend(pinned);
+            self.filtered_items.extend(recent);
+            // Broken entries sink to the bottom
+            self.filtered_items.sort_by_key(|item| self.broken.contains(&item.value));
         } else {
-            let matcher = SkimMatcherV2::default();
-            
-            // Filter static items using fuzzy matching
-            let mut static_filtered: Vec<CommandItem> = self.items
-                .iter()
-                .filter(|item| {
-                    item.label.to_lowercase().contains(&query.to_lowercase())
-                        || item.value.to_lowercase().contains(&query.to_lowercase())
-                        || matcher.fuzzy_match(&item.label, &query).unwrap_or(0) > 0
-                        || matcher.fuzzy_match(&item.value, &query).unwrap_or(0) > 0
-                })
-                .cloned()
-                .collect();
+            let (scope, query) = self.scoped_query();
+            let in_scope = |source: Source| scope.is_none_or(|scoped| scoped == source);
+            let pattern = matcher::pattern(&query);
 
-            // Filter dynamic items (FS + Web)
-            let mut fs_filtered: Vec<CommandItem> = self.fs_items
-                .iter()
-                .filter(|item| {
-                    item.label.to_lowercase().contains(&query.to_lowercase())
-                        || item.value.to_lowercase().contains(&query.to_lowercase())
-                        || matcher.fuzzy_match(&item.label, &query).unwrap_or(0) > 0
-                        || matcher.fuzzy_match(&item.value, &query).unwrap_or(0) > 0
-                })
-                .cloned()
+            // Indexed items (and workspace pins) keep their scores between
+            // keystrokes
+            let indexed: Vec<&CommandItem> = pinned.iter().chain(self.items.iter()).collect();
+            let static_matches = self.matcher.match_items(&query, &indexed, self.items_generation).to_vec();
+            let mut static_scored: Vec<(u32, CommandItem)> = static_matches
+                .into_iter()
+                .map(|(index, score)| (score, indexed[index]))
+                .filter(|(_, item)| in_scope(Source::of(item)) && self.source_enabled(Source::of(item)))
+                .map(|(score, item)| (score, with_body_snippet(item, &query)))
                 .collect();
+            if scope.is_none() {
+                for item in internal::refresh_items().into_iter().chain(self.join_items()) {
+                    if let Some(score) = self.matcher.score(&pattern, &item) {
+                        static_scored.push((score, item));
+                    }
+                }
+                // The `--cwd` folder may also be a repository with indexed tasks
+                let indexed_tasks: HashSet<String> = static_scored
+                    .iter()
+                    .filter(|(_, item)| item.handler == Handler::Shell)
+                    .map(|(_, item)| item.value.clone())
+                    .collect();
+                for item in self.cwd_tasks.iter().filter(|item| !indexed_tasks.contains(&item.value)) {
+                    if let Some(score) = self.matcher.score(&pattern, item) {
+                        static_scored.push((score, item.clone()));
+                    }
+                }
+            }
+            for container in self.container_index() {
+                if !in_scope(container.source) || !self.source_enabled(container.source) {
+                    continue;
+                }
+                let item = container.to_item();
+                if let Some(score) = self.matcher.score(&pattern, &item) {
+                    static_scored.push((score, item));
+                }
+            }
 
-            let mut web_filtered: Vec<CommandItem> = self.web_items
-                .iter()
-                .filter(|item| {
-                    item.label.to_lowercase().contains(&query.to_lowercase())
-                        || item.value.to_lowercase().contains(&query.to_lowercase())
-                        || matcher.fuzzy_match(&item.label, &query).unwrap_or(0) > 0
-                        || matcher.fuzzy_match(&item.value, &query).unwrap_or(0) > 0
-                })
-                .cloned()
-                .collect();
-            
-            // Combine all dynamic results: FS + Web suggestions
-            let mut new_filtered = Vec::new();
-            new_filtered.append(&mut static_filtered);
-            new_filtered.append(&mut fs_filtered);
-            new_filtered.append(&mut web_filtered);
-            
-            self.filtered_items = new_filtered;
-            
-            // Sort by fuzzy match score FIRST, then by type as tie-breaker
-            self.filtered_items.sort_by(|a, b| {
-                use crate::core::CommandType;
-                
-                // Primary sort: by fuzzy match score (higher score = better match)
-                let a_label_fuzzy = matcher.fuzzy_match(&a.label, &query).unwrap_or(0);
-                let a_value_fuzzy = matcher.fuzzy_match(&a.value, &query).unwrap_or(0);
-                let a_fuzzy = a_label_fuzzy.max(a_value_fuzzy);
-                
-                let b_label_fuzzy = matcher.fuzzy_match(&b.label, &query).unwrap_or(0);
-                let b_value_fuzzy = matcher.fuzzy_match(&b.value, &query).unwrap_or(0);
-                let b_fuzzy = b_label_fuzzy.max(b_value_fuzzy);
-                
-                match b_fuzzy.cmp(&a_fuzzy) {
-                    std::cmp::Ordering::Equal => {
-                        // Tie-breaker: prefer local items over web suggestions
-                        let a_priority = match a.kind {
-                            CommandType::App => 1,
-                            CommandType::Note => 1,
-                            CommandType::Bookmark => 1,
-                            CommandType::Unknown => 1,
-                            CommandType::WebSearch => 2,
-                            CommandType::WebSuggestion => 2,
-                        };
-                        
-                        let b_priority = match b.kind {
-                            CommandType::App => 1,
-                            CommandType::Note => 1,
-                            CommandType::Bookmark => 1,
-                            CommandType::Unknown => 1,
-                            CommandType::WebSearch => 2,
-                            CommandType::WebSuggestion => 2,
-                        };
-                        
-                        match a_priority.cmp(&b_priority) {
-                            std::cmp::Ordering::Equal => a.label.cmp(&b.label),
-                            other => other
-                        }
+            // Dynamic items (FS + Web) change with every search, so they
+            // are scored afresh
+            let mut fs_scored = Vec::new();
+            if self.searches(Source::Files) {
+                // Folders already indexed, such as zoxide's, aren't listed twice
+                let indexed_folders: HashSet<&str> = static_scored
+                    .iter()
+                    .filter(|(_, item)| item.handler == Handler::Folder)
+                    .map(|(_, item)| item.value.as_str())
+                    .collect();
+                for item in self.fs_items.iter().filter(|item| !indexed_folders.contains(item.value.as_str())) {
+                    if let Some(score) = self.matcher.score(&pattern, item) {
+                        fs_scored.push((score, item.clone()));
+                    }
+                }
+            }
+            let mut web_scored = Vec::new();
+            if self.searches(Source::Web) {
+                for item in &self.web_items {
+                    if let Some(score) = self.matcher.score(&pattern, item) {
+                        web_scored.push((score, item.clone()));
                     }
-                    other => other
                 }
+            }
+
+            let nothing_local = static_scored.is_empty() && fs_scored.is_empty();
+
+            let mut scored = static_scored;
+            scored.append(&mut fs_scored);
+            scored.append(&mut web_scored);
+
+            let frecency = history::frecency_scores(&self.history);
+            // Ranked the same way as `grinta search`; the keys are
+            // normalized, so frecency is looked up once per item
+            let ranking = &config::get().ranking;
+            let mut scored: Vec<(Rank, CommandItem)> = scored
+                .into_iter()
+                .map(|(score, item)| {
+                    let rank = ranking.rank(&query, score as i64, history::item_frecency(&frecency, &item), &item);
+                    (rank, item)
+                })
+                .collect();
+
+            // Broken entries come last whatever their rank
+            let broken = |item: &CommandItem| self.broken.contains(&item.value);
+            scored.sort_by(|(a_rank, a), (b_rank, b)| {
+                broken(a)
+                    .cmp(&broken(b))
+                    .then_with(|| a_rank.order(b_rank))
+                    .then_with(|| a.label.cmp(&b.label))
             });
+            self.filtered_items = scored.into_iter().map(|(_, item)| item).collect();
+            if scope.is_some() {
+                // Scoped searches skip computed answers
+                self.defer_late_results();
+                self.restore_selection(anchor);
+                return;
+            }
+
+            let bang_usage = web_search::bang_usage(&self.history);
+            let mut query_items = data_sources::get_query_items(&query);
+            query_items.extend(web_search::used_bang_items(&query, &bang_usage));
+            query_items.extend(self.answer_items.iter().cloned());
+            // Today's note goes with the other answers, such as the date
+            if cfg!(target_os = "macos") && self.source_enabled(Source::Notes) {
+                let daily = notes::daily_note_items(&query, &self.items, Local::now().date_naive());
+                query_items.extend(daily.unwrap_or_default());
+            }
+            if nothing_local && query_items.is_empty() {
+                // Free text that matches nothing is probably meant to be transformed
+                query_items.extend(transforms::registry().long_query_items(&query));
+            }
+            query_items.append(&mut self.filtered_items);
+            // Completing a bang is rarely what a short query is meant for
+            query_items.extend(web_search::bang_completions(&query, &bang_usage));
+            self.filtered_items = query_items;
+            self.defer_late_results();
+        }
+        self.restore_selection(anchor);
+    }
+
+    /// Every folder of the indexed items, worked out again when they change.
+    fn container_index(&mut self) -> Vec<Container> {
+        match &self.container_index {
+            Some((generation, containers)) if *generation == self.items_generation => containers.clone(),
+            _ => {
+                let containers = containers::all_containers(&self.items);
+                self.container_index = Some((self.items_generation, containers.clone()));
+                containers
+            }
         }
+    }
 
+    /// The contents of `container`, listed as they are kept without a query
+    /// and ranked by match with one.
+    fn container_items(&mut self, container: &Container, query: &str) -> Vec<CommandItem> {
+        let index = self.container_index();
+        let children = containers::children(container, &index, &self.items, !query.is_empty());
+        if query.is_empty() {
+            return children;
+        }
+        let pattern = matcher::pattern(query);
+        let mut scored: Vec<(u32, CommandItem)> = children
+            .into_iter()
+            .filter_map(|item| Some((self.matcher.score(&pattern, &item)?, item)))
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| b_score.cmp(a_score).then_with(|| a.label.cmp(&b.label)));
+        scored.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Leave the innermost folder drilled into. Returns false when there
+    /// was none.
+    pub fn leave_container(&mut self) -> bool {
+        if self.containers.pop().is_none() {
+            return false;
+        }
+        self.query_changed = true;
+        self.filter_items();
+        true
+    }
+
+    /// Called before file, web or answer results are merged: once the
+    /// latency budget has run out, the results on screen stay in place and
+    /// the ones arriving later go below them.
+    pub fn settle_results(&mut self) {
+        let budget = Duration::from_millis(config::get().ranking.latency_budget_ms);
+        if self.settled.is_none() && !self.filtered_items.is_empty() && self.query_edited.elapsed() >= budget {
+            self.settled = Some(self.filtered_items.iter().map(|item| item.value.clone()).collect());
+        }
+    }
+
+    /// Move the results that weren't on screen when they settled below the
+    /// ones that were, keeping each group's order.
+    fn defer_late_results(&mut self) {
+        self.late_from = None;
+        let Some(settled) = &self.settled else {
+            return;
+        };
+        let (on_time, late): (Vec<CommandItem>, Vec<CommandItem>) = std::mem::take(&mut self.filtered_items)
+            .into_iter()
+            .partition(|item| settled.contains(&item.value));
+        if !on_time.is_empty() && !late.is_empty() {
+            self.late_from = Some(on_time.len());
+        }
+        self.filtered_items = on_time;
+        self.filtered_items.extend(late);
+    }
+
+    /// Select the item identified by `anchor` (handler and value) if it is
+    /// still listed, otherwise the first result.
+    fn restore_selection(&mut self, anchor: Option<(Handler, String)>) {
         if self.filtered_items.is_empty() {
             self.table_state.select(None);
+            return;
+        }
+        let index = anchor.and_then(|(handler, value)| {
+            self.filtered_items
+                .iter()
+                .position(|item| item.handler == handler && item.value == value)
+        });
+        self.table_state.select(Some(index.unwrap_or(0)));
+    }
+
+    /// The source the query is scoped to by a prefix such as `f `, and the
+    /// query to search for.
+    pub fn scoped_query(&self) -> (Option<Source>, String) {
+        let query = self.query.lines().join(" ").trim().to_string();
+        match config::get().prefixes.parse(&query) {
+            Some((source, rest)) => (Some(source), rest.to_string()),
+            None => (None, query),
+        }
+    }
+
+    /// Whether `source` is searched for the current query: enabled in the
+    /// workspace and not excluded by a prefix.
+    pub fn searches(&self, source: Source) -> bool {
+        let (scope, _) = self.scoped_query();
+        // Inside a folder only its items are listed
+        self.containers.is_empty() && scope.is_none_or(|scoped| scoped == source) && self.source_enabled(source)
+    }
+
+    /// Note that a refresh of `target` was started.
+    pub fn start_refresh(&mut self, target: Option<Source>) {
+        if !self.refreshing.contains(&target) {
+            self.refreshing.push(target);
+        }
+    }
+
+    /// Take the items read by a refresh: everything for `None`, otherwise
+    /// only the items of that source are replaced.
+    pub fn finish_refresh(&mut self, target: Option<Source>, items: Vec<CommandItem>) {
+        match target {
+            Some(source) => {
+                self.items.retain(|item| Source::origin(item) != source);
+                self.items.extend(items);
+            }
+            None => self.items = items,
+        }
+        self.mark_dirty_repos();
+        self.items_generation += 1;
+        self.refreshing.retain(|refreshing| *refreshing != target);
+    }
+
+    /// Results of the new query are on time again, and the first one is
+    /// selected.
+    fn mark_query_edited(&mut self) {
+        self.query_edited = Instant::now();
+        self.settled = None;
+        self.query_changed = true;
+    }
+
+    /// Empty the query, as after launching an item, and list the history
+    /// again.
+    pub fn clear_query(&mut self) {
+        self.query.delete_line_by_end();
+        self.query.delete_line_by_head();
+        self.mark_query_edited();
+        self.filter_items();
+    }
+
+    /// Take the repositories found to have uncommitted changes.
+    pub fn set_dirty_repos(&mut self, dirty: HashSet<String>) {
+        self.dirty_repos = dirty;
+        self.mark_dirty_repos();
+        self.items_generation += 1;
+    }
+
+    fn mark_dirty_repos(&mut self) {
+        for item in self.items.iter_mut().filter(|item| repos::is_repo(item)) {
+            repos::set_dirty(item, self.dirty_repos.contains(&item.value));
+        }
+    }
+
+    /// Filter now, or defer until typing pauses when the profile asks for
+    /// it. Results of the edited query are on time again.
+    pub fn request_filter(&mut self) {
+        self.mark_query_edited();
+        if self.profile.filter_debounce_ms == 0 {
+            self.filter_items();
         } else {
-            if self.table_state.selected().is_none() {
-                self.table_state.select(Some(0));
+            self.pending_filter = Some(Instant::now());
+        }
+    }
+
+    /// Run a deferred filter once the debounce has elapsed.
+    /// Returns true when the results were rebuilt.
+    pub fn flush_pending_filter(&mut self) -> bool {
+        match self.pending_filter {
+            Some(since)
+                if since.elapsed() >= Duration::from_millis(self.profile.filter_debounce_ms) =>
+            {
+                self.pending_filter = None;
+                self.filter_items();
+                true
             }
+            _ => false,
         }
     }
 
+    /// Open the action menu on the selected item, if any.
+    pub fn open_action_menu(&mut self) {
+        self.action_menu = self.get_selected_item().cloned().map(ActionMenu::new);
+    }
+
     pub fn get_selected_item(&self) -> Option<&CommandItem> {
         self.table_state
             .selected()
             .and_then(|i| self.filtered_items.get(i))
     }
 
+    pub fn active_workspace(&self) -> Option<&Workspace> {
+        self.workspace.as_ref().and_then(|name| self.workspaces.get(name))
+    }
+
+    pub fn source_enabled(&self, source: Source) -> bool {
+        self.active_workspace().is_none_or(|w| w.allows(source))
+    }
+
+    /// Directories file search is scoped to (empty means the home directory).
+    pub fn search_roots(&self) -> Vec<PathBuf> {
+        self.active_workspace().map(|w| w.search_roots()).unwrap_or_default()
+    }
+
+    fn palette_settings(&self) -> palette::Settings {
+        palette::Settings {
+            theme: self.theme,
+            low_bandwidth: self.profile.remote,
+            incognito: self.incognito,
+        }
+    }
+
+    /// Check the history and the pins for apps, files and notes that are
+    /// gone. Returns whether the set of broken entries changed.
+    pub fn validate_entries(&mut self) -> bool {
+        let note_ids: HashSet<&str> = self
+            .items
+            .iter()
+            .filter(|item| item.handler == Handler::Note)
+            .map(|item| item.value.as_str())
+            .collect();
+        let pins = self.workspaces.values().flat_map(|workspace| workspace.pinned_items());
+        let broken: HashSet<String> = self
+            .history
+            .iter()
+            .cloned()
+            .chain(pins)
+            .filter(|item| history::is_broken(item, &note_ids))
+            .map(|item| item.value)
+            .collect();
+        let changed = broken != self.broken;
+        self.broken = broken;
+        changed
+    }
+
+    /// Remove the broken history entries. Pins come from the config file,
+    /// so the broken ones are listed for the user to remove.
+    fn clean_up_broken(&mut self) {
+        let before = self.history.len();
+        self.history.retain(|item| !self.broken.contains(&item.value));
+        let removed = before - self.history.len();
+        if let Err(e) = history::save_workspace_history(&self.history, self.workspace.as_deref()) {
+            self.set_error(format!("Failed to clean up history: {}", e));
+            return;
+        }
+        let broken_pins: Vec<String> = self
+            .workspaces
+            .iter()
+            .flat_map(|(name, workspace)| workspace.pins.iter().map(move |pin| (name, pin)))
+            .filter(|(_, pin)| self.broken.contains(&pin.value))
+            .map(|(name, pin)| format!("{} · {} ({})", name, pin.label, pin.value))
+            .collect();
+        self.broken.clear();
+        if broken_pins.is_empty() {
+            self.show_toast(format!("Removed {} broken entries", removed));
+        } else {
+            self.overlay = Some(Overlay {
+                title: format!("Removed {} broken history entries", removed),
+                text: format!("These pins are broken too; remove them from the config file:\n\n{}", broken_pins.join("\n")),
+            });
+        }
+    }
+
+    /// Confirm something in the status bar for a moment.
+    pub fn show_toast(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+    }
+
+    /// Drop the toast once it was shown long enough, returning whether
+    /// one was dropped.
+    pub fn expire_toast(&mut self) -> bool {
+        let expired = self.toast.as_ref().is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION);
+        if expired {
+            self.toast = None;
+        }
+        expired
+    }
+
+    /// Record an executed item in the active workspace's history slice.
+    pub fn add_to_history(&mut self, item: CommandItem) -> anyhow::Result<()> {
+        if self.incognito {
+            return Ok(());
+        }
+        let workspace = self.workspace.clone();
+        history::add_to_workspace_history(&mut self.history, item, workspace.as_deref())
+    }
+
+    pub fn run_internal(&mut self, command: InternalCommand) {
+        match command {
+            InternalCommand::SwitchWorkspace(name) => self.switch_workspace(name),
+            InternalCommand::TrackStart(description) => match time_tracking::start(&description) {
+                Ok(entry) => self.running_timer = Some(entry),
+                Err(e) => self.set_error(format!("Failed to start timer: {}", e)),
+            },
+            InternalCommand::TrackStop => match time_tracking::stop() {
+                Ok(_) => self.running_timer = None,
+                Err(e) => self.set_error(format!("Failed to stop timer: {}", e)),
+            },
+            InternalCommand::ToggleTheme => self.theme = self.theme.toggled(),
+            InternalCommand::ToggleProfile => {
+                self.profile = Profile::new(!self.profile.remote, self.profile.low_power);
+            }
+            InternalCommand::OpenConfig => {
+                if let Err(e) = config::ensure_config_file().and_then(|path| commands::open_target(&path.to_string_lossy())) {
+                    self.set_error(format!("Failed to open config: {}", e));
+                }
+            }
+            InternalCommand::ClearHistory => {
+                self.history.clear();
+                if let Err(e) = history::save_workspace_history(&self.history, self.workspace.as_deref()) {
+                    self.set_error(format!("Failed to clear history: {}", e));
+                }
+            }
+            InternalCommand::ToggleIncognito => self.incognito = !self.incognito,
+            InternalCommand::CleanUpBroken => self.clean_up_broken(),
+            InternalCommand::SetNoteFolder(value) => {
+                self.note_folder = NoteFolder::parse(&value);
+                self.show_toast(format!("New notes go to {}", self.note_folder));
+            }
+            // The input handler cleared the query before running the command
+            InternalCommand::SetQuery(query) => {
+                self.query.insert_str(query);
+            }
+            InternalCommand::EnterContainer(source, path) => {
+                self.containers.push(Container::new(source, &path));
+                self.query_changed = true;
+            }
+            InternalCommand::ShowHelp => {
+                let bindings = hotkeys::bindings(&self.workspaces, self.workspace.as_deref());
+                self.overlay = Some(Overlay {
+                    title: "Key bindings".to_string(),
+                    text: hotkeys::help_text(&config::get().keys, &bindings),
+                });
+            }
+            // Run in the background or by the input handler
+            InternalCommand::ScreenOcr
+            | InternalCommand::CreateNote(_)
+            | InternalCommand::CreateReminder(_)
+            | InternalCommand::Refresh(_)
+            | InternalCommand::TrustCatalog(_)
+            | InternalCommand::Quit => {}
+        }
+    }
+
+    /// The `env` of the active workspace, added to launched commands.
+    pub fn workspace_env(&self) -> BTreeMap<String, String> {
+        self.active_workspace().map(|workspace| workspace.env.clone()).unwrap_or_default()
+    }
+
+    fn switch_workspace(&mut self, name: Option<String>) {
+        if let Some(name) = &name {
+            if !self.workspaces.contains_key(name) {
+                self.set_error(format!("Unknown workspace: {}", name));
+                return;
+            }
+        }
+
+        self.workspace = name;
+        self.items_generation += 1;
+
+        self.history = history::load_workspace_history(self.workspace.as_deref()).unwrap_or_default();
+        self.fs_items.clear();
+        self.web_items.clear();
+        self.clear_error();
+        self.filter_items();
+    }
+
+    /// Put the AI's answer in place of "Thinking…". An answer coming after
+    /// the overlay was dismissed is dropped; an error is always shown.
+    pub fn finish_thinking(&mut self, answer: Result<String, String>) {
+        let title = self.thinking.take();
+        match (answer, title) {
+            (Ok(text), Some(title)) => self.overlay = Some(Overlay { title, text }),
+            (Ok(_), None) => {}
+            (Err(e), title) => {
+                if title.is_some() {
+                    self.overlay = None;
+                }
+                self.set_error(e);
+            }
+        }
+    }
+
     pub fn set_error(&mut self, error: String) {
         self.error_message = Some(error);
     }
@@ -153,6 +751,27 @@ impl<'a> AppState<'a> {
     }
 }
 
+/// A copy of `item` showing where `query` occurs in its note body, if it
+/// has one: inline, and at the top of the preview pane.
+fn with_body_snippet(item: &CommandItem, query: &str) -> CommandItem {
+    let mut item = item.clone();
+    // The title already shows the match
+    if item.label.to_lowercase().contains(&query.to_lowercase()) {
+        return item;
+    }
+    let snippet = item
+        .metadata
+        .get("body")
+        .and_then(|body| notes::body_snippet(body, query));
+    if let Some(snippet) = snippet {
+        let body = &item.metadata["body"];
+        let preview = format!("{}\n\n{}", snippet, body);
+        item.metadata.insert("preview".to_string(), preview);
+        item.metadata.insert("snippet".to_string(), snippet);
+    }
+    item
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,6 +892,47 @@ mod tests {
         assert!(labels.contains(&"web test"));
     }
 
+    #[test]
+    fn test_cwd_tasks() {
+        let indexed = create_test_item("make build (grinta)", Handler::Shell, "cd '/src/grinta' && make build");
+        let history = vec![create_test_item("Recent App", Handler::App, "recent")];
+        let mut state = AppState::new(history, vec![indexed.clone()]);
+        state.cwd_tasks = vec![
+            indexed,
+            create_test_item("make lint (grinta)", Handler::Shell, "cd '/src/grinta' && make lint"),
+        ];
+
+        // Listed first without a query
+        state.filter_items();
+        assert_eq!(state.filtered_items[0].label, "make build (grinta)");
+        assert_eq!(state.filtered_items[2].label, "Recent App");
+
+        // Also indexed as a repository's task, it is listed once
+        state.query.insert_str("make");
+        state.filter_items();
+        let tasks = state.filtered_items.iter().filter(|item| item.handler == Handler::Shell).count();
+        assert_eq!(tasks, 2);
+    }
+
+    #[test]
+    fn test_frecency_breaks_fuzzy_ties() {
+        let items = vec![
+            create_test_item("App A", Handler::App, "a"),
+            create_test_item("App B", Handler::App, "b"),
+        ];
+        let mut launched = create_test_item("App B", Handler::App, "b");
+        launched.mark_executed();
+        launched.launch_count = 50;
+
+        let mut state = AppState::new(vec![launched], items);
+        state.query.insert_str("app");
+        state.filter_items();
+
+        // Same fuzzy score: the frequently launched item wins over the label order
+        assert_eq!(state.filtered_items[0].label, "App B");
+        assert_eq!(state.filtered_items[1].label, "App A");
+    }
+
     #[test]
     fn test_local_vs_web_priority() {
         let items = vec![create_test_item("test app", Handler::App, "test")];
@@ -290,6 +950,103 @@ mod tests {
         assert_eq!(state.filtered_items[1].label, "test");
     }
 
+    #[test]
+    fn test_late_results_go_below() {
+        let items = vec![
+            create_test_item("Slack", Handler::App, "/Applications/Slack.app"),
+            create_test_item("Slides", Handler::App, "/Applications/Slides.app"),
+        ];
+        let mut state = AppState::new(vec![], items);
+        state.query.insert_str("sl");
+        state.request_filter();
+        state.filter_items();
+
+        // Within the budget, arrivals are merged by score
+        state.settle_results();
+        state.fs_items = vec![create_test_item("sl", Handler::File, "/tmp/sl")];
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 3);
+        assert_eq!(state.late_from, None);
+        let on_screen: Vec<String> = state.filtered_items.iter().map(|item| item.label.clone()).collect();
+
+        // Past it, the results on screen keep their place
+        state.query_edited = Instant::now() - Duration::from_secs(1);
+        state.settle_results();
+        state.web_items = vec![create_web_item("sl")];
+        state.filter_items();
+        let labels: Vec<String> = state.filtered_items.iter().map(|item| item.label.clone()).collect();
+        assert_eq!(labels[..3], on_screen[..]);
+        assert_eq!(labels.len(), 4);
+        assert_eq!(state.late_from, Some(3));
+
+        // Editing the query merges everything again
+        state.query.insert_str("a");
+        state.request_filter();
+        state.filter_items();
+        assert_eq!(state.late_from, None);
+    }
+
+    #[test]
+    fn test_cleared_query_has_no_late_results() {
+        let mut state = AppState::new(vec![], vec![create_test_item("Slack", Handler::App, "/Applications/Slack.app")]);
+        state.history = vec![
+            create_test_item("Slides", Handler::App, "/Applications/Slides.app"),
+            create_test_item("Slack", Handler::App, "/Applications/Slack.app"),
+        ];
+        state.query.insert_str("sla");
+        state.request_filter();
+        state.filter_items();
+        state.query_edited = Instant::now() - Duration::from_secs(1);
+        state.settle_results();
+
+        // Launching clears the query: the history is listed whole
+        state.clear_query();
+        assert_eq!(state.filtered_items.len(), 2);
+        assert_eq!(state.late_from, None);
+        assert!(state.settled.is_none());
+
+        // Even when filtered again without an edit
+        state.query.insert_str("x");
+        state.filter_items();
+        state.query.delete_line_by_head();
+        state.settle_results();
+        state.filter_items();
+        assert_eq!(state.late_from, None);
+    }
+
+    #[test]
+    fn test_selection_follows_item() {
+        let items = vec![
+            create_test_item("Slack", Handler::App, "/Applications/Slack.app"),
+            create_test_item("Slides", Handler::App, "/Applications/Slides.app"),
+        ];
+        let mut state = AppState::new(vec![], items);
+        state.query.insert_str("sl");
+        state.request_filter();
+        state.filter_items();
+        state.table_state.select(Some(1));
+        let selected = state.get_selected_item().unwrap().clone();
+
+        // A better match arriving pushes the selected item down
+        state.fs_items = vec![create_test_item("sl", Handler::File, "/tmp/sl")];
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 3);
+        assert_eq!(state.get_selected_item(), Some(&selected));
+
+        // Once it is gone, the first result is selected
+        state.items.retain(|item| item.value != selected.value);
+        state.items_generation += 1;
+        state.filter_items();
+        assert_eq!(state.table_state.selected(), Some(0));
+
+        // Editing the query starts from the first result
+        state.table_state.select(Some(1));
+        state.query.insert_str("a");
+        state.request_filter();
+        state.filter_items();
+        assert_eq!(state.table_state.selected(), Some(0));
+    }
+
     #[test]
     fn test_get_selected_item() {
         // Test with history items (which are shown when query is empty)
@@ -402,6 +1159,423 @@ mod tests {
         assert_eq!(state.filtered_items, first_run);
     }
 
+    #[test]
+    fn test_request_filter_immediate_by_default() {
+        let items = vec![create_test_item("Cursor", Handler::App, "cursor")];
+        let mut state = AppState::new(vec![], items);
+
+        state.query.insert_str("cur");
+        state.request_filter();
+
+        assert!(state.pending_filter.is_none());
+        assert_eq!(state.filtered_items.len(), 1);
+    }
+
+    #[test]
+    fn test_request_filter_deferred_for_remote_profile() {
+        let items = vec![create_test_item("Cursor", Handler::App, "cursor")];
+        let mut state = AppState::new(vec![], items);
+        state.profile = Profile::remote();
+
+        state.query.insert_str("cur");
+        state.request_filter();
+
+        // Nothing is rebuilt until the debounce elapses
+        assert!(state.pending_filter.is_some());
+        assert!(state.filtered_items.is_empty());
+        assert!(!state.flush_pending_filter());
+
+        std::thread::sleep(Duration::from_millis(state.profile.filter_debounce_ms + 10));
+        assert!(state.flush_pending_filter());
+        assert!(state.pending_filter.is_none());
+        assert_eq!(state.filtered_items.len(), 1);
+    }
+
+    fn create_workspace_state() -> AppState<'static> {
+        let items = vec![
+            create_test_item("Report App", Handler::App, "report-app"),
+            create_test_item("report.pdf", Handler::File, "/tmp/report.pdf"),
+        ];
+        let mut state = AppState::new(vec![], items);
+        state.workspaces.clear();
+        state.workspaces.insert(
+            "clientx".to_string(),
+            Workspace {
+                sources: vec![Source::Files],
+                pins: vec![crate::workspace::Pin {
+                    label: "Client wiki".to_string(),
+                    value: "https://wiki.example.com".to_string(),
+                    handler: Handler::Url,
+                    hotkey: None,
+                }],
+                ..Workspace::default()
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn test_workspace_command_lists_workspaces() {
+        let mut state = create_workspace_state();
+
+        state.query.insert_str("ws cl");
+        state.filter_items();
+
+        assert_eq!(state.filtered_items.len(), 1);
+        assert_eq!(state.filtered_items[0].handler, Handler::Internal);
+        assert_eq!(state.filtered_items[0].value, "workspace:clientx");
+    }
+
+    #[test]
+    fn test_workspace_scopes_sources_and_pins() {
+        let mut state = create_workspace_state();
+        state.workspace = Some("clientx".to_string());
+
+        // Pins are shown first on the empty query
+        state.filter_items();
+        assert_eq!(state.filtered_items[0].label, "Client wiki");
+
+        // Only enabled sources are searched
+        state.query.insert_str("report");
+        state.filter_items();
+        let labels: Vec<&str> = state.filtered_items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["report.pdf"]);
+        assert!(!state.source_enabled(Source::Apps));
+    }
+
+    #[test]
+    fn test_workspace_env() {
+        let mut state = create_workspace_state();
+        let workspace = state.workspaces.get_mut("clientx").unwrap();
+        workspace.env.insert("JIRA_PROJECT".to_string(), "CX".to_string());
+        assert!(state.workspace_env().is_empty());
+
+        state.switch_workspace(Some("clientx".to_string()));
+        assert_eq!(state.workspace_env()["JIRA_PROJECT"], "CX");
+        // Kept for launched commands, not put into Grinta's environment
+        assert!(std::env::var("JIRA_PROJECT").is_err());
+    }
+
+    #[test]
+    fn test_dirty_repos_survive_refresh() {
+        let mut repo = create_test_item("grinta", Handler::Folder, "/src/grinta");
+        repo.metadata.insert("repo".to_string(), "true".to_string());
+        let mut state = AppState::new(vec![], vec![repo.clone()]);
+        state.set_dirty_repos(HashSet::from(["/src/grinta".to_string()]));
+        assert!(state.items[0].metadata.contains_key("dirty"));
+
+        // The apps come back before the repositories are checked again
+        state.finish_refresh(Some(Source::Apps), vec![repo]);
+        assert!(state.items[0].metadata.contains_key("dirty"));
+        state.set_dirty_repos(HashSet::new());
+        assert!(!state.items[0].metadata.contains_key("dirty"));
+    }
+
+    #[test]
+    fn test_today_keeps_other_results() {
+        let mut state = AppState::new(vec![], vec![create_test_item("Today Widget", Handler::App, "today")]);
+        state.query.insert_str("today");
+        state.filter_items();
+        assert!(state.filtered_items.iter().any(|item| item.label == "Today Widget"));
+        // The daily note is only offered where Notes are
+        let daily = state.filtered_items.iter().any(|item| item.label.starts_with("Create today's note"));
+        assert_eq!(daily, cfg!(target_os = "macos"));
+    }
+
+    #[test]
+    fn test_finish_thinking() {
+        let mut state = AppState::new(vec![], vec![]);
+        let thinking = Overlay { title: "🦙 llama3".to_string(), text: "Thinking…".to_string() };
+        state.overlay = Some(thinking.clone());
+        state.thinking = Some(thinking.title.clone());
+        state.finish_thinking(Err("Ollama is not running".to_string()));
+        assert!(state.overlay.is_none());
+        assert_eq!(state.error_message.as_deref(), Some("Ollama is not running"));
+
+        // Dismissed before the answer came
+        state.finish_thinking(Ok("42".to_string()));
+        assert!(state.overlay.is_none());
+
+        state.thinking = Some(thinking.title.clone());
+        state.finish_thinking(Ok("42".to_string()));
+        assert_eq!(state.overlay.as_ref().map(|o| o.text.as_str()), Some("42"));
+    }
+
+    #[test]
+    fn test_track_command_items() {
+        let mut state = AppState::new(vec![], vec![create_test_item("Tracker", Handler::App, "tracker")]);
+
+        state.query.insert_str("track start deep work");
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 1);
+        assert_eq!(state.filtered_items[0].value, "track:start:deep work");
+
+        // Without a running timer there is nothing to stop
+        state.query.select_all();
+        state.query.cut();
+        state.query.insert_str("track stop");
+        state.filter_items();
+        assert!(state.filtered_items.is_empty());
+    }
+
+    #[test]
+    fn test_calculator_result_ranked_first() {
+        let items = vec![create_test_item("12 Monkeys", Handler::File, "/movies/12*7.mkv")];
+        let mut state = AppState::new(vec![], items);
+
+        state.query.insert_str("12*7");
+        state.filter_items();
+
+        assert_eq!(state.filtered_items[0].handler, Handler::Copy);
+        assert_eq!(state.filtered_items[0].value, "84");
+    }
+
+    #[test]
+    fn test_answer_items_ranked_first() {
+        let items = vec![create_test_item("Weather App", Handler::App, "weather-app")];
+        let mut state = AppState::new(vec![], items);
+        let mut answer = create_test_item("Berlin: 12°C, Partly cloudy", Handler::Copy, "Berlin");
+        answer.metadata.insert("preview".to_string(), "Berlin".to_string());
+        state.answer_items = vec![answer];
+
+        state.query.insert_str("weather berlin");
+        state.filter_items();
+
+        assert_eq!(state.filtered_items[0].label, "Berlin: 12°C, Partly cloudy");
+        assert!(state.get_selected_item().unwrap().metadata.contains_key("preview"));
+    }
+
+    #[test]
+    fn test_long_unmatched_query_offers_text_transforms() {
+        let items = vec![create_test_item("Firefox", Handler::App, "firefox")];
+        let mut state = AppState::new(vec![], items);
+
+        state.query.insert_str("zq xv wk jj ploo");
+        state.filter_items();
+        assert_eq!(state.filtered_items[0].handler, Handler::Transform);
+        assert_eq!(state.filtered_items[0].value, "zqXvWkJjPloo");
+
+        state.query = TextArea::default();
+        state.query.insert_str("firefox");
+        state.filter_items();
+        assert!(state.filtered_items.iter().all(|i| i.handler != Handler::Transform));
+    }
+
+    #[test]
+    fn test_open_action_menu_on_selection() {
+        let items = vec![create_test_item("notes.txt", Handler::File, "/tmp/notes.txt")];
+        let mut state = AppState::new(vec![], items);
+
+        state.table_state.select(None);
+        state.open_action_menu();
+        assert!(state.action_menu.is_none());
+
+        state.query.insert_str("notes");
+        state.filter_items();
+        state.open_action_menu();
+        let menu = state.action_menu.as_ref().unwrap();
+        assert_eq!(menu.item.value, "/tmp/notes.txt");
+        assert_eq!(menu.actions[0], crate::actions::Action::Open);
+    }
+
+    #[test]
+    fn test_track_number_is_package_tracking() {
+        let mut state = AppState::new(vec![], vec![]);
+
+        state.query.insert_str("track 1Z999AA10123456784");
+        state.filter_items();
+
+        assert_eq!(state.filtered_items.len(), 1);
+        assert_eq!(state.filtered_items[0].handler, Handler::Url);
+        assert!(state.filtered_items[0].value.starts_with("https://www.ups.com/"));
+    }
+
+    #[test]
+    fn test_targeted_refresh_replaces_source_items() {
+        let bookmark = |label: &str, value: &str| {
+            let mut item = CommandItem::new(label, Handler::Url, value);
+            item.kind = CommandType::Bookmark;
+            item
+        };
+        let items = vec![
+            create_test_item("Firefox", Handler::App, "/Applications/Firefox.app"),
+            bookmark("Old Bookmark", "https://old.example.com"),
+        ];
+        let mut state = AppState::new(vec![], items);
+        state.start_refresh(Some(Source::Bookmarks));
+        state.start_refresh(Some(Source::Bookmarks));
+        assert_eq!(state.refreshing, vec![Some(Source::Bookmarks)]);
+
+        let bookmarks = vec![bookmark("New Bookmark", "https://new.example.com")];
+        state.finish_refresh(Some(Source::Bookmarks), bookmarks);
+
+        let labels: Vec<&str> = state.items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["Firefox", "New Bookmark"]);
+        assert!(state.refreshing.is_empty());
+
+        state.finish_refresh(None, vec![]);
+        assert!(state.items.is_empty());
+    }
+
+    #[test]
+    fn test_apps_refresh_keeps_other_sources_commands() {
+        let mut firefox = create_test_item("Firefox", Handler::App, "/Applications/Firefox.app");
+        Source::Apps.tag(&mut firefox);
+        let mut reload = create_test_item("Reload notes", Handler::Internal, "refresh:notes");
+        Source::Notes.tag(&mut reload);
+        let mut state = AppState::new(vec![], vec![firefox, reload]);
+
+        let mut safari = create_test_item("Safari", Handler::App, "/Applications/Safari.app");
+        Source::Apps.tag(&mut safari);
+        state.finish_refresh(Some(Source::Apps), vec![safari]);
+
+        let labels: Vec<&str> = state.items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["Reload notes", "Safari"]);
+    }
+
+    #[test]
+    fn test_refresh_rescores_cached_matches() {
+        let mut state = AppState::new(vec![], vec![create_test_item("Firefox", Handler::App, "firefox")]);
+        state.query.insert_str("safari");
+        state.filter_items();
+        assert!(state.filtered_items.is_empty());
+
+        state.finish_refresh(None, vec![create_test_item("Safari", Handler::App, "safari")]);
+        state.filter_items();
+        assert_eq!(state.filtered_items[0].label, "Safari");
+    }
+
+    #[test]
+    fn test_refresh_commands_are_searchable() {
+        let mut state = AppState::new(vec![], vec![]);
+        state.query.insert_str("refresh bookmarks");
+        state.filter_items();
+
+        let item = state.get_selected_item().unwrap();
+        assert_eq!(item.handler, Handler::Internal);
+        assert_eq!(
+            InternalCommand::parse(&item.value),
+            Some(InternalCommand::Refresh(Some(Source::Bookmarks)))
+        );
+    }
+
+    #[test]
+    fn test_palette_toggles() {
+        let mut state = AppState::new(vec![], vec![]);
+        state.query.insert_str("grinta: incognito");
+        state.filter_items();
+        assert_eq!(state.filtered_items[0].label, "Turn incognito on (stop recording history)");
+
+        state.run_internal(InternalCommand::ToggleIncognito);
+        state.run_internal(InternalCommand::ToggleTheme);
+        state.run_internal(InternalCommand::ToggleProfile);
+        assert!(state.incognito);
+        assert_eq!(state.theme, Theme::Light);
+        assert_eq!(state.profile, Profile::remote());
+
+        state.add_to_history(create_test_item("Secret", Handler::App, "secret")).unwrap();
+        assert!(state.history.is_empty());
+    }
+
+    #[test]
+    fn test_prefix_scopes_results() {
+        let mut bookmark = CommandItem::new("Rust docs", Handler::Url, "https://doc.rust-lang.org");
+        bookmark.kind = CommandType::Bookmark;
+        let items = vec![create_test_item("RustRover", Handler::App, "/Applications/RustRover.app"), bookmark];
+        let mut state = AppState::new(vec![], items);
+
+        state.query.insert_str("rust");
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 2);
+
+        state.query = TextArea::default();
+        state.query.insert_str("b rust");
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 1);
+        assert_eq!(state.filtered_items[0].label, "Rust docs");
+        assert!(state.searches(Source::Bookmarks));
+        assert!(!state.searches(Source::Files));
+        assert_eq!(state.scoped_query(), (Some(Source::Bookmarks), "rust".to_string()));
+    }
+
+    #[test]
+    fn test_drill_into_container() {
+        let in_folder = |label: &str, folder: &str| {
+            let mut item = CommandItem::new(label, Handler::Url, &format!("https://{}", label));
+            item.metadata.insert("folder".to_string(), folder.to_string());
+            item
+        };
+        let items = vec![
+            in_folder("Rust docs", "Bar/Rust"),
+            in_folder("Rust blog", "Bar"),
+            create_test_item("RustRover", Handler::App, "/Applications/RustRover.app"),
+        ];
+        let mut state = AppState::new(vec![], items);
+
+        // Folders are found like any other item
+        state.query.insert_str("rust");
+        state.filter_items();
+        let folder = state
+            .filtered_items
+            .iter()
+            .find(|item| item.label == "Rust (Bookmark folder)")
+            .cloned()
+            .unwrap();
+
+        state.query = TextArea::default();
+        state.run_internal(InternalCommand::parse(&folder.value).unwrap());
+        state.filter_items();
+        let labels: Vec<&str> = state.filtered_items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["Rust docs"]);
+        assert!(!state.searches(Source::Files));
+
+        // Going back up to where the folder was found
+        state.run_internal(InternalCommand::EnterContainer(Source::Bookmarks, "Bar".to_string()));
+        state.query.insert_str("rust");
+        state.filter_items();
+        assert_eq!(state.filtered_items.len(), 3);
+        assert!(state.filtered_items.iter().all(|item| item.label != "RustRover"));
+
+        assert!(state.leave_container());
+        assert_eq!(state.containers, vec![Container::new(Source::Bookmarks, "Bar/Rust")]);
+        assert!(state.leave_container());
+        assert!(!state.leave_container());
+        assert!(state.filtered_items.iter().any(|item| item.label == "RustRover"));
+    }
+
+    #[test]
+    fn test_broken_entries_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.pdf");
+        std::fs::write(&report, "").unwrap();
+        let deleted = dir.path().join("report-old.pdf");
+        let history = vec![
+            create_test_item("report-old.pdf", Handler::File, deleted.to_str().unwrap()),
+            create_test_item("report.pdf", Handler::File, report.to_str().unwrap()),
+        ];
+        let mut state = AppState::new(history.clone(), history);
+        assert!(state.validate_entries());
+        assert!(!state.validate_entries());
+        assert!(state.broken.contains(deleted.to_str().unwrap()));
+
+        state.filter_items();
+        let labels: Vec<&str> = state.filtered_items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels.iter().position(|label| *label == "report-old.pdf"), Some(labels.len() - 1));
+
+        state.query.insert_str("report");
+        state.filter_items();
+        assert_eq!(state.filtered_items.last().unwrap().label, "report-old.pdf");
+    }
+
+    #[test]
+    fn test_switch_to_unknown_workspace() {
+        let mut state = create_workspace_state();
+        state.run_internal(InternalCommand::SwitchWorkspace(Some("nope".to_string())));
+
+        assert!(state.workspace.is_none());
+        assert_eq!(state.error_message, Some("Unknown workspace: nope".to_string()));
+    }
+
     #[test]
     fn test_mixed_handler_types() {
         let items = vec![
@@ -0,0 +1,142 @@
+use crate::core::Handler;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+/// Colors suited to the terminal's background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// The `theme` setting: a fixed theme, or the one matching the system's
+/// appearance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// Dark or light as the system is, following it while Grinta runs
+    Auto,
+}
+
+impl ThemeMode {
+    /// The theme to start with, before the system's appearance is known.
+    pub fn initial(self) -> Theme {
+        match self {
+            ThemeMode::Light => Theme::Light,
+            ThemeMode::Dark | ThemeMode::Auto => Theme::Dark,
+        }
+    }
+}
+
+impl Theme {
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Theme::Dark => Theme::Light,
+            Theme::Light => Theme::Dark,
+        }
+    }
+
+    /// Characters of a label that matched the query.
+    pub fn match_style(self) -> Style {
+        let color = match self {
+            Theme::Dark => Color::Yellow,
+            // Yellow is unreadable on a light background
+            Theme::Light => Color::Blue,
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// The selected row of the results and the action menu.
+    pub fn selection_style(self) -> Style {
+        match self {
+            Theme::Dark => Style::default().add_modifier(Modifier::REVERSED),
+            Theme::Light => Style::default().bg(Color::LightCyan).fg(Color::Black),
+        }
+    }
+}
+
+impl Theme {
+    /// The badge of a handler, colored by the kind of thing it opens.
+    pub fn badge_style(self, handler: Handler) -> Style {
+        let (dark, light) = match handler {
+            Handler::App => (Color::LightBlue, Color::Blue),
+            Handler::File | Handler::Folder => (Color::LightGreen, Color::Green),
+            Handler::Note | Handler::Reminder => (Color::LightYellow, Color::Yellow),
+            Handler::Url | Handler::Wifi | Handler::Kube | Handler::Database => (Color::LightCyan, Color::Cyan),
+            Handler::Automation | Handler::Plugin => (Color::LightMagenta, Color::Magenta),
+            Handler::Shell | Handler::Process | Handler::System | Handler::Ssh => (Color::LightRed, Color::Red),
+            Handler::Internal | Handler::Copy | Handler::Transform => (Color::Gray, Color::DarkGray),
+        };
+        match self {
+            Theme::Dark => Style::default().bg(dark).fg(Color::Black),
+            Theme::Light => Style::default().bg(light).fg(Color::White),
+        }
+    }
+}
+
+/// How much room each result takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// One line per result
+    #[default]
+    Compact,
+    /// A second, dimmed line with the path or URL, or the rest of a long
+    /// label
+    Comfortable,
+}
+
+impl Density {
+    /// Lines of a result row.
+    pub fn row_height(self) -> u16 {
+        match self {
+            Density::Compact => 1,
+            Density::Comfortable => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggled() {
+        assert_eq!(Theme::default(), Theme::Dark);
+        assert_eq!(Theme::Dark.toggled(), Theme::Light);
+        assert_eq!(Theme::Light.toggled().name(), "dark");
+    }
+
+    #[test]
+    fn test_theme_mode() {
+        assert_eq!(ThemeMode::default().initial(), Theme::Dark);
+        assert_eq!(ThemeMode::Light.initial(), Theme::Light);
+        assert_eq!(ThemeMode::Auto.initial(), Theme::Dark);
+    }
+
+    #[test]
+    fn test_badge_style() {
+        assert_eq!(Theme::Dark.badge_style(Handler::App).bg, Some(Color::LightBlue));
+        assert_eq!(Theme::Light.badge_style(Handler::App).bg, Some(Color::Blue));
+        assert_eq!(Theme::Dark.badge_style(Handler::File), Theme::Dark.badge_style(Handler::Folder));
+        assert_ne!(Theme::Dark.badge_style(Handler::Note), Theme::Dark.badge_style(Handler::Url));
+    }
+
+    #[test]
+    fn test_density() {
+        assert_eq!(Density::default().row_height(), 1);
+        assert_eq!(Density::Comfortable.row_height(), 2);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::config;
+use crate::core::CommandItem;
+use crate::internal::InternalCommand;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const TIME_LOG_FILE: &str = "grinta_time_log.json";
+const TOGGL_API: &str = "https://api.track.toggl.com/api/v9";
+
+/// Optional Toggl Track integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeTrackingConfig {
+    pub toggl_token: Option<String>,
+    pub toggl_workspace_id: Option<u64>,
+}
+
+/// One timer in the local time log; `end` is None while it runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub description: String,
+    pub start: DateTime<Local>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Local>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toggl_id: Option<u64>,
+}
+
+impl TimeEntry {
+    pub fn elapsed(&self, now: DateTime<Local>) -> Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+fn time_log_path() -> Result<PathBuf> {
+    let mut path = dirs::data_dir().context("Failed to get data directory")?;
+    path.push("grinta-rs");
+    fs::create_dir_all(&path)?;
+    path.push(TIME_LOG_FILE);
+    Ok(path)
+}
+
+pub fn load_entries() -> Result<Vec<TimeEntry>> {
+    let path = time_log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path)?;
+    // An unreadable log is an error, so it is never saved over
+    serde_json::from_str(&contents).with_context(|| format!("The time log {} is damaged", path.display()))
+}
+
+fn save_entries(entries: &[TimeEntry]) -> Result<()> {
+    fs::write(time_log_path()?, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+/// The timer currently running, if any.
+pub fn running_entry() -> Result<Option<TimeEntry>> {
+    Ok(load_entries()?.into_iter().rev().find(|e| e.end.is_none()))
+}
+
+/// Stop any running timer and start a new one.
+fn start_entry(entries: &mut Vec<TimeEntry>, description: &str, now: DateTime<Local>) -> TimeEntry {
+    stop_entry(entries, now);
+    let entry = TimeEntry {
+        description: description.to_string(),
+        start: now,
+        end: None,
+        toggl_id: None,
+    };
+    entries.push(entry.clone());
+    entry
+}
+
+fn stop_entry(entries: &mut [TimeEntry], now: DateTime<Local>) -> Option<TimeEntry> {
+    let running = entries.iter_mut().rev().find(|e| e.end.is_none())?;
+    running.end = Some(now);
+    Some(running.clone())
+}
+
+/// Start a timer in the local log (and in Toggl when configured).
+pub fn start(description: &str) -> Result<TimeEntry> {
+    let mut entries = load_entries()?;
+    let previous = stop_entry(&mut entries, Local::now());
+    let entry = start_entry(&mut entries, description, Local::now());
+    save_entries(&entries)?;
+
+    if let Some(previous) = previous {
+        sync_toggl_stop(previous);
+    }
+    sync_toggl_start(entry.clone());
+    Ok(entry)
+}
+
+/// Stop the running timer, returning it.
+pub fn stop() -> Result<Option<TimeEntry>> {
+    let mut entries = load_entries()?;
+    let stopped = stop_entry(&mut entries, Local::now());
+    save_entries(&entries)?;
+
+    if let Some(stopped) = &stopped {
+        sync_toggl_stop(stopped.clone());
+    }
+    Ok(stopped)
+}
+
+/// Total time per description for entries started after `since`,
+/// longest first.
+pub fn summarize(
+    entries: &[TimeEntry],
+    since: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Vec<(String, Duration)> {
+    let mut totals: HashMap<&str, Duration> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.start >= since) {
+        *totals.entry(entry.description.as_str()).or_insert_with(Duration::zero) += entry.elapsed(now);
+    }
+
+    let mut summary: Vec<(String, Duration)> = totals
+        .into_iter()
+        .map(|(description, total)| (description.to_string(), total))
+        .collect();
+    summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary
+}
+
+/// `1:02:03` style clock for the status bar.
+pub fn format_clock(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!("{}:{:02}:{:02}", seconds / 3600, (seconds % 3600) / 60, seconds % 60)
+}
+
+/// `2h 05m` style total for reports.
+pub fn format_total(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Items for a `track start <description>` / `track stop` query, or None
+/// when the query is not a time tracking command.
+pub fn command_items(query: &str, running: Option<&TimeEntry>) -> Option<Vec<CommandItem>> {
+    let rest = query.strip_prefix("track ")?.trim_start();
+    let (action, argument) = rest.split_once(' ').unwrap_or((rest, ""));
+    let argument = argument.trim().trim_matches('"');
+
+    let mut items = Vec::new();
+    match action {
+        "start" if !argument.is_empty() => {
+            let label = format!("Start tracking \"{}\"", argument);
+            items.push(InternalCommand::TrackStart(argument.to_string()).to_item(&label));
+        }
+        "start" => {}
+        "stop" => {
+            if let Some(entry) = running {
+                let label = format!(
+                    "Stop tracking \"{}\" ({})",
+                    entry.description,
+                    format_clock(entry.elapsed(Local::now()))
+                );
+                items.push(InternalCommand::TrackStop.to_item(&label));
+            }
+        }
+        _ => return None,
+    }
+
+    for item in &mut items {
+        item.icon = "⏱".to_string();
+    }
+    Some(items)
+}
+
+fn toggl_settings() -> Option<(String, u64)> {
+    let settings = &config::get().time_tracking;
+    Some((settings.toggl_token.clone()?, settings.toggl_workspace_id?))
+}
+
+/// Mirror a started timer to Toggl in the background and remember its id.
+fn sync_toggl_start(entry: TimeEntry) {
+    let Some((token, workspace_id)) = toggl_settings() else {
+        return;
+    };
+    if tokio::runtime::Handle::try_current().is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        let body = json!({
+            "created_with": "grinta",
+            "description": entry.description,
+            "duration": -1,
+            "start": entry.start.with_timezone(&Utc).to_rfc3339(),
+            "workspace_id": workspace_id,
+        });
+        let response = reqwest::Client::new()
+            .post(format!("{}/workspaces/{}/time_entries", TOGGL_API, workspace_id))
+            .basic_auth(token, Some("api_token"))
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        let Ok(response) = response else {
+            return;
+        };
+        let Some(toggl_id) = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|v| v["id"].as_u64())
+        else {
+            return;
+        };
+        if let Ok(mut entries) = load_entries() {
+            if let Some(local) = entries.iter_mut().find(|e| e.start == entry.start) {
+                local.toggl_id = Some(toggl_id);
+                let _ = save_entries(&entries);
+            }
+        }
+    });
+}
+
+/// Stop the Toggl copy of a timer in the background.
+fn sync_toggl_stop(entry: TimeEntry) {
+    let (Some((token, workspace_id)), Some(toggl_id)) = (toggl_settings(), entry.toggl_id) else {
+        return;
+    };
+    if tokio::runtime::Handle::try_current().is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        let _ = reqwest::Client::new()
+            .patch(format!(
+                "{}/workspaces/{}/time_entries/{}/stop",
+                TOGGL_API, workspace_id, toggl_id
+            ))
+            .basic_auth(token, Some("api_token"))
+            .send()
+            .await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Handler;
+    use serial_test::serial;
+    use std::env;
+    use tempfile::TempDir;
+
+    fn entry(description: &str, start: DateTime<Local>, minutes: i64) -> TimeEntry {
+        TimeEntry {
+            description: description.to_string(),
+            start,
+            end: Some(start + Duration::minutes(minutes)),
+            toggl_id: None,
+        }
+    }
+
+    #[test]
+    fn test_start_stops_running_entry() {
+        let now = Local::now();
+        let mut entries = vec![];
+
+        start_entry(&mut entries, "writing", now);
+        start_entry(&mut entries, "review", now + Duration::minutes(30));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].end, Some(now + Duration::minutes(30)));
+        assert!(entries[1].end.is_none());
+
+        let stopped = stop_entry(&mut entries, now + Duration::minutes(45)).unwrap();
+        assert_eq!(stopped.description, "review");
+        assert!(stop_entry(&mut entries, now).is_none());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let now = Local::now();
+        let entries = vec![
+            entry("old", now - Duration::days(30), 600),
+            entry("writing", now - Duration::hours(5), 60),
+            entry("review", now - Duration::hours(3), 30),
+            entry("writing", now - Duration::hours(2), 45),
+        ];
+
+        let summary = summarize(&entries, now - Duration::days(7), now);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0], ("writing".to_string(), Duration::minutes(105)));
+        assert_eq!(summary[1], ("review".to_string(), Duration::minutes(30)));
+    }
+
+    #[test]
+    fn test_format_durations() {
+        assert_eq!(format_clock(Duration::seconds(3723)), "1:02:03");
+        assert_eq!(format_total(Duration::minutes(125)), "2h 05m");
+    }
+
+    #[test]
+    fn test_command_items() {
+        let start = command_items("track start \"writing report\"", None).unwrap();
+        assert_eq!(start.len(), 1);
+        assert_eq!(start[0].handler, Handler::Internal);
+        assert_eq!(start[0].label, "Start tracking \"writing report\"");
+
+        assert!(command_items("track stop", None).unwrap().is_empty());
+
+        let running = entry("writing", Local::now(), 0);
+        let stop = command_items("track stop", Some(&TimeEntry { end: None, ..running })).unwrap();
+        assert_eq!(stop.len(), 1);
+        assert!(stop[0].label.starts_with("Stop tracking \"writing\""));
+
+        // Anything else is left to other sources
+        assert!(command_items("track 1Z999AA10123456784", None).is_none());
+        assert!(command_items("tracks", None).is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_start_and_stop_persist() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path());
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+
+        assert!(running_entry().unwrap().is_none());
+
+        start("writing report").unwrap();
+        let running = running_entry().unwrap().unwrap();
+        assert_eq!(running.description, "writing report");
+
+        let stopped = stop().unwrap().unwrap();
+        assert_eq!(stopped.description, "writing report");
+        assert!(running_entry().unwrap().is_none());
+        assert_eq!(load_entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_damaged_log_is_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        env::set_var("HOME", temp_dir.path());
+        env::set_var("XDG_DATA_HOME", temp_dir.path());
+        let path = time_log_path().unwrap();
+        fs::write(&path, "[{\"description\": ").unwrap();
+
+        assert!(start("writing report").is_err());
+        assert!(stop().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[{\"description\": ");
+    }
+}
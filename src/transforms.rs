@@ -0,0 +1,462 @@
+use crate::clipboard;
+use crate::core::{CommandItem, Handler};
+use base64::Engine;
+use md5::Md5;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+
+const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
+    eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis \
+    nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure \
+    dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. \
+    Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit \
+    anim id est laborum.";
+
+/// Most words `lorem N` generates.
+const MAX_LOREM_WORDS: usize = 1000;
+
+/// Prefix applying every text manipulation to the rest of the query.
+const TEXT_PREFIX: &str = "text:";
+
+/// Transforms offered for `text:` queries and long free-text queries.
+const TEXT_TRANSFORMS: [&str; 6] = ["camel", "snake", "kebab", "title", "slug", "count"];
+
+/// Queries with at least this many words that match nothing else are
+/// treated as text to manipulate.
+const LONG_QUERY_WORDS: usize = 4;
+
+/// Longest result shown in an item label; the full result is in the preview.
+const LABEL_PREVIEW_CHARS: usize = 60;
+
+/// What a transform works on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// The text after the command, or the clipboard when there is none
+    Text,
+    /// Optional arguments only (generators such as `uuid`)
+    Arguments,
+}
+
+/// The clipboard as read when a query started needing it, kept until the
+/// query no longer does so it isn't read on every keystroke.
+static CLIPBOARD: Lazy<Mutex<Option<Option<String>>>> = Lazy::new(|| Mutex::new(None));
+
+/// The clipboard text in `kept`, read with `read` only if not already there.
+fn kept_clipboard(kept: &Mutex<Option<Option<String>>>, read: impl FnOnce() -> Option<String>) -> Option<String> {
+    kept.lock().unwrap().get_or_insert_with(read).clone()
+}
+
+fn clipboard_text() -> Option<String> {
+    kept_clipboard(&CLIPBOARD, || clipboard::paste().ok().filter(|text| !text.is_empty()))
+}
+
+/// A named text transformation, invoked as `<name> <text>`.
+#[derive(Clone)]
+pub struct Transform {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input: Input,
+    pub apply: fn(&str) -> Result<String, String>,
+}
+
+/// The transforms available from the search box. Built-ins are registered
+/// by `with_builtins`; other modules add theirs with `register`.
+#[derive(Clone, Default)]
+pub struct TransformRegistry {
+    transforms: Vec<Transform>,
+}
+
+impl TransformRegistry {
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::default();
+        registry.register(Transform { name: "base64", description: "Base64 encode", input: Input::Text, apply: base64_encode });
+        registry.register(Transform { name: "unbase64", description: "Base64 decode", input: Input::Text, apply: base64_decode });
+        registry.register(Transform { name: "urlencode", description: "URL encode", input: Input::Text, apply: url_encode });
+        registry.register(Transform { name: "urldecode", description: "URL decode", input: Input::Text, apply: url_decode });
+        registry.register(Transform { name: "json", description: "Pretty-print JSON", input: Input::Text, apply: json_pretty });
+        registry.register(Transform { name: "sha256", description: "SHA-256", input: Input::Text, apply: sha256 });
+        registry.register(Transform { name: "md5", description: "MD5", input: Input::Text, apply: md5 });
+        registry.register(Transform { name: "uuid", description: "UUID v4", input: Input::Arguments, apply: uuid });
+        registry.register(Transform { name: "lorem", description: "Lorem ipsum", input: Input::Arguments, apply: lorem });
+        registry.register(Transform { name: "camel", description: "camelCase", input: Input::Text, apply: camel_case });
+        registry.register(Transform { name: "snake", description: "snake_case", input: Input::Text, apply: snake_case });
+        registry.register(Transform { name: "kebab", description: "kebab-case", input: Input::Text, apply: kebab_case });
+        registry.register(Transform { name: "title", description: "Title Case", input: Input::Text, apply: title_case });
+        registry.register(Transform { name: "slug", description: "Slug", input: Input::Text, apply: slugify });
+        registry.register(Transform { name: "count", description: "Count", input: Input::Text, apply: count });
+        registry
+    }
+
+    /// Add a transform, replacing any existing one with the same name.
+    pub fn register(&mut self, transform: Transform) {
+        self.transforms.retain(|t| t.name != transform.name);
+        self.transforms.push(transform);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Transform> {
+        self.transforms.iter().find(|t| t.name == name)
+    }
+
+    /// The result item for a `<name> [text]` query, if `name` is a
+    /// registered transform. Text transforms fall back to the clipboard.
+    pub fn items_for(&self, query: &str) -> Vec<CommandItem> {
+        let items = self.items_with_clipboard(query, clipboard_text);
+        if !self.needs_clipboard(query) {
+            *CLIPBOARD.lock().unwrap() = None;
+        }
+        items
+    }
+
+    /// Whether `query` works on the clipboard, having no text of its own.
+    fn needs_clipboard(&self, query: &str) -> bool {
+        if let Some(text) = query.strip_prefix(TEXT_PREFIX) {
+            return text.trim().is_empty();
+        }
+        let (name, argument) = query.split_once(' ').unwrap_or((query, ""));
+        argument.is_empty() && self.get(&name.to_lowercase()).is_some_and(|t| t.input == Input::Text)
+    }
+
+    fn items_with_clipboard(&self, query: &str, clipboard: impl FnOnce() -> Option<String>) -> Vec<CommandItem> {
+        if let Some(text) = query.strip_prefix(TEXT_PREFIX) {
+            let text = match text.trim() {
+                "" => match clipboard() {
+                    Some(text) => text,
+                    None => return Vec::new(),
+                },
+                text => text.to_string(),
+            };
+            return self.text_items(&text);
+        }
+
+        let (name, argument) = query.split_once(' ').unwrap_or((query, ""));
+        let Some(transform) = self.get(&name.to_lowercase()) else {
+            return Vec::new();
+        };
+
+        let input = match transform.input {
+            Input::Text if argument.is_empty() => match clipboard() {
+                Some(text) => text,
+                None => return Vec::new(),
+            },
+            _ => argument.to_string(),
+        };
+
+        vec![apply(transform, &input)]
+    }
+
+    /// Case conversions and counts of `text`.
+    pub fn text_items(&self, text: &str) -> Vec<CommandItem> {
+        TEXT_TRANSFORMS
+            .iter()
+            .filter_map(|name| self.get(name))
+            .map(|transform| apply(transform, text))
+            .collect()
+    }
+
+    /// Text manipulations for a long query that matched nothing else.
+    pub fn long_query_items(&self, query: &str) -> Vec<CommandItem> {
+        if query.split_whitespace().count() < LONG_QUERY_WORDS {
+            return Vec::new();
+        }
+        self.text_items(query)
+    }
+}
+
+fn apply(transform: &Transform, input: &str) -> CommandItem {
+    match (transform.apply)(input) {
+        Ok(result) => transform_item(transform, &result),
+        Err(error) => {
+            let mut item = CommandItem::new(
+                &format!("{}: {}", transform.description, error),
+                Handler::Transform,
+                "",
+            );
+            item.icon = "⚠️".to_string();
+            item
+        }
+    }
+}
+
+static REGISTRY: Lazy<TransformRegistry> = Lazy::new(TransformRegistry::with_builtins);
+
+pub fn registry() -> &'static TransformRegistry {
+    &REGISTRY
+}
+
+fn transform_item(transform: &Transform, result: &str) -> CommandItem {
+    let first_line = result.lines().next().unwrap_or("");
+    let mut shown: String = first_line.chars().take(LABEL_PREVIEW_CHARS).collect();
+    if shown.len() < result.len() {
+        shown.push('…');
+    }
+    let mut item = CommandItem::new(
+        &format!("{}: {}", transform.description, shown),
+        Handler::Transform,
+        result,
+    );
+    item.metadata.insert("preview".to_string(), result.to_string());
+    item
+}
+
+fn base64_encode(input: &str) -> Result<String, String> {
+    Ok(base64::engine::general_purpose::STANDARD.encode(input))
+}
+
+fn base64_decode(input: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(input.trim())
+        .map_err(|_| "not valid base64".to_string())?;
+    String::from_utf8(bytes).map_err(|_| "decoded data is not text".to_string())
+}
+
+fn url_encode(input: &str) -> Result<String, String> {
+    Ok(urlencoding::encode(input).into_owned())
+}
+
+fn url_decode(input: &str) -> Result<String, String> {
+    urlencoding::decode(input)
+        .map(|s| s.into_owned())
+        .map_err(|_| "not valid percent-encoding".to_string())
+}
+
+fn json_pretty(input: &str) -> Result<String, String> {
+    let value: serde_json::Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256(input: &str) -> Result<String, String> {
+    Ok(hex(&Sha256::digest(input.as_bytes())))
+}
+
+fn md5(input: &str) -> Result<String, String> {
+    Ok(hex(&Md5::digest(input.as_bytes())))
+}
+
+fn uuid(_arguments: &str) -> Result<String, String> {
+    Ok(uuid::Uuid::new_v4().to_string())
+}
+
+/// `lorem` gives a paragraph, `lorem 20` the first 20 words.
+fn lorem(arguments: &str) -> Result<String, String> {
+    match arguments.trim() {
+        "" => Ok(LOREM_IPSUM.to_string()),
+        count => {
+            let count: usize = count.parse().map_err(|_| "expected a word count".to_string())?;
+            if count > MAX_LOREM_WORDS {
+                return Err(format!("at most {} words", MAX_LOREM_WORDS));
+            }
+            let words: Vec<&str> = LOREM_IPSUM.split_whitespace().cycle().take(count).collect();
+            Ok(words.join(" "))
+        }
+    }
+}
+
+/// Split text into words at separators and camelCase boundaries
+/// (`parseHTTPResponse` → parse, HTTP, Response).
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+fn camel_case(input: &str) -> Result<String, String> {
+    Ok(words(input)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+        .collect())
+}
+
+fn snake_case(input: &str) -> Result<String, String> {
+    Ok(words(input).iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("_"))
+}
+
+fn kebab_case(input: &str) -> Result<String, String> {
+    Ok(words(input).iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("-"))
+}
+
+/// Capitalize each whitespace-separated word, keeping punctuation.
+fn title_case(input: &str) -> Result<String, String> {
+    Ok(input.split_whitespace().map(capitalize).collect::<Vec<_>>().join(" "))
+}
+
+/// A URL-safe slug: lowercase ASCII letters and digits joined by dashes.
+fn slugify(input: &str) -> Result<String, String> {
+    let slug = input
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        return Err("nothing to slugify".to_string());
+    }
+    Ok(slug)
+}
+
+fn count(input: &str) -> Result<String, String> {
+    let words = input.split_whitespace().count();
+    let chars = input.chars().count();
+    let lines = input.lines().count();
+    Ok(format!("{} words, {} characters, {} lines", words, chars, lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings_round_trip() {
+        assert_eq!(base64_encode("hello").unwrap(), "aGVsbG8=");
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), "hello");
+        assert!(base64_decode("not base64!").is_err());
+
+        assert_eq!(url_encode("a b&c").unwrap(), "a%20b%26c");
+        assert_eq!(url_decode("a%20b%26c").unwrap(), "a b&c");
+    }
+
+    #[test]
+    fn test_json_pretty() {
+        assert_eq!(json_pretty("{\"a\":1}").unwrap(), "{\n  \"a\": 1\n}");
+        assert!(json_pretty("{oops").is_err());
+    }
+
+    #[test]
+    fn test_hashes() {
+        assert_eq!(
+            sha256("abc").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(md5("abc").unwrap(), "900150983cd24fb0d6963f7d28e17f72");
+    }
+
+    #[test]
+    fn test_generators() {
+        let id = uuid("").unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.chars().nth(14), Some('4'));
+
+        assert_eq!(lorem("3").unwrap(), "Lorem ipsum dolor");
+        assert!(lorem("").unwrap().ends_with("laborum."));
+        assert!(lorem("many").is_err());
+        assert_eq!(lorem("1000").unwrap().split_whitespace().count(), MAX_LOREM_WORDS);
+        assert_eq!(lorem("1000000000"), Err("at most 1000 words".to_string()));
+    }
+
+    #[test]
+    fn test_items_for_query() {
+        let items = registry().items_for("base64 hello");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].handler, Handler::Transform);
+        assert_eq!(items[0].value, "aGVsbG8=");
+        assert_eq!(items[0].label, "Base64 encode: aGVsbG8=");
+
+        let failed = registry().items_for("unbase64 ???");
+        assert_eq!(failed[0].label, "Base64 decode: not valid base64");
+        assert!(failed[0].value.is_empty());
+
+        assert!(registry().items_for("firefox").is_empty());
+    }
+
+    #[test]
+    fn test_clipboard_is_read_once() {
+        let registry = registry();
+        assert!(registry.needs_clipboard("base64"));
+        assert!(registry.needs_clipboard("text: "));
+        assert!(!registry.needs_clipboard("base64 hello"));
+        assert!(!registry.needs_clipboard("uuid"));
+
+        let items = registry.items_with_clipboard("base64", || Some("hello".to_string()));
+        assert_eq!(items[0].value, "aGVsbG8=");
+
+        let kept = Mutex::new(None);
+        let mut reads = 0;
+        for _ in 0..3 {
+            kept_clipboard(&kept, || {
+                reads += 1;
+                Some("hello".to_string())
+            });
+        }
+        assert_eq!(reads, 1);
+    }
+
+    #[test]
+    fn test_case_conversions() {
+        assert_eq!(words("parseHTTPResponse v2"), vec!["parse", "HTTP", "Response", "v2"]);
+        assert_eq!(camel_case("hello big_world").unwrap(), "helloBigWorld");
+        assert_eq!(snake_case("parseHTTPResponse").unwrap(), "parse_http_response");
+        assert_eq!(kebab_case("Hello World_again").unwrap(), "hello-world-again");
+        assert_eq!(title_case("the QUICK fox's den").unwrap(), "The Quick Fox's Den");
+        assert_eq!(slugify("Héllo, World! 2024").unwrap(), "h-llo-world-2024");
+        assert!(slugify("!!").is_err());
+        assert_eq!(count("one two\nthree").unwrap(), "3 words, 13 characters, 2 lines");
+    }
+
+    #[test]
+    fn test_text_prefix_and_long_queries() {
+        let items = registry().items_for("text: Hello there world");
+        let values: Vec<&str> = items.iter().map(|i| i.value.as_str()).collect();
+        assert_eq!(
+            values,
+            vec![
+                "helloThereWorld",
+                "hello_there_world",
+                "hello-there-world",
+                "Hello There World",
+                "hello-there-world",
+                "3 words, 17 characters, 1 lines",
+            ]
+        );
+
+        assert!(registry().long_query_items("short query").is_empty());
+        assert_eq!(registry().long_query_items("a much longer free text query").len(), 6);
+    }
+
+    #[test]
+    fn test_register_replaces_by_name() {
+        let mut registry = TransformRegistry::with_builtins();
+        registry.register(Transform {
+            name: "md5",
+            description: "Shout",
+            input: Input::Text,
+            apply: |s| Ok(s.to_uppercase()),
+        });
+        assert_eq!(registry.items_for("md5 hi")[0].value, "HI");
+    }
+}
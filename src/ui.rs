@@ -1,18 +1,227 @@
+use crate::config;
+use crate::containers;
+use crate::core::{CommandItem, FileKind, Handler, FOLDER_GLYPH};
+use crate::data_sources::{self, calendar::{self, Meeting}};
+use crate::export::{self, EXPORT_OPTIONS};
+use crate::relative_time::{self, DateStyle};
 use crate::state::AppState;
-use chrono::Local;
+use crate::theme::{Density, Theme};
+use crate::time_tracking;
+use crate::workspace::Source;
+use chrono::{Local, NaiveDateTime};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Style},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, Wrap,
+    },
     Frame,
 };
 
-pub fn render(frame: &mut Frame, app_state: &mut AppState) {
-    let constraints = if app_state.error_message.is_some() {
-        [Constraint::Length(3), Constraint::Min(1), Constraint::Length(3)].as_ref()
-    } else {
-        [Constraint::Length(3), Constraint::Min(1), Constraint::Length(0)].as_ref()
+/// The icon column of an item. With a Nerd Font, files and folders keeping
+/// their default icon are drawn with the font's glyphs.
+fn item_icon(item: &CommandItem, nerd_font: bool) -> String {
+    match item.handler {
+        Handler::File if nerd_font && item.icon == FileKind::of(&item.value).to_icon() => {
+            FileKind::of(&item.value).to_glyph().to_string()
+        }
+        Handler::Folder if nerd_font && item.icon == Handler::Folder.to_icon() => FOLDER_GLYPH.to_string(),
+        _ => item.icon.clone(),
+    }
+}
+
+/// The dimmed second line of a result in the comfortable density: where
+/// a file, folder or app lives, the URL of a bookmark, a note's folder or
+/// what a task does.
+fn subtitle(item: &CommandItem) -> Option<String> {
+    match item.handler {
+        Handler::File | Handler::Folder | Handler::App => {
+            let home = dirs::home_dir();
+            let path = std::path::Path::new(&item.value);
+            let location = match home.as_deref().and_then(|home| path.strip_prefix(home).ok()) {
+                Some(relative) => format!("~/{}", relative.display()),
+                None => item.value.clone(),
+            };
+            // A repository's branch and whether it has changes
+            Some(match data_sources::repos::status_line(item) {
+                Some(status) => format!("{} · {}", location, status),
+                None => location,
+            })
+        }
+        // Reading list links tell when they were saved
+        Handler::Url => match (item.metadata.get("added"), item.value != item.label) {
+            (Some(added), true) => Some(format!("{} · {}", item.value, added)),
+            (Some(added), false) => Some(added.clone()),
+            (None, true) => Some(item.value.clone()),
+            (None, false) => None,
+        },
+        Handler::Note => item.metadata.get("folder").cloned(),
+        Handler::Ssh => item.metadata.get("destination").filter(|destination| **destination != item.value).cloned(),
+        Handler::Database => item.metadata.get("destination").cloned(),
+        Handler::Kube => data_sources::kube::describe(item),
+        Handler::Shell => item.metadata.get("description").cloned(),
+        _ => None,
+    }
+}
+
+/// Split a line after `width` characters, at the last space when there is
+/// one, so a long label continues on the next line.
+fn wrap_line(line: Line<'static>, width: usize) -> (Line<'static>, Option<Line<'static>>) {
+    let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+    let chars: Vec<char> = text.chars().collect();
+    if width == 0 || chars.len() <= width {
+        return (line, None);
+    }
+    let split = match chars[..width].iter().rposition(|c| *c == ' ') {
+        Some(space) if space > 0 => space + 1,
+        _ => width,
     };
+    let mut first = Vec::new();
+    let mut rest = Vec::new();
+    let mut position = 0;
+    for span in line.spans {
+        let len = span.content.chars().count();
+        if position + len <= split {
+            first.push(span);
+        } else if position >= split {
+            rest.push(span);
+        } else {
+            let head: String = span.content.chars().take(split - position).collect();
+            let tail: String = span.content.chars().skip(split - position).collect();
+            first.push(Span::styled(head, span.style));
+            rest.push(Span::styled(tail, span.style));
+        }
+        position += len;
+    }
+    (Line::from(first), Some(Line::from(rest)))
+}
+
+/// Where the selection is among the results, and the sources they come
+/// from, such as `12/87 results · sources: apps, files, web`.
+pub fn result_summary(app_state: &AppState) -> Option<String> {
+    let total = app_state.filtered_items.len();
+    if total == 0 {
+        return None;
+    }
+    let position = match app_state.table_state.selected() {
+        Some(selected) => format!("{}/{}", selected.min(total - 1) + 1, total),
+        None => total.to_string(),
+    };
+    let noun = if total == 1 { "result" } else { "results" };
+    let sources: Vec<&str> = Source::ALL
+        .into_iter()
+        .filter(|source| app_state.filtered_items.iter().any(|item| Source::of(item) == *source))
+        .map(|source| source.name())
+        .collect();
+    Some(format!("{} {} · sources: {}", position, noun, sources.join(", ")))
+}
+
+/// `📅 Standup in 5 min · Alt+J to join`, muted with 🔕 during Do Not
+/// Disturb.
+fn meeting_segment(meeting: &Meeting, do_not_disturb: bool, now: NaiveDateTime) -> String {
+    let icon = if do_not_disturb { "🔕" } else { "📅" };
+    let mut segment = format!("{} {} {}", icon, meeting.title, calendar::starts_in(meeting, now));
+    if meeting.join_url.is_some() {
+        if let Some(join) = config::get().keys.join_key() {
+            segment.push_str(&format!(" · {} to join", join));
+        }
+    }
+    segment
+}
+
+/// Segments shown in the status bar below the results, if any.
+pub fn status_segments(app_state: &AppState) -> Vec<String> {
+    let mut segments: Vec<String> = result_summary(app_state).into_iter().collect();
+    if let Some((message, _)) = &app_state.toast {
+        segments.push(format!("✓ {}", message));
+    }
+    if let Some(timer) = &app_state.running_timer {
+        segments.push(format!(
+            "⏱ {} {}",
+            timer.description,
+            time_tracking::format_clock(timer.elapsed(Local::now()))
+        ));
+    }
+    if let Some(meeting) = calendar::next_meeting(&app_state.meetings, Local::now().naive_local()) {
+        segments.push(meeting_segment(meeting, app_state.do_not_disturb, Local::now().naive_local()));
+    }
+    if app_state.incognito {
+        segments.push("🕶 Incognito".to_string());
+    }
+    if let Some(notice) = data_sources::fs::file_search_notice() {
+        segments.push(format!("⚠ {}", notice));
+    }
+    for (task, percent) in &app_state.progress {
+        segments.push(format!("⇪ {}… {}%", task, percent));
+    }
+    for target in &app_state.refreshing {
+        let name = target.map_or("everything", |source| source.name());
+        segments.push(format!("⟳ Refreshing {}…", name));
+    }
+    segments
+}
+
+/// Character indices of `label` matched by `query`: a case-insensitive
+/// substring when there is one, the fuzzy matcher's picks otherwise.
+fn matched_indices(label: &str, query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let lower_label = label.to_lowercase();
+    let lower_query = query.to_lowercase();
+    // Lowercasing can change the number of characters, which would shift
+    // the indices
+    if lower_label.chars().count() == label.chars().count() {
+        if let Some(start) = lower_label.find(&lower_query) {
+            let start = lower_label[..start].chars().count();
+            return (start..start + lower_query.chars().count()).collect();
+        }
+    }
+    SkimMatcherV2::default()
+        .fuzzy_indices(label, query)
+        .map(|(_, indices)| indices)
+        .unwrap_or_default()
+}
+
+/// Split `label` into spans, emphasizing the characters that matched
+/// `query`.
+pub fn highlight_label(label: &str, query: &str, highlight: Style) -> Line<'static> {
+    let matched = matched_indices(label, query);
+    if matched.is_empty() {
+        return Line::from(label.to_string());
+    }
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in label.chars().enumerate() {
+        let is_matched = matched.contains(&i);
+        if is_matched != run_matched && !run.is_empty() {
+            let style = if run_matched { highlight } else { Style::default() };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_matched;
+        run.push(c);
+    }
+    let style = if run_matched { highlight } else { Style::default() };
+    spans.push(Span::styled(run, style));
+    Line::from(spans)
+}
+
+pub fn render(frame: &mut Frame, app_state: &mut AppState) {
+    let status = status_segments(app_state);
+    let error_height = if app_state.error_message.is_some() { 3 } else { 0 };
+    let status_height = if status.is_empty() { 0 } else { 1 };
+    let constraints = [
+        Constraint::Length(3),
+        Constraint::Min(1),
+        Constraint::Length(status_height),
+        Constraint::Length(error_height),
+    ];
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -20,54 +229,225 @@ pub fn render(frame: &mut Frame, app_state: &mut AppState) {
         .constraints(constraints)
         .split(frame.area());
 
+    let mut search_title = match &app_state.workspace {
+        Some(name) => format!("Search · {}", name),
+        None => "Search".to_string(),
+    };
+    if let Some(breadcrumb) = containers::breadcrumb(&app_state.containers) {
+        search_title = format!("{} · {} · Esc to go back", search_title, breadcrumb);
+    }
     app_state
         .query
-        .set_block(Block::default().borders(Borders::ALL).title("Search"));
+        .set_block(Block::default().borders(Borders::ALL).title(search_title));
     let input_widget = app_state.query.widget();
     frame.render_widget(input_widget, chunks[0]);
 
-    let is_history_view = app_state.query.is_empty();
+    let is_history_view = app_state.query.is_empty() && app_state.containers.is_empty();
     let title = if is_history_view {
         "Recent Commands"
     } else {
         "Commands"
     };
 
+    let show_icons = app_state.profile.show_icons;
+    let nerd_font = config::get().nerd_font;
+    let density = config::get().density;
+    let date_style = DateStyle::current();
+    let theme = app_state.theme;
+    let query = app_state.query.lines().join(" ").trim().to_string();
+
+    // Items with a preview (weather, ...) get a pane next to the results
+    let preview = app_state
+        .get_selected_item()
+        .and_then(|item| Some((item.metadata.get("preview")?.clone(), item.ran_at)));
+    let results_area = match &preview {
+        Some((preview, ran_at)) => {
+            let preview_title = match ran_at {
+                Some(ran_at) => format!("Preview · opened {}", relative_time::format(*ran_at, Local::now(), date_style)),
+                None => "Preview".to_string(),
+            };
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(chunks[1]);
+            let preview_paragraph = Paragraph::new(preview.as_str())
+                .block(Block::default().borders(Borders::ALL).title(preview_title))
+                .wrap(Wrap { trim: false });
+            frame.render_widget(preview_paragraph, columns[1]);
+            columns[0]
+        }
+        None => chunks[1],
+    };
+    // Roughly the label column: 70% of what the borders and icons leave
+    let icon_width = if show_icons { 5 } else { 0 };
+    let label_width = usize::from(results_area.width.saturating_sub(2 + icon_width)) * 70 / 100;
+
     let rows: Vec<Row> = app_state
         .filtered_items
         .iter()
-        .map(|item| {
-            let icon_cell = Cell::from(item.icon.clone());
-            let label_cell = Cell::from(item.label.clone());
-            let context_cell = if is_history_view {
-                if let Some(ran_at) = item.ran_at {
-                    let now = Local::now();
-                    if ran_at.date_naive() == now.date_naive() {
-                        Cell::from(format!("Today {}", ran_at.format("%H:%M")))
-                    } else {
-                        Cell::from(ran_at.format("%b %d %H:%M").to_string())
+        .enumerate()
+        .map(|(index, item)| {
+            // Results that came in after the latency budget start below a divider
+            let divider = app_state.late_from == Some(index);
+            let with_divider = |lines: Vec<Line<'static>>, divider_text: &str| {
+                let mut text = Text::default();
+                if divider {
+                    let dim = Style::default().fg(Color::DarkGray);
+                    text.lines.push(Line::styled(divider_text.to_string(), dim));
+                }
+                text.lines.extend(lines);
+                text
+            };
+            let icon_cell = Cell::from(with_divider(vec![Line::from(item_icon(item, nerd_font))], "──"));
+            let mut label = highlight_label(&item.label, &query, theme.match_style());
+            if let Some(answer) = item.metadata.get("answer") {
+                label.push_span(Span::styled(format!(" · {}", answer), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some(due) = item.metadata.get("due") {
+                label.push_span(Span::styled(format!(" · due {}", due), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some(snippet) = item.metadata.get("snippet") {
+                label.push_span(Span::styled(format!(" · {}", snippet), Style::default().fg(Color::DarkGray)));
+            }
+            if app_state.broken.contains(&item.value) {
+                label.push_span(Span::styled(" ⚠ missing", Style::default().fg(Color::DarkGray)));
+            }
+            let label_lines = match density {
+                Density::Compact => vec![label],
+                Density::Comfortable => match subtitle(item) {
+                    Some(subtitle) => vec![label, Line::styled(subtitle, Style::default().fg(Color::DarkGray))],
+                    None => {
+                        let (first, rest) = wrap_line(label, label_width);
+                        std::iter::once(first).chain(rest).collect()
                     }
-                } else {
-                    Cell::from("")
+                },
+            };
+            let label_cell = Cell::from(with_divider(label_lines, "── late results ──"));
+            let mut context = Line::from(Span::styled(
+                format!(" {} ", item.handler.badge()),
+                theme.badge_style(item.handler),
+            ));
+            let repo_badge = app_state.repo_status.get(&item.value).map(|status| status.badge());
+            if let Some(badge) = repo_badge.filter(|badge| !badge.is_empty() && data_sources::repos::is_repo(item)) {
+                context.push_span(Span::styled(format!(" {}", badge), Style::default().fg(Color::DarkGray)));
+            }
+            if is_history_view {
+                if let Some(ran_at) = item.ran_at {
+                    let when = relative_time::format(ran_at, Local::now(), date_style);
+                    context.push_span(Span::styled(format!(" {}", when), Style::default().fg(Color::DarkGray)));
                 }
+            }
+            let context_cell = Cell::from(with_divider(vec![context], "──"));
+            let row = if show_icons {
+                Row::new(vec![icon_cell, label_cell, context_cell])
             } else {
-                Cell::from(item.handler.to_string())
+                Row::new(vec![label_cell, context_cell])
             };
-            Row::new(vec![icon_cell, label_cell, context_cell])
+            row.height(density.row_height() + u16::from(divider))
         })
         .collect();
 
-    let constraints = [
-        Constraint::Length(4),
-        Constraint::Percentage(70),
-        Constraint::Percentage(30),
-    ];
+    let constraints = if show_icons {
+        vec![
+            Constraint::Length(4),
+            Constraint::Percentage(70),
+            Constraint::Percentage(30),
+        ]
+    } else {
+        vec![Constraint::Percentage(70), Constraint::Percentage(30)]
+    };
 
     let table = Table::new(rows, constraints)
         .block(Block::default().borders(Borders::ALL).title(title))
-        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        .row_highlight_style(theme.selection_style());
+
+    frame.render_stateful_widget(table, results_area, &mut app_state.table_state);
+
+    // A scrollbar on the right border once the results overflow the table
+    let visible_rows = usize::from(results_area.height.saturating_sub(2) / density.row_height());
+    let total = app_state.filtered_items.len();
+    if total > visible_rows {
+        let mut scrollbar_state =
+            ScrollbarState::new(total).position(app_state.table_state.selected().unwrap_or(0));
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None);
+        frame.render_stateful_widget(
+            scrollbar,
+            results_area.inner(Margin { vertical: 1, horizontal: 0 }),
+            &mut scrollbar_state,
+        );
+    }
+
+    if let Some(menu) = &app_state.action_menu {
+        let labels = menu.actions.iter().map(|a| a.label().to_string()).collect();
+        render_menu(frame, results_area, "Actions", labels, menu.selected, theme);
+    }
+
+    if let Some(menu) = &app_state.export_menu {
+        let labels = EXPORT_OPTIONS
+            .iter()
+            .map(|(format, target)| export::option_label(*format, *target))
+            .collect();
+        render_menu(frame, results_area, "Export results", labels, menu.selected, theme);
+    }
 
-    frame.render_stateful_widget(table, chunks[1], &mut app_state.table_state);
+    if let Some(editor) = &mut app_state.note_editor {
+        editor.text.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} · Ctrl+S to save, Esc to cancel", editor.note.label)),
+        );
+        frame.render_widget(Clear, results_area);
+        frame.render_widget(editor.text.widget(), results_area);
+    }
+
+    if let Some(overlay) = &app_state.overlay {
+        let width = results_area.width * 4 / 5;
+        let height = results_area.height * 3 / 4;
+        let area = Rect {
+            x: results_area.x + (results_area.width - width) / 2,
+            y: results_area.y + (results_area.height - height) / 2,
+            width,
+            height,
+        };
+        let paragraph = Paragraph::new(overlay.text.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} · any key to close", overlay.title)),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    if let Some(confirmation) = &app_state.confirmation {
+        let width = (results_area.width * 4 / 5).max(20).min(results_area.width);
+        let height = 5.min(results_area.height);
+        let area = Rect {
+            x: results_area.x + (results_area.width - width) / 2,
+            y: results_area.y + (results_area.height - height) / 2,
+            width,
+            height,
+        };
+        let text = format!("{}\n\nEnter or y to confirm, any other key to cancel", confirmation.prompt());
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Confirm")
+                    .border_style(Style::default().fg(Color::Red)),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    if !status.is_empty() {
+        let status_bar = Paragraph::new(status.join(" · ")).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(status_bar, chunks[2]);
+    }
 
     // Render error bar if there's an error
     if let Some(error_msg) = &app_state.error_message {
@@ -79,6 +459,152 @@ pub fn render(frame: &mut Frame, app_state: &mut AppState) {
                     .border_style(Style::default().fg(Color::Red))
             )
             .style(Style::default().fg(Color::Red));
-        frame.render_widget(error_paragraph, chunks[2]);
+        frame.render_widget(error_paragraph, chunks[3]);
+    }
+}
+
+/// A small menu in the top right corner of the results.
+fn render_menu(frame: &mut Frame, results_area: Rect, title: &str, labels: Vec<String>, selected: usize, theme: Theme) {
+    let height = labels.len() as u16 + 2;
+    let area = Rect {
+        x: results_area.x + results_area.width.saturating_sub(32),
+        y: results_area.y + 1,
+        width: 32.min(results_area.width),
+        height: height.min(results_area.height),
+    };
+    let items: Vec<ListItem> = labels.into_iter().map(ListItem::new).collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title.to_string()))
+        .highlight_style(theme.selection_style());
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Modifier;
+
+    fn highlighted(line: &Line) -> Vec<String> {
+        line.spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::BOLD))
+            .map(|span| span.content.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_item_icon() {
+        let pdf = CommandItem::new("doc.pdf", Handler::File, "/tmp/doc.pdf");
+        assert_eq!(item_icon(&pdf, false), "📕");
+        assert_eq!(item_icon(&pdf, true), "\u{f1c1}");
+        assert_eq!(item_icon(&CommandItem::new("src", Handler::Folder, "/tmp/src"), true), FOLDER_GLYPH);
+
+        // Icons set by a source are kept
+        let mut custom = pdf.clone();
+        custom.icon = "📚".to_string();
+        assert_eq!(item_icon(&custom, true), "📚");
+    }
+
+    #[test]
+    fn test_subtitle() {
+        let url = CommandItem::new("Rust docs", Handler::Url, "https://doc.rust-lang.org");
+        assert_eq!(subtitle(&url).as_deref(), Some("https://doc.rust-lang.org"));
+        let file = CommandItem::new("notes.txt", Handler::File, "/tmp/notes.txt");
+        assert_eq!(subtitle(&file).as_deref(), Some("/tmp/notes.txt"));
+        if let Some(home) = dirs::home_dir() {
+            let path = home.join("Documents/cv.pdf").display().to_string();
+            let file = CommandItem::new("cv.pdf", Handler::File, &path);
+            assert_eq!(subtitle(&file).as_deref(), Some("~/Documents/cv.pdf"));
+        }
+        let mut note = CommandItem::new("Standup", Handler::Note, "x-coredata://1");
+        note.metadata.insert("folder".to_string(), "Work".to_string());
+        assert_eq!(subtitle(&note).as_deref(), Some("Work"));
+        let mut repo = CommandItem::new("grinta", Handler::Folder, "/src/grinta");
+        repo.metadata.insert("repo".to_string(), "true".to_string());
+        repo.metadata.insert("branch".to_string(), "main".to_string());
+        repo.metadata.insert("dirty".to_string(), "true".to_string());
+        assert_eq!(subtitle(&repo).as_deref(), Some("/src/grinta · main · modified"));
+        assert_eq!(subtitle(&CommandItem::new("Quit", Handler::Internal, "quit")), None);
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        let text = |line: &Line| line.spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+        let line = highlight_label("Quarterly planning notes", "plan", Theme::Dark.match_style());
+        let (first, rest) = wrap_line(line, 14);
+        assert_eq!(text(&first), "Quarterly ");
+        assert_eq!(text(&rest.unwrap()), "planning notes");
+
+        // Highlighting survives the split
+        let line = highlight_label("abcdefgh", "cdef", Theme::Dark.match_style());
+        let (first, rest) = wrap_line(line, 4);
+        assert_eq!(highlighted(&first), vec!["cd"]);
+        assert_eq!(highlighted(&rest.unwrap()), vec!["ef"]);
+
+        let (first, rest) = wrap_line(Line::from("short"), 14);
+        assert_eq!(text(&first), "short");
+        assert!(rest.is_none());
+    }
+
+    #[test]
+    fn test_highlight_label() {
+        let line = highlight_label("Visual Studio Code", "vsc", Theme::Dark.match_style());
+        assert_eq!(line.spans.iter().map(|s| s.content.as_ref()).collect::<String>(), "Visual Studio Code");
+        assert_eq!(highlighted(&line), vec!["V", "S", "C"]);
+
+        let line = highlight_label("Firefox", "fox", Theme::Dark.match_style());
+        assert_eq!(highlighted(&line), vec!["fox"]);
+
+        let line = highlight_label("Firefox", "", Theme::Dark.match_style());
+        assert_eq!(line.spans.len(), 1);
+        assert!(highlighted(&line).is_empty());
+    }
+
+    #[test]
+    fn test_result_summary() {
+        let mut state = AppState::new(vec![], vec![]);
+        assert_eq!(result_summary(&state), None);
+
+        state.filtered_items = vec![
+            CommandItem::new("Firefox", Handler::App, "/usr/bin/firefox"),
+            CommandItem::new("report.pdf", Handler::File, "/tmp/report.pdf"),
+            CommandItem::new("Notes", Handler::Note, "1"),
+        ];
+        state.table_state.select(Some(1));
+        assert_eq!(result_summary(&state).unwrap(), "2/3 results · sources: apps, notes, files");
+
+        state.filtered_items.truncate(1);
+        state.table_state.select(None);
+        assert_eq!(result_summary(&state).unwrap(), "1 result · sources: apps");
+    }
+
+    #[test]
+    fn test_status_segments_show_refreshes() {
+        let mut state = AppState::new(vec![], vec![]);
+        assert!(status_segments(&state).is_empty());
+
+        state.start_refresh(Some(crate::workspace::Source::Bookmarks));
+        state.start_refresh(None);
+        assert_eq!(
+            status_segments(&state),
+            vec!["⟳ Refreshing bookmarks…", "⟳ Refreshing everything…"]
+        );
+    }
+
+    #[test]
+    fn test_meeting_segment() {
+        let now = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(9, 55, 0).unwrap();
+        let mut standup = Meeting {
+            title: "Standup".to_string(),
+            start: now + chrono::Duration::minutes(5),
+            end: None,
+            join_url: Some("https://meet.google.com/abc".to_string()),
+        };
+        assert_eq!(meeting_segment(&standup, false, now), "📅 Standup in 5 min · Alt+J to join");
+        standup.join_url = None;
+        assert_eq!(meeting_segment(&standup, true, now), "🔕 Standup in 5 min");
     }
 }
@@ -0,0 +1,114 @@
+use crate::config;
+use crate::core::{CommandItem, Handler};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// Parameters added to links to follow clicks, `*` standing for any ending.
+const TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid",
+    "_hsenc", "_hsmi", "mkt_tok",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlCleanerConfig {
+    /// Strip tracking parameters from URLs before opening or copying them
+    pub enabled: bool,
+    /// More parameters to strip, such as `ref` or `si`
+    pub params: Vec<String>,
+    /// Domains whose URLs are left as they are, with their subdomains
+    pub allow: Vec<String>,
+}
+
+fn matches_param(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+fn is_allowed(host: &str, allow: &[String]) -> bool {
+    allow.iter().any(|domain| {
+        let domain = domain.trim_start_matches("*.").to_lowercase();
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// `url` without its tracking parameters. The other parameters are kept
+/// as they were written, and URLs of allowed domains are left alone.
+pub fn clean(url: &str, settings: &UrlCleanerConfig) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let (Some(host), Some(query)) = (parsed.host_str(), parsed.query()) else {
+        return url.to_string();
+    };
+    if !matches!(parsed.scheme(), "http" | "https") || is_allowed(host, &settings.allow) {
+        return url.to_string();
+    }
+    let is_tracking = |pair: &&str| {
+        let name = pair.split('=').next().unwrap_or_default();
+        TRACKING_PARAMS
+            .iter()
+            .copied()
+            .chain(settings.params.iter().map(String::as_str))
+            .any(|pattern| matches_param(pattern, name))
+    };
+    let pairs: Vec<&str> = query.split('&').collect();
+    if !pairs.iter().any(is_tracking) {
+        return url.to_string();
+    }
+    let kept: Vec<&str> = pairs.into_iter().filter(|pair| !pair.is_empty() && !is_tracking(pair)).collect();
+    let query = kept.join("&");
+    let mut cleaned = parsed;
+    cleaned.set_query(Some(query.as_str()).filter(|query| !query.is_empty()));
+    cleaned.to_string()
+}
+
+/// The URL of `item` to open or copy: cleaned when the option is on.
+pub fn url_of(item: &CommandItem) -> String {
+    let settings = &config::get().url_cleaner;
+    match item.handler {
+        Handler::Url if settings.enabled => clean(&item.value, settings),
+        _ => item.value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(params: &[&str], allow: &[&str]) -> UrlCleanerConfig {
+        UrlCleanerConfig {
+            enabled: true,
+            params: params.iter().map(|param| param.to_string()).collect(),
+            allow: allow.iter().map(|domain| domain.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_clean() {
+        let settings = settings(&["ref"], &["example.org"]);
+        assert_eq!(
+            clean("https://shop.com/item?id=4&utm_source=news&utm_medium=mail#reviews", &settings),
+            "https://shop.com/item?id=4#reviews"
+        );
+        assert_eq!(clean("https://a.com/?fbclid=XYZ", &settings), "https://a.com/");
+        assert_eq!(clean("https://a.com/?gclid=1&ref=hn&q=a+b%20c", &settings), "https://a.com/?q=a+b%20c");
+        // Nothing to strip: the URL is kept as written
+        assert_eq!(clean("https://a.com/search?q=a%2Bb&page=2", &settings), "https://a.com/search?q=a%2Bb&page=2");
+        assert_eq!(clean("https://a.com/?utmost=1", &settings), "https://a.com/?utmost=1");
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let settings = settings(&[], &["example.org"]);
+        assert_eq!(
+            clean("https://mail.example.org/?utm_source=x", &settings),
+            "https://mail.example.org/?utm_source=x"
+        );
+        assert_eq!(clean("https://notexample.org/?utm_source=x", &settings), "https://notexample.org/");
+        assert_eq!(clean("file:///tmp/a?utm_source=x", &settings), "file:///tmp/a?utm_source=x");
+        assert_eq!(clean("not a url", &settings), "not a url");
+    }
+}
@@ -0,0 +1,253 @@
+use crate::config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Window class popups are started with, for window manager rules.
+pub const POPUP_CLASS: &str = "grinta-popup";
+/// Approximate size of a terminal cell, used to center the window.
+const CELL_WIDTH_PX: i32 = 9;
+const CELL_HEIGHT_PX: i32 = 20;
+
+/// Terminals that can be started with a given size and position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Terminal {
+    Kitty,
+    Alacritty,
+    Wezterm,
+}
+
+/// Terminal window `grinta window` opens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub terminal: Option<Terminal>,
+    pub columns: u16,
+    pub rows: u16,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            terminal: None,
+            columns: 100,
+            rows: 24,
+        }
+    }
+}
+
+/// A monitor's area in the combined screen space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Monitor {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Monitor {
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Connected monitors from `xrandr --query` lines such as
+/// `HDMI-1 connected primary 2560x1440+1920+0 (normal …`.
+pub fn parse_xrandr(output: &str) -> Vec<Monitor> {
+    output
+        .lines()
+        .filter(|line| line.contains(" connected"))
+        .filter_map(|line| {
+            let geometry = line.split_whitespace().find(|word| word.contains('x') && word.contains('+'))?;
+            let (size, offset) = geometry.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+            let (x, y) = offset.split_once('+')?;
+            Some(Monitor {
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// The pointer position from `xdotool getmouselocation --shell`.
+pub fn parse_mouse_location(output: &str) -> Option<(i32, i32)> {
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('=')?.trim().parse().ok())
+    };
+    Some((value("X")?, value("Y")?))
+}
+
+/// Top-left corner of a `width`×`height` window centered on the monitor
+/// holding `pointer`, or on the first monitor.
+pub fn centered_position(monitors: &[Monitor], pointer: Option<(i32, i32)>, width: i32, height: i32) -> Option<(i32, i32)> {
+    let monitor = pointer
+        .and_then(|pointer| monitors.iter().find(|monitor| monitor.contains(pointer)))
+        .or_else(|| monitors.first())?;
+    Some((
+        monitor.x + (monitor.width - width).max(0) / 2,
+        monitor.y + (monitor.height - height).max(0) / 2,
+    ))
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Where to put the popup, on X11 where the monitors can be queried.
+fn popup_position(config: &WindowConfig) -> Option<(i32, i32)> {
+    let monitors = parse_xrandr(&command_output("xrandr", &["--query"])?);
+    let pointer = command_output("xdotool", &["getmouselocation", "--shell"]).and_then(|out| parse_mouse_location(&out));
+    centered_position(
+        &monitors,
+        pointer,
+        i32::from(config.columns) * CELL_WIDTH_PX,
+        i32::from(config.rows) * CELL_HEIGHT_PX,
+    )
+}
+
+/// The command starting `terminal` with grinta (`grinta` plus `args`).
+pub fn terminal_command(
+    terminal: Terminal,
+    config: &WindowConfig,
+    popup: bool,
+    position: Option<(i32, i32)>,
+    grinta: &[String],
+) -> Vec<String> {
+    let mut command: Vec<String> = match terminal {
+        Terminal::Kitty => {
+            let mut command = vec![
+                "kitty".to_string(),
+                "-o".to_string(),
+                format!("initial_window_width={}c", config.columns),
+                "-o".to_string(),
+                format!("initial_window_height={}c", config.rows),
+                "-o".to_string(),
+                "remember_window_size=no".to_string(),
+            ];
+            if popup {
+                command.extend(["--class".to_string(), POPUP_CLASS.to_string()]);
+            }
+            command
+        }
+        Terminal::Alacritty => {
+            let mut command = vec![
+                "alacritty".to_string(),
+                "-o".to_string(),
+                format!("window.dimensions.columns={}", config.columns),
+                "-o".to_string(),
+                format!("window.dimensions.lines={}", config.rows),
+            ];
+            if popup {
+                command.extend(["--class".to_string(), POPUP_CLASS.to_string()]);
+                if let Some((x, y)) = position {
+                    command.extend([
+                        "-o".to_string(),
+                        format!("window.position.x={}", x),
+                        "-o".to_string(),
+                        format!("window.position.y={}", y),
+                    ]);
+                }
+                command.extend(["-o".to_string(), "window.decorations=\"None\"".to_string()]);
+            }
+            command.push("-e".to_string());
+            command
+        }
+        Terminal::Wezterm => {
+            let mut command = vec![
+                "wezterm".to_string(),
+                "--config".to_string(),
+                format!("initial_cols={}", config.columns),
+                "--config".to_string(),
+                format!("initial_rows={}", config.rows),
+                "start".to_string(),
+            ];
+            if popup {
+                command.extend(["--class".to_string(), POPUP_CLASS.to_string()]);
+                if let Some((x, y)) = position {
+                    command.extend(["--position".to_string(), format!("{},{}", x, y)]);
+                }
+            }
+            command.push("--".to_string());
+            command
+        }
+    };
+    command.extend(grinta.iter().cloned());
+    command
+}
+
+/// Open grinta in a new terminal window. A popup is centered on the
+/// monitor with the pointer and closes once an item is launched.
+pub fn open(popup: bool) -> Result<()> {
+    let config = &config::get().window;
+    let terminal = config
+        .terminal
+        .context("Set `terminal` under [window] in config.toml to kitty, alacritty or wezterm")?;
+    let exe = std::env::current_exe().context("Failed to locate the grinta executable")?;
+    let mut grinta = vec![exe.to_string_lossy().into_owned()];
+    if popup {
+        grinta.push("--close-on-launch".to_string());
+    }
+    let position = if popup { popup_position(config) } else { None };
+
+    let command = terminal_command(terminal, config, popup, position, &grinta);
+    Command::new(&command[0])
+        .args(&command[1..])
+        .spawn()
+        .with_context(|| format!("Failed to start {}", command[0]))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XRANDR: &str = "Screen 0: minimum 320 x 200, current 4480 x 1440, maximum 16384 x 16384
+eDP-1 connected 1920x1080+0+360 (normal left inverted right x axis y axis) 344mm x 194mm
+   1920x1080     60.00*+
+HDMI-1 connected primary 2560x1440+1920+0 (normal left inverted right x axis y axis) 597mm x 336mm
+DP-1 disconnected (normal left inverted right x axis y axis)";
+
+    #[test]
+    fn test_parse_xrandr() {
+        let monitors = parse_xrandr(XRANDR);
+        assert_eq!(
+            monitors,
+            vec![
+                Monitor { x: 0, y: 360, width: 1920, height: 1080 },
+                Monitor { x: 1920, y: 0, width: 2560, height: 1440 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_centered_position() {
+        let monitors = parse_xrandr(XRANDR);
+        let pointer = parse_mouse_location("X=2500\nY=700\nSCREEN=0\nWINDOW=123\n");
+        assert_eq!(pointer, Some((2500, 700)));
+        assert_eq!(centered_position(&monitors, pointer, 900, 480), Some((2750, 480)));
+        // Without a pointer the first monitor is used
+        assert_eq!(centered_position(&monitors, None, 900, 480), Some((510, 660)));
+        assert_eq!(centered_position(&[], None, 900, 480), None);
+    }
+
+    #[test]
+    fn test_terminal_command() {
+        let config = WindowConfig::default();
+        let grinta = vec!["grinta".to_string(), "--close-on-launch".to_string()];
+
+        let wezterm = terminal_command(Terminal::Wezterm, &config, true, Some((10, 20)), &grinta);
+        assert!(wezterm.windows(2).any(|pair| pair == ["--position", "10,20"]));
+        assert_eq!(wezterm[wezterm.len() - 3..], ["--", "grinta", "--close-on-launch"]);
+
+        let kitty = terminal_command(Terminal::Kitty, &config, false, None, &grinta);
+        assert!(!kitty.contains(&POPUP_CLASS.to_string()));
+        assert!(kitty.contains(&"initial_window_width=100c".to_string()));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::config::expand_tilde;
+use crate::core::{CommandItem, CommandType, Handler};
+use crate::internal::InternalCommand;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Query command listing the workspaces to switch to.
+const WORKSPACE_COMMAND: &str = "ws";
+
+/// Metadata naming the indexed source an item was read by.
+const ORIGIN_KEY: &str = "source";
+
+/// The data sources a workspace can enable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Apps,
+    Notes,
+    Bookmarks,
+    Shortcuts,
+    Reminders,
+    Files,
+    Web,
+}
+
+impl Source {
+    pub const ALL: [Source; 7] = [
+        Source::Apps,
+        Source::Notes,
+        Source::Bookmarks,
+        Source::Shortcuts,
+        Source::Reminders,
+        Source::Files,
+        Source::Web,
+    ];
+
+    /// Lowercase name, as used in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Apps => "apps",
+            Source::Notes => "notes",
+            Source::Bookmarks => "bookmarks",
+            Source::Shortcuts => "shortcuts",
+            Source::Reminders => "reminders",
+            Source::Files => "files",
+            Source::Web => "web",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Source> {
+        Source::ALL.into_iter().find(|source| source.name() == name)
+    }
+
+    /// Mark `item` as read by this source.
+    pub fn tag(self, item: &mut CommandItem) {
+        item.metadata.insert(ORIGIN_KEY.to_string(), self.name().to_string());
+    }
+
+    /// The indexed source `item` was read by, whose refresh replaces it.
+    /// Items cached before they were tagged fall back to `Source::of`.
+    pub fn origin(item: &CommandItem) -> Source {
+        item.metadata
+            .get(ORIGIN_KEY)
+            .and_then(|name| Source::from_name(name))
+            .unwrap_or_else(|| Source::of(item))
+    }
+
+    /// The source an item was produced by.
+    pub fn of(item: &CommandItem) -> Source {
+        // Config items, repositories, zoxide directories and recent
+        // documents are read along with the apps
+        if ["custom", "recent", "repo", "zoxide"].iter().any(|key| item.metadata.contains_key(*key)) {
+            return Source::Apps;
+        }
+        match item.handler {
+            // Computed results are always available
+            Handler::App
+            | Handler::Internal
+            | Handler::Copy
+            | Handler::Transform
+            | Handler::Plugin
+            | Handler::Shell
+            | Handler::Process
+            | Handler::System
+            | Handler::Wifi
+            | Handler::Ssh
+            | Handler::Kube
+            | Handler::Database => Source::Apps,
+            Handler::Note => Source::Notes,
+            Handler::Automation => Source::Shortcuts,
+            Handler::Reminder => Source::Reminders,
+            Handler::File | Handler::Folder => Source::Files,
+            Handler::Url => match item.kind {
+                CommandType::WebSearch | CommandType::WebSuggestion => Source::Web,
+                _ => Source::Bookmarks,
+            },
+        }
+    }
+}
+
+/// Query prefixes limiting the results to one source, such as `f invoice`
+/// for files only. Configuring `[prefixes]` replaces the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SourcePrefixes(pub BTreeMap<String, Source>);
+
+impl Default for SourcePrefixes {
+    fn default() -> Self {
+        let defaults = [
+            ("a", Source::Apps),
+            ("f", Source::Files),
+            ("b", Source::Bookmarks),
+            ("n", Source::Notes),
+            ("s", Source::Shortcuts),
+            ("r", Source::Reminders),
+            ("?", Source::Web),
+        ];
+        Self(defaults.into_iter().map(|(prefix, source)| (prefix.to_string(), source)).collect())
+    }
+}
+
+impl SourcePrefixes {
+    /// The source a query is scoped to and the query without its prefix.
+    pub fn parse<'a>(&self, query: &'a str) -> Option<(Source, &'a str)> {
+        let (prefix, rest) = query.split_once(' ')?;
+        let source = *self.0.get(prefix)?;
+        let rest = rest.trim();
+        (!rest.is_empty()).then_some((source, rest))
+    }
+}
+
+/// An item always shown at the top of the workspace's recent view.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub label: String,
+    pub value: String,
+    #[serde(default = "default_pin_handler")]
+    pub handler: Handler,
+    /// Key opening the pin from anywhere in the app, such as `F1` or `alt+s`
+    #[serde(default)]
+    pub hotkey: Option<String>,
+}
+
+fn default_pin_handler() -> Handler {
+    Handler::Url
+}
+
+impl Pin {
+    pub fn to_item(&self) -> CommandItem {
+        let value = match self.handler {
+            Handler::File | Handler::Folder | Handler::App => expand_tilde(&self.value).to_string_lossy().to_string(),
+            _ => self.value.clone(),
+        };
+        let mut item = CommandItem::new(&self.label, self.handler, &value);
+        item.icon = "📌".to_string();
+        item.metadata.insert("pinned".to_string(), "true".to_string());
+        item
+    }
+}
+
+/// A named context bundling pins, file search scopes, enabled sources and
+/// environment variables. Each workspace keeps its own history slice.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub pins: Vec<Pin>,
+    /// Directories file search is restricted to (home directory when empty)
+    pub dirs: Vec<String>,
+    /// Enabled sources (all sources when empty)
+    pub sources: Vec<Source>,
+    /// Environment variables set for launched commands
+    pub env: BTreeMap<String, String>,
+}
+
+impl Workspace {
+    pub fn allows(&self, source: Source) -> bool {
+        self.sources.is_empty() || self.sources.contains(&source)
+    }
+
+    pub fn search_roots(&self) -> Vec<PathBuf> {
+        self.dirs.iter().map(|dir| expand_tilde(dir)).collect()
+    }
+
+    pub fn pinned_items(&self) -> Vec<CommandItem> {
+        self.pins.iter().map(Pin::to_item).collect()
+    }
+}
+
+/// Return the filter text when the query is a `ws` command.
+pub fn parse_command(query: &str) -> Option<&str> {
+    if query == WORKSPACE_COMMAND {
+        return Some("");
+    }
+    query
+        .strip_prefix(WORKSPACE_COMMAND)
+        .and_then(|rest| rest.strip_prefix(' '))
+        .map(str::trim)
+}
+
+/// Items switching to each configured workspace matching `filter`, plus an
+/// item leaving the active workspace.
+pub fn switch_items(
+    filter: &str,
+    workspaces: &BTreeMap<String, Workspace>,
+    active: Option<&str>,
+) -> Vec<CommandItem> {
+    let matcher = SkimMatcherV2::default();
+    let mut items: Vec<CommandItem> = workspaces
+        .keys()
+        .filter(|name| filter.is_empty() || matcher.fuzzy_match(name, filter).is_some())
+        .map(|name| {
+            let label = if Some(name.as_str()) == active {
+                format!("{} (active)", name)
+            } else {
+                format!("Switch to workspace {}", name)
+            };
+            let mut item = InternalCommand::SwitchWorkspace(Some(name.clone())).to_item(&label);
+            item.icon = "🧭".to_string();
+            item
+        })
+        .collect();
+
+    if active.is_some() {
+        let mut leave = InternalCommand::SwitchWorkspace(None).to_item("Leave workspace");
+        leave.icon = "🧭".to_string();
+        items.push(leave);
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspaces() -> BTreeMap<String, Workspace> {
+        let mut map = BTreeMap::new();
+        map.insert("clientx".to_string(), Workspace::default());
+        map.insert("personal".to_string(), Workspace::default());
+        map
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("ws"), Some(""));
+        assert_eq!(parse_command("ws "), Some(""));
+        assert_eq!(parse_command("ws client"), Some("client"));
+        assert_eq!(parse_command("wsl"), None);
+        assert_eq!(parse_command("news"), None);
+    }
+
+    #[test]
+    fn test_source_of_item() {
+        let mut suggestion = CommandItem::new("rust", Handler::Url, "https://duckduckgo.com/?q=rust");
+        suggestion.kind = CommandType::WebSuggestion;
+        let bookmark = CommandItem::new("Docs (Bookmark)", Handler::Url, "https://docs.rs");
+
+        assert_eq!(Source::of(&suggestion), Source::Web);
+        assert_eq!(Source::of(&bookmark), Source::Bookmarks);
+        assert_eq!(Source::of(&CommandItem::new("a", Handler::Folder, "/a")), Source::Files);
+        assert_eq!(Source::of(&CommandItem::new("n", Handler::Note, "id")), Source::Notes);
+
+        let mut custom = CommandItem::new("Intranet", Handler::Url, "https://intranet.example.com");
+        custom.metadata.insert("custom".to_string(), "true".to_string());
+        assert_eq!(Source::of(&custom), Source::Apps);
+
+        let mut repo = CommandItem::new("grinta", Handler::Folder, "/src/grinta");
+        repo.metadata.insert("repo".to_string(), "true".to_string());
+        assert_eq!(Source::of(&repo), Source::Apps);
+
+        let mut visited = CommandItem::new("api", Handler::Folder, "/src/api");
+        visited.metadata.insert("zoxide".to_string(), "true".to_string());
+        assert_eq!(Source::of(&visited), Source::Apps);
+
+        let mut recent = CommandItem::new("report.pdf", Handler::File, "/home/me/report.pdf");
+        recent.metadata.insert("recent".to_string(), "true".to_string());
+        assert_eq!(Source::of(&recent), Source::Apps);
+    }
+
+    #[test]
+    fn test_origin_of_item() {
+        let mut reload = CommandItem::new("Reload notes", Handler::Internal, "refresh:notes");
+        assert_eq!(Source::origin(&reload), Source::Apps);
+        Source::Notes.tag(&mut reload);
+        assert_eq!(Source::origin(&reload), Source::Notes);
+        assert_eq!(Source::of(&reload), Source::Apps);
+    }
+
+    #[test]
+    fn test_source_prefixes() {
+        let prefixes = SourcePrefixes::default();
+        assert_eq!(prefixes.parse("f invoice 2024"), Some((Source::Files, "invoice 2024")));
+        assert_eq!(prefixes.parse("? rust traits"), Some((Source::Web, "rust traits")));
+        assert_eq!(prefixes.parse("f "), None);
+        assert_eq!(prefixes.parse("firefox"), None);
+        assert_eq!(prefixes.parse("fx rates"), None);
+    }
+
+    #[test]
+    fn test_allows_all_sources_when_empty() {
+        let open = Workspace::default();
+        let scoped = Workspace { sources: vec![Source::Files], ..Workspace::default() };
+
+        assert!(open.allows(Source::Web));
+        assert!(scoped.allows(Source::Files));
+        assert!(!scoped.allows(Source::Web));
+    }
+
+    #[test]
+    fn test_pinned_items() {
+        let workspace = Workspace {
+            pins: vec![Pin {
+                label: "Wiki".to_string(),
+                value: "https://wiki.example.com".to_string(),
+                handler: Handler::Url,
+                hotkey: None,
+            }],
+            ..Workspace::default()
+        };
+
+        let items = workspace.pinned_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].value, "https://wiki.example.com");
+        assert_eq!(items[0].icon, "📌");
+        assert_eq!(items[0].metadata.get("pinned").map(String::as_str), Some("true"));
+    }
+
+    #[test]
+    fn test_switch_items() {
+        let items = switch_items("", &workspaces(), None);
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|item| item.handler == Handler::Internal));
+
+        let filtered = switch_items("cli", &workspaces(), Some("personal"));
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].label, "Switch to workspace clientx");
+        assert_eq!(filtered[1].label, "Leave workspace");
+    }
+}